@@ -17,7 +17,7 @@ use {
         BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
         BanksTransactionResultWithSimulation,
     },
-    solana_clock::Slot,
+    solana_clock::{Clock, Slot, UnixTimestamp},
     solana_commitment_config::CommitmentLevel,
     solana_hash::Hash,
     solana_message::Message,
@@ -510,6 +510,45 @@ impl BanksClient {
             .await
             .map_err(Into::into)
     }
+
+    /// Move the clock of the working bank forward by `seconds` and return
+    /// the updated clock. Only supported by local test servers.
+    pub async fn advance_time(&self, seconds: u64) -> Result<Clock, BanksClientError> {
+        self.inner
+            .advance_time_with_context(context::current(), seconds)
+            .await?
+            .ok_or(BanksClientError::ClientError("test controls not enabled"))
+    }
+
+    /// Set and freeze the clock's `unix_timestamp` until `unfreeze_clock()`
+    /// is called. Only supported by local test servers.
+    pub async fn set_clock(
+        &self,
+        unix_timestamp: UnixTimestamp,
+    ) -> Result<Clock, BanksClientError> {
+        self.inner
+            .set_clock_with_context(context::current(), unix_timestamp)
+            .await?
+            .ok_or(BanksClientError::ClientError("test controls not enabled"))
+    }
+
+    /// Let the clock follow the runtime's timestamp estimate again. Only
+    /// supported by local test servers.
+    pub async fn unfreeze_clock(&self) -> Result<Clock, BanksClientError> {
+        self.inner
+            .unfreeze_clock_with_context(context::current())
+            .await?
+            .ok_or(BanksClientError::ClientError("test controls not enabled"))
+    }
+
+    /// Advance the working bank by `slots` slots, one slot at a time, and
+    /// return the new working slot. Only supported by local test servers.
+    pub async fn step_slots(&self, slots: u64) -> Result<Slot, BanksClientError> {
+        self.inner
+            .step_slots_with_context(context::current(), slots)
+            .await?
+            .ok_or(BanksClientError::ClientError("test controls not enabled"))
+    }
 }
 
 pub async fn start_client<C>(transport: C) -> Result<BanksClient, BanksClientError>
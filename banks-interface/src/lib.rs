@@ -3,7 +3,7 @@
 use {
     serde_derive::{Deserialize, Serialize},
     solana_account::Account,
-    solana_clock::Slot,
+    solana_clock::{Clock, Slot, UnixTimestamp},
     solana_commitment_config::CommitmentLevel,
     solana_hash::Hash,
     solana_message::{inner_instruction::InnerInstructions, Message},
@@ -97,6 +97,12 @@ pub trait Banks {
         message: Message,
         commitment: CommitmentLevel,
     ) -> Option<u64>;
+    // The following methods are only served by loopback servers started with
+    // test controls enabled; every other server returns `None`.
+    async fn advance_time_with_context(seconds: u64) -> Option<Clock>;
+    async fn set_clock_with_context(unix_timestamp: UnixTimestamp) -> Option<Clock>;
+    async fn unfreeze_clock_with_context() -> Option<Clock>;
+    async fn step_slots_with_context(slots: u64) -> Option<Slot>;
 }
 
 #[cfg(test)]
@@ -9,7 +9,7 @@ use {
         TransactionSimulationDetails, TransactionStatus,
     },
     solana_client::connection_cache::ConnectionCache,
-    solana_clock::Slot,
+    solana_clock::{Clock, Slot, UnixTimestamp},
     solana_commitment_config::CommitmentLevel,
     solana_hash::Hash,
     solana_message::{Message, SanitizedMessage},
@@ -52,12 +52,134 @@ mod transaction {
     pub use solana_transaction_error::TransactionResult as Result;
 }
 
+/// Deterministic clock and slot controls over a `BankForks` that is only
+/// driven by tests.
+///
+/// Never hand these to a server backed by a live validator: stepping slots
+/// creates and roots banks outside of replay.
+#[derive(Clone)]
+pub struct TestControls {
+    bank_forks: Arc<RwLock<BankForks>>,
+    block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    /// When set, every bank created through these controls (or passed to
+    /// `apply_clock_override()`) gets this `unix_timestamp`
+    frozen_unix_timestamp: Arc<RwLock<Option<UnixTimestamp>>>,
+}
+
+impl TestControls {
+    pub fn new(
+        bank_forks: Arc<RwLock<BankForks>>,
+        block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    ) -> Self {
+        Self {
+            bank_forks,
+            block_commitment_cache,
+            frozen_unix_timestamp: Arc::default(),
+        }
+    }
+
+    /// Return the `Clock` of the working bank
+    pub fn clock(&self) -> Clock {
+        self.bank_forks.read().unwrap().working_bank().clock()
+    }
+
+    /// Return the frozen `unix_timestamp`, if any
+    pub fn frozen_unix_timestamp(&self) -> Option<UnixTimestamp> {
+        *self.frozen_unix_timestamp.read().unwrap()
+    }
+
+    /// Set `unix_timestamp` of the working bank and keep it frozen at that
+    /// value across `step_slots()` until `unfreeze_clock()` is called
+    pub fn set_clock(&self, unix_timestamp: UnixTimestamp) -> Clock {
+        *self.frozen_unix_timestamp.write().unwrap() = Some(unix_timestamp);
+        let bank = self.bank_forks.read().unwrap().working_bank();
+        self.apply_clock_override(&bank);
+        bank.clock()
+    }
+
+    /// Move `unix_timestamp` of the working bank forward by `seconds`.
+    ///
+    /// If the clock is frozen, it stays frozen at the new value. Otherwise
+    /// subsequent banks keep advancing from the new value, since the runtime
+    /// never lets the timestamp go backwards.
+    pub fn advance_time(&self, seconds: u64) -> Clock {
+        let seconds = UnixTimestamp::try_from(seconds).unwrap_or(UnixTimestamp::MAX);
+        let bank = self.bank_forks.read().unwrap().working_bank();
+        let mut frozen_unix_timestamp = self.frozen_unix_timestamp.write().unwrap();
+        let mut clock = bank.clock();
+        clock.unix_timestamp = clock.unix_timestamp.saturating_add(seconds);
+        if frozen_unix_timestamp.is_some() {
+            *frozen_unix_timestamp = Some(clock.unix_timestamp);
+        }
+        bank.set_sysvar_for_tests(&clock);
+        clock
+    }
+
+    /// Let `unix_timestamp` follow the runtime's estimate again
+    pub fn unfreeze_clock(&self) -> Clock {
+        *self.frozen_unix_timestamp.write().unwrap() = None;
+        self.clock()
+    }
+
+    /// Apply the frozen `unix_timestamp`, if any, to `bank`
+    pub fn apply_clock_override(&self, bank: &Bank) {
+        if let Some(unix_timestamp) = self.frozen_unix_timestamp() {
+            let clock = Clock {
+                unix_timestamp,
+                ..bank.clock()
+            };
+            bank.set_sysvar_for_tests(&clock);
+        }
+    }
+
+    /// Advance the working bank by `slots` consecutive slots and return the
+    /// new working slot.
+    ///
+    /// Unlike warping, every intermediate slot is created from its parent and
+    /// registers exactly one new blockhash, so blockhashes used by in-flight
+    /// transactions stay valid for as long as they would on a live cluster.
+    pub fn step_slots(&self, slots: u64) -> Slot {
+        let mut bank_forks = self.bank_forks.write().unwrap();
+        let mut bank = bank_forks.working_bank();
+        if slots == 0 {
+            return bank.slot();
+        }
+
+        for _ in 0..slots {
+            bank.fill_bank_with_ticks_for_tests();
+            bank.freeze();
+            let child = Bank::new_from_parent(bank.clone(), &Pubkey::default(), bank.slot() + 1);
+            self.apply_clock_override(&child);
+            bank = bank_forks.insert(child).clone_without_scheduler();
+        }
+
+        let parent_slot = bank.parent_slot();
+        bank_forks
+            .set_root(
+                parent_slot,
+                None, // snapshots are disabled
+                Some(parent_slot),
+            )
+            .unwrap();
+
+        // Update block commitment cache, otherwise banks server will poll at
+        // the wrong slot
+        let slot = bank.slot();
+        self.block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(slot, slot);
+        slot
+    }
+}
+
 #[derive(Clone)]
 struct BanksServer {
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     transaction_sender: Sender<TransactionInfo>,
     poll_signature_status_sleep_duration: Duration,
+    test_controls: Option<TestControls>,
 }
 
 impl BanksServer {
@@ -70,12 +192,14 @@ impl BanksServer {
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         transaction_sender: Sender<TransactionInfo>,
         poll_signature_status_sleep_duration: Duration,
+        test_controls: Option<TestControls>,
     ) -> Self {
         Self {
             bank_forks,
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            test_controls,
         }
     }
 
@@ -109,6 +233,7 @@ impl BanksServer {
         bank_forks: Arc<RwLock<BankForks>>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         poll_signature_status_sleep_duration: Duration,
+        test_controls: Option<TestControls>,
     ) -> Self {
         let (transaction_sender, transaction_receiver) = unbounded();
         let bank = bank_forks.read().unwrap().working_bank();
@@ -128,6 +253,7 @@ impl BanksServer {
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            test_controls,
         )
     }
 
@@ -407,17 +533,55 @@ impl Banks for BanksServer {
                 .ok()?;
         bank.get_fee_for_message(&sanitized_message)
     }
+
+    async fn advance_time_with_context(self, _: Context, seconds: u64) -> Option<Clock> {
+        Some(self.test_controls?.advance_time(seconds))
+    }
+
+    async fn set_clock_with_context(
+        self,
+        _: Context,
+        unix_timestamp: UnixTimestamp,
+    ) -> Option<Clock> {
+        Some(self.test_controls?.set_clock(unix_timestamp))
+    }
+
+    async fn unfreeze_clock_with_context(self, _: Context) -> Option<Clock> {
+        Some(self.test_controls?.unfreeze_clock())
+    }
+
+    async fn step_slots_with_context(self, _: Context, slots: u64) -> Option<Slot> {
+        Some(self.test_controls?.step_slots(slots))
+    }
 }
 
 pub async fn start_local_server(
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     poll_signature_status_sleep_duration: Duration,
+) -> UnboundedChannel<Response<BanksResponse>, ClientMessage<BanksRequest>> {
+    start_local_server_with_test_controls(
+        bank_forks,
+        block_commitment_cache,
+        poll_signature_status_sleep_duration,
+        None,
+    )
+    .await
+}
+
+/// Like `start_local_server()`, but also serves the clock and slot control
+/// methods through the given `TestControls`
+pub async fn start_local_server_with_test_controls(
+    bank_forks: Arc<RwLock<BankForks>>,
+    block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    poll_signature_status_sleep_duration: Duration,
+    test_controls: Option<TestControls>,
 ) -> UnboundedChannel<Response<BanksResponse>, ClientMessage<BanksRequest>> {
     let banks_server = BanksServer::new_loopback(
         bank_forks,
         block_commitment_cache,
         poll_signature_status_sleep_duration,
+        test_controls,
     );
     let (client_transport, server_transport) = transport::channel::unbounded();
     let server = server::BaseChannel::with_defaults(server_transport).execute(banks_server.serve());
@@ -475,6 +639,7 @@ pub async fn start_tcp_server(
                 block_commitment_cache.clone(),
                 sender,
                 Duration::from_millis(200),
+                None, // test controls are never served to remote clients
             );
             chan.execute(server.serve())
         })
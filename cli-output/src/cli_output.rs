@@ -29,7 +29,7 @@ use {
     solana_pubkey::Pubkey,
     solana_rpc_client_api::response::{
         RpcAccountBalance, RpcContactInfo, RpcInflationGovernor, RpcInflationRate, RpcKeyedAccount,
-        RpcSupply, RpcVoteAccountInfo,
+        RpcSupply, RpcVoteAccountAnalytics, RpcVoteAccountInfo,
     },
     solana_signature::Signature,
     solana_stake_interface::state::{Authorized, Lockup},
@@ -1000,6 +1000,168 @@ impl fmt::Display for CliKeyedStakeState {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStakePortfolio {
+    pub stake_accounts: Vec<CliKeyedStakeState>,
+    pub validators: Vec<CliStakePortfolioValidator>,
+    pub total_balance: u64,
+    pub total_active_stake: u64,
+    pub total_activating_stake: u64,
+    pub total_deactivating_stake: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_rewards: Option<u64>,
+    #[serde(skip_serializing)]
+    pub use_lamports_unit: bool,
+}
+
+impl QuietDisplay for CliStakePortfolio {}
+impl VerboseDisplay for CliStakePortfolio {}
+
+impl fmt::Display for CliStakePortfolio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for state in &self.stake_accounts {
+            writeln!(f)?;
+            write!(f, "{state}")?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}",
+            style(format!(
+                "  {:<44}  {:>8}  {:>20}  {:>20}  {:>20}",
+                "Vote Account",
+                "Accounts",
+                "Active Stake",
+                "Activating Stake",
+                "Deactivating Stake",
+            ))
+            .bold()
+        )?;
+        let config = BuildBalanceMessageConfig {
+            use_lamports_unit: self.use_lamports_unit,
+            show_unit: false,
+            trim_trailing_zeros: false,
+        };
+        for validator in &self.validators {
+            writeln!(
+                f,
+                "  {:<44}  {:>8}  {:>20}  {:>20}  {:>20}",
+                validator.vote_account_address,
+                validator.stake_accounts,
+                build_balance_message_with_config(validator.active_stake, &config),
+                build_balance_message_with_config(validator.activating_stake, &config),
+                build_balance_message_with_config(validator.deactivating_stake, &config),
+            )?;
+        }
+        writeln!(f)?;
+        writeln_name_value(f, "Stake Accounts:", &self.stake_accounts.len().to_string())?;
+        writeln_name_value(
+            f,
+            "Total Balance:",
+            &build_balance_message(self.total_balance, self.use_lamports_unit, true),
+        )?;
+        writeln_name_value(
+            f,
+            "Total Active Stake:",
+            &build_balance_message(self.total_active_stake, self.use_lamports_unit, true),
+        )?;
+        writeln_name_value(
+            f,
+            "Total Activating Stake:",
+            &build_balance_message(self.total_activating_stake, self.use_lamports_unit, true),
+        )?;
+        writeln_name_value(
+            f,
+            "Total Deactivating Stake:",
+            &build_balance_message(self.total_deactivating_stake, self.use_lamports_unit, true),
+        )?;
+        if let Some(total_rewards) = self.total_rewards {
+            writeln_name_value(
+                f,
+                "Total Rewards:",
+                &build_balance_message(total_rewards, self.use_lamports_unit, true),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStakePortfolioValidator {
+    pub vote_account_address: String,
+    pub stake_accounts: usize,
+    pub active_stake: u64,
+    pub activating_stake: u64,
+    pub deactivating_stake: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStakeBatch {
+    pub operations: Vec<CliStakeBatchOperation>,
+    pub transaction_count: usize,
+    pub signatures: Vec<String>,
+    pub dry_run: bool,
+}
+
+impl QuietDisplay for CliStakeBatch {}
+impl VerboseDisplay for CliStakeBatch {}
+
+impl fmt::Display for CliStakeBatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}",
+            style(format!(
+                "  {:<44}  {:<10}  {:>11}  Details",
+                "Stake Account", "Action", "Transaction"
+            ))
+            .bold()
+        )?;
+        for operation in &self.operations {
+            writeln!(
+                f,
+                "  {:<44}  {:<10}  {:>11}  {}",
+                operation.stake_account,
+                operation.action,
+                operation
+                    .transaction
+                    .map(|index| index.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                operation.details.as_deref().unwrap_or_default(),
+            )?;
+        }
+        writeln!(f)?;
+        if self.dry_run {
+            writeln_name_value(
+                f,
+                "Transactions (dry run, not sent):",
+                &self.transaction_count.to_string(),
+            )?;
+        } else {
+            writeln_name_value(f, "Transactions:", &self.transaction_count.to_string())?;
+            for (index, signature) in self.signatures.iter().enumerate() {
+                writeln!(f, "  {index:>3}  {signature}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStakeBatchOperation {
+    pub stake_account: String,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<usize>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliEpochReward {
@@ -1746,6 +1908,78 @@ impl fmt::Display for CliVoteAccount {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliVoteAnalytics {
+    #[serde(flatten)]
+    pub analytics: RpcVoteAccountAnalytics,
+    #[serde(skip_serializing)]
+    pub use_lamports_unit: bool,
+}
+
+impl QuietDisplay for CliVoteAnalytics {}
+impl VerboseDisplay for CliVoteAnalytics {}
+
+impl fmt::Display for CliVoteAnalytics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let analytics = &self.analytics;
+        writeln_name_value(f, "Vote Account:", &analytics.vote_pubkey)?;
+        writeln_name_value(f, "Validator Identity:", &analytics.node_pubkey)?;
+        writeln_name_value(f, "Commission:", &format!("{}%", analytics.commission))?;
+        writeln_name_value(
+            f,
+            "Active Stake:",
+            &build_balance_message(analytics.activated_stake, self.use_lamports_unit, true),
+        )?;
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}",
+            style(format!(
+                "  {:<8}  {:>12}  {:>12}  {:>12}  {:>10}  {:>10}",
+                "Epoch", "Credits", "Cluster Max", "Missed", "Efficiency", "Commission",
+            ))
+            .bold()
+        )?;
+        for epoch in &analytics.epochs {
+            writeln!(
+                f,
+                "  {:<8}  {:>12}  {:>12}  {:>12}  {:>9.2}%  {:>10}",
+                epoch.epoch,
+                epoch.credits,
+                epoch.cluster_max_credits,
+                epoch.missed_credits,
+                epoch.efficiency,
+                epoch
+                    .commission
+                    .map(|commission| format!("{commission}%"))
+                    .unwrap_or_else(|| "-".to_string()),
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}",
+            style(format!("  {:<8}  {:>6}", "Latency", "Votes")).bold()
+        )?;
+        for latency_count in &analytics.vote_latencies {
+            writeln!(
+                f,
+                "  {:<8}  {:>6}",
+                latency_count.latency, latency_count.votes
+            )?;
+        }
+        writeln!(f)?;
+        writeln_name_value(
+            f,
+            "Late Vote Credits Missed:",
+            &analytics.late_vote_credits_missed.to_string(),
+        )
+    }
+}
+
 #[derive(Default, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliAuthorizedVoters {
@@ -1,7 +1,8 @@
 use {
     crate::{
         address_lookup_table::*, clap_app::*, cluster_query::*, feature::*, inflation::*, nonce::*,
-        program::*, program_v4::*, spend_utils::*, stake::*, validator_info::*, vote::*, wallet::*,
+        program::*, program_v4::*, spend_utils::*, stake::*, stake_portfolio::*, validator_info::*,
+        vote::*, wallet::*,
     },
     clap::{crate_description, crate_name, value_t_or_exit, ArgMatches, Shell},
     log::*,
@@ -346,6 +347,12 @@ pub enum CliCommand {
         with_rewards: Option<usize>,
         starting_epoch: Option<u64>,
     },
+    ShowVoteAnalytics {
+        pubkey: Pubkey,
+        num_epochs: Option<usize>,
+        with_commission_history: bool,
+        use_lamports_unit: bool,
+    },
     WithdrawFromVoteAccount {
         vote_account_pubkey: Pubkey,
         destination_account_pubkey: Pubkey,
@@ -452,6 +459,7 @@ pub enum CliCommand {
     StakeMinimumDelegation {
         use_lamports_unit: bool,
     },
+    StakePortfolio(StakePortfolioCliCommand),
     // Address lookup table commands
     AddressLookupTable(AddressLookupTableCliCommand),
     SignOffchainMessage {
@@ -750,6 +758,9 @@ pub fn parse_command(
         ("stake-account", Some(matches)) => parse_show_stake_account(matches, wallet_manager),
         ("stake-history", Some(matches)) => parse_show_stake_history(matches),
         ("stake-minimum-delegation", Some(matches)) => parse_stake_minimum_delegation(matches),
+        ("stake-portfolio", Some(matches)) => {
+            parse_stake_portfolio_subcommand(matches, default_signer, wallet_manager)
+        }
         // Validator Info Commands
         ("validator-info", Some(matches)) => match matches.subcommand() {
             ("publish", Some(matches)) => {
@@ -797,6 +808,7 @@ pub fn parse_command(
             CHECKED,
         ),
         ("vote-account", Some(matches)) => parse_vote_get_account_command(matches, wallet_manager),
+        ("vote-analytics", Some(matches)) => parse_vote_analytics_command(matches, wallet_manager),
         ("withdraw-from-vote-account", Some(matches)) => {
             parse_withdraw_from_vote_account(matches, default_signer, wallet_manager)
         }
@@ -1434,6 +1446,9 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
         CliCommand::StakeMinimumDelegation { use_lamports_unit } => {
             process_stake_minimum_delegation(&rpc_client, config, *use_lamports_unit)
         }
+        CliCommand::StakePortfolio(subcommand) => {
+            process_stake_portfolio_subcommand(&rpc_client, config, subcommand)
+        }
 
         // Validator Info Commands
 
@@ -1507,6 +1522,19 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
             *with_rewards,
             *starting_epoch,
         ),
+        CliCommand::ShowVoteAnalytics {
+            pubkey: vote_account_pubkey,
+            num_epochs,
+            with_commission_history,
+            use_lamports_unit,
+        } => process_show_vote_analytics(
+            &rpc_client,
+            config,
+            vote_account_pubkey,
+            *num_epochs,
+            *with_commission_history,
+            *use_lamports_unit,
+        ),
         CliCommand::WithdrawFromVoteAccount {
             vote_account_pubkey,
             withdraw_authority,
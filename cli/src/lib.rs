@@ -36,6 +36,7 @@ pub mod program;
 pub mod program_v4;
 pub mod spend_utils;
 pub mod stake;
+pub mod stake_portfolio;
 pub mod test_utils;
 pub mod validator_info;
 pub mod vote;
@@ -81,7 +81,7 @@ pub const CUSTODIAN_ARG: ArgConstant<'static> = ArgConstant {
     help: "Authority to override account lockup",
 };
 
-fn stake_authority_arg<'a, 'b>() -> Arg<'a, 'b> {
+pub(crate) fn stake_authority_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name(STAKE_AUTHORITY_ARG.name)
         .long(STAKE_AUTHORITY_ARG.long)
         .takes_value(true)
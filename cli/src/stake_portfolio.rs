@@ -0,0 +1,1450 @@
+use {
+    crate::{
+        checks::check_account_for_fee_with_commitment,
+        cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+        compute_budget::{
+            simulate_and_update_compute_unit_limit, ComputeUnitConfig, WithComputeUnitConfig,
+        },
+        feature::get_feature_activation_epoch,
+        stake::{
+            build_stake_state, get_epoch_boundary_timestamps, make_cli_reward, stake_authority_arg,
+            STAKE_AUTHORITY_ARG,
+        },
+    },
+    clap::{value_t_or_exit, App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand},
+    solana_account::{from_account, state_traits::StateMut, Account},
+    solana_clap_utils::{
+        compute_budget::{compute_unit_price_arg, ComputeUnitLimit, COMPUTE_UNIT_PRICE_ARG},
+        fee_payer::{fee_payer_arg, FEE_PAYER_ARG},
+        input_parsers::*,
+        input_validators::*,
+        keypair::{CliSigners, DefaultSigner, SignerIndex},
+    },
+    solana_cli_output::{
+        display::new_spinner_progress_bar, CliEpochReward, CliKeyedStakeState, CliStakeBatch,
+        CliStakeBatchOperation, CliStakePortfolio, CliStakePortfolioValidator,
+    },
+    solana_clock::{Clock, Epoch},
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_native_token::Sol,
+    solana_packet::PACKET_DATA_SIZE,
+    solana_pubkey::Pubkey,
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+        request::MAX_MULTIPLE_ACCOUNTS,
+    },
+    solana_sdk_ids::sysvar::{clock, stake_history},
+    solana_signature::Signature,
+    solana_signer::Signer,
+    solana_stake_interface::{
+        self as stake, instruction as stake_instruction, state::StakeStateV2,
+    },
+    solana_stake_program::stake_state::check_merge,
+    solana_system_interface::instruction as system_instruction,
+    solana_sysvar::stake_history::StakeHistory,
+    solana_transaction::Transaction,
+    std::{
+        collections::{BTreeMap, HashMap},
+        rc::Rc,
+    },
+};
+
+// Byte offsets of fields within a serialized `StakeStateV2`
+const STAKER_OFFSET: usize = 12;
+const WITHDRAWER_OFFSET: usize = 44;
+const VOTER_OFFSET: usize = 124;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StakePortfolioCliCommand {
+    Show {
+        staker: Option<Pubkey>,
+        withdrawer: Option<Pubkey>,
+        vote_account: Option<Pubkey>,
+        num_rewards_epochs: Option<usize>,
+        use_lamports_unit: bool,
+    },
+    Deactivate {
+        batch: StakeBatchConfig,
+        vote_account: Option<Pubkey>,
+    },
+    Delegate {
+        batch: StakeBatchConfig,
+        vote_account: Pubkey,
+    },
+    Merge {
+        batch: StakeBatchConfig,
+    },
+    Split {
+        batch: StakeBatchConfig,
+        stake_account_pubkey: Pubkey,
+        lamports: u64,
+        count: usize,
+        seed_prefix: String,
+    },
+}
+
+/// Settings shared by the commands that operate on many stake accounts
+#[derive(Debug, PartialEq, Eq)]
+pub struct StakeBatchConfig {
+    /// Stake accounts to operate on. When empty, every stake account whose
+    /// staker is the stake authority is used.
+    pub stake_account_pubkeys: Vec<Pubkey>,
+    pub stake_authority: SignerIndex,
+    pub fee_payer: SignerIndex,
+    pub compute_unit_price: Option<u64>,
+    pub dry_run: bool,
+}
+
+pub trait StakePortfolioSubCommands {
+    fn stake_portfolio_subcommands(self) -> Self;
+}
+
+impl StakePortfolioSubCommands for App<'_, '_> {
+    fn stake_portfolio_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("stake-portfolio")
+                .about("View and manage many stake accounts at once")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Show all stake accounts of a staker or withdrawer")
+                        .arg(pubkey!(
+                            Arg::with_name("staker").long("staker").value_name("PUBKEY"),
+                            "Only show stake accounts with the provided stake authority."
+                        ))
+                        .arg(pubkey!(
+                            Arg::with_name("withdrawer")
+                                .long("withdrawer")
+                                .value_name("PUBKEY"),
+                            "Only show stake accounts with the provided withdraw authority."
+                        ))
+                        .group(
+                            ArgGroup::with_name("authority")
+                                .args(&["staker", "withdrawer"])
+                                .multiple(true)
+                                .required(true),
+                        )
+                        .arg(pubkey!(
+                            Arg::with_name("vote_account")
+                                .long("vote-account")
+                                .value_name("VOTE_ACCOUNT_ADDRESS"),
+                            "Only show stake accounts delegated to the provided vote account."
+                        ))
+                        .arg(
+                            Arg::with_name("lamports")
+                                .long("lamports")
+                                .takes_value(false)
+                                .help("Display balance in lamports instead of SOL"),
+                        )
+                        .arg(
+                            Arg::with_name("with_rewards")
+                                .long("with-rewards")
+                                .takes_value(false)
+                                .help("Display inflation rewards"),
+                        )
+                        .arg(
+                            Arg::with_name("num_rewards_epochs")
+                                .long("num-rewards-epochs")
+                                .takes_value(true)
+                                .value_name("NUM")
+                                .validator(|s| is_within_range(s, 1..=50))
+                                .default_value_if("with_rewards", None, "1")
+                                .requires("with_rewards")
+                                .help(
+                                    "Display rewards for NUM recent epochs \
+                                    [default: latest epoch only]",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("deactivate")
+                        .about("Deactivate the delegated stake of many stake accounts")
+                        .arg(stake_accounts_arg(1))
+                        .arg(pubkey!(
+                            Arg::with_name("vote_account")
+                                .long("vote-account")
+                                .value_name("VOTE_ACCOUNT_ADDRESS"),
+                            "Only deactivate stake delegated to the provided vote account."
+                        ))
+                        .stake_batch_args(),
+                )
+                .subcommand(
+                    SubCommand::with_name("delegate")
+                        .about("Delegate or redelegate many stake accounts to a vote account")
+                        .long_about(
+                            "Delegate many stake accounts to a vote account. Inactive stake is \
+                             delegated right away; active stake delegated elsewhere is \
+                             deactivated so that it can be delegated by running this command \
+                             again once it has cooled down.",
+                        )
+                        .arg(pubkey!(
+                            Arg::with_name("vote_account")
+                                .index(1)
+                                .value_name("VOTE_ACCOUNT_ADDRESS")
+                                .required(true),
+                            "Vote account to delegate the stake to."
+                        ))
+                        .arg(stake_accounts_arg(2))
+                        .stake_batch_args(),
+                )
+                .subcommand(
+                    SubCommand::with_name("merge")
+                        .about("Merge all compatible stake accounts together")
+                        .arg(stake_accounts_arg(1))
+                        .stake_batch_args(),
+                )
+                .subcommand(
+                    SubCommand::with_name("split")
+                        .about("Split a stake account into several accounts at derived addresses")
+                        .arg(pubkey!(
+                            Arg::with_name("stake_account_pubkey")
+                                .index(1)
+                                .value_name("STAKE_ACCOUNT_ADDRESS")
+                                .required(true),
+                            "Stake account to split."
+                        ))
+                        .arg(
+                            Arg::with_name("amount")
+                                .index(2)
+                                .value_name("AMOUNT")
+                                .takes_value(true)
+                                .validator(is_amount)
+                                .required(true)
+                                .help("The amount to move into each new stake account, in SOL"),
+                        )
+                        .arg(
+                            Arg::with_name("count")
+                                .long("count")
+                                .value_name("NUMBER")
+                                .takes_value(true)
+                                .default_value("1")
+                                .validator(|s| is_within_range(s, 1..=256))
+                                .help("Number of new stake accounts to split off"),
+                        )
+                        .arg(
+                            Arg::with_name("seed_prefix")
+                                .long("seed-prefix")
+                                .value_name("STRING")
+                                .takes_value(true)
+                                .default_value("split-")
+                                .help(
+                                    "Prefix of the seeds used to derive the new stake account \
+                                     addresses from the stake authority",
+                                ),
+                        )
+                        .stake_batch_args(),
+                ),
+        )
+    }
+}
+
+trait StakeBatchArgs {
+    fn stake_batch_args(self) -> Self;
+}
+
+impl StakeBatchArgs for App<'_, '_> {
+    fn stake_batch_args(self) -> Self {
+        self.arg(stake_authority_arg())
+            .arg(fee_payer_arg())
+            .arg(compute_unit_price_arg())
+            .arg(
+                Arg::with_name("dry_run")
+                    .long("dry-run")
+                    .takes_value(false)
+                    .help("Show the planned operations without sending any transactions"),
+            )
+    }
+}
+
+fn stake_accounts_arg<'a, 'b>(index: u64) -> Arg<'a, 'b> {
+    pubkey!(
+        Arg::with_name("stake_account_pubkeys")
+            .index(index)
+            .value_name("STAKE_ACCOUNT_ADDRESS")
+            .multiple(true),
+        "Stake accounts to operate on [default: all stake accounts of the stake authority]."
+    )
+}
+
+pub fn parse_stake_portfolio_subcommand(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let (subcommand, sub_matches) = matches.subcommand();
+
+    let response = match (subcommand, sub_matches) {
+        ("show", Some(matches)) => {
+            let num_rewards_epochs = if matches.is_present("with_rewards") {
+                Some(value_of(matches, "num_rewards_epochs").unwrap())
+            } else {
+                None
+            };
+            CliCommandInfo::without_signers(CliCommand::StakePortfolio(
+                StakePortfolioCliCommand::Show {
+                    staker: pubkey_of(matches, "staker"),
+                    withdrawer: pubkey_of(matches, "withdrawer"),
+                    vote_account: pubkey_of(matches, "vote_account"),
+                    num_rewards_epochs,
+                    use_lamports_unit: matches.is_present("lamports"),
+                },
+            ))
+        }
+        ("deactivate", Some(matches)) => {
+            let (batch, signers) =
+                parse_stake_batch_config(matches, default_signer, wallet_manager)?;
+            CliCommandInfo {
+                command: CliCommand::StakePortfolio(StakePortfolioCliCommand::Deactivate {
+                    batch,
+                    vote_account: pubkey_of(matches, "vote_account"),
+                }),
+                signers,
+            }
+        }
+        ("delegate", Some(matches)) => {
+            let (batch, signers) =
+                parse_stake_batch_config(matches, default_signer, wallet_manager)?;
+            CliCommandInfo {
+                command: CliCommand::StakePortfolio(StakePortfolioCliCommand::Delegate {
+                    batch,
+                    vote_account: pubkey_of(matches, "vote_account").unwrap(),
+                }),
+                signers,
+            }
+        }
+        ("merge", Some(matches)) => {
+            let (batch, signers) =
+                parse_stake_batch_config(matches, default_signer, wallet_manager)?;
+            CliCommandInfo {
+                command: CliCommand::StakePortfolio(StakePortfolioCliCommand::Merge { batch }),
+                signers,
+            }
+        }
+        ("split", Some(matches)) => {
+            let (batch, signers) =
+                parse_stake_batch_config(matches, default_signer, wallet_manager)?;
+            CliCommandInfo {
+                command: CliCommand::StakePortfolio(StakePortfolioCliCommand::Split {
+                    batch,
+                    stake_account_pubkey: pubkey_of(matches, "stake_account_pubkey").unwrap(),
+                    lamports: lamports_of_sol(matches, "amount").unwrap(),
+                    count: value_t_or_exit!(matches, "count", usize),
+                    seed_prefix: value_t_or_exit!(matches, "seed_prefix", String),
+                }),
+                signers,
+            }
+        }
+        _ => unreachable!(),
+    };
+    Ok(response)
+}
+
+fn parse_stake_batch_config(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<(StakeBatchConfig, CliSigners), CliError> {
+    let stake_account_pubkeys =
+        pubkeys_of_multiple_signers(matches, "stake_account_pubkeys", wallet_manager)?
+            .unwrap_or_default();
+    let (stake_authority, stake_authority_pubkey) =
+        signer_of(matches, STAKE_AUTHORITY_ARG.name, wallet_manager)?;
+    let (fee_payer, fee_payer_pubkey) = signer_of(matches, FEE_PAYER_ARG.name, wallet_manager)?;
+    let signer_info = default_signer.generate_unique_signers(
+        vec![stake_authority, fee_payer],
+        matches,
+        wallet_manager,
+    )?;
+
+    let batch = StakeBatchConfig {
+        stake_account_pubkeys,
+        stake_authority: signer_info.index_of(stake_authority_pubkey).unwrap(),
+        fee_payer: signer_info.index_of(fee_payer_pubkey).unwrap(),
+        compute_unit_price: value_of(matches, COMPUTE_UNIT_PRICE_ARG.name),
+        dry_run: matches.is_present("dry_run"),
+    };
+    Ok((batch, signer_info.signers))
+}
+
+pub fn process_stake_portfolio_subcommand(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    subcommand: &StakePortfolioCliCommand,
+) -> ProcessResult {
+    match subcommand {
+        StakePortfolioCliCommand::Show {
+            staker,
+            withdrawer,
+            vote_account,
+            num_rewards_epochs,
+            use_lamports_unit,
+        } => process_show_stake_portfolio(
+            rpc_client,
+            config,
+            staker.as_ref(),
+            withdrawer.as_ref(),
+            vote_account.as_ref(),
+            *num_rewards_epochs,
+            *use_lamports_unit,
+        ),
+        StakePortfolioCliCommand::Deactivate {
+            batch,
+            vote_account,
+        } => {
+            let stake_authority = config.signers[batch.stake_authority].pubkey();
+            let stake_accounts = get_batch_stake_accounts(rpc_client, batch, &stake_authority)?;
+            let operations =
+                plan_deactivate(&stake_accounts, &stake_authority, vote_account.as_ref());
+            process_stake_batch(rpc_client, config, batch, operations)
+        }
+        StakePortfolioCliCommand::Delegate {
+            batch,
+            vote_account,
+        } => {
+            let vote_account_info = rpc_client.get_account(vote_account)?;
+            if vote_account_info.owner != solana_sdk_ids::vote::id() {
+                return Err(CliError::BadParameter(format!(
+                    "{vote_account} is not a vote account"
+                ))
+                .into());
+            }
+            let stake_authority = config.signers[batch.stake_authority].pubkey();
+            let stake_accounts = get_batch_stake_accounts(rpc_client, batch, &stake_authority)?;
+            let (clock, stake_history) = get_clock_and_stake_history(rpc_client)?;
+            let new_rate_activation_epoch = get_feature_activation_epoch(
+                rpc_client,
+                &agave_feature_set::reduce_stake_warmup_cooldown::id(),
+            )?;
+            let operations = plan_delegate(
+                &stake_accounts,
+                &stake_authority,
+                vote_account,
+                &clock,
+                &stake_history,
+                new_rate_activation_epoch,
+            );
+            process_stake_batch(rpc_client, config, batch, operations)
+        }
+        StakePortfolioCliCommand::Merge { batch } => {
+            let stake_authority = config.signers[batch.stake_authority].pubkey();
+            let stake_accounts = get_batch_stake_accounts(rpc_client, batch, &stake_authority)?;
+            let (clock, stake_history) = get_clock_and_stake_history(rpc_client)?;
+            let operations = plan_merge(&stake_accounts, &stake_authority, &clock, &stake_history);
+            process_stake_batch(rpc_client, config, batch, operations)
+        }
+        StakePortfolioCliCommand::Split {
+            batch,
+            stake_account_pubkey,
+            lamports,
+            count,
+            seed_prefix,
+        } => process_split_stake_portfolio(
+            rpc_client,
+            config,
+            batch,
+            stake_account_pubkey,
+            *lamports,
+            *count,
+            seed_prefix,
+        ),
+    }
+}
+
+fn process_show_stake_portfolio(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    staker: Option<&Pubkey>,
+    withdrawer: Option<&Pubkey>,
+    vote_account: Option<&Pubkey>,
+    num_rewards_epochs: Option<usize>,
+    use_lamports_unit: bool,
+) -> ProcessResult {
+    let mut filters = vec![];
+    if let Some(staker) = staker {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            STAKER_OFFSET,
+            staker.as_ref(),
+        )));
+    }
+    if let Some(withdrawer) = withdrawer {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            WITHDRAWER_OFFSET,
+            withdrawer.as_ref(),
+        )));
+    }
+    if let Some(vote_account) = vote_account {
+        // Filter by `StakeStateV2::Stake(_, _)` and `Delegation::voter_pubkey`
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &[2, 0, 0, 0],
+        )));
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            VOTER_OFFSET,
+            vote_account.as_ref(),
+        )));
+    }
+
+    let progress_bar = new_spinner_progress_bar();
+    progress_bar.set_message("Fetching stake accounts...");
+    let stake_accounts = get_stake_accounts_with_filters(rpc_client, filters)?;
+    let (clock, stake_history) = get_clock_and_stake_history(rpc_client)?;
+    let new_rate_activation_epoch = get_feature_activation_epoch(
+        rpc_client,
+        &agave_feature_set::reduce_stake_warmup_cooldown::id(),
+    )?;
+    let mut rewards = if let Some(num_rewards_epochs) = num_rewards_epochs {
+        progress_bar.set_message("Fetching inflation rewards...");
+        let addresses: Vec<_> = stake_accounts
+            .iter()
+            .map(|account| account.pubkey)
+            .collect();
+        fetch_portfolio_rewards(rpc_client, &addresses, num_rewards_epochs)?
+    } else {
+        HashMap::new()
+    };
+    progress_bar.finish_and_clear();
+
+    if stake_accounts.is_empty() {
+        return Ok("No stake accounts found".into());
+    }
+
+    let mut portfolio = CliStakePortfolio {
+        stake_accounts: vec![],
+        validators: vec![],
+        total_balance: 0,
+        total_active_stake: 0,
+        total_activating_stake: 0,
+        total_deactivating_stake: 0,
+        total_rewards: num_rewards_epochs.map(|_| 0),
+        use_lamports_unit,
+    };
+    let mut validators = BTreeMap::new();
+    for KeyedStakeAccount {
+        pubkey,
+        lamports,
+        state,
+    } in &stake_accounts
+    {
+        let mut stake_state = build_stake_state(
+            *lamports,
+            state,
+            use_lamports_unit,
+            &stake_history,
+            &clock,
+            new_rate_activation_epoch,
+            false,
+        );
+        let active_stake = stake_state.active_stake.unwrap_or_default();
+        let activating_stake = stake_state.activating_stake.unwrap_or_default();
+        let deactivating_stake = stake_state.deactivating_stake.unwrap_or_default();
+        portfolio.total_balance += lamports;
+        portfolio.total_active_stake += active_stake;
+        portfolio.total_activating_stake += activating_stake;
+        portfolio.total_deactivating_stake += deactivating_stake;
+
+        if let Some(vote_account_address) = &stake_state.delegated_vote_account_address {
+            let validator = validators
+                .entry(vote_account_address.clone())
+                .or_insert_with(|| CliStakePortfolioValidator {
+                    vote_account_address: vote_account_address.clone(),
+                    stake_accounts: 0,
+                    active_stake: 0,
+                    activating_stake: 0,
+                    deactivating_stake: 0,
+                });
+            validator.stake_accounts += 1;
+            validator.active_stake += active_stake;
+            validator.activating_stake += activating_stake;
+            validator.deactivating_stake += deactivating_stake;
+        }
+
+        if let Some(total_rewards) = portfolio.total_rewards.as_mut() {
+            let epoch_rewards = rewards.remove(pubkey).unwrap_or_default();
+            *total_rewards += epoch_rewards
+                .iter()
+                .map(|reward| reward.amount)
+                .sum::<u64>();
+            stake_state.epoch_rewards = Some(epoch_rewards);
+        }
+
+        portfolio.stake_accounts.push(CliKeyedStakeState {
+            stake_pubkey: pubkey.to_string(),
+            stake_state,
+        });
+    }
+    portfolio.validators = validators.into_values().collect();
+
+    Ok(config.output_format.formatted_string(&portfolio))
+}
+
+/// Fetches the inflation rewards of `addresses` for the `num_epochs` epochs
+/// preceding the current one, requesting all addresses of an epoch at once
+fn fetch_portfolio_rewards(
+    rpc_client: &RpcClient,
+    addresses: &[Pubkey],
+    num_epochs: usize,
+) -> Result<HashMap<Pubkey, Vec<CliEpochReward>>, Box<dyn std::error::Error>> {
+    let mut rewards_by_address: HashMap<Pubkey, Vec<CliEpochReward>> = HashMap::new();
+    let epoch_schedule = rpc_client.get_epoch_schedule()?;
+    let first_epoch = rpc_client
+        .get_epoch_info()?
+        .epoch
+        .saturating_sub(num_epochs as u64);
+
+    for epoch in first_epoch..first_epoch.saturating_add(num_epochs as u64) {
+        // Every reward of an epoch is paid out at the same slot
+        let mut epoch_boundary_timestamps = None;
+        for addresses in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let Ok(rewards) = rpc_client.get_inflation_reward(addresses, Some(epoch)) else {
+                eprintln!("Rewards not available for epoch {epoch}");
+                break;
+            };
+            for (address, reward) in addresses.iter().zip(rewards) {
+                let Some(reward) = reward else {
+                    continue;
+                };
+                let (epoch_start_time, epoch_end_time) = match epoch_boundary_timestamps {
+                    Some(timestamps) => timestamps,
+                    None => {
+                        let timestamps =
+                            get_epoch_boundary_timestamps(rpc_client, &reward, &epoch_schedule)?;
+                        epoch_boundary_timestamps = Some(timestamps);
+                        timestamps
+                    }
+                };
+                if let Some(cli_reward) =
+                    make_cli_reward(&reward, epoch_end_time, epoch_start_time, epoch_end_time)
+                {
+                    rewards_by_address
+                        .entry(*address)
+                        .or_default()
+                        .push(cli_reward);
+                }
+            }
+        }
+    }
+    Ok(rewards_by_address)
+}
+
+struct KeyedStakeAccount {
+    pubkey: Pubkey,
+    lamports: u64,
+    state: StakeStateV2,
+}
+
+impl KeyedStakeAccount {
+    fn staker(&self) -> Option<Pubkey> {
+        self.state.authorized().map(|authorized| authorized.staker)
+    }
+}
+
+fn get_clock_and_stake_history(
+    rpc_client: &RpcClient,
+) -> Result<(Clock, StakeHistory), Box<dyn std::error::Error>> {
+    let clock_account = rpc_client.get_account(&clock::id())?;
+    let clock: Clock = from_account(&clock_account).ok_or_else(|| {
+        CliError::RpcRequestError("Failed to deserialize clock sysvar".to_string())
+    })?;
+    let stake_history_account = rpc_client.get_account(&stake_history::id())?;
+    let stake_history = from_account(&stake_history_account).ok_or_else(|| {
+        CliError::RpcRequestError("Failed to deserialize stake history".to_string())
+    })?;
+    Ok((clock, stake_history))
+}
+
+fn get_stake_accounts_with_filters(
+    rpc_client: &RpcClient,
+    filters: Vec<RpcFilterType>,
+) -> Result<Vec<KeyedStakeAccount>, Box<dyn std::error::Error>> {
+    let program_accounts_config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(solana_account_decoder::UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let mut stake_accounts: Vec<_> = rpc_client
+        .get_program_accounts_with_config(&stake::program::id(), program_accounts_config)?
+        .into_iter()
+        .filter_map(|(pubkey, account)| {
+            let state = account.state().ok()?;
+            Some(KeyedStakeAccount {
+                pubkey,
+                lamports: account.lamports,
+                state,
+            })
+        })
+        .collect();
+    stake_accounts.sort_by_key(|account| account.pubkey);
+    Ok(stake_accounts)
+}
+
+fn get_batch_stake_accounts(
+    rpc_client: &RpcClient,
+    batch: &StakeBatchConfig,
+    stake_authority: &Pubkey,
+) -> Result<Vec<KeyedStakeAccount>, Box<dyn std::error::Error>> {
+    if batch.stake_account_pubkeys.is_empty() {
+        return get_stake_accounts_with_filters(
+            rpc_client,
+            vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                STAKER_OFFSET,
+                stake_authority.as_ref(),
+            ))],
+        );
+    }
+
+    let mut stake_accounts = vec![];
+    for pubkeys in batch.stake_account_pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc_client.get_multiple_accounts(pubkeys)?;
+        for (pubkey, account) in pubkeys.iter().zip(accounts) {
+            let account = account.ok_or_else(|| {
+                CliError::RpcRequestError(format!("{pubkey} account does not exist"))
+            })?;
+            stake_accounts.push(keyed_stake_account(*pubkey, account)?);
+        }
+    }
+    Ok(stake_accounts)
+}
+
+fn keyed_stake_account(pubkey: Pubkey, account: Account) -> Result<KeyedStakeAccount, CliError> {
+    if account.owner != stake::program::id() {
+        return Err(CliError::BadParameter(format!(
+            "{pubkey} is not a stake account"
+        )));
+    }
+    let state = account.state().map_err(|err| {
+        CliError::RpcRequestError(format!(
+            "{pubkey} could not be deserialized to stake state: {err}"
+        ))
+    })?;
+    Ok(KeyedStakeAccount {
+        pubkey,
+        lamports: account.lamports,
+        state,
+    })
+}
+
+/// A planned change to one stake account. Operations without instructions
+/// are reported but not sent.
+struct StakeOperation {
+    stake_account: Pubkey,
+    action: &'static str,
+    details: Option<String>,
+    instructions: Vec<Instruction>,
+}
+
+impl StakeOperation {
+    fn skip(stake_account: Pubkey, details: String) -> Self {
+        Self {
+            stake_account,
+            action: "skip",
+            details: Some(details),
+            instructions: vec![],
+        }
+    }
+}
+
+/// Returns a skip operation if `stake_authority` cannot act on the account
+fn check_staker(
+    stake_account: &KeyedStakeAccount,
+    stake_authority: &Pubkey,
+) -> Option<StakeOperation> {
+    match stake_account.staker() {
+        Some(staker) if staker == *stake_authority => None,
+        Some(staker) => Some(StakeOperation::skip(
+            stake_account.pubkey,
+            format!("stake authority is {staker}"),
+        )),
+        None => Some(StakeOperation::skip(
+            stake_account.pubkey,
+            "not initialized".to_string(),
+        )),
+    }
+}
+
+fn plan_deactivate(
+    stake_accounts: &[KeyedStakeAccount],
+    stake_authority: &Pubkey,
+    vote_account: Option<&Pubkey>,
+) -> Vec<StakeOperation> {
+    stake_accounts
+        .iter()
+        .map(|stake_account| {
+            if let Some(skip) = check_staker(stake_account, stake_authority) {
+                return skip;
+            }
+            let Some(delegation) = stake_account.state.delegation() else {
+                return StakeOperation::skip(stake_account.pubkey, "not delegated".to_string());
+            };
+            if vote_account.is_some_and(|vote_account| delegation.voter_pubkey != *vote_account) {
+                StakeOperation::skip(
+                    stake_account.pubkey,
+                    format!("delegated to {}", delegation.voter_pubkey),
+                )
+            } else if delegation.deactivation_epoch != Epoch::MAX {
+                StakeOperation::skip(
+                    stake_account.pubkey,
+                    format!("deactivated in epoch {}", delegation.deactivation_epoch),
+                )
+            } else {
+                StakeOperation {
+                    stake_account: stake_account.pubkey,
+                    action: "deactivate",
+                    details: Some(format!("from {}", delegation.voter_pubkey)),
+                    instructions: vec![stake_instruction::deactivate_stake(
+                        &stake_account.pubkey,
+                        stake_authority,
+                    )],
+                }
+            }
+        })
+        .collect()
+}
+
+fn plan_delegate(
+    stake_accounts: &[KeyedStakeAccount],
+    stake_authority: &Pubkey,
+    vote_account: &Pubkey,
+    clock: &Clock,
+    stake_history: &StakeHistory,
+    new_rate_activation_epoch: Option<Epoch>,
+) -> Vec<StakeOperation> {
+    let delegate = |stake_account: &KeyedStakeAccount, details| StakeOperation {
+        stake_account: stake_account.pubkey,
+        action: "delegate",
+        details,
+        instructions: vec![stake_instruction::delegate_stake(
+            &stake_account.pubkey,
+            stake_authority,
+            vote_account,
+        )],
+    };
+
+    stake_accounts
+        .iter()
+        .map(|stake_account| {
+            if let Some(skip) = check_staker(stake_account, stake_authority) {
+                return skip;
+            }
+            let Some(delegation) = stake_account.state.delegation() else {
+                return delegate(stake_account, None);
+            };
+            let status = delegation.stake_activating_and_deactivating(
+                clock.epoch,
+                stake_history,
+                new_rate_activation_epoch,
+            );
+            if delegation.voter_pubkey == *vote_account {
+                if delegation.deactivation_epoch == Epoch::MAX {
+                    StakeOperation::skip(stake_account.pubkey, "already delegated".to_string())
+                } else if status.effective == 0 || delegation.deactivation_epoch == clock.epoch {
+                    delegate(stake_account, Some("reactivate".to_string()))
+                } else {
+                    StakeOperation::skip(
+                        stake_account.pubkey,
+                        "deactivating, delegate again once inactive".to_string(),
+                    )
+                }
+            } else if status.effective == 0 {
+                delegate(
+                    stake_account,
+                    Some(format!("from {}", delegation.voter_pubkey)),
+                )
+            } else if delegation.deactivation_epoch == Epoch::MAX {
+                // Active stake can't move directly; it has to cool down first
+                StakeOperation {
+                    stake_account: stake_account.pubkey,
+                    action: "deactivate",
+                    details: Some(format!(
+                        "from {}, delegate again once inactive",
+                        delegation.voter_pubkey
+                    )),
+                    instructions: vec![stake_instruction::deactivate_stake(
+                        &stake_account.pubkey,
+                        stake_authority,
+                    )],
+                }
+            } else {
+                StakeOperation::skip(
+                    stake_account.pubkey,
+                    "deactivating, delegate again once inactive".to_string(),
+                )
+            }
+        })
+        .collect()
+}
+
+/// Greedily groups the stake accounts that the stake program would allow to
+/// be merged, merging every group into its largest account
+fn plan_merge(
+    stake_accounts: &[KeyedStakeAccount],
+    stake_authority: &Pubkey,
+    clock: &Clock,
+    stake_history: &StakeHistory,
+) -> Vec<StakeOperation> {
+    let mut operations = vec![];
+    let mut candidates = vec![];
+    for stake_account in stake_accounts {
+        if let Some(skip) = check_staker(stake_account, stake_authority) {
+            operations.push(skip);
+        } else if let Err(reason) = check_merge(
+            &stake_account.state,
+            stake_account.lamports,
+            &stake_account.state,
+            stake_account.lamports,
+            clock,
+            stake_history,
+        ) {
+            operations.push(StakeOperation::skip(
+                stake_account.pubkey,
+                reason.to_string(),
+            ));
+        } else {
+            candidates.push(stake_account);
+        }
+    }
+    candidates.sort_by(|a, b| b.lamports.cmp(&a.lamports));
+
+    let mut groups: Vec<(&KeyedStakeAccount, Vec<&KeyedStakeAccount>)> = vec![];
+    for stake_account in candidates {
+        let destination = groups.iter_mut().find(|(destination, _)| {
+            check_merge(
+                &destination.state,
+                destination.lamports,
+                &stake_account.state,
+                stake_account.lamports,
+                clock,
+                stake_history,
+            )
+            .is_ok()
+        });
+        match destination {
+            Some((_, sources)) => sources.push(stake_account),
+            None => groups.push((stake_account, vec![])),
+        }
+    }
+
+    for (destination, sources) in groups {
+        if sources.is_empty() {
+            operations.push(StakeOperation::skip(
+                destination.pubkey,
+                "no compatible stake account to merge with".to_string(),
+            ));
+            continue;
+        }
+        operations.push(StakeOperation {
+            stake_account: destination.pubkey,
+            action: "keep",
+            details: Some(format!("absorbs {} stake accounts", sources.len())),
+            instructions: vec![],
+        });
+        for source in sources {
+            operations.push(StakeOperation {
+                stake_account: source.pubkey,
+                action: "merge",
+                details: Some(format!("into {}", destination.pubkey)),
+                instructions: stake_instruction::merge(
+                    &destination.pubkey,
+                    &source.pubkey,
+                    stake_authority,
+                ),
+            });
+        }
+    }
+    operations
+}
+
+fn process_split_stake_portfolio(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    batch: &StakeBatchConfig,
+    stake_account_pubkey: &Pubkey,
+    lamports: u64,
+    count: usize,
+    seed_prefix: &str,
+) -> ProcessResult {
+    let stake_authority = config.signers[batch.stake_authority].pubkey();
+    let fee_payer = config.signers[batch.fee_payer].pubkey();
+    let stake_account = keyed_stake_account(
+        *stake_account_pubkey,
+        rpc_client.get_account(stake_account_pubkey)?,
+    )?;
+    if let Some(skip) = check_staker(&stake_account, &stake_authority) {
+        return Err(CliError::BadParameter(format!(
+            "Unable to split {stake_account_pubkey}: {}",
+            skip.details.unwrap_or_default()
+        ))
+        .into());
+    }
+
+    let stake_minimum_delegation = rpc_client.get_stake_minimum_delegation()?;
+    if lamports < stake_minimum_delegation {
+        return Err(CliError::BadParameter(format!(
+            "need at least {} for minimum stake delegation, provided: {}",
+            Sol(stake_minimum_delegation),
+            Sol(lamports)
+        ))
+        .into());
+    }
+    let rent_exempt_reserve =
+        rpc_client.get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())?;
+    let split_lamports = lamports.saturating_mul(count as u64);
+    let available_lamports = stake_account.lamports.saturating_sub(rent_exempt_reserve);
+    if split_lamports > available_lamports {
+        return Err(CliError::BadParameter(format!(
+            "{stake_account_pubkey} holds {} above its rent-exempt reserve, cannot split off {}",
+            Sol(available_lamports),
+            Sol(split_lamports)
+        ))
+        .into());
+    }
+
+    let split_addresses =
+        find_unused_split_addresses(rpc_client, &stake_authority, seed_prefix, count)?;
+    let operations = split_addresses
+        .into_iter()
+        .map(|(seed, split_stake_address)| {
+            let mut instructions = vec![system_instruction::transfer(
+                &fee_payer,
+                &split_stake_address,
+                rent_exempt_reserve,
+            )];
+            instructions.extend(stake_instruction::split_with_seed(
+                stake_account_pubkey,
+                &stake_authority,
+                lamports,
+                &split_stake_address,
+                &stake_authority,
+                &seed,
+            ));
+            StakeOperation {
+                stake_account: split_stake_address,
+                action: "split",
+                details: Some(format!(
+                    "{} from {stake_account_pubkey}, seed \"{seed}\"",
+                    Sol(lamports)
+                )),
+                instructions,
+            }
+        })
+        .collect();
+    process_stake_batch(rpc_client, config, batch, operations)
+}
+
+/// Derives `count` stake account addresses from `base` with seeds
+/// `<seed_prefix><n>`, skipping addresses that are already in use
+fn find_unused_split_addresses(
+    rpc_client: &RpcClient,
+    base: &Pubkey,
+    seed_prefix: &str,
+    count: usize,
+) -> Result<Vec<(String, Pubkey)>, Box<dyn std::error::Error>> {
+    let mut unused = vec![];
+    let mut next_index = 0usize;
+    while unused.len() < count {
+        let candidates = (next_index..next_index.saturating_add(MAX_MULTIPLE_ACCOUNTS))
+            .map(|index| {
+                let seed = format!("{seed_prefix}{index}");
+                let address = Pubkey::create_with_seed(base, &seed, &stake::program::id())?;
+                Ok((seed, address))
+            })
+            .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+        next_index = next_index.saturating_add(MAX_MULTIPLE_ACCOUNTS);
+        let addresses: Vec<_> = candidates.iter().map(|(_, address)| *address).collect();
+        let accounts = rpc_client.get_multiple_accounts(&addresses)?;
+        unused.extend(
+            candidates
+                .into_iter()
+                .zip(accounts)
+                .filter_map(|(candidate, account)| account.is_none().then_some(candidate))
+                .take(count - unused.len()),
+        );
+    }
+    Ok(unused)
+}
+
+fn process_stake_batch(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    batch: &StakeBatchConfig,
+    operations: Vec<StakeOperation>,
+) -> ProcessResult {
+    let fee_payer = config.signers[batch.fee_payer];
+    let compute_unit_config = ComputeUnitConfig {
+        compute_unit_price: batch.compute_unit_price,
+        compute_unit_limit: ComputeUnitLimit::Simulated,
+    };
+    let groups: Vec<&[Instruction]> = operations
+        .iter()
+        .map(|operation| operation.instructions.as_slice())
+        .collect();
+    let (transactions, assignments) =
+        pack_instruction_groups(&groups, &fee_payer.pubkey(), &compute_unit_config)?;
+
+    let mut signatures = vec![];
+    if !batch.dry_run {
+        let transaction_count = transactions.len();
+        for (index, instructions) in transactions.into_iter().enumerate() {
+            let mut message = Message::new(
+                &instructions.with_compute_unit_config(&compute_unit_config),
+                Some(&fee_payer.pubkey()),
+            );
+            simulate_and_update_compute_unit_limit(
+                &compute_unit_config.compute_unit_limit,
+                rpc_client,
+                &mut message,
+            )?;
+            let recent_blockhash = rpc_client.get_latest_blockhash()?;
+            let mut tx = Transaction::new_unsigned(message);
+            tx.try_sign(&config.signers, recent_blockhash)?;
+            check_account_for_fee_with_commitment(
+                rpc_client,
+                &fee_payer.pubkey(),
+                &tx.message,
+                config.commitment,
+            )?;
+            let signature = rpc_client
+                .send_and_confirm_transaction_with_spinner_and_config(
+                    &tx,
+                    config.commitment,
+                    config.send_transaction_config,
+                )
+                .map_err(|err| {
+                    CliError::RpcRequestError(format!(
+                        "Transaction {index} of {transaction_count} failed: {err}. Confirmed \
+                         transactions: {signatures:?}"
+                    ))
+                })?;
+            signatures.push(signature.to_string());
+        }
+    }
+
+    let batch_output = CliStakeBatch {
+        transaction_count: assignments
+            .iter()
+            .flatten()
+            .max()
+            .map_or(0, |index| index + 1),
+        operations: operations
+            .into_iter()
+            .zip(assignments)
+            .map(|(operation, transaction)| CliStakeBatchOperation {
+                stake_account: operation.stake_account.to_string(),
+                action: operation.action.to_string(),
+                details: operation.details,
+                transaction,
+            })
+            .collect(),
+        signatures,
+        dry_run: batch.dry_run,
+    };
+    Ok(config.output_format.formatted_string(&batch_output))
+}
+
+/// Packs groups of instructions, each of which must land in one transaction,
+/// into as few transactions as fit in a packet while keeping their order.
+/// Returns the transactions and, for every group, the index of the transaction
+/// carrying it.
+fn pack_instruction_groups(
+    groups: &[&[Instruction]],
+    fee_payer: &Pubkey,
+    compute_unit_config: &ComputeUnitConfig,
+) -> Result<(Vec<Vec<Instruction>>, Vec<Option<usize>>), CliError> {
+    let fits = |instructions: &[Instruction]| {
+        let message = Message::new(
+            &instructions
+                .to_vec()
+                .with_compute_unit_config(compute_unit_config),
+            Some(fee_payer),
+        );
+        let tx_size = bincode::serialized_size(&Transaction {
+            signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+            message,
+        })
+        .unwrap() as usize;
+        tx_size <= PACKET_DATA_SIZE
+    };
+
+    let mut transactions: Vec<Vec<Instruction>> = vec![];
+    let mut assignments = Vec::with_capacity(groups.len());
+    for group in groups {
+        if group.is_empty() {
+            assignments.push(None);
+            continue;
+        }
+        let packed = transactions.last_mut().is_some_and(|transaction| {
+            let len = transaction.len();
+            transaction.extend_from_slice(group);
+            let packed = fits(transaction);
+            if !packed {
+                transaction.truncate(len);
+            }
+            packed
+        });
+        if !packed {
+            if !fits(group) {
+                return Err(CliError::BadParameter(
+                    "Stake operation does not fit in a single transaction".to_string(),
+                ));
+            }
+            transactions.push(group.to_vec());
+        }
+        assignments.push(Some(transactions.len() - 1));
+    }
+    Ok((transactions, assignments))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{clap_app::get_clap_app, cli::parse_command},
+        solana_keypair::{read_keypair_file, write_keypair, Keypair},
+        solana_signer::Signer,
+        solana_stake_interface::state::{Authorized, Delegation, Lockup, Meta, Stake, StakeFlags},
+        tempfile::NamedTempFile,
+    };
+
+    fn make_tmp_file() -> (String, NamedTempFile) {
+        let tmp_file = NamedTempFile::new().unwrap();
+        (String::from(tmp_file.path().to_str().unwrap()), tmp_file)
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let test_commands = get_clap_app("test", "desc", "version");
+        let default_keypair = Keypair::new();
+        let (default_keypair_file, mut tmp_file) = make_tmp_file();
+        write_keypair(&default_keypair, tmp_file.as_file_mut()).unwrap();
+        let default_signer = DefaultSigner::new("", &default_keypair_file);
+
+        let staker = solana_pubkey::new_rand();
+        let test_show = test_commands.clone().get_matches_from(vec![
+            "test",
+            "stake-portfolio",
+            "show",
+            "--staker",
+            &staker.to_string(),
+            "--with-rewards",
+        ]);
+        assert_eq!(
+            parse_command(&test_show, &default_signer, &mut None).unwrap(),
+            CliCommandInfo::without_signers(CliCommand::StakePortfolio(
+                StakePortfolioCliCommand::Show {
+                    staker: Some(staker),
+                    withdrawer: None,
+                    vote_account: None,
+                    num_rewards_epochs: Some(1),
+                    use_lamports_unit: false,
+                }
+            ))
+        );
+
+        // Either a staker or a withdrawer is required
+        assert!(test_commands
+            .clone()
+            .get_matches_from_safe(vec!["test", "stake-portfolio", "show"])
+            .is_err());
+
+        let vote_account = solana_pubkey::new_rand();
+        let stake_accounts = [solana_pubkey::new_rand(), solana_pubkey::new_rand()];
+        let test_delegate = test_commands.clone().get_matches_from(vec![
+            "test",
+            "stake-portfolio",
+            "delegate",
+            &vote_account.to_string(),
+            &stake_accounts[0].to_string(),
+            &stake_accounts[1].to_string(),
+            "--dry-run",
+        ]);
+        assert_eq!(
+            parse_command(&test_delegate, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::StakePortfolio(StakePortfolioCliCommand::Delegate {
+                    batch: StakeBatchConfig {
+                        stake_account_pubkeys: stake_accounts.to_vec(),
+                        stake_authority: 0,
+                        fee_payer: 0,
+                        compute_unit_price: None,
+                        dry_run: true,
+                    },
+                    vote_account,
+                }),
+                signers: vec![Box::new(read_keypair_file(&default_keypair_file).unwrap())],
+            }
+        );
+
+        let stake_authority_keypair = Keypair::new();
+        let (stake_authority_keypair_file, mut tmp_file) = make_tmp_file();
+        write_keypair(&stake_authority_keypair, tmp_file.as_file_mut()).unwrap();
+        let test_merge = test_commands.clone().get_matches_from(vec![
+            "test",
+            "stake-portfolio",
+            "merge",
+            "--stake-authority",
+            &stake_authority_keypair_file,
+            "--with-compute-unit-price",
+            "99",
+        ]);
+        assert_eq!(
+            parse_command(&test_merge, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::StakePortfolio(StakePortfolioCliCommand::Merge {
+                    batch: StakeBatchConfig {
+                        stake_account_pubkeys: vec![],
+                        stake_authority: 1,
+                        fee_payer: 0,
+                        compute_unit_price: Some(99),
+                        dry_run: false,
+                    },
+                }),
+                signers: vec![
+                    Box::new(read_keypair_file(&default_keypair_file).unwrap()),
+                    Box::new(read_keypair_file(&stake_authority_keypair_file).unwrap()),
+                ],
+            }
+        );
+
+        let test_split = test_commands.clone().get_matches_from(vec![
+            "test",
+            "stake-portfolio",
+            "split",
+            &stake_accounts[0].to_string(),
+            "2.5",
+            "--count",
+            "4",
+        ]);
+        assert_eq!(
+            parse_command(&test_split, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::StakePortfolio(StakePortfolioCliCommand::Split {
+                    batch: StakeBatchConfig {
+                        stake_account_pubkeys: vec![],
+                        stake_authority: 0,
+                        fee_payer: 0,
+                        compute_unit_price: None,
+                        dry_run: false,
+                    },
+                    stake_account_pubkey: stake_accounts[0],
+                    lamports: 2_500_000_000,
+                    count: 4,
+                    seed_prefix: "split-".to_string(),
+                }),
+                signers: vec![Box::new(read_keypair_file(&default_keypair_file).unwrap())],
+            }
+        );
+    }
+
+    #[test]
+    fn test_pack_instruction_groups() {
+        let fee_payer = Pubkey::new_unique();
+        let stake_authority = Pubkey::new_unique();
+        let groups: Vec<Vec<Instruction>> = (0..64)
+            .map(|_| {
+                vec![stake_instruction::deactivate_stake(
+                    &Pubkey::new_unique(),
+                    &stake_authority,
+                )]
+            })
+            .chain(std::iter::once(vec![]))
+            .collect();
+        let groups: Vec<&[Instruction]> = groups.iter().map(Vec::as_slice).collect();
+        let compute_unit_config = ComputeUnitConfig {
+            compute_unit_price: Some(1),
+            compute_unit_limit: ComputeUnitLimit::Simulated,
+        };
+
+        let (transactions, assignments) =
+            pack_instruction_groups(&groups, &fee_payer, &compute_unit_config).unwrap();
+        assert!(transactions.len() > 1);
+        assert!(transactions.len() < 64);
+        assert_eq!(
+            transactions.iter().map(Vec::len).sum::<usize>(),
+            groups.len() - 1
+        );
+        assert_eq!(assignments.last(), Some(&None));
+        assert!(assignments
+            .windows(2)
+            .all(|pair| pair[1].is_none() || pair[0] <= pair[1]));
+
+        // A single group that does not fit is rejected
+        let oversized: Vec<Instruction> = (0..64)
+            .map(|_| stake_instruction::deactivate_stake(&Pubkey::new_unique(), &stake_authority))
+            .collect();
+        assert!(pack_instruction_groups(&[&oversized], &fee_payer, &compute_unit_config).is_err());
+    }
+
+    #[test]
+    fn test_plan_merge() {
+        let stake_authority = Pubkey::new_unique();
+        let meta = Meta {
+            authorized: Authorized::auto(&stake_authority),
+            lockup: Lockup::default(),
+            rent_exempt_reserve: 1,
+        };
+        let stake = |voter_pubkey| {
+            StakeStateV2::Stake(
+                meta,
+                Stake {
+                    delegation: Delegation {
+                        voter_pubkey,
+                        stake: 100,
+                        activation_epoch: 0,
+                        ..Delegation::default()
+                    },
+                    credits_observed: 0,
+                },
+                StakeFlags::empty(),
+            )
+        };
+        let keyed = |lamports, state| KeyedStakeAccount {
+            pubkey: Pubkey::new_unique(),
+            lamports,
+            state,
+        };
+        let vote_a = Pubkey::new_unique();
+        let vote_b = Pubkey::new_unique();
+        let stake_accounts = vec![
+            keyed(101, stake(vote_a)),
+            keyed(201, stake(vote_a)),
+            keyed(101, stake(vote_b)),
+            keyed(10, StakeStateV2::Initialized(meta)),
+            keyed(20, StakeStateV2::Initialized(meta)),
+            keyed(
+                10,
+                StakeStateV2::Initialized(Meta::auto(&Pubkey::new_unique())),
+            ),
+        ];
+        let clock = Clock {
+            epoch: 10,
+            ..Clock::default()
+        };
+
+        let operations = plan_merge(
+            &stake_accounts,
+            &stake_authority,
+            &clock,
+            &StakeHistory::default(),
+        );
+        let action_of = |pubkey: &Pubkey| {
+            operations
+                .iter()
+                .find(|operation| operation.stake_account == *pubkey)
+                .map(|operation| operation.action)
+                .unwrap()
+        };
+        assert_eq!(action_of(&stake_accounts[0].pubkey), "merge");
+        assert_eq!(action_of(&stake_accounts[1].pubkey), "keep");
+        assert_eq!(action_of(&stake_accounts[2].pubkey), "skip");
+        assert_eq!(action_of(&stake_accounts[3].pubkey), "merge");
+        assert_eq!(action_of(&stake_accounts[4].pubkey), "keep");
+        assert_eq!(action_of(&stake_accounts[5].pubkey), "skip");
+        assert_eq!(operations.len(), stake_accounts.len());
+    }
+}
@@ -27,7 +27,7 @@ use {
     },
     solana_cli_output::{
         return_signers_with_config, CliEpochVotingHistory, CliLandedVote, CliVoteAccount,
-        ReturnSignersConfig,
+        CliVoteAnalytics, ReturnSignersConfig,
     },
     solana_commitment_config::CommitmentConfig,
     solana_message::Message,
@@ -35,7 +35,7 @@ use {
     solana_pubkey::Pubkey,
     solana_remote_wallet::remote_wallet::RemoteWalletManager,
     solana_rpc_client::rpc_client::RpcClient,
-    solana_rpc_client_api::config::RpcGetVoteAccountsConfig,
+    solana_rpc_client_api::config::{RpcGetVoteAccountsConfig, RpcVoteAccountAnalyticsConfig},
     solana_rpc_client_nonce_utils::blockhash_query::BlockhashQuery,
     solana_system_interface::error::SystemError,
     solana_transaction::Transaction,
@@ -376,6 +376,41 @@ impl VoteSubCommands for App<'_, '_> {
                         ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("vote-analytics")
+                .about("Show vote credit efficiency and vote latency of a vote account")
+                .arg(pubkey!(
+                    Arg::with_name("vote_account_pubkey")
+                        .index(1)
+                        .value_name("VOTE_ACCOUNT_ADDRESS")
+                        .required(true),
+                    "Vote account."
+                ))
+                .arg(
+                    Arg::with_name("num_epochs")
+                        .long("num-epochs")
+                        .takes_value(true)
+                        .value_name("NUM")
+                        .validator(|s| is_within_range(s, 1..=64))
+                        .help("Analyze NUM recent epochs [default: 10]"),
+                )
+                .arg(
+                    Arg::with_name("with_commission_history")
+                        .long("with-commission-history")
+                        .takes_value(false)
+                        .help(
+                            "Look up the commission charged in each of the last 5 completed \
+                             epochs from the epoch rewards. Requires an RPC node with \
+                             transaction history and the relevant blocks",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("lamports")
+                        .long("lamports")
+                        .takes_value(false)
+                        .help("Display balance in lamports instead of SOL"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("withdraw-from-vote-account")
                 .about("Withdraw lamports from a vote account into a specified account")
@@ -699,6 +734,22 @@ pub fn parse_vote_get_account_command(
     ))
 }
 
+pub fn parse_vote_analytics_command(
+    matches: &ArgMatches<'_>,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let vote_account_pubkey =
+        pubkey_of_signer(matches, "vote_account_pubkey", wallet_manager)?.unwrap();
+    Ok(CliCommandInfo::without_signers(
+        CliCommand::ShowVoteAnalytics {
+            pubkey: vote_account_pubkey,
+            num_epochs: value_of(matches, "num_epochs"),
+            with_commission_history: matches.is_present("with_commission_history"),
+            use_lamports_unit: matches.is_present("lamports"),
+        },
+    ))
+}
+
 pub fn parse_withdraw_from_vote_account(
     matches: &ArgMatches<'_>,
     default_signer: &DefaultSigner,
@@ -1362,6 +1413,29 @@ pub fn process_show_vote_account(
     Ok(config.output_format.formatted_string(&vote_account_data))
 }
 
+pub fn process_show_vote_analytics(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    vote_account_address: &Pubkey,
+    num_epochs: Option<usize>,
+    with_commission_history: bool,
+    use_lamports_unit: bool,
+) -> ProcessResult {
+    let analytics = rpc_client.get_vote_account_analytics(
+        vote_account_address,
+        RpcVoteAccountAnalyticsConfig {
+            commitment: Some(config.commitment),
+            num_epochs,
+            with_commission_history: Some(with_commission_history),
+            ..RpcVoteAccountAnalyticsConfig::default()
+        },
+    )?;
+    Ok(config.output_format.formatted_string(&CliVoteAnalytics {
+        analytics,
+        use_lamports_unit,
+    }))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn process_withdraw_from_vote_account(
     rpc_client: &RpcClient,
@@ -2393,5 +2467,24 @@ mod tests {
                 ],
             }
         );
+
+        // Test VoteAnalytics subcommand
+        let test_vote_analytics = test_commands.clone().get_matches_from(vec![
+            "test",
+            "vote-analytics",
+            &pubkey_string,
+            "--num-epochs",
+            "5",
+            "--with-commission-history",
+        ]);
+        assert_eq!(
+            parse_command(&test_vote_analytics, &default_signer, &mut None).unwrap(),
+            CliCommandInfo::without_signers(CliCommand::ShowVoteAnalytics {
+                pubkey,
+                num_epochs: Some(5),
+                with_commission_history: true,
+                use_lamports_unit: false,
+            })
+        );
     }
 }
@@ -1,17 +1,21 @@
 use {
     crate::{
         cluster_slots_service::cluster_slots::ClusterSlots,
+        consensus::vote_decision_history::VoteDecisionHistory,
         repair::{outstanding_requests::OutstandingRequests, serve_repair::ShredRepairType},
     },
     solana_gossip::cluster_info::ClusterInfo,
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
     solana_pubkey::Pubkey,
     solana_quic_definitions::NotifyKeyUpdate,
-    solana_runtime::bank_forks::BankForks,
+    solana_rpc::rpc_health::RpcHealth,
+    solana_runtime::{bank_forks::BankForks, snapshot_controller::SnapshotController},
     solana_streamer::atomic_udp_socket::AtomicUdpSocket,
+    solana_turbine::shred_arrivals::ShredArrivals,
     std::{
         collections::{HashMap, HashSet},
         net::UdpSocket,
-        sync::{Arc, RwLock},
+        sync::{atomic::AtomicU64, Arc, RwLock},
     },
 };
 
@@ -80,4 +84,11 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub outstanding_repair_requests: Arc<RwLock<OutstandingRequests<ShredRepairType>>>,
     pub cluster_slots: Arc<ClusterSlots>,
     pub gossip_socket: Option<AtomicUdpSocket>,
+    pub blockstore: Arc<Blockstore>,
+    pub leader_schedule_cache: Arc<LeaderScheduleCache>,
+    pub shred_arrivals: Option<Arc<ShredArrivals>>,
+    pub rpc_request_count: Option<Arc<AtomicU64>>,
+    pub rpc_health: Option<Arc<RpcHealth>>,
+    pub snapshot_controller: Arc<SnapshotController>,
+    pub vote_decision_history: Arc<VoteDecisionHistory>,
 }
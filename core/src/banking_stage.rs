@@ -81,6 +81,8 @@ conditional_vis_mod!(
 );
 conditional_vis_mod!(unified_scheduler, feature = "dev-context-only-utils", pub, pub(crate));
 
+pub use transaction_scheduler::scheduler_metrics::{SchedulerCountTotals, SCHEDULER_COUNT_TOTALS};
+
 // Fixed thread size seems to be fastest on GCP setup
 pub const NUM_THREADS: u32 = 6;
 
@@ -6,10 +6,65 @@ use {
     solana_time_utils::AtomicInterval,
     std::{
         num::Saturating,
+        sync::atomic::{AtomicU64, Ordering},
         time::{Duration, Instant},
     },
 };
 
+/// Counts of the scheduler since the process started, for
+/// `agave-validator monitor`. Updated once per metrics interval.
+pub static SCHEDULER_COUNT_TOTALS: SchedulerCountTotals = SchedulerCountTotals::new();
+
+#[derive(Debug)]
+pub struct SchedulerCountTotals {
+    /// Number of packets received.
+    pub num_received: AtomicU64,
+    /// Number of packets buffered.
+    pub num_buffered: AtomicU64,
+    /// Number of transactions scheduled.
+    pub num_scheduled: AtomicU64,
+    /// Number of completed transactions received from workers.
+    pub num_finished: AtomicU64,
+    /// Number of transactions that were retryable.
+    pub num_retryable: AtomicU64,
+    /// Number of transactions dropped for any reason.
+    pub num_dropped: AtomicU64,
+}
+
+impl SchedulerCountTotals {
+    const fn new() -> Self {
+        Self {
+            num_received: AtomicU64::new(0),
+            num_buffered: AtomicU64::new(0),
+            num_scheduled: AtomicU64::new(0),
+            num_finished: AtomicU64::new(0),
+            num_retryable: AtomicU64::new(0),
+            num_dropped: AtomicU64::new(0),
+        }
+    }
+
+    fn add(&self, metrics: &SchedulerCountMetricsInner) {
+        let add = |total: &AtomicU64, Saturating(count): Saturating<usize>| {
+            total.fetch_add(count as u64, Ordering::Relaxed);
+        };
+        add(&self.num_received, metrics.num_received);
+        add(&self.num_buffered, metrics.num_buffered);
+        add(&self.num_scheduled, metrics.num_scheduled);
+        add(&self.num_finished, metrics.num_finished);
+        add(&self.num_retryable, metrics.num_retryable);
+        add(
+            &self.num_dropped,
+            metrics.num_dropped_on_receive
+                + metrics.num_dropped_on_sanitization
+                + metrics.num_dropped_on_validate_locks
+                + metrics.num_dropped_on_receive_transaction_checks
+                + metrics.num_dropped_on_clear
+                + metrics.num_dropped_on_age_and_status
+                + metrics.num_dropped_on_capacity,
+        );
+    }
+}
+
 #[derive(Default)]
 pub struct SchedulerCountMetrics {
     interval: IntervalSchedulerCountMetrics,
@@ -94,6 +149,7 @@ impl IntervalSchedulerCountMetrics {
             if should_report {
                 self.metrics.report("banking_stage_scheduler_counts", None);
             }
+            SCHEDULER_COUNT_TOTALS.add(&self.metrics);
             self.metrics.reset();
         }
     }
@@ -4,9 +4,11 @@ pub(crate) mod latest_validator_votes_for_frozen_banks;
 pub mod progress_map;
 mod tower1_14_11;
 mod tower1_7_14;
+pub mod tower_lease;
 pub mod tower_storage;
 pub(crate) mod tower_vote_state;
 pub mod tree_diff;
+pub mod vote_decision_history;
 pub mod vote_stake_tracker;
 
 use {
@@ -68,7 +70,11 @@ impl ThresholdDecision {
 #[cfg_attr(feature = "frozen-abi", derive(AbiExample))]
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum SwitchForkDecision {
-    SwitchProof(Hash),
+    SwitchProof(
+        Hash,
+        /* Switch proof stake */ u64,
+        /* Total stake */ u64,
+    ),
     SameFork,
     FailedSwitchThreshold(
         /* Switch proof stake */ u64,
@@ -103,7 +109,7 @@ impl SwitchForkDecision {
             (SwitchForkDecision::SameFork, VoteTransaction::TowerSync(t)) => Some(
                 vote_instruction::tower_sync(vote_account_pubkey, authorized_voter_pubkey, t),
             ),
-            (SwitchForkDecision::SwitchProof(switch_proof_hash), VoteTransaction::Vote(v)) => {
+            (SwitchForkDecision::SwitchProof(switch_proof_hash, ..), VoteTransaction::Vote(v)) => {
                 Some(vote_instruction::vote_switch(
                     vote_account_pubkey,
                     authorized_voter_pubkey,
@@ -112,7 +118,7 @@ impl SwitchForkDecision {
                 ))
             }
             (
-                SwitchForkDecision::SwitchProof(switch_proof_hash),
+                SwitchForkDecision::SwitchProof(switch_proof_hash, ..),
                 VoteTransaction::VoteStateUpdate(v),
             ) => Some(vote_instruction::update_vote_state_switch(
                 vote_account_pubkey,
@@ -120,14 +126,15 @@ impl SwitchForkDecision {
                 v,
                 *switch_proof_hash,
             )),
-            (SwitchForkDecision::SwitchProof(switch_proof_hash), VoteTransaction::TowerSync(t)) => {
-                Some(vote_instruction::tower_sync_switch(
-                    vote_account_pubkey,
-                    authorized_voter_pubkey,
-                    t,
-                    *switch_proof_hash,
-                ))
-            }
+            (
+                SwitchForkDecision::SwitchProof(switch_proof_hash, ..),
+                VoteTransaction::TowerSync(t),
+            ) => Some(vote_instruction::tower_sync_switch(
+                vote_account_pubkey,
+                authorized_voter_pubkey,
+                t,
+                *switch_proof_hash,
+            )),
             (SwitchForkDecision::SameFork, VoteTransaction::CompactVoteStateUpdate(v)) => {
                 Some(vote_instruction::compact_update_vote_state(
                     vote_account_pubkey,
@@ -136,7 +143,7 @@ impl SwitchForkDecision {
                 ))
             }
             (
-                SwitchForkDecision::SwitchProof(switch_proof_hash),
+                SwitchForkDecision::SwitchProof(switch_proof_hash, ..),
                 VoteTransaction::CompactVoteStateUpdate(v),
             ) => Some(vote_instruction::compact_update_vote_state_switch(
                 vote_account_pubkey,
@@ -152,7 +159,7 @@ impl SwitchForkDecision {
             SwitchForkDecision::FailedSwitchThreshold(_, _) => false,
             SwitchForkDecision::FailedSwitchDuplicateRollback(_) => false,
             SwitchForkDecision::SameFork => true,
-            SwitchForkDecision::SwitchProof(_) => true,
+            SwitchForkDecision::SwitchProof(..) => true,
         }
     }
 }
@@ -553,6 +560,11 @@ impl Tower {
         self.vote_state.tower()
     }
 
+    /// Returns the lockouts of the vote stack, oldest vote first
+    pub fn tower_lockouts(&self) -> impl Iterator<Item = &Lockout> {
+        self.vote_state.votes.iter()
+    }
+
     pub(crate) fn last_vote_tx_blockhash(&self) -> BlockhashStatus {
         self.last_vote_tx_blockhash
     }
@@ -1032,7 +1044,8 @@ impl Tower {
                     (switch_slot, switch_hash),
                     (last_voted_slot, last_voted_hash)
                 );
-                return SwitchForkDecision::SwitchProof(Hash::default());
+                // No stake backs this switch
+                return SwitchForkDecision::SwitchProof(Hash::default(), 0, total_stake);
             }
         }
 
@@ -1166,7 +1179,11 @@ impl Tower {
                             .unwrap_or(0);
                         locked_out_stake += stake;
                         if (locked_out_stake as f64 / total_stake as f64) > SWITCH_FORK_THRESHOLD {
-                            return SwitchForkDecision::SwitchProof(switch_proof);
+                            return SwitchForkDecision::SwitchProof(
+                                switch_proof,
+                                locked_out_stake,
+                                total_stake,
+                            );
                         }
                         locked_out_vote_accounts.insert(vote_account_pubkey);
                     }
@@ -1220,7 +1237,11 @@ impl Tower {
                     .unwrap_or(0);
                 locked_out_stake += stake;
                 if (locked_out_stake as f64 / total_stake as f64) > SWITCH_FORK_THRESHOLD {
-                    return SwitchForkDecision::SwitchProof(switch_proof);
+                    return SwitchForkDecision::SwitchProof(
+                        switch_proof,
+                        locked_out_stake,
+                        total_stake,
+                    );
                 }
                 locked_out_vote_accounts.insert(vote_account_pubkey);
             }
@@ -1674,6 +1695,15 @@ pub enum TowerError {
 
     #[error("The tower is useless because of new hard fork: {0}")]
     HardFork(Slot),
+
+    #[error("The tower lease for {0} is held by another instance")]
+    LeaseHeld(Pubkey),
+
+    #[error("The tower lease for {0} is not held by this instance")]
+    LeaseNotHeld(Pubkey),
+
+    #[error("Unable to renew the tower lease for {0}: {1}")]
+    LeaseUnavailable(Pubkey, String),
 }
 
 impl TowerError {
@@ -1687,6 +1717,16 @@ impl TowerError {
     pub fn is_too_old(&self) -> bool {
         matches!(self, TowerError::TooOldTower(_, _))
     }
+    /// Whether the tower lease rather than the tower itself prevented
+    /// saving the tower
+    pub fn is_lease_error(&self) -> bool {
+        matches!(
+            self,
+            TowerError::LeaseHeld(_)
+                | TowerError::LeaseNotHeld(_)
+                | TowerError::LeaseUnavailable(_, _)
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -1863,7 +1903,7 @@ pub mod test {
             ))
         );
 
-        decision = SwitchForkDecision::SwitchProof(Hash::default());
+        decision = SwitchForkDecision::SwitchProof(Hash::default(), 10, 100);
         assert_eq!(
             decision.to_vote_instruction(
                 VoteTransaction::from(vote.clone()),
@@ -2180,7 +2220,7 @@ pub mod test {
                 &vote_simulator.latest_validator_votes_for_frozen_banks,
                 &vote_simulator.heaviest_subtree_fork_choice,
             ),
-            SwitchForkDecision::SwitchProof(Hash::default())
+            SwitchForkDecision::SwitchProof(Hash::default(), 10000, 20000)
         );
 
         // Adding another unfrozen descendant of the tip of 14 should not remove
@@ -2198,7 +2238,7 @@ pub mod test {
                 &vote_simulator.latest_validator_votes_for_frozen_banks,
                 &vote_simulator.heaviest_subtree_fork_choice,
             ),
-            SwitchForkDecision::SwitchProof(Hash::default())
+            SwitchForkDecision::SwitchProof(Hash::default(), 10000, 20000)
         );
 
         // If we set a root, then any lockout intervals below the root shouldn't
@@ -2297,7 +2337,7 @@ pub mod test {
                 &vote_simulator.latest_validator_votes_for_frozen_banks,
                 &vote_simulator.heaviest_subtree_fork_choice,
             ),
-            SwitchForkDecision::SwitchProof(Hash::default())
+            SwitchForkDecision::SwitchProof(Hash::default(), 10000, 20000)
         );
 
         // If we now set a root that causes slot 112 to be purged from BankForks, then
@@ -3068,7 +3108,7 @@ pub mod test {
                 &vote_simulator.latest_validator_votes_for_frozen_banks,
                 &vote_simulator.heaviest_subtree_fork_choice,
             ),
-            SwitchForkDecision::SwitchProof(Hash::default())
+            SwitchForkDecision::SwitchProof(Hash::default(), 10000, 20000)
         );
 
         assert_eq!(tower.voted_slots(), vec![43, 44, 45, 46, 47, 48, 49]);
@@ -3160,7 +3200,7 @@ pub mod test {
                 &vote_simulator.latest_validator_votes_for_frozen_banks,
                 &vote_simulator.heaviest_subtree_fork_choice,
             ),
-            SwitchForkDecision::SwitchProof(Hash::default())
+            SwitchForkDecision::SwitchProof(Hash::default(), 10000, 20000)
         );
 
         tower.record_vote(110, Hash::default());
@@ -3783,7 +3823,7 @@ pub mod test {
                     &vote_simulator.latest_validator_votes_for_frozen_banks,
                     &vote_simulator.heaviest_subtree_fork_choice,
                 ),
-                SwitchForkDecision::SwitchProof(Hash::default())
+                SwitchForkDecision::SwitchProof(Hash::default(), 10000, 20000)
             );
             vote_simulator.clear_lockout_intervals(candidate_slot);
         }
@@ -3841,7 +3881,7 @@ pub mod test {
                     &vote_simulator.latest_validator_votes_for_frozen_banks,
                     &vote_simulator.heaviest_subtree_fork_choice,
                 ),
-                SwitchForkDecision::SwitchProof(Hash::default())
+                SwitchForkDecision::SwitchProof(Hash::default(), 10000, 20000)
             );
             vote_simulator.latest_validator_votes_for_frozen_banks =
                 LatestValidatorVotesForFrozenBanks::default();
@@ -303,7 +303,7 @@ fn select_candidate_vote_and_reset_banks<'a>(
                 initial_switch_fork_decision,
             )
         }
-        SwitchForkDecision::SameFork | SwitchForkDecision::SwitchProof(_) => {
+        SwitchForkDecision::SameFork | SwitchForkDecision::SwitchProof(..) => {
             CandidateVoteAndResetBanks {
                 candidate_vote_bank: Some(heaviest_bank),
                 reset_bank: Some(heaviest_bank),
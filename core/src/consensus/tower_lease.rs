@@ -0,0 +1,819 @@
+//! Leases that fence saving towers, and therefore voting, to a single validator
+//! instance per identity.
+//!
+//! A primary and a hot spare validator that can take over its identity each
+//! wrap their tower storage in a [`LeasedTowerStorage`]. Loading a tower
+//! acquires the identity's lease, which is then renewed until it is released,
+//! and only the instance holding the lease can save a tower and send the vote
+//! that follows.
+//! Once the hot spare has taken over, an old primary that comes back can no
+//! longer vote with the identity.
+
+use {
+    crate::consensus::{
+        tower_storage::{SavedTowerVersions, TowerStorage},
+        Result, Tower, TowerError,
+    },
+    serde::{de::DeserializeOwned, Serialize},
+    solana_pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        fs::{self, File, OpenOptions},
+        io::{self, BufReader, Read, Write},
+        net::{IpAddr, SocketAddr, TcpListener, TcpStream},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, sleep, Builder, JoinHandle},
+        time::{Duration, Instant, SystemTime},
+    },
+};
+
+pub const DEFAULT_TOWER_LEASE_DURATION: Duration = Duration::from_secs(10);
+
+pub trait TowerLease: Sync + Send {
+    /// Acquires the lease on `node_pubkey` for this instance, failing with
+    /// `TowerError::LeaseHeld` if another instance holds an unexpired lease.
+    /// Returns how long the lease is held for.
+    fn acquire(&self, node_pubkey: &Pubkey) -> Result<Duration>;
+
+    /// Extends the lease this instance holds on `node_pubkey`, failing with
+    /// `TowerError::LeaseNotHeld` if another instance has taken it over.
+    /// Returns how long the lease is held for.
+    fn renew(&self, node_pubkey: &Pubkey) -> Result<Duration>;
+
+    /// Gives up the lease on `node_pubkey` if this instance holds it, so
+    /// another instance can acquire it without waiting for it to expire
+    fn release(&self, node_pubkey: &Pubkey) -> Result<()>;
+}
+
+/// Identifies one run of a validator to the lease holders
+fn new_instance_id() -> u64 {
+    rand::random()
+}
+
+/// How often the leases held by a [`LeasedTowerStorage`] are checked for
+/// renewal
+const LEASE_RENEWAL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Tower storage that only saves towers for identities whose lease this
+/// instance holds.
+///
+/// Held leases are renewed by a background thread, so saving a tower and
+/// voting never wait for the lease holder.
+pub struct LeasedTowerStorage {
+    storage: Arc<dyn TowerStorage>,
+    held_leases: Arc<HeldLeases>,
+}
+
+struct HeldLeases {
+    lease: Box<dyn TowerLease>,
+    // When each held lease was last granted and for how long
+    leases: Mutex<HashMap<Pubkey, (Instant, Duration)>>,
+}
+
+impl HeldLeases {
+    fn holds(&self, node_pubkey: &Pubkey) -> bool {
+        self.leases
+            .lock()
+            .unwrap()
+            .get(node_pubkey)
+            .is_some_and(|(granted_at, duration)| granted_at.elapsed() < *duration)
+    }
+
+    fn renew_expiring(&self) {
+        // Renewing once half of the lease has passed leaves time for a few
+        // more attempts if the lease holder can't be reached
+        let expiring: Vec<Pubkey> = self
+            .leases
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, (granted_at, duration))| granted_at.elapsed() >= *duration / 2)
+            .map(|(node_pubkey, _)| *node_pubkey)
+            .collect();
+        for node_pubkey in expiring {
+            let now = Instant::now();
+            let result = self.lease.renew(&node_pubkey);
+            let mut leases = self.leases.lock().unwrap();
+            // The lease may have been released while it was renewed
+            let Some(held_lease) = leases.get_mut(&node_pubkey) else {
+                continue;
+            };
+            match result {
+                Ok(duration) => *held_lease = (now, duration),
+                Err(err) if err.is_lease_error() => {
+                    warn!("Lost the tower lease for {node_pubkey}: {err}");
+                    leases.remove(&node_pubkey);
+                }
+                // The lease holder could not be reached, the lease is still
+                // held until it expires
+                Err(err) => warn!("Failed to renew the tower lease for {node_pubkey}: {err}"),
+            }
+        }
+    }
+}
+
+impl LeasedTowerStorage {
+    pub fn new(storage: Arc<dyn TowerStorage>, lease: Box<dyn TowerLease>) -> Self {
+        let held_leases = Arc::new(HeldLeases {
+            lease,
+            leases: Mutex::default(),
+        });
+        {
+            // The thread exits once the storage is dropped
+            let held_leases = Arc::downgrade(&held_leases);
+            Builder::new()
+                .name("solTowerLeaseRn".to_string())
+                .spawn(move || {
+                    while let Some(held_leases) = held_leases.upgrade() {
+                        held_leases.renew_expiring();
+                        drop(held_leases);
+                        sleep(LEASE_RENEWAL_INTERVAL);
+                    }
+                })
+                .unwrap();
+        }
+        Self {
+            storage,
+            held_leases,
+        }
+    }
+}
+
+impl TowerStorage for LeasedTowerStorage {
+    fn load(&self, node_pubkey: &Pubkey) -> Result<Tower> {
+        self.acquire_lease(node_pubkey)?;
+        self.storage.load(node_pubkey)
+    }
+
+    fn acquire_lease(&self, node_pubkey: &Pubkey) -> Result<()> {
+        let now = Instant::now();
+        let duration = self
+            .held_leases
+            .lease
+            .acquire(node_pubkey)
+            .map_err(|err| {
+                if err.is_lease_error() {
+                    err
+                } else {
+                    TowerError::LeaseUnavailable(*node_pubkey, err.to_string())
+                }
+            })
+            .inspect_err(|err| {
+                error!("Failed to acquire the tower lease for {node_pubkey}: {err}");
+            })?;
+        info!("Acquired the tower lease for {node_pubkey}");
+        self.held_leases
+            .leases
+            .lock()
+            .unwrap()
+            .insert(*node_pubkey, (now, duration));
+        Ok(())
+    }
+
+    fn store(&self, saved_tower: &SavedTowerVersions) -> Result<()> {
+        let node_pubkey = saved_tower.pubkey();
+        if !self.held_leases.holds(&node_pubkey) {
+            return Err(TowerError::LeaseNotHeld(node_pubkey));
+        }
+        self.storage.store(saved_tower)
+    }
+
+    fn holds_lease(&self, node_pubkey: &Pubkey) -> bool {
+        let holds_lease = self.held_leases.holds(node_pubkey);
+        if !holds_lease {
+            warn!("Not voting: the tower lease for {node_pubkey} is not held by this instance");
+        }
+        holds_lease
+    }
+
+    fn release(&self, node_pubkey: &Pubkey) -> Result<()> {
+        if self
+            .held_leases
+            .leases
+            .lock()
+            .unwrap()
+            .remove(node_pubkey)
+            .is_some()
+        {
+            info!("Releasing the tower lease for {node_pubkey}");
+        }
+        self.held_leases.lease.release(node_pubkey)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FileLeaseRecord {
+    instance_id: u64,
+    /// Wall clock time, see `FileTowerLease::now`, after which another instance
+    /// may take the lease. The instances sharing the file must have synced clocks
+    expires_at: u64,
+}
+
+/// How long to wait for another instance to finish updating a lease
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Lease kept in a file in a directory shared by all the instances, e.g. over
+/// NFS.
+///
+/// Updates of the lease are serialized with `flock()`, so the shared file
+/// system must support advisory locks across hosts, as Linux NFS clients do.
+/// Lease expiry is compared with the local clock of each instance, so their
+/// clocks must be synchronized to well within the lease duration.
+pub struct FileTowerLease {
+    lease_path: PathBuf,
+    duration: Duration,
+    instance_id: u64,
+}
+
+impl FileTowerLease {
+    pub fn new(lease_path: PathBuf, duration: Duration) -> Self {
+        Self {
+            lease_path,
+            duration,
+            instance_id: new_instance_id(),
+        }
+    }
+
+    pub fn filename(&self, node_pubkey: &Pubkey) -> PathBuf {
+        self.lease_path.join(format!("tower-lease-{node_pubkey}"))
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    /// Runs `f` on the lease file while holding an exclusive lock on its lock
+    /// file, so that only one instance reads and updates the lease at a time.
+    ///
+    /// The lease file itself can't be locked since every update replaces it.
+    /// The lock is released when its holder exits, however that happens.
+    fn with_lock<R>(&self, node_pubkey: &Pubkey, f: impl FnOnce(&Path) -> Result<R>) -> Result<R> {
+        let filename = self.filename(node_pubkey);
+        let lock_filename = filename.with_extension("lock");
+        let mut lock = fd_lock::RwLock::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&lock_filename)?,
+        );
+        let start = Instant::now();
+        let _lock_guard = loop {
+            match lock.try_write() {
+                Ok(lock_guard) => break lock_guard,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    if start.elapsed() > LOCK_TIMEOUT {
+                        return Err(TowerError::IoError(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!("Timed out waiting for {}", lock_filename.display()),
+                        )));
+                    }
+                    sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
+        f(&filename)
+    }
+
+    fn read_record(filename: &Path) -> Result<Option<FileLeaseRecord>> {
+        match File::open(filename) {
+            Ok(file) => Ok(Some(bincode::deserialize_from(BufReader::new(file))?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write_record(&self, filename: &Path) -> Result<Duration> {
+        let record = FileLeaseRecord {
+            instance_id: self.instance_id,
+            expires_at: Self::now().saturating_add(self.duration.as_millis() as u64),
+        };
+        let new_filename = filename.with_extension("new");
+        {
+            let mut file = File::create(&new_filename)?;
+            bincode::serialize_into(&mut file, &record)?;
+            file.sync_all()?;
+        }
+        fs::rename(&new_filename, filename)?;
+        Ok(self.duration)
+    }
+}
+
+impl TowerLease for FileTowerLease {
+    fn acquire(&self, node_pubkey: &Pubkey) -> Result<Duration> {
+        fs::create_dir_all(&self.lease_path)?;
+        self.with_lock(node_pubkey, |filename| match Self::read_record(filename)? {
+            Some(record)
+                if record.instance_id != self.instance_id && record.expires_at > Self::now() =>
+            {
+                Err(TowerError::LeaseHeld(*node_pubkey))
+            }
+            _ => self.write_record(filename),
+        })
+    }
+
+    fn renew(&self, node_pubkey: &Pubkey) -> Result<Duration> {
+        self.with_lock(node_pubkey, |filename| match Self::read_record(filename)? {
+            Some(record) if record.instance_id == self.instance_id => self.write_record(filename),
+            _ => Err(TowerError::LeaseNotHeld(*node_pubkey)),
+        })
+    }
+
+    fn release(&self, node_pubkey: &Pubkey) -> Result<()> {
+        self.with_lock(node_pubkey, |filename| match Self::read_record(filename)? {
+            Some(record) if record.instance_id == self.instance_id => {
+                fs::remove_file(filename)?;
+                Ok(())
+            }
+            _ => Ok(()),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum LeaseRequest {
+    Acquire {
+        node_pubkey: Pubkey,
+        instance_id: u64,
+    },
+    Renew {
+        node_pubkey: Pubkey,
+        instance_id: u64,
+    },
+    Release {
+        node_pubkey: Pubkey,
+        instance_id: u64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum LeaseResponse {
+    Granted { duration_ms: u64 },
+    Held,
+    NotHeld,
+    Released,
+}
+
+const MAX_LEASE_MESSAGE_SIZE: u64 = 1024;
+const LEASE_COORDINATOR_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn read_message<T: DeserializeOwned>(stream: &TcpStream) -> Result<T> {
+    Ok(bincode::deserialize_from(
+        stream.take(MAX_LEASE_MESSAGE_SIZE),
+    )?)
+}
+
+fn write_message<T: Serialize>(mut stream: &TcpStream, message: &T) -> Result<()> {
+    stream.write_all(&bincode::serialize(message)?)?;
+    Ok(())
+}
+
+/// Lease held by a [`TowerLeaseCoordinator`] reached over TCP
+pub struct TcpTowerLease {
+    coordinator_addr: SocketAddr,
+    instance_id: u64,
+}
+
+impl TcpTowerLease {
+    pub fn new(coordinator_addr: SocketAddr) -> Self {
+        Self {
+            coordinator_addr,
+            instance_id: new_instance_id(),
+        }
+    }
+
+    fn request(&self, request: &LeaseRequest) -> Result<LeaseResponse> {
+        let stream = TcpStream::connect_timeout(&self.coordinator_addr, LEASE_COORDINATOR_TIMEOUT)?;
+        stream.set_read_timeout(Some(LEASE_COORDINATOR_TIMEOUT))?;
+        stream.set_write_timeout(Some(LEASE_COORDINATOR_TIMEOUT))?;
+        write_message(&stream, request)?;
+        read_message(&stream)
+    }
+
+    fn unexpected_response(response: LeaseResponse) -> TowerError {
+        TowerError::IoError(io::Error::other(format!(
+            "Unexpected tower lease coordinator response: {response:?}"
+        )))
+    }
+}
+
+impl TowerLease for TcpTowerLease {
+    fn acquire(&self, node_pubkey: &Pubkey) -> Result<Duration> {
+        match self.request(&LeaseRequest::Acquire {
+            node_pubkey: *node_pubkey,
+            instance_id: self.instance_id,
+        })? {
+            LeaseResponse::Granted { duration_ms } => Ok(Duration::from_millis(duration_ms)),
+            LeaseResponse::Held => Err(TowerError::LeaseHeld(*node_pubkey)),
+            response => Err(Self::unexpected_response(response)),
+        }
+    }
+
+    fn renew(&self, node_pubkey: &Pubkey) -> Result<Duration> {
+        match self.request(&LeaseRequest::Renew {
+            node_pubkey: *node_pubkey,
+            instance_id: self.instance_id,
+        })? {
+            LeaseResponse::Granted { duration_ms } => Ok(Duration::from_millis(duration_ms)),
+            LeaseResponse::NotHeld => Err(TowerError::LeaseNotHeld(*node_pubkey)),
+            response => Err(Self::unexpected_response(response)),
+        }
+    }
+
+    fn release(&self, node_pubkey: &Pubkey) -> Result<()> {
+        match self.request(&LeaseRequest::Release {
+            node_pubkey: *node_pubkey,
+            instance_id: self.instance_id,
+        })? {
+            LeaseResponse::Released => Ok(()),
+            response => Err(Self::unexpected_response(response)),
+        }
+    }
+}
+
+/// Grants tower leases to the validator instances connecting over TCP.
+///
+/// The coordinator is a single process without replication: run it on a host
+/// other than the validators it arbitrates between. While it is unreachable
+/// no instance can renew its lease, so none of them vote. Leases are only kept
+/// in memory and only an acquire creates one: after a restart of the
+/// coordinator the validators stop voting until they acquire their lease
+/// again, by restarting them or running `set-identity --require-tower`.
+///
+/// Requests are not authenticated: anyone who can reach the coordinator can
+/// take a lease while it is free and keep the validators from voting. It only
+/// listens on loopback and private network addresses, which must be
+/// reachable by the validators alone.
+pub struct TowerLeaseCoordinator {
+    local_addr: SocketAddr,
+    exit: Arc<AtomicBool>,
+    thread_hdl: JoinHandle<()>,
+}
+
+impl TowerLeaseCoordinator {
+    pub fn new(bind_addr: SocketAddr, duration: Duration) -> io::Result<Self> {
+        if !Self::is_private_addr(&bind_addr.ip()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Refusing to listen on {bind_addr}, the tower lease coordinator only listens \
+                     on loopback and private network addresses"
+                ),
+            ));
+        }
+        let listener = TcpListener::bind(bind_addr)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        let exit = Arc::new(AtomicBool::new(false));
+        let thread_hdl = {
+            let exit = exit.clone();
+            Builder::new()
+                .name("solTowerLease".to_string())
+                .spawn(move || Self::run(listener, duration, &exit))
+                .unwrap()
+        };
+        info!("Tower lease coordinator listening on {local_addr}");
+        Ok(Self {
+            local_addr,
+            exit,
+            thread_hdl,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    fn is_private_addr(ip: &IpAddr) -> bool {
+        match ip {
+            IpAddr::V4(ip) => ip.is_loopback() || ip.is_private(),
+            // fc00::/7 are the unique local addresses
+            IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00,
+        }
+    }
+
+    pub fn exit(&self) {
+        self.exit.store(true, Ordering::Relaxed);
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+
+    fn run(listener: TcpListener, duration: Duration, exit: &AtomicBool) {
+        // Instance id and expiry of the lease on each identity
+        let mut leases = HashMap::<Pubkey, (u64, Instant)>::new();
+        while !exit.load(Ordering::Relaxed) {
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    sleep(Duration::from_millis(10));
+                    continue;
+                }
+                Err(err) => {
+                    warn!("Tower lease coordinator failed to accept a connection: {err}");
+                    continue;
+                }
+            };
+            // Requests are handled one at a time, which makes each of them atomic
+            let result = stream
+                .set_nonblocking(false)
+                .and_then(|()| stream.set_read_timeout(Some(LEASE_COORDINATOR_TIMEOUT)))
+                .map_err(TowerError::from)
+                .and_then(|()| read_message(&stream))
+                .map(|request| Self::handle_request(&mut leases, duration, request))
+                .and_then(|response| write_message(&stream, &response));
+            if let Err(err) = result {
+                warn!("Tower lease coordinator request failed: {err}");
+            }
+        }
+    }
+
+    fn handle_request(
+        leases: &mut HashMap<Pubkey, (u64, Instant)>,
+        duration: Duration,
+        request: LeaseRequest,
+    ) -> LeaseResponse {
+        let now = Instant::now();
+        let granted = LeaseResponse::Granted {
+            duration_ms: duration.as_millis() as u64,
+        };
+        match request {
+            LeaseRequest::Acquire {
+                node_pubkey,
+                instance_id,
+            } => match leases.get(&node_pubkey) {
+                Some((holder, expires_at)) if *holder != instance_id && *expires_at > now => {
+                    LeaseResponse::Held
+                }
+                _ => {
+                    info!("Tower lease for {node_pubkey} granted to instance {instance_id:x}");
+                    leases.insert(node_pubkey, (instance_id, now + duration));
+                    granted
+                }
+            },
+            LeaseRequest::Renew {
+                node_pubkey,
+                instance_id,
+            } => match leases.get_mut(&node_pubkey) {
+                Some((holder, expires_at)) if *holder == instance_id => {
+                    *expires_at = now + duration;
+                    granted
+                }
+                _ => LeaseResponse::NotHeld,
+            },
+            LeaseRequest::Release {
+                node_pubkey,
+                instance_id,
+            } => {
+                if leases
+                    .get(&node_pubkey)
+                    .is_some_and(|(holder, _)| *holder == instance_id)
+                {
+                    info!("Tower lease for {node_pubkey} released by instance {instance_id:x}");
+                    leases.remove(&node_pubkey);
+                }
+                LeaseResponse::Released
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::consensus::tower_storage::{FileTowerStorage, NullTowerStorage, SavedTower},
+        solana_keypair::Keypair,
+        solana_signer::Signer,
+        tempfile::TempDir,
+    };
+
+    fn check_lease_handoff(primary: &dyn TowerLease, standby: &dyn TowerLease) {
+        let node_pubkey = Pubkey::new_unique();
+
+        primary.acquire(&node_pubkey).unwrap();
+        primary.renew(&node_pubkey).unwrap();
+        assert!(matches!(
+            standby.acquire(&node_pubkey),
+            Err(TowerError::LeaseHeld(_))
+        ));
+        assert!(matches!(
+            standby.renew(&node_pubkey),
+            Err(TowerError::LeaseNotHeld(_))
+        ));
+        // Releasing a lease held by another instance does nothing
+        standby.release(&node_pubkey).unwrap();
+        assert!(standby.acquire(&node_pubkey).is_err());
+
+        primary.release(&node_pubkey).unwrap();
+        assert!(matches!(
+            primary.renew(&node_pubkey),
+            Err(TowerError::LeaseNotHeld(_))
+        ));
+        standby.acquire(&node_pubkey).unwrap();
+        assert!(matches!(
+            primary.renew(&node_pubkey),
+            Err(TowerError::LeaseNotHeld(_))
+        ));
+        assert!(matches!(
+            primary.acquire(&node_pubkey),
+            Err(TowerError::LeaseHeld(_))
+        ));
+    }
+
+    fn check_lease_expiry(primary: &dyn TowerLease, standby: &dyn TowerLease) {
+        let node_pubkey = Pubkey::new_unique();
+
+        let duration = primary.acquire(&node_pubkey).unwrap();
+        sleep(duration + Duration::from_millis(10));
+        // The primary stopped renewing, the standby takes over and the
+        // primary can't renew the lease when it comes back
+        standby.acquire(&node_pubkey).unwrap();
+        assert!(matches!(
+            primary.renew(&node_pubkey),
+            Err(TowerError::LeaseNotHeld(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_tower_lease() {
+        let lease_path = TempDir::new().unwrap();
+        let new_lease = |duration| FileTowerLease::new(lease_path.path().to_path_buf(), duration);
+
+        check_lease_handoff(
+            &new_lease(DEFAULT_TOWER_LEASE_DURATION),
+            &new_lease(DEFAULT_TOWER_LEASE_DURATION),
+        );
+        check_lease_expiry(
+            &new_lease(Duration::from_millis(50)),
+            &new_lease(Duration::from_millis(50)),
+        );
+    }
+
+    #[test]
+    fn test_file_tower_lease_concurrent_acquire() {
+        let lease_path = TempDir::new().unwrap();
+        let node_pubkey = Pubkey::new_unique();
+        let leases: Vec<_> = (0..8)
+            .map(|_| {
+                FileTowerLease::new(
+                    lease_path.path().to_path_buf(),
+                    DEFAULT_TOWER_LEASE_DURATION,
+                )
+            })
+            .collect();
+
+        let acquired = thread::scope(|scope| {
+            leases
+                .iter()
+                .map(|lease| scope.spawn(|| lease.acquire(&node_pubkey)))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(Result::is_ok)
+                .count()
+        });
+        assert_eq!(acquired, 1);
+    }
+
+    #[test]
+    fn test_tcp_tower_lease() {
+        let coordinator = TowerLeaseCoordinator::new(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            Duration::from_millis(200),
+        )
+        .unwrap();
+        let new_lease = || TcpTowerLease::new(coordinator.local_addr());
+
+        check_lease_handoff(&new_lease(), &new_lease());
+        check_lease_expiry(&new_lease(), &new_lease());
+
+        // Leases are lost when the coordinator restarts, only an acquire can
+        // take the lease again
+        let node_pubkey = Pubkey::new_unique();
+        let primary = new_lease();
+        primary.acquire(&node_pubkey).unwrap();
+        coordinator.exit();
+        coordinator.join().unwrap();
+        let coordinator = TowerLeaseCoordinator::new(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            Duration::from_millis(200),
+        )
+        .unwrap();
+        let primary = TcpTowerLease {
+            coordinator_addr: coordinator.local_addr(),
+            ..primary
+        };
+        assert!(matches!(
+            primary.renew(&node_pubkey),
+            Err(TowerError::LeaseNotHeld(_))
+        ));
+        primary.acquire(&node_pubkey).unwrap();
+        assert!(matches!(
+            TcpTowerLease::new(coordinator.local_addr()).acquire(&node_pubkey),
+            Err(TowerError::LeaseHeld(_))
+        ));
+
+        coordinator.exit();
+        coordinator.join().unwrap();
+    }
+
+    #[test]
+    fn test_tower_lease_coordinator_bind_addr() {
+        for bind_addr in [
+            SocketAddr::from(([0, 0, 0, 0], 0)),
+            SocketAddr::from(([1, 1, 1, 1], 0)),
+            "[::]:0".parse().unwrap(),
+        ] {
+            assert_eq!(
+                TowerLeaseCoordinator::new(bind_addr, DEFAULT_TOWER_LEASE_DURATION)
+                    .err()
+                    .unwrap()
+                    .kind(),
+                io::ErrorKind::InvalidInput,
+            );
+        }
+    }
+
+    #[test]
+    fn test_leased_tower_storage_renewal() {
+        let lease_path = TempDir::new().unwrap();
+        let duration = Duration::from_secs(1);
+        let node_pubkey = Pubkey::new_unique();
+        let storage = LeasedTowerStorage::new(
+            Arc::new(NullTowerStorage::default()),
+            Box::new(FileTowerLease::new(
+                lease_path.path().to_path_buf(),
+                duration,
+            )),
+        );
+        storage.acquire_lease(&node_pubkey).unwrap();
+
+        // The lease is renewed in the background without saving towers
+        sleep(duration * 3);
+        assert!(storage.holds_lease(&node_pubkey));
+        assert!(matches!(
+            FileTowerLease::new(lease_path.path().to_path_buf(), duration).acquire(&node_pubkey),
+            Err(TowerError::LeaseHeld(_))
+        ));
+    }
+
+    #[test]
+    fn test_leased_tower_storage() {
+        let tower_path = TempDir::new().unwrap();
+        let identity_keypair = Keypair::new();
+        let node_pubkey = identity_keypair.pubkey();
+        let tower = Tower {
+            node_pubkey,
+            ..Tower::default()
+        };
+        let saved_tower =
+            SavedTowerVersions::from(SavedTower::new(&tower, &identity_keypair).unwrap());
+        let new_storage = || {
+            LeasedTowerStorage::new(
+                Arc::new(FileTowerStorage::new(tower_path.path().to_path_buf())),
+                Box::new(FileTowerLease::new(
+                    tower_path.path().to_path_buf(),
+                    DEFAULT_TOWER_LEASE_DURATION,
+                )),
+            )
+        };
+        let primary = new_storage();
+        let standby = new_storage();
+
+        // Loading acquires the lease even if there is no tower to load yet
+        assert!(primary.load(&node_pubkey).unwrap_err().is_file_missing());
+        primary.store(&saved_tower).unwrap();
+        assert!(matches!(
+            standby.load(&node_pubkey),
+            Err(TowerError::LeaseHeld(_))
+        ));
+        assert!(matches!(
+            standby.store(&saved_tower),
+            Err(TowerError::LeaseNotHeld(_))
+        ));
+        assert!(primary.holds_lease(&node_pubkey));
+        assert!(!standby.holds_lease(&node_pubkey));
+
+        primary.release(&node_pubkey).unwrap();
+        let loaded = standby.load(&node_pubkey).unwrap();
+        assert_eq!(loaded.node_pubkey, node_pubkey);
+        standby.store(&saved_tower).unwrap();
+        assert!(matches!(
+            primary.store(&saved_tower),
+            Err(TowerError::LeaseNotHeld(_))
+        ));
+        assert!(!primary.holds_lease(&node_pubkey));
+        assert!(standby.holds_lease(&node_pubkey));
+    }
+}
@@ -25,37 +25,47 @@ impl SavedTowerVersions {
         // This method assumes that `self` was just deserialized
         assert_eq!(self.pubkey(), Pubkey::default());
 
+        if !self.verify_signature(node_pubkey) {
+            return Err(TowerError::InvalidSignature);
+        }
+        let tower = self.deserialize_tower()?;
+        if tower.node_pubkey != *node_pubkey {
+            return Err(TowerError::WrongTower(format!(
+                "node_pubkey is {:?} but found tower for {:?}",
+                node_pubkey, tower.node_pubkey
+            )));
+        }
+        Ok(tower)
+    }
+
+    /// Returns whether the saved tower data was signed by `node_pubkey`
+    pub fn verify_signature(&self, node_pubkey: &Pubkey) -> bool {
+        match self {
+            SavedTowerVersions::V1_17_14(t) => t.signature.verify(node_pubkey.as_ref(), &t.data),
+            SavedTowerVersions::Current(t) => t.signature.verify(node_pubkey.as_ref(), &t.data),
+        }
+    }
+
+    /// Deserializes the saved tower without verifying its signature or owner,
+    /// for inspecting a tower that fails to load
+    pub fn deserialize_tower(&self) -> Result<Tower> {
         let tv = match self {
             SavedTowerVersions::V1_17_14(t) => {
-                if !t.signature.verify(node_pubkey.as_ref(), &t.data) {
-                    return Err(TowerError::InvalidSignature);
-                }
                 bincode::deserialize(&t.data).map(TowerVersions::V1_7_14)
             }
             SavedTowerVersions::Current(t) => {
-                if !t.signature.verify(node_pubkey.as_ref(), &t.data) {
-                    return Err(TowerError::InvalidSignature);
-                }
                 bincode::deserialize(&t.data).map(TowerVersions::V1_14_11)
             }
         };
-        tv.map_err(|e| e.into()).and_then(|tv: TowerVersions| {
-            let tower = tv.convert_to_current();
-            if tower.node_pubkey != *node_pubkey {
-                return Err(TowerError::WrongTower(format!(
-                    "node_pubkey is {:?} but found tower for {:?}",
-                    node_pubkey, tower.node_pubkey
-                )));
-            }
-            Ok(tower)
-        })
+        tv.map(TowerVersions::convert_to_current)
+            .map_err(|e| e.into())
     }
 
     fn serialize_into(&self, file: &mut File) -> Result<()> {
         bincode::serialize_into(file, self).map_err(|e| e.into())
     }
 
-    fn pubkey(&self) -> Pubkey {
+    pub fn pubkey(&self) -> Pubkey {
         match self {
             SavedTowerVersions::V1_17_14(t) => t.node_pubkey,
             SavedTowerVersions::Current(t) => t.node_pubkey,
@@ -115,6 +125,24 @@ impl SavedTower {
 pub trait TowerStorage: Sync + Send {
     fn load(&self, node_pubkey: &Pubkey) -> Result<Tower>;
     fn store(&self, saved_tower: &SavedTowerVersions) -> Result<()>;
+
+    /// Allows this instance to save towers for `node_pubkey`, which `load()`
+    /// also does. Fails if another instance still holds the tower lease
+    fn acquire_lease(&self, _node_pubkey: &Pubkey) -> Result<()> {
+        Ok(())
+    }
+
+    /// Stops saving towers for `node_pubkey` from this instance, so another
+    /// instance can take over its identity
+    fn release(&self, _node_pubkey: &Pubkey) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether this instance may vote with `node_pubkey`, which is always
+    /// the case unless voting is fenced by a tower lease
+    fn holds_lease(&self, _node_pubkey: &Pubkey) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -155,6 +183,23 @@ impl FileTowerStorage {
             .with_extension("bin")
     }
 
+    /// Reads the saved tower of `node_pubkey` without verifying it, falling
+    /// back to the pre 1.9 file if there is no current one
+    pub fn load_saved_tower(&self, node_pubkey: &Pubkey) -> Result<SavedTowerVersions> {
+        if let Ok(file) = File::open(self.filename(node_pubkey)) {
+            // New format
+            let mut stream = BufReader::new(file);
+            bincode::deserialize_from(&mut stream).map_err(|e| e.into())
+        } else {
+            // Old format
+            let file = File::open(self.old_filename(node_pubkey))?;
+            let mut stream = BufReader::new(file);
+            bincode::deserialize_from::<_, SavedTower1_7_14>(&mut stream)
+                .map(SavedTowerVersions::from)
+                .map_err(|e| e.into())
+        }
+    }
+
     #[cfg(test)]
     fn store_old(&self, saved_tower: &SavedTower1_7_14) -> Result<()> {
         let pubkey = saved_tower.node_pubkey;
@@ -182,23 +227,8 @@ impl TowerStorage for FileTowerStorage {
         // Ensure to create parent dir here, because restore() precedes save() always
         fs::create_dir_all(filename.parent().unwrap())?;
 
-        if let Ok(file) = File::open(&filename) {
-            // New format
-            let mut stream = BufReader::new(file);
-
-            bincode::deserialize_from(&mut stream)
-                .map_err(|e| e.into())
-                .and_then(|t: SavedTowerVersions| t.try_into_tower(node_pubkey))
-        } else {
-            // Old format
-            let file = File::open(self.old_filename(node_pubkey))?;
-            let mut stream = BufReader::new(file);
-            bincode::deserialize_from(&mut stream)
-                .map_err(|e| e.into())
-                .and_then(|t: SavedTower1_7_14| {
-                    SavedTowerVersions::from(t).try_into_tower(node_pubkey)
-                })
-        }
+        self.load_saved_tower(node_pubkey)
+            .and_then(|t| t.try_into_tower(node_pubkey))
     }
 
     fn store(&self, saved_tower: &SavedTowerVersions) -> Result<()> {
@@ -422,4 +452,45 @@ pub mod test {
         assert_eq!(loaded.vote_state.root_slot, Some(1));
         assert_eq!(loaded.stray_restored_slot(), None);
     }
+    #[test]
+    fn test_load_saved_tower_unverified() {
+        let tower_path = TempDir::new().unwrap();
+        let identity_keypair = Keypair::new();
+        let node_pubkey = identity_keypair.pubkey();
+        let tower_storage = FileTowerStorage::new(tower_path.path().to_path_buf());
+
+        let mut tower = Tower {
+            node_pubkey,
+            ..Tower::default()
+        };
+        tower.record_vote(5, Hash::new_unique());
+        tower.save(&tower_storage, &identity_keypair).unwrap();
+
+        let saved_tower = tower_storage.load_saved_tower(&node_pubkey).unwrap();
+        assert!(saved_tower.verify_signature(&node_pubkey));
+        assert!(!saved_tower.verify_signature(&Pubkey::new_unique()));
+        let loaded = saved_tower.deserialize_tower().unwrap();
+        assert_eq!(loaded.node_pubkey, node_pubkey);
+        assert_eq!(loaded.tower_slots(), tower.tower_slots());
+        assert_eq!(loaded.last_voted_slot_hash(), tower.last_voted_slot_hash());
+
+        // A tower file renamed to another identity still deserializes, but
+        // fails to load
+        let other_pubkey = Pubkey::new_unique();
+        fs::rename(
+            tower_storage.filename(&node_pubkey),
+            tower_storage.filename(&other_pubkey),
+        )
+        .unwrap();
+        let saved_tower = tower_storage.load_saved_tower(&other_pubkey).unwrap();
+        assert!(!saved_tower.verify_signature(&other_pubkey));
+        assert_eq!(
+            saved_tower.deserialize_tower().unwrap().node_pubkey,
+            node_pubkey
+        );
+        assert!(matches!(
+            Tower::restore(&tower_storage, &other_pubkey),
+            Err(TowerError::InvalidSignature)
+        ));
+    }
 }
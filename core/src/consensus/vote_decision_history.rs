@@ -0,0 +1,598 @@
+//! A bounded history of the voting decisions made by `ReplayStage`: the votes
+//! it cast, why it could not vote on the heaviest fork, and how it handled
+//! duplicate slots. The history can be retrieved over the admin RPC and
+//! optionally appended to a file as JSON lines, for post-mortems on missed
+//! votes.
+
+use {
+    crate::{
+        consensus::{SwitchForkDecision, SWITCH_FORK_THRESHOLD},
+        replay_stage::HeaviestForkFailures,
+    },
+    chrono::{DateTime, SecondsFormat},
+    crossbeam_channel::{bounded, Receiver, Sender, TrySendError},
+    solana_clock::Slot,
+    solana_hash::Hash,
+    solana_time_utils::timestamp,
+    std::{
+        collections::VecDeque,
+        fmt::{self, Display},
+        fs::{File, OpenOptions},
+        io::{self, BufWriter, Write},
+        path::Path,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        thread::Builder,
+    },
+};
+
+/// Number of decisions kept in memory
+pub const VOTE_DECISION_HISTORY_CAPACITY: usize = 4096;
+/// Number of decisions waiting to be written to the log file. Decisions are
+/// dropped rather than queued without bound if the file can't keep up.
+const LOG_CHANNEL_CAPACITY: usize = 4096;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteDecisionRecord {
+    /// When the decision was made, as a UNIX timestamp in milliseconds
+    pub timestamp: u64,
+    pub decision: VoteDecision,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum VoteDecision {
+    /// Voted for `slot`
+    #[serde(rename_all = "camelCase")]
+    Vote {
+        slot: Slot,
+        /// Number of slots between the voted slot and the highest slot replay
+        /// had started when voting
+        latency: u64,
+        switch: VoteSwitch,
+    },
+    /// Could not vote on the heaviest fork
+    #[serde(rename_all = "camelCase")]
+    NoVote {
+        heaviest_slot: Slot,
+        last_voted_slot: Option<Slot>,
+        switch: Option<VoteSwitch>,
+        failures: Vec<VoteFailure>,
+    },
+    /// `slot` was found to be a duplicate block. `frozen_hash` is the hash of
+    /// the version replayed by this node, if it was frozen
+    #[serde(rename_all = "camelCase")]
+    DuplicateSlot {
+        slot: Slot,
+        frozen_hash: Option<String>,
+    },
+    /// The version of duplicate `slot` replayed by this node was dumped to
+    /// repair the version confirmed by the cluster
+    #[serde(rename_all = "camelCase")]
+    DumpedDuplicateSlot { slot: Slot, cluster_hash: String },
+}
+
+/// Whether voting on the heaviest fork needs to switch forks, see
+/// `SwitchForkDecision`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum VoteSwitch {
+    SameFork,
+    /// More than `SWITCH_FORK_THRESHOLD` of the stake is locked out of the
+    /// last voted fork
+    #[serde(rename_all = "camelCase")]
+    SwitchProof {
+        observed_stake: u64,
+        total_stake: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    FailedSwitchThreshold {
+        observed_stake: u64,
+        total_stake: u64,
+    },
+    /// The last vote is on a duplicate fork that has not been confirmed
+    #[serde(rename_all = "camelCase")]
+    FailedSwitchDuplicateRollback {
+        latest_duplicate_ancestor: Slot,
+    },
+}
+
+impl From<&SwitchForkDecision> for VoteSwitch {
+    fn from(decision: &SwitchForkDecision) -> Self {
+        match decision {
+            SwitchForkDecision::SameFork => Self::SameFork,
+            SwitchForkDecision::SwitchProof(_, observed_stake, total_stake) => Self::SwitchProof {
+                observed_stake: *observed_stake,
+                total_stake: *total_stake,
+            },
+            SwitchForkDecision::FailedSwitchThreshold(observed_stake, total_stake) => {
+                Self::FailedSwitchThreshold {
+                    observed_stake: *observed_stake,
+                    total_stake: *total_stake,
+                }
+            }
+            SwitchForkDecision::FailedSwitchDuplicateRollback(latest_duplicate_ancestor) => {
+                Self::FailedSwitchDuplicateRollback {
+                    latest_duplicate_ancestor: *latest_duplicate_ancestor,
+                }
+            }
+        }
+    }
+}
+
+/// Why a bank could not be voted on, see `HeaviestForkFailures`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum VoteFailure {
+    LockedOut {
+        slot: Slot,
+    },
+    #[serde(rename_all = "camelCase")]
+    FailedThreshold {
+        slot: Slot,
+        vote_depth: u64,
+        observed_stake: u64,
+        total_stake: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    FailedSwitchThreshold {
+        slot: Slot,
+        observed_stake: u64,
+        total_stake: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    NoPropagatedConfirmation {
+        slot: Slot,
+        observed_stake: u64,
+        total_stake: u64,
+    },
+}
+
+impl From<&HeaviestForkFailures> for VoteFailure {
+    fn from(failure: &HeaviestForkFailures) -> Self {
+        match *failure {
+            HeaviestForkFailures::LockedOut(slot) => Self::LockedOut { slot },
+            HeaviestForkFailures::FailedThreshold(
+                slot,
+                vote_depth,
+                observed_stake,
+                total_stake,
+            ) => Self::FailedThreshold {
+                slot,
+                vote_depth,
+                observed_stake,
+                total_stake,
+            },
+            HeaviestForkFailures::FailedSwitchThreshold(slot, observed_stake, total_stake) => {
+                Self::FailedSwitchThreshold {
+                    slot,
+                    observed_stake,
+                    total_stake,
+                }
+            }
+            HeaviestForkFailures::NoPropagatedConfirmation(slot, observed_stake, total_stake) => {
+                Self::NoPropagatedConfirmation {
+                    slot,
+                    observed_stake,
+                    total_stake,
+                }
+            }
+        }
+    }
+}
+
+fn stake_percent(observed_stake: u64, total_stake: u64) -> f64 {
+    if total_stake == 0 {
+        0.0
+    } else {
+        100.0 * observed_stake as f64 / total_stake as f64
+    }
+}
+
+impl Display for VoteSwitch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SameFork => write!(f, "same fork"),
+            Self::SwitchProof {
+                observed_stake,
+                total_stake,
+            } => write!(
+                f,
+                "switching proof with {:.2}% of the stake, over the {:.0}% threshold",
+                stake_percent(*observed_stake, *total_stake),
+                100.0 * SWITCH_FORK_THRESHOLD
+            ),
+            Self::FailedSwitchThreshold {
+                observed_stake,
+                total_stake,
+            } => write!(
+                f,
+                "switching proof failed with {:.2}% of the stake",
+                stake_percent(*observed_stake, *total_stake)
+            ),
+            Self::FailedSwitchDuplicateRollback {
+                latest_duplicate_ancestor,
+            } => write!(
+                f,
+                "cannot roll back from unconfirmed duplicate slot {latest_duplicate_ancestor}"
+            ),
+        }
+    }
+}
+
+impl Display for VoteFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::LockedOut { slot } => write!(f, "locked out of slot {slot}"),
+            Self::FailedThreshold {
+                slot,
+                vote_depth,
+                observed_stake,
+                total_stake,
+            } => write!(
+                f,
+                "slot {slot} failed the vote threshold at depth {vote_depth} with {:.2}% of the \
+                 stake",
+                stake_percent(*observed_stake, *total_stake)
+            ),
+            Self::FailedSwitchThreshold {
+                slot,
+                observed_stake,
+                total_stake,
+            } => write!(
+                f,
+                "switching to slot {slot} failed with {:.2}% of the stake",
+                stake_percent(*observed_stake, *total_stake)
+            ),
+            Self::NoPropagatedConfirmation {
+                slot,
+                observed_stake,
+                total_stake,
+            } => write!(
+                f,
+                "leader slot {slot} propagated to {:.2}% of the stake",
+                stake_percent(*observed_stake, *total_stake)
+            ),
+        }
+    }
+}
+
+impl Display for VoteDecisionRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match DateTime::from_timestamp_millis(self.timestamp as i64) {
+            Some(time) => write!(f, "{} ", time.to_rfc3339_opts(SecondsFormat::Millis, true))?,
+            None => write!(f, "{} ", self.timestamp)?,
+        }
+        match &self.decision {
+            VoteDecision::Vote {
+                slot,
+                latency,
+                switch,
+            } => write!(
+                f,
+                "voted for slot {slot}, latency {latency} slots, {switch}"
+            ),
+            VoteDecision::NoVote {
+                heaviest_slot,
+                last_voted_slot,
+                switch,
+                failures,
+            } => {
+                write!(f, "did not vote for heaviest slot {heaviest_slot}")?;
+                if let Some(last_voted_slot) = last_voted_slot {
+                    write!(f, ", last voted slot {last_voted_slot}")?;
+                }
+                if let Some(switch) = switch {
+                    write!(f, ", {switch}")?;
+                }
+                for failure in failures {
+                    write!(f, "; {failure}")?;
+                }
+                Ok(())
+            }
+            VoteDecision::DuplicateSlot { slot, frozen_hash } => {
+                write!(f, "slot {slot} is a duplicate block")?;
+                match frozen_hash {
+                    Some(frozen_hash) => write!(f, ", replayed version {frozen_hash}"),
+                    None => write!(f, ", replayed version not frozen"),
+                }
+            }
+            VoteDecision::DumpedDuplicateSlot { slot, cluster_hash } => write!(
+                f,
+                "dumped duplicate slot {slot} to repair the cluster version {cluster_hash}"
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Records {
+    records: VecDeque<VoteDecisionRecord>,
+    /// The last `VoteDecision::NoVote` recorded. Replay tries to vote on
+    /// every iteration, only changes in the outcome are recorded.
+    last_no_vote: Option<VoteDecision>,
+}
+
+pub struct VoteDecisionHistory {
+    records: Mutex<Records>,
+    capacity: usize,
+    log_sender: Option<Sender<VoteDecisionRecord>>,
+    /// Number of decisions dropped because the log file fell behind
+    num_dropped_log_records: Arc<AtomicU64>,
+}
+
+impl Default for VoteDecisionHistory {
+    fn default() -> Self {
+        Self::new(VOTE_DECISION_HISTORY_CAPACITY)
+    }
+}
+
+impl VoteDecisionHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Mutex::default(),
+            capacity,
+            log_sender: None,
+            num_dropped_log_records: Arc::default(),
+        }
+    }
+
+    /// Also appends every decision to the file at `path` as a line of JSON.
+    /// The file is written by a separate thread to keep I/O out of replay.
+    pub fn new_with_log_file(capacity: usize, path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let (log_sender, log_receiver) = bounded(LOG_CHANNEL_CAPACITY);
+        let num_dropped_log_records = Arc::<AtomicU64>::default();
+        let path = path.display().to_string();
+        Builder::new().name("solVoteDecLog".to_string()).spawn({
+            let num_dropped_log_records = num_dropped_log_records.clone();
+            move || Self::write_log_file(file, &path, log_receiver, &num_dropped_log_records)
+        })?;
+        Ok(Self {
+            log_sender: Some(log_sender),
+            num_dropped_log_records,
+            ..Self::new(capacity)
+        })
+    }
+
+    fn write_log_file(
+        file: File,
+        path: &str,
+        log_receiver: Receiver<VoteDecisionRecord>,
+        num_dropped_log_records: &AtomicU64,
+    ) {
+        let mut writer = BufWriter::new(file);
+        let mut num_reported_dropped = 0;
+        // Exits once the history is dropped
+        for record in log_receiver.iter() {
+            let num_dropped = num_dropped_log_records.load(Ordering::Relaxed);
+            if num_dropped > num_reported_dropped {
+                warn!(
+                    "{} vote decisions were not written to {path} because writing fell behind",
+                    num_dropped - num_reported_dropped
+                );
+                num_reported_dropped = num_dropped;
+            }
+            let result = serde_json::to_writer(&mut writer, &record)
+                .map_err(io::Error::from)
+                .and_then(|()| writeln!(writer))
+                .and_then(|()| {
+                    if log_receiver.is_empty() {
+                        writer.flush()
+                    } else {
+                        Ok(())
+                    }
+                });
+            if let Err(err) = result {
+                warn!("Unable to write vote decision to {path}: {err}");
+            }
+        }
+    }
+
+    pub fn record(&self, decision: VoteDecision) {
+        let record = {
+            let mut records = self.records.lock().unwrap();
+            if matches!(decision, VoteDecision::NoVote { .. }) {
+                if records.last_no_vote.as_ref() == Some(&decision) {
+                    return;
+                }
+                records.last_no_vote = Some(decision.clone());
+            }
+            let record = VoteDecisionRecord {
+                timestamp: timestamp(),
+                decision,
+            };
+            if records.records.len() == self.capacity {
+                records.records.pop_front();
+            }
+            if self.capacity > 0 {
+                records.records.push_back(record.clone());
+            }
+            record
+        };
+        if let Some(log_sender) = &self.log_sender {
+            if let Err(TrySendError::Full(_)) = log_sender.try_send(record) {
+                self.num_dropped_log_records.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Number of decisions which were not written to the log file because
+    /// writing fell behind
+    pub fn num_dropped_log_records(&self) -> u64 {
+        self.num_dropped_log_records.load(Ordering::Relaxed)
+    }
+
+    pub fn record_vote(&self, slot: Slot, highest_slot: Slot, switch: &SwitchForkDecision) {
+        self.record(VoteDecision::Vote {
+            slot,
+            latency: highest_slot.saturating_sub(slot),
+            switch: VoteSwitch::from(switch),
+        })
+    }
+
+    pub fn record_no_vote(
+        &self,
+        heaviest_slot: Slot,
+        last_voted_slot: Option<Slot>,
+        switch: Option<&SwitchForkDecision>,
+        failures: &[HeaviestForkFailures],
+    ) {
+        self.record(VoteDecision::NoVote {
+            heaviest_slot,
+            last_voted_slot,
+            switch: switch.map(VoteSwitch::from),
+            failures: failures.iter().map(VoteFailure::from).collect(),
+        })
+    }
+
+    pub fn record_duplicate_slot(&self, slot: Slot, frozen_hash: Option<Hash>) {
+        self.record(VoteDecision::DuplicateSlot {
+            slot,
+            // The hash of an unfrozen bank is the default hash
+            frozen_hash: frozen_hash
+                .filter(|hash| *hash != Hash::default())
+                .map(|hash| hash.to_string()),
+        })
+    }
+
+    pub fn record_dumped_duplicate_slot(&self, slot: Slot, cluster_hash: Hash) {
+        self.record(VoteDecision::DumpedDuplicateSlot {
+            slot,
+            cluster_hash: cluster_hash.to_string(),
+        })
+    }
+
+    /// Returns up to `limit` of the most recent decisions, oldest first
+    pub fn recent(&self, limit: usize) -> Vec<VoteDecisionRecord> {
+        let records = self.records.lock().unwrap();
+        let skip = records.records.len().saturating_sub(limit);
+        records.records.iter().skip(skip).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{fs, thread::sleep, time::Duration},
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_vote_decision_history() {
+        let history = VoteDecisionHistory::new(3);
+        history.record_vote(1, 2, &SwitchForkDecision::SameFork);
+        let failures = [
+            HeaviestForkFailures::FailedSwitchThreshold(4, 10, 100),
+            HeaviestForkFailures::LockedOut(4),
+        ];
+        let switch = SwitchForkDecision::FailedSwitchThreshold(10, 100);
+        history.record_no_vote(4, Some(1), Some(&switch), &failures);
+        // Unchanged outcomes are only recorded once
+        history.record_no_vote(4, Some(1), Some(&switch), &failures);
+        history.record_duplicate_slot(3, Some(Hash::default()));
+
+        let decisions: Vec<_> = history
+            .recent(usize::MAX)
+            .into_iter()
+            .map(|record| record.decision)
+            .collect();
+        assert_eq!(
+            decisions,
+            vec![
+                VoteDecision::Vote {
+                    slot: 1,
+                    latency: 1,
+                    switch: VoteSwitch::SameFork,
+                },
+                VoteDecision::NoVote {
+                    heaviest_slot: 4,
+                    last_voted_slot: Some(1),
+                    switch: Some(VoteSwitch::FailedSwitchThreshold {
+                        observed_stake: 10,
+                        total_stake: 100,
+                    }),
+                    failures: vec![
+                        VoteFailure::FailedSwitchThreshold {
+                            slot: 4,
+                            observed_stake: 10,
+                            total_stake: 100,
+                        },
+                        VoteFailure::LockedOut { slot: 4 },
+                    ],
+                },
+                VoteDecision::DuplicateSlot {
+                    slot: 3,
+                    frozen_hash: None,
+                },
+            ]
+        );
+
+        // The oldest decisions are evicted
+        history.record_dumped_duplicate_slot(3, Hash::new_unique());
+        let recent = history.recent(2);
+        assert_eq!(recent.len(), 2);
+        assert!(matches!(
+            recent[1].decision,
+            VoteDecision::DumpedDuplicateSlot { slot: 3, .. }
+        ));
+        assert_eq!(history.recent(usize::MAX).len(), 3);
+        assert!(recent[0]
+            .to_string()
+            .ends_with("slot 3 is a duplicate block, replayed version not frozen"));
+    }
+
+    #[test]
+    fn test_vote_decision_history_log_file() {
+        let log_dir = TempDir::new().unwrap();
+        let log_path = log_dir.path().join("vote-decisions.json");
+        let history = VoteDecisionHistory::new_with_log_file(1, &log_path).unwrap();
+        history.record_vote(1, 1, &SwitchForkDecision::SameFork);
+        history.record_vote(
+            2,
+            3,
+            &SwitchForkDecision::SwitchProof(Hash::default(), 50, 100),
+        );
+        let history_num_dropped_log_records = history.num_dropped_log_records();
+        drop(history);
+
+        // The whole history is written to the file, even what was evicted
+        // from memory
+        let mut decisions = vec![];
+        for _ in 0..100 {
+            let contents = fs::read_to_string(&log_path).unwrap();
+            decisions = contents
+                .lines()
+                .filter_map(|line| serde_json::from_str::<VoteDecisionRecord>(line).ok())
+                .map(|record| record.decision)
+                .collect();
+            if decisions.len() == 2 {
+                break;
+            }
+            sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            decisions,
+            vec![
+                VoteDecision::Vote {
+                    slot: 1,
+                    latency: 0,
+                    switch: VoteSwitch::SameFork,
+                },
+                VoteDecision::Vote {
+                    slot: 2,
+                    latency: 1,
+                    switch: VoteSwitch::SwitchProof {
+                        observed_stake: 50,
+                        total_stake: 100,
+                    },
+                },
+            ]
+        );
+        assert_eq!(history_num_dropped_log_records, 0);
+    }
+}
@@ -1,7 +1,9 @@
-//! this service asynchronously reports CostTracker stats
+//! this service asynchronously reports CostTracker stats and records each
+//! frozen block's cost details in the blockstore
 
 use {
     crossbeam_channel::Receiver,
+    solana_ledger::{blockstore::Blockstore, blockstore_meta::BlockCostDetails},
     solana_runtime::bank::Bank,
     std::{
         sync::Arc,
@@ -27,13 +29,15 @@ pub struct CostUpdateService {
 const MAX_LOOP_COUNT: usize = 25;
 // Throttle checking the count to avoid excessive polling
 const LOOP_LIMITER: Duration = Duration::from_millis(10);
+// The number of most contended writable accounts recorded per block
+pub const BLOCK_COST_TOP_WRITABLE_ACCOUNTS: usize = 16;
 
 impl CostUpdateService {
-    pub fn new(cost_update_receiver: CostUpdateReceiver) -> Self {
+    pub fn new(blockstore: Arc<Blockstore>, cost_update_receiver: CostUpdateReceiver) -> Self {
         let thread_hdl = Builder::new()
             .name("solCostUpdtSvc".to_string())
             .spawn(move || {
-                Self::service_loop(cost_update_receiver);
+                Self::service_loop(&blockstore, cost_update_receiver);
             })
             .unwrap();
 
@@ -44,7 +48,7 @@ impl CostUpdateService {
         self.thread_hdl.join()
     }
 
-    fn service_loop(cost_update_receiver: CostUpdateReceiver) {
+    fn service_loop(blockstore: &Blockstore, cost_update_receiver: CostUpdateReceiver) {
         for cost_update in cost_update_receiver.iter() {
             match cost_update {
                 CostUpdate::FrozenBank {
@@ -59,11 +63,12 @@ impl CostUpdateService {
                         )
                     };
                     for loop_count in 1..=MAX_LOOP_COUNT {
-                        {
+                        let block_cost_details = {
                             // Release the lock so that the thread that will
                             // update the count is able to obtain a write lock
                             //
-                            // Use inner scope to avoid sleeping with the lock
+                            // Use inner scope to avoid sleeping or writing to the
+                            // blockstore with the lock
                             let cost_tracker = bank.read_cost_tracker().unwrap();
                             let in_flight_transaction_count =
                                 cost_tracker.in_flight_transaction_count();
@@ -80,8 +85,29 @@ impl CostUpdateService {
                                     total_transaction_fee,
                                     total_priority_fee,
                                 );
-                                break;
+                                Some(BlockCostDetails {
+                                    block_cost: cost_tracker.block_cost(),
+                                    block_cost_limit: cost_tracker.get_block_limit(),
+                                    vote_cost: cost_tracker.vote_cost(),
+                                    transaction_count: cost_tracker.transaction_count(),
+                                    is_leader_block,
+                                    top_writable_accounts: cost_tracker
+                                        .top_writable_accounts(BLOCK_COST_TOP_WRITABLE_ACCOUNTS),
+                                })
+                            } else {
+                                None
+                            }
+                        };
+                        if let Some(block_cost_details) = block_cost_details {
+                            let slot = bank.slot();
+                            if let Err(err) =
+                                blockstore.write_block_cost_details(slot, block_cost_details)
+                            {
+                                warn!(
+                                    "failed to write block cost details for slot {slot}: {err:?}"
+                                );
                             }
+                            break;
                         }
                         std::thread::sleep(LOOP_LIMITER);
                     }
@@ -904,6 +904,7 @@ mod test {
             repair::{
                 cluster_slot_state_verifier::{DuplicateSlotsToRepair, PurgeRepairSlotCounter},
                 duplicate_repair_status::DuplicateAncestorDecision,
+                repair_peer_selection::StakeWeightedRepairPeers,
                 serve_repair::MAX_ANCESTOR_RESPONSES,
                 serve_repair_service::adapt_repair_requests_packets,
             },
@@ -1392,6 +1393,8 @@ mod test {
                 repair_validators: None,
                 repair_whitelist,
                 wen_restart_repair_slots: None,
+                repair_peer_selection: Arc::new(StakeWeightedRepairPeers),
+                local_repair_source: None,
             };
 
             let (ancestor_hashes_replay_update_sender, ancestor_hashes_replay_update_receiver) =
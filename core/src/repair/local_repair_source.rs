@@ -0,0 +1,302 @@
+//! Answers shred repair requests from the blockstore of another node on the
+//! same host.
+//!
+//! When several nodes share a machine or a filesystem, a node restarting
+//! behind its siblings can read their ledger instead of repairing every
+//! shred over the network. The sibling's blockstore is opened with secondary
+//! (read only) access and requests are answered by the standard repair
+//! handler. The responses are fed into the channel carrying repair responses
+//! received over QUIC, so they are checked against the outstanding request
+//! nonces and signature verified like shreds repaired from any other peer.
+//!
+//! The sibling's blockstore is only read by a dedicated thread, so the repair
+//! loop decides which requests to answer locally from the progress of the
+//! slots that thread last read and never waits for RocksDB.
+
+use {
+    crate::repair::{
+        outstanding_requests::OutstandingRequests,
+        repair_handler::RepairHandler,
+        serve_repair::{ShredRepairType, MAX_ORPHAN_REPAIR_RESPONSES},
+        standard_repair_handler::StandardRepairHandler,
+    },
+    bytes::Bytes,
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender},
+    log::*,
+    solana_clock::Slot,
+    solana_ledger::{
+        blockstore::{Blockstore, BlockstoreError},
+        blockstore_options::{AccessType, BlockstoreOptions},
+        shred::Nonce,
+    },
+    solana_perf::packet::PacketBatchRecycler,
+    solana_pubkey::Pubkey,
+    solana_time_utils::timestamp,
+    std::{
+        collections::{BTreeSet, HashMap},
+        net::{Ipv4Addr, SocketAddr},
+        path::Path,
+        sync::{Arc, RwLock},
+        thread::Builder,
+        time::{Duration, Instant},
+    },
+};
+
+// How often the secondary blockstore replays the sibling's latest writes.
+const CATCH_UP_INTERVAL: Duration = Duration::from_millis(200);
+// Number of recently requested slots whose progress is read from the sibling.
+const MAX_TRACKED_SLOTS: usize = 1024;
+// Requests beyond this many waiting for the serving thread go to the network.
+const MAX_PENDING_REQUESTS: usize = 4096;
+
+enum LocalRepairRequest {
+    /// Reads the progress of the slot in the sibling's ledger from now on.
+    Track(Slot),
+    Repair(ShredRepairType, Nonce),
+}
+
+/// How many shreds of a slot the sibling's ledger holds, from its `SlotMeta`.
+#[derive(Clone, Copy, Debug)]
+struct SlotProgress {
+    consumed: u64,
+    received: u64,
+}
+
+impl SlotProgress {
+    fn has_response(&self, request: &ShredRepairType) -> bool {
+        match *request {
+            ShredRepairType::Shred(_, index) => index < self.consumed,
+            ShredRepairType::HighestShred(_, index) => self.received > index,
+            ShredRepairType::Orphan(_) => self.received > 0,
+        }
+    }
+}
+
+pub struct LocalRepairSource {
+    slot_progress: Arc<RwLock<HashMap<Slot, SlotProgress>>>,
+    request_sender: Sender<LocalRepairRequest>,
+}
+
+impl LocalRepairSource {
+    /// Opens the blockstore at `ledger_path` with secondary access. Responses
+    /// are sent to `repair_response_sender`, the sender paired with the
+    /// receiver of repair responses passed to the TVU.
+    ///
+    /// The thread serving the requests exits once `self` is dropped.
+    pub fn new(
+        ledger_path: &Path,
+        repair_response_sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    ) -> Result<Self, BlockstoreError> {
+        let blockstore = Blockstore::open_with_options(
+            ledger_path,
+            BlockstoreOptions {
+                access_type: AccessType::Secondary,
+                enforce_ulimit_nofile: false,
+                ..BlockstoreOptions::default()
+            },
+        )?;
+        info!("Serving repairs from the local ledger at {ledger_path:?}");
+        let slot_progress = Arc::<RwLock<HashMap<_, _>>>::default();
+        let (request_sender, request_receiver) = bounded(MAX_PENDING_REQUESTS);
+        let server = LocalRepairServer {
+            handler: StandardRepairHandler::new(Arc::new(blockstore)),
+            repair_response_sender,
+            recycler: PacketBatchRecycler::default(),
+            slot_progress: slot_progress.clone(),
+            tracked_slots: BTreeSet::new(),
+        };
+        Builder::new()
+            .name("solLocalRepair".to_string())
+            .spawn(move || server.run(request_receiver))
+            .unwrap();
+        Ok(Self {
+            slot_progress,
+            request_sender,
+        })
+    }
+
+    /// Answers `request` from the local ledger if it has the shreds, returning
+    /// false if the request should be sent to a peer instead.
+    pub(crate) fn repair(
+        &self,
+        request: &ShredRepairType,
+        outstanding_requests: &mut OutstandingRequests<ShredRepairType>,
+    ) -> bool {
+        let slot_progress = self
+            .slot_progress
+            .read()
+            .unwrap()
+            .get(&request.slot())
+            .copied();
+        let Some(slot_progress) = slot_progress else {
+            // Later requests for the slot can be answered locally once the
+            // serving thread has read it
+            let _ = self
+                .request_sender
+                .try_send(LocalRepairRequest::Track(request.slot()));
+            return false;
+        };
+        // Only register a nonce for requests which can be answered, so that
+        // misses do not evict requests outstanding with network peers.
+        if !slot_progress.has_response(request) {
+            return false;
+        }
+        let nonce = outstanding_requests.add_request(*request, timestamp());
+        self.request_sender
+            .try_send(LocalRepairRequest::Repair(*request, nonce))
+            .is_ok()
+    }
+}
+
+struct LocalRepairServer {
+    handler: StandardRepairHandler,
+    repair_response_sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    recycler: PacketBatchRecycler,
+    slot_progress: Arc<RwLock<HashMap<Slot, SlotProgress>>>,
+    tracked_slots: BTreeSet<Slot>,
+}
+
+impl LocalRepairServer {
+    fn run(mut self, request_receiver: Receiver<LocalRepairRequest>) {
+        let mut last_catch_up = Instant::now();
+        loop {
+            match request_receiver.recv_timeout(CATCH_UP_INTERVAL) {
+                Ok(LocalRepairRequest::Track(slot)) => {
+                    self.tracked_slots.insert(slot);
+                    while self.tracked_slots.len() > MAX_TRACKED_SLOTS {
+                        self.tracked_slots.pop_first();
+                    }
+                }
+                Ok(LocalRepairRequest::Repair(request, nonce)) => self.repair(&request, nonce),
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if last_catch_up.elapsed() >= CATCH_UP_INTERVAL {
+                self.catch_up();
+                last_catch_up = Instant::now();
+            }
+        }
+    }
+
+    fn catch_up(&self) {
+        let blockstore = self.handler.blockstore();
+        if let Err(err) = blockstore.try_catch_up_with_primary() {
+            warn!("Failed to catch up with the local repair ledger: {err}");
+        }
+        let slot_progress = self
+            .tracked_slots
+            .iter()
+            .filter_map(|slot| {
+                let meta = blockstore.meta(*slot).ok()??;
+                Some((
+                    *slot,
+                    SlotProgress {
+                        consumed: meta.consumed,
+                        received: meta.received,
+                    },
+                ))
+            })
+            .collect();
+        *self.slot_progress.write().unwrap() = slot_progress;
+    }
+
+    fn repair(&self, request: &ShredRepairType, nonce: Nonce) {
+        let from_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+        let packets =
+            match *request {
+                ShredRepairType::Shred(slot, index) => {
+                    self.handler
+                        .run_window_request(&self.recycler, &from_addr, slot, index, nonce)
+                }
+                ShredRepairType::HighestShred(slot, index) => self
+                    .handler
+                    .run_highest_window_request(&self.recycler, &from_addr, slot, index, nonce),
+                ShredRepairType::Orphan(slot) => self.handler.run_orphan(
+                    &self.recycler,
+                    &from_addr,
+                    slot,
+                    MAX_ORPHAN_REPAIR_RESPONSES,
+                    nonce,
+                ),
+            };
+        // Unanswered requests time out and are repaired again like those sent
+        // to network peers
+        let Some(packets) = packets else {
+            return;
+        };
+        for packet in packets.iter() {
+            let Some(bytes) = packet.data(..) else {
+                continue;
+            };
+            let response = (Pubkey::default(), from_addr, Bytes::from(Vec::from(bytes)));
+            if self.repair_response_sender.send(response).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_ledger::{
+            blockstore::make_many_slot_shreds, get_tmp_ledger_path_auto_delete, shred,
+        },
+        solana_perf::packet::{Packet, PacketFlags},
+        std::thread::sleep,
+    };
+
+    fn decode_response(bytes: &[u8]) -> (Vec<u8>, shred::Nonce) {
+        let mut packet = Packet::default();
+        packet.buffer_mut()[..bytes.len()].copy_from_slice(bytes);
+        packet.meta_mut().size = bytes.len();
+        packet.meta_mut().flags |= PacketFlags::REPAIR;
+        let (shred, nonce) = shred::layout::get_shred_and_repair_nonce(packet.as_ref()).unwrap();
+        (shred.to_vec(), nonce.unwrap())
+    }
+
+    #[test]
+    fn test_local_repair_source() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let sibling = Blockstore::open(ledger_path.path()).unwrap();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let source = LocalRepairSource::new(ledger_path.path(), sender).unwrap();
+        let mut outstanding_requests = OutstandingRequests::default();
+        let response_timeout = Duration::from_secs(5);
+
+        // Nothing to serve yet.
+        assert!(!source.repair(&ShredRepairType::Shred(1, 0), &mut outstanding_requests));
+        assert!(receiver.is_empty());
+
+        let (shreds, _) = make_many_slot_shreds(1, 1, 5);
+        sibling.insert_shreds(shreds.clone(), None, false).unwrap();
+        // The slot is answered locally once the serving thread caught up with
+        // the sibling's writes.
+        let start = Instant::now();
+        while !source.repair(&ShredRepairType::Shred(1, 2), &mut outstanding_requests) {
+            assert!(start.elapsed() < response_timeout);
+            sleep(CATCH_UP_INTERVAL / 2);
+        }
+        let (_, _, bytes) = receiver.recv_timeout(response_timeout).unwrap();
+        let (shred, nonce) = decode_response(&bytes);
+        assert_eq!(shred, shreds[2].payload().as_ref());
+        assert!(outstanding_requests
+            .register_response(nonce, &shred[..], timestamp(), |_| ())
+            .is_some());
+
+        assert!(source.repair(
+            &ShredRepairType::HighestShred(1, 0),
+            &mut outstanding_requests
+        ));
+        let (_, _, bytes) = receiver.recv_timeout(response_timeout).unwrap();
+        let (shred, nonce) = decode_response(&bytes);
+        assert_eq!(shred, shreds.last().unwrap().payload().as_ref());
+        assert!(outstanding_requests
+            .register_response(nonce, &shred[..], timestamp(), |_| ())
+            .is_some());
+
+        assert!(!source.repair(&ShredRepairType::Shred(2, 0), &mut outstanding_requests));
+        assert!(receiver.is_empty());
+    }
+}
@@ -1,12 +1,14 @@
 pub mod ancestor_hashes_service;
 pub mod cluster_slot_state_verifier;
 pub mod duplicate_repair_status;
+pub mod local_repair_source;
 pub(crate) mod malicious_repair_handler;
 pub mod outstanding_requests;
 pub mod packet_threshold;
 pub(crate) mod quic_endpoint;
 pub mod repair_generic_traversal;
 pub mod repair_handler;
+pub mod repair_peer_selection;
 pub mod repair_response;
 pub mod repair_service;
 pub mod repair_weight;
@@ -3,12 +3,42 @@ use {
     lru::LruCache,
     rand::{thread_rng, Rng},
     solana_ledger::shred::Nonce,
+    solana_pubkey::Pubkey,
 };
 
 pub const DEFAULT_REQUEST_EXPIRATION_MS: u64 = 60_000;
 
+// Number of peers whose response time is remembered
+const RESPONSE_TIMES_CAPACITY: usize = 4 * 1024;
+
 pub struct OutstandingRequests<T> {
     requests: LruCache<Nonce, RequestStatus<T>>,
+    response_times: PeerResponseTimes,
+}
+
+/// Smoothed time peers took to answer requests sent to them
+pub struct PeerResponseTimes(LruCache<Pubkey, u64>);
+
+impl PeerResponseTimes {
+    /// Milliseconds the peer usually takes to answer, if it answered any request yet
+    pub fn get(&self, peer: &Pubkey) -> Option<u64> {
+        self.0.peek(peer).copied()
+    }
+
+    pub(crate) fn record(&mut self, peer: Pubkey, response_time_ms: u64) {
+        // Exponential moving average, weighing the new sample by 1/8
+        let response_time_ms = match self.0.peek(&peer) {
+            Some(average) => average.saturating_mul(7).saturating_add(response_time_ms) / 8,
+            None => response_time_ms,
+        };
+        self.0.put(peer, response_time_ms);
+    }
+}
+
+impl Default for PeerResponseTimes {
+    fn default() -> Self {
+        Self(LruCache::new(RESPONSE_TIMES_CAPACITY))
+    }
 }
 
 impl<T, S: ?Sized> OutstandingRequests<T>
@@ -18,19 +48,34 @@ where
     // Returns boolean indicating whether sufficient time has passed for a request with
     // the given timestamp to be made
     pub fn add_request(&mut self, request: T, now: u64) -> Nonce {
+        self.do_add_request(request, None, now)
+    }
+
+    /// Like `add_request()`, also measuring the time `peer` takes to answer
+    pub fn add_request_to_peer(&mut self, request: T, peer: Pubkey, now: u64) -> Nonce {
+        self.do_add_request(request, Some(peer), now)
+    }
+
+    fn do_add_request(&mut self, request: T, peer: Option<Pubkey>, now: u64) -> Nonce {
         let num_expected_responses = request.num_expected_responses();
         let nonce = thread_rng().gen_range(0..Nonce::MAX);
         self.requests.put(
             nonce,
             RequestStatus {
+                sent_timestamp: now,
                 expire_timestamp: now + DEFAULT_REQUEST_EXPIRATION_MS,
                 num_expected_responses,
+                peer,
                 request,
             },
         );
         nonce
     }
 
+    pub fn response_times(&self) -> &PeerResponseTimes {
+        &self.response_times
+    }
+
     pub fn register_response<R>(
         &mut self,
         nonce: u32,
@@ -39,6 +84,7 @@ where
         // runs if the response was valid
         success_fn: impl Fn(&T) -> R,
     ) -> Option<R> {
+        let response_times = &mut self.response_times;
         let (response, should_delete) = self
             .requests
             .get_mut(&nonce)
@@ -48,6 +94,10 @@ where
                     && status.request.verify_response(response)
                 {
                     status.num_expected_responses -= 1;
+                    // Only the first response measures how fast the peer answers
+                    if let Some(peer) = status.peer.take() {
+                        response_times.record(peer, now.saturating_sub(status.sent_timestamp));
+                    }
                     (
                         Some(success_fn(&status.request)),
                         status.num_expected_responses == 0,
@@ -72,13 +122,17 @@ impl<T> Default for OutstandingRequests<T> {
     fn default() -> Self {
         Self {
             requests: LruCache::new(16 * 1024),
+            response_times: PeerResponseTimes::default(),
         }
     }
 }
 
 pub struct RequestStatus<T> {
+    sent_timestamp: u64,
     expire_timestamp: u64,
     num_expected_responses: u32,
+    // Peer the request was sent to, until it answers
+    peer: Option<Pubkey>,
     request: T,
 }
 
@@ -200,4 +254,42 @@ pub(crate) mod tests {
         }
         assert!(outstanding_requests.requests.get(&nonce).is_none());
     }
+
+    #[test]
+    fn test_response_times() {
+        let repair_type = ShredRepairType::Orphan(9);
+        let mut outstanding_requests = OutstandingRequests::default();
+        let shred = Shred::new_from_data(0, 0, 0, &[], ShredFlags::empty(), 0, 0, 0);
+        let peer = Pubkey::new_unique();
+        let now = timestamp();
+
+        // Requests not sent to a known peer are not measured
+        let nonce = outstanding_requests.add_request(repair_type, now);
+        assert!(outstanding_requests
+            .register_response(nonce, shred.payload(), now + 10, |_| ())
+            .is_some());
+        assert_eq!(outstanding_requests.response_times().get(&peer), None);
+
+        // Only the first response to a request is measured
+        let nonce = outstanding_requests.add_request_to_peer(repair_type, peer, now);
+        for delay in [80, 500] {
+            assert!(outstanding_requests
+                .register_response(nonce, shred.payload(), now + delay, |_| ())
+                .is_some());
+        }
+        assert_eq!(outstanding_requests.response_times().get(&peer), Some(80));
+
+        // Later responses are averaged in
+        let nonce = outstanding_requests.add_request_to_peer(repair_type, peer, now);
+        assert!(outstanding_requests
+            .register_response(nonce, shred.payload(), now + 8, |_| ())
+            .is_some());
+        assert_eq!(outstanding_requests.response_times().get(&peer), Some(71));
+        assert_eq!(
+            outstanding_requests
+                .response_times()
+                .get(&Pubkey::new_unique()),
+            None
+        );
+    }
 }
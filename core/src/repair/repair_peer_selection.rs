@@ -0,0 +1,209 @@
+//! Strategies for choosing the peers shred repair requests are sent to.
+//!
+//! `ServeRepair::repair_request` samples a peer for each request with weights
+//! computed by `ClusterSlots` from the peers' stake and the stake which has
+//! confirmed the slot. A [`RepairPeerSelection`] then adjusts those weights,
+//! e.g. to favor nodes co-located with this one, using how fast peers answered
+//! earlier repair requests.
+
+use {
+    crate::repair::outstanding_requests::PeerResponseTimes, solana_clock::Slot,
+    solana_gossip::contact_info::ContactInfo, solana_pubkey::Pubkey, std::collections::HashSet,
+};
+
+// Preferred peers collectively get this many times the weight of all other
+// peers, so repairs still make progress if they lack the requested shreds.
+const PREFERRED_PEERS_WEIGHT_MULTIPLIER: u64 = 9;
+
+pub trait RepairPeerSelection {
+    /// Adjusts the sampling `weights` of `peers`, index for index, for a
+    /// repair request for `slot`.
+    fn adjust_weights(
+        &self,
+        slot: Slot,
+        peers: &[ContactInfo],
+        response_times: &PeerResponseTimes,
+        weights: &mut [u64],
+    );
+}
+
+/// Samples peers by the weights computed from stake and slot support alone.
+pub struct StakeWeightedRepairPeers;
+
+impl RepairPeerSelection for StakeWeightedRepairPeers {
+    fn adjust_weights(
+        &self,
+        _slot: Slot,
+        _peers: &[ContactInfo],
+        _response_times: &PeerResponseTimes,
+        _weights: &mut [u64],
+    ) {
+    }
+}
+
+/// Sends most repair requests to a set of preferred peers, typically nodes
+/// in the same rack or data center, ranked by how fast they answered earlier
+/// requests so that the closest of them get the most requests.
+pub struct PreferredRepairPeers {
+    preferred: HashSet<Pubkey>,
+}
+
+impl PreferredRepairPeers {
+    pub fn new(preferred: HashSet<Pubkey>) -> Self {
+        Self { preferred }
+    }
+}
+
+impl RepairPeerSelection for PreferredRepairPeers {
+    fn adjust_weights(
+        &self,
+        _slot: Slot,
+        peers: &[ContactInfo],
+        response_times: &PeerResponseTimes,
+        weights: &mut [u64],
+    ) {
+        // How fast each preferred peer answers, the inverse of its response
+        // time, or None if it never answered yet. Preferred peers without
+        // weight, e.g. because they don't have the slot, are left out.
+        let preferred_speeds: Vec<Option<Option<u64>>> = peers
+            .iter()
+            .zip(weights.iter())
+            .map(|(peer, weight)| {
+                (*weight > 0 && self.preferred.contains(peer.pubkey())).then(|| {
+                    response_times
+                        .get(peer.pubkey())
+                        .map(|response_time_ms| 1_000_000 / response_time_ms.max(1))
+                })
+            })
+            .collect();
+        if preferred_speeds.iter().all(Option::is_none) {
+            return;
+        }
+        let other_weight: u64 = preferred_speeds
+            .iter()
+            .zip(weights.iter())
+            .filter(|(speed, _)| speed.is_none())
+            .map(|(_, weight)| *weight)
+            .fold(0, u64::saturating_add);
+        // Peers which never answered are deemed as fast as the fastest one, so
+        // that they are tried and measured too
+        let fastest = preferred_speeds.iter().flatten().flatten().max();
+        let speeds: Vec<Option<u64>> = preferred_speeds
+            .iter()
+            .map(|speed| speed.map(|speed| speed.or(fastest.copied()).unwrap_or(1)))
+            .collect();
+        let total_speed = u128::from(speeds.iter().flatten().sum::<u64>());
+        let preferred_weight =
+            u128::from(other_weight.saturating_mul(PREFERRED_PEERS_WEIGHT_MULTIPLIER));
+        for (speed, weight) in speeds.into_iter().zip(weights.iter_mut()) {
+            if let Some(speed) = speed {
+                *weight = if other_weight == 0 {
+                    // Only preferred peers are candidates, they are ranked by
+                    // how fast they answer alone
+                    speed
+                } else {
+                    let share = preferred_weight * u128::from(speed) / total_speed;
+                    u64::try_from(share).unwrap_or(u64::MAX)
+                }
+                .max(1);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum RepairPeerSelectionType {
+    #[default]
+    StakeWeighted,
+    Preferred(HashSet<Pubkey>),
+}
+
+impl RepairPeerSelectionType {
+    pub fn to_selection(&self) -> Box<dyn RepairPeerSelection + Send + Sync> {
+        match self {
+            RepairPeerSelectionType::StakeWeighted => Box::new(StakeWeightedRepairPeers),
+            RepairPeerSelectionType::Preferred(preferred) => {
+                Box::new(PreferredRepairPeers::new(preferred.clone()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_time_utils::timestamp};
+
+    #[test]
+    fn test_preferred_repair_peers() {
+        let peers: Vec<_> = std::iter::repeat_with(|| {
+            ContactInfo::new_localhost(&Pubkey::new_unique(), timestamp())
+        })
+        .take(4)
+        .collect();
+        let selection = PreferredRepairPeers::new(HashSet::from([
+            *peers[1].pubkey(),
+            *peers[3].pubkey(),
+            Pubkey::new_unique(),
+        ]));
+        let mut response_times = PeerResponseTimes::default();
+
+        // Preferred peers share 9 times the weight of the others, evenly until
+        // they answered requests.
+        let mut weights = vec![10, 1, 30, 1];
+        selection.adjust_weights(0, &peers, &response_times, &mut weights);
+        assert_eq!(weights, vec![10, 180, 30, 180]);
+
+        // Peers which never answered are deemed as fast as the fastest one.
+        response_times.record(*peers[1].pubkey(), 2);
+        let mut weights = vec![10, 1, 30, 1];
+        selection.adjust_weights(0, &peers, &response_times, &mut weights);
+        assert_eq!(weights, vec![10, 180, 30, 180]);
+
+        // Then preferred peers get requests in inverse proportion to the time
+        // they take to answer, and other peers are not affected by how fast
+        // they answer.
+        response_times.record(*peers[3].pubkey(), 8);
+        response_times.record(*peers[0].pubkey(), 1);
+        let mut weights = vec![10, 1, 30, 1];
+        selection.adjust_weights(0, &peers, &response_times, &mut weights);
+        assert_eq!(weights, vec![10, 288, 30, 72]);
+
+        // Without preferred peers among the candidates the weights are unchanged.
+        let mut weights = vec![10, 30];
+        selection.adjust_weights(
+            0,
+            &[peers[0].clone(), peers[2].clone()],
+            &response_times,
+            &mut weights,
+        );
+        assert_eq!(weights, vec![10, 30]);
+
+        // When only preferred peers are candidates they are ranked by how fast
+        // they answer alone.
+        let mut weights = vec![1, 2];
+        selection.adjust_weights(
+            0,
+            &[peers[1].clone(), peers[3].clone()],
+            &response_times,
+            &mut weights,
+        );
+        assert_eq!(weights, vec![500_000, 125_000]);
+
+        // Preferred peers without weight for the slot keep it.
+        let mut weights = vec![10, 0, 30, 1];
+        selection.adjust_weights(0, &peers, &response_times, &mut weights);
+        assert_eq!(weights, vec![10, 0, 30, 360]);
+        let mut weights = vec![0, 0];
+        selection.adjust_weights(
+            0,
+            &[peers[1].clone(), peers[3].clone()],
+            &response_times,
+            &mut weights,
+        );
+        assert_eq!(weights, vec![0, 0]);
+
+        let mut weights = vec![10, 1, 30, 1];
+        StakeWeightedRepairPeers.adjust_weights(0, &peers, &response_times, &mut weights);
+        assert_eq!(weights, vec![10, 1, 30, 1]);
+    }
+}
@@ -10,7 +10,9 @@ use {
                 AncestorHashesChannels, AncestorHashesReplayUpdateReceiver, AncestorHashesService,
             },
             duplicate_repair_status::AncestorDuplicateSlotToRepair,
+            local_repair_source::LocalRepairSource,
             outstanding_requests::OutstandingRequests,
+            repair_peer_selection::RepairPeerSelection,
             repair_weight::RepairWeight,
             serve_repair::{
                 self, RepairPeers, RepairProtocol, RepairRequestHeader, ServeRepair,
@@ -157,6 +159,8 @@ pub struct RepairStats {
     pub shred: RepairStatsGroup,
     pub highest_shred: RepairStatsGroup,
     pub orphan: RepairStatsGroup,
+    // Requests answered from the local repair ledger
+    pub local_count: u64,
     pub get_best_orphans_us: u64,
     pub get_best_shreds_us: u64,
 }
@@ -173,7 +177,7 @@ impl RepairStats {
             .map(|(slot, slot_repairs)| (slot, slot_repairs.pubkey_repairs.values().sum::<u64>()))
             .collect();
         info!("repair_stats: {slot_to_count:?}");
-        if repair_total > 0 {
+        if repair_total > 0 || self.local_count > 0 {
             let nonzero_num = |x| if x == 0 { None } else { Some(x) };
             datapoint_info!(
                 "repair_service-my_requests",
@@ -181,6 +185,7 @@ impl RepairStats {
                 ("shred-count", self.shred.count, i64),
                 ("highest-shred-count", self.highest_shred.count, i64),
                 ("orphan-count", self.orphan.count, i64),
+                ("local-count", self.local_count, i64),
                 ("shred-slot-max", nonzero_num(self.shred.max), Option<i64>),
                 ("shred-slot-min", nonzero_num(self.shred.min), Option<i64>),
                 ("repair-highest-slot", self.highest_shred.max, i64), // deprecated
@@ -362,6 +367,10 @@ pub struct RepairInfo {
     pub repair_whitelist: Arc<RwLock<HashSet<Pubkey>>>,
     // A given list of slots to repair when in wen_restart
     pub wen_restart_repair_slots: Option<Arc<RwLock<Vec<Slot>>>>,
+    // Adjusts which peers repairs are requested from
+    pub repair_peer_selection: Arc<dyn RepairPeerSelection + Send + Sync>,
+    // Ledger of a node on the same host which is tried before any peer
+    pub local_repair_source: Option<Arc<LocalRepairSource>>,
 }
 
 pub struct RepairSlotRange {
@@ -635,6 +644,12 @@ impl RepairService {
             repairs
                 .into_iter()
                 .filter_map(|repair_request| {
+                    if let Some(local_repair_source) = &repair_info.local_repair_source {
+                        if local_repair_source.repair(&repair_request, &mut outstanding_requests) {
+                            repair_metrics.stats.local_count += 1;
+                            return None;
+                        }
+                    }
                     let (to, req) = serve_repair
                         .repair_request(
                             &repair_info.cluster_slots,
@@ -642,6 +657,7 @@ impl RepairService {
                             peers_cache,
                             &mut repair_metrics.stats,
                             &repair_info.repair_validators,
+                            repair_info.repair_peer_selection.as_ref(),
                             &mut outstanding_requests,
                             &repair_info.cluster_info.keypair(),
                             repair_request_quic_sender,
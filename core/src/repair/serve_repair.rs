@@ -10,6 +10,7 @@ use {
             duplicate_repair_status::get_ancestor_hash_repair_sample_size,
             quic_endpoint::RemoteRequest,
             repair_handler::RepairHandler,
+            repair_peer_selection::RepairPeerSelection,
             repair_service::{OutstandingShredRepairs, RepairStats, REPAIR_MS},
             request_response::RequestResponse,
             result::{Error, RepairVerifyError, Result},
@@ -1078,6 +1079,7 @@ impl ServeRepair {
         peers_cache: &mut LruCache<Slot, RepairPeers>,
         repair_stats: &mut RepairStats,
         repair_validators: &Option<HashSet<Pubkey>>,
+        repair_peer_selection: &dyn RepairPeerSelection,
         outstanding_requests: &mut OutstandingShredRepairs,
         identity_keypair: &Keypair,
         repair_request_quic_sender: &AsyncSender<(SocketAddr, Bytes)>,
@@ -1091,14 +1093,21 @@ impl ServeRepair {
             _ => {
                 peers_cache.pop(&slot);
                 let repair_peers = self.repair_peers(repair_validators, slot);
-                let weights = cluster_slots.compute_weights(slot, &repair_peers);
+                let mut weights = cluster_slots.compute_weights(slot, &repair_peers);
+                repair_peer_selection.adjust_weights(
+                    slot,
+                    &repair_peers,
+                    outstanding_requests.response_times(),
+                    &mut weights,
+                );
                 let repair_peers = RepairPeers::new(Instant::now(), &repair_peers, &weights)?;
                 peers_cache.put(slot, repair_peers);
                 peers_cache.get(&slot).unwrap()
             }
         };
         let peer = repair_peers.sample(&mut rand::thread_rng());
-        let nonce = outstanding_requests.add_request(repair_request, timestamp());
+        let nonce =
+            outstanding_requests.add_request_to_peer(repair_request, peer.pubkey, timestamp());
         let out = self.map_repair_request(
             &repair_request,
             &peer.pubkey,
@@ -1336,7 +1345,7 @@ fn send_response(
 mod tests {
     use {
         super::*,
-        crate::repair::repair_response,
+        crate::repair::{repair_peer_selection::StakeWeightedRepairPeers, repair_response},
         agave_feature_set::FeatureSet,
         solana_gossip::{contact_info::ContactInfo, socketaddr, socketaddr_any},
         solana_hash::Hash,
@@ -1921,6 +1930,7 @@ mod tests {
                 &mut LruCache::new(100),
                 &mut RepairStats::default(),
                 &None,
+                &StakeWeightedRepairPeers,
                 &mut outstanding_requests,
                 &identity_keypair,
                 &repair_request_quic_sender,
@@ -1960,6 +1970,7 @@ mod tests {
                     &mut LruCache::new(100),
                     &mut RepairStats::default(),
                     &None,
+                    &StakeWeightedRepairPeers,
                     &mut outstanding_requests,
                     &identity_keypair,
                     &repair_request_quic_sender,
@@ -2204,6 +2215,7 @@ mod tests {
                     &mut LruCache::new(100),
                     &mut RepairStats::default(),
                     &known_validators,
+                    &StakeWeightedRepairPeers,
                     &mut OutstandingShredRepairs::default(),
                     &identity_keypair,
                     &repair_request_quic_sender,
@@ -2225,6 +2237,7 @@ mod tests {
                 &mut LruCache::new(100),
                 &mut RepairStats::default(),
                 &known_validators,
+                &StakeWeightedRepairPeers,
                 &mut OutstandingShredRepairs::default(),
                 &identity_keypair,
                 &repair_request_quic_sender,
@@ -2250,6 +2263,7 @@ mod tests {
                 &mut LruCache::new(100),
                 &mut RepairStats::default(),
                 &None,
+                &StakeWeightedRepairPeers,
                 &mut OutstandingShredRepairs::default(),
                 &identity_keypair,
                 &repair_request_quic_sender,
@@ -16,6 +16,7 @@ use {
             progress_map::{ForkProgress, ProgressMap, PropagatedStats},
             tower_storage::{SavedTower, SavedTowerVersions, TowerStorage},
             tower_vote_state::TowerVoteState,
+            vote_decision_history::VoteDecisionHistory,
             BlockhashStatus, ComputedBankState, Stake, SwitchForkDecision, Tower, TowerError,
             VotedStakes, SWITCH_FORK_THRESHOLD,
         },
@@ -277,6 +278,7 @@ pub struct ReplayStageConfig {
     pub prioritization_fee_cache: Arc<PrioritizationFeeCache>,
     pub banking_tracer: Arc<BankingTracer>,
     pub snapshot_controller: Option<Arc<SnapshotController>>,
+    pub vote_decision_history: Arc<VoteDecisionHistory>,
 }
 
 pub struct ReplaySenders {
@@ -577,6 +579,7 @@ impl ReplayStage {
             prioritization_fee_cache,
             banking_tracer,
             snapshot_controller,
+            vote_decision_history,
         } = config;
 
         let ReplaySenders {
@@ -841,7 +844,7 @@ impl ReplayStage {
                 // Check to remove any duplicated slots from fork choice
                 let mut process_duplicate_slots_time = Measure::start("process_duplicate_slots");
                 if !tpu_has_bank {
-                    Self::process_duplicate_slots(
+                    let new_duplicate_slots = Self::process_duplicate_slots(
                         &blockstore,
                         &duplicate_slots_receiver,
                         &mut duplicate_slots_tracker,
@@ -854,6 +857,9 @@ impl ReplayStage {
                         &ancestor_hashes_replay_update_sender,
                         &mut purge_repair_slot_counter,
                     );
+                    for (duplicate_slot, bank_hash) in new_duplicate_slots {
+                        vote_decision_history.record_duplicate_slot(duplicate_slot, bank_hash);
+                    }
                 }
                 process_duplicate_slots_time.stop();
 
@@ -963,6 +969,17 @@ impl ReplayStage {
 
                 let mut heaviest_fork_failures_time = Measure::start("heaviest_fork_failures_time");
                 if tower.is_recent(heaviest_bank.slot()) && !heaviest_fork_failures.is_empty() {
+                    if vote_bank.is_none() {
+                        vote_decision_history.record_no_vote(
+                            heaviest_bank.slot(),
+                            tower.last_voted_slot(),
+                            tower
+                                .last_switch_threshold_check
+                                .as_ref()
+                                .map(|(_slot, switch_fork_decision)| switch_fork_decision),
+                            &heaviest_fork_failures,
+                        );
+                    }
                     Self::log_heaviest_fork_failures(
                         &heaviest_fork_failures,
                         &bank_forks,
@@ -1020,6 +1037,11 @@ impl ReplayStage {
                         error!("Unable to set root: {e}");
                         return;
                     }
+                    vote_decision_history.record_vote(
+                        vote_bank.slot(),
+                        bank_forks.read().unwrap().highest_slot(),
+                        switch_fork_decision,
+                    );
                 }
                 voting_time.stop();
 
@@ -1126,7 +1148,7 @@ impl ReplayStage {
                 //
                 // Has to be before `maybe_start_leader()`. Otherwise, `ancestors` and `descendants`
                 // will be outdated, and we cannot assume `poh_bank` will be in either of these maps.
-                Self::dump_then_repair_correct_slots(
+                let dumped_slots = Self::dump_then_repair_correct_slots(
                     &mut duplicate_slots_to_repair,
                     &mut ancestors,
                     &mut descendants,
@@ -1139,6 +1161,9 @@ impl ReplayStage {
                     &my_pubkey,
                     &leader_schedule_cache,
                 );
+                for (dumped_slot, correct_hash) in dumped_slots {
+                    vote_decision_history.record_dumped_duplicate_slot(dumped_slot, correct_hash);
+                }
                 dump_then_repair_correct_slots_time.stop();
 
                 let mut retransmit_not_propagated_time =
@@ -1268,6 +1293,20 @@ impl ReplayStage {
                     vote_account,
                 ))
             }
+            // Without the lease this instance doesn't vote, see
+            // TowerStorage::holds_lease(), so there is no tower to protect
+            // until set-identity is called again
+            Err(err) if err.is_lease_error() => {
+                warn!(
+                    "Failed to load tower, lease not acquired for {node_pubkey}: {err}. Creating \
+                     a new tower from bankforks and not voting."
+                );
+                Ok(Tower::new_from_bankforks(
+                    &bank_forks.read().unwrap(),
+                    node_pubkey,
+                    vote_account,
+                ))
+            }
             Err(err) => Err(err),
         }
     }
@@ -1494,9 +1533,9 @@ impl ReplayStage {
         dumped_slots_sender: &DumpedSlotsSender,
         my_pubkey: &Pubkey,
         leader_schedule_cache: &LeaderScheduleCache,
-    ) {
+    ) -> Vec<(Slot, Hash)> {
         if duplicate_slots_to_repair.is_empty() {
-            return;
+            return vec![];
         }
 
         let root_bank = bank_forks.read().unwrap().root_bank();
@@ -1625,7 +1664,8 @@ impl ReplayStage {
 
         // Notify repair of the dumped slots along with the correct hash
         trace!("Dumped {} slots", dumped.len());
-        dumped_slots_sender.send(dumped).unwrap();
+        dumped_slots_sender.send(dumped.clone()).unwrap();
+        dumped
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1938,7 +1978,8 @@ impl ReplayStage {
         }
     }
 
-    // Checks for and handle forks with duplicate slots.
+    // Checks for and handle forks with duplicate slots. Returns the new duplicate
+    // slots along with the hash of the version replayed by this node.
     #[allow(clippy::too_many_arguments)]
     fn process_duplicate_slots(
         blockstore: &Blockstore,
@@ -1952,7 +1993,7 @@ impl ReplayStage {
         duplicate_slots_to_repair: &mut DuplicateSlotsToRepair,
         ancestor_hashes_replay_update_sender: &AncestorHashesReplayUpdateSender,
         purge_repair_slot_counter: &mut PurgeRepairSlotCounter,
-    ) {
+    ) -> Vec<(Slot, Option<Hash>)> {
         let new_duplicate_slots: Vec<Slot> = duplicate_slots_receiver.try_iter().collect();
         let (root_slot, bank_hashes) = {
             let r_bank_forks = bank_forks.read().unwrap();
@@ -1963,9 +2004,9 @@ impl ReplayStage {
 
             (r_bank_forks.root(), bank_hashes)
         };
-        for (duplicate_slot, bank_hash) in
-            new_duplicate_slots.into_iter().zip(bank_hashes.into_iter())
-        {
+        let new_duplicate_slots: Vec<_> =
+            new_duplicate_slots.into_iter().zip(bank_hashes).collect();
+        for &(duplicate_slot, bank_hash) in &new_duplicate_slots {
             // WindowService should only send the signal once per slot
             let duplicate_state = DuplicateState::new_from_state(
                 duplicate_slot,
@@ -1987,6 +2028,7 @@ impl ReplayStage {
                 SlotStateUpdate::Duplicate(duplicate_state),
             );
         }
+        new_duplicate_slots
     }
 
     fn log_leader_change(
@@ -4316,6 +4358,7 @@ pub(crate) mod tests {
         crate::{
             consensus::{
                 progress_map::{ValidatorStakeInfo, RETRANSMIT_BASE_DELAY_MS},
+                tower_lease::{FileTowerLease, LeasedTowerStorage, DEFAULT_TOWER_LEASE_DURATION},
                 tower_storage::{FileTowerStorage, NullTowerStorage},
                 tree_diff::TreeDiff,
                 ThresholdDecision, Tower, VOTE_THRESHOLD_DEPTH,
@@ -7361,7 +7404,7 @@ pub(crate) mod tests {
             vote_bank
                 .map(|(bank, switch_decision)| (bank.slot(), switch_decision))
                 .unwrap(),
-            (7, SwitchForkDecision::SwitchProof(_))
+            (7, SwitchForkDecision::SwitchProof(..))
         );
         assert_eq!(reset_bank.unwrap().slot(), 7);
         assert!(heaviest_fork_failures.is_empty());
@@ -7465,7 +7508,7 @@ pub(crate) mod tests {
             vote_bank
                 .map(|(bank, switch_decision)| (bank.slot(), switch_decision))
                 .unwrap(),
-            (5, SwitchForkDecision::SwitchProof(_))
+            (5, SwitchForkDecision::SwitchProof(..))
         );
         assert_eq!(reset_bank.unwrap().slot(), 5);
         assert!(heaviest_fork_failures.is_empty());
@@ -9101,6 +9144,35 @@ pub(crate) mod tests {
         assert_eq!(tower.node_pubkey, expected_tower.node_pubkey);
     }
 
+    #[test]
+    fn test_tower_load_lease_held() {
+        let tower_file = tempdir().unwrap().keep();
+        let new_tower_storage = || {
+            LeasedTowerStorage::new(
+                Arc::new(FileTowerStorage::new(tower_file.clone())),
+                Box::new(FileTowerLease::new(
+                    tower_file.clone(),
+                    DEFAULT_TOWER_LEASE_DURATION,
+                )),
+            )
+        };
+        let primary = new_tower_storage();
+        let standby = new_tower_storage();
+        let node_pubkey = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let tree = tr(0) / (tr(1) / (tr(3) / (tr(4))) / (tr(2) / (tr(5) / (tr(6)))));
+        let (vote_simulator, _blockstore) = setup_forks_from_tree(tree, 3, None);
+        let bank_forks = vote_simulator.bank_forks;
+        primary.acquire_lease(&node_pubkey).unwrap();
+
+        // The standby keeps running, without voting, while the primary holds
+        // the lease
+        let tower =
+            ReplayStage::load_tower(&standby, &node_pubkey, &vote_account, &bank_forks).unwrap();
+        assert_eq!(tower.node_pubkey, node_pubkey);
+        assert!(!standby.holds_lease(&node_pubkey));
+    }
+
     #[test]
     fn test_initialize_progress_and_fork_choice_with_duplicates() {
         solana_logger::setup();
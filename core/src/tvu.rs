@@ -10,10 +10,16 @@ use {
         },
         cluster_slots_service::{cluster_slots::ClusterSlots, ClusterSlotsService},
         completed_data_sets_service::CompletedDataSetsSender,
-        consensus::{tower_storage::TowerStorage, Tower},
+        consensus::{
+            tower_storage::TowerStorage, vote_decision_history::VoteDecisionHistory, Tower,
+        },
         cost_update_service::CostUpdateService,
         drop_bank_service::DropBankService,
-        repair::repair_service::{OutstandingShredRepairs, RepairInfo, RepairServiceChannels},
+        repair::{
+            local_repair_source::LocalRepairSource,
+            repair_peer_selection::RepairPeerSelectionType,
+            repair_service::{OutstandingShredRepairs, RepairInfo, RepairServiceChannels},
+        },
         replay_stage::{ReplayReceivers, ReplaySenders, ReplayStage, ReplayStageConfig},
         shred_fetch_stage::{ShredFetchStage, SHRED_FETCH_CHANNEL_SIZE},
         voting_service::VotingService,
@@ -31,8 +37,10 @@ use {
     },
     solana_keypair::Keypair,
     solana_ledger::{
-        blockstore::Blockstore, blockstore_cleanup_service::BlockstoreCleanupService,
-        blockstore_processor::TransactionStatusSender, entry_notifier_service::EntryNotifierSender,
+        blockstore::{Blockstore, ColumnGroup},
+        blockstore_cleanup_service::{BlockstoreCleanupService, RetentionPolicy},
+        blockstore_processor::TransactionStatusSender,
+        entry_notifier_service::EntryNotifierSender,
         leader_schedule_cache::LeaderScheduleCache,
     },
     solana_poh::poh_recorder::PohRecorder,
@@ -47,9 +55,11 @@ use {
         vote_sender_types::ReplayVoteSender,
     },
     solana_streamer::evicting_sender::EvictingSender,
-    solana_turbine::{retransmit_stage::RetransmitStage, xdp::XdpSender},
+    solana_turbine::{
+        retransmit_stage::RetransmitStage, shred_arrivals::ShredArrivals, xdp::XdpSender,
+    },
     std::{
-        collections::HashSet,
+        collections::{HashMap, HashSet},
         net::{SocketAddr, UdpSocket},
         num::NonZeroUsize,
         sync::{atomic::AtomicBool, Arc, RwLock},
@@ -90,30 +100,45 @@ pub struct TvuSockets {
 
 pub struct TvuConfig {
     pub max_ledger_shreds: Option<u64>,
+    // Column groups retained separately from max_ledger_shreds
+    pub blockstore_retention: HashMap<ColumnGroup, RetentionPolicy>,
     pub shred_version: u16,
     // Validators from which repairs are requested
     pub repair_validators: Option<HashSet<Pubkey>>,
     // Validators which should be given priority when serving repairs
     pub repair_whitelist: Arc<RwLock<HashSet<Pubkey>>>,
+    // Adjusts which peers repairs are requested from
+    pub repair_peer_selection: RepairPeerSelectionType,
+    // Ledger of a node on the same host which is tried before any peer
+    pub local_repair_source: Option<Arc<LocalRepairSource>>,
     pub wait_for_vote_to_start_leader: bool,
     pub replay_forks_threads: NonZeroUsize,
     pub replay_transactions_threads: NonZeroUsize,
     pub shred_sigverify_threads: NonZeroUsize,
     pub xdp_sender: Option<XdpSender>,
+    // Records shred arrival times and turbine tree positions
+    pub shred_arrivals: Option<Arc<ShredArrivals>>,
+    // Records the voting decisions of replay
+    pub vote_decision_history: Arc<VoteDecisionHistory>,
 }
 
 impl Default for TvuConfig {
     fn default() -> Self {
         Self {
             max_ledger_shreds: None,
+            blockstore_retention: HashMap::default(),
             shred_version: 0,
             repair_validators: None,
             repair_whitelist: Arc::new(RwLock::new(HashSet::default())),
+            repair_peer_selection: RepairPeerSelectionType::default(),
+            local_repair_source: None,
             wait_for_vote_to_start_leader: false,
             replay_forks_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             replay_transactions_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             shred_sigverify_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             xdp_sender: None,
+            shred_arrivals: None,
+            vote_decision_history: Arc::default(),
         }
     }
 }
@@ -214,6 +239,7 @@ impl Tvu {
             retransmit_sender.clone(),
             verified_sender,
             tvu_config.shred_sigverify_threads,
+            tvu_config.shred_arrivals.clone(),
         );
 
         let retransmit_stage = RetransmitStage::new(
@@ -227,6 +253,7 @@ impl Tvu {
             rpc_subscriptions.clone(),
             slot_status_notifier.clone(),
             tvu_config.xdp_sender,
+            tvu_config.shred_arrivals,
         );
 
         let (ancestor_duplicate_slots_sender, ancestor_duplicate_slots_receiver) = unbounded();
@@ -251,6 +278,8 @@ impl Tvu {
                 cluster_info: cluster_info.clone(),
                 cluster_slots: cluster_slots.clone(),
                 wen_restart_repair_slots,
+                repair_peer_selection: Arc::from(tvu_config.repair_peer_selection.to_selection()),
+                local_repair_source: tvu_config.local_repair_source,
             };
             let repair_service_channels = RepairServiceChannels::new(
                 repair_request_quic_sender,
@@ -342,6 +371,7 @@ impl Tvu {
             prioritization_fee_cache: prioritization_fee_cache.clone(),
             banking_tracer,
             snapshot_controller,
+            vote_decision_history: tvu_config.vote_decision_history,
         };
 
         let voting_service = VotingService::new(
@@ -360,7 +390,7 @@ impl Tvu {
             &exit,
         );
 
-        let cost_update_service = CostUpdateService::new(cost_update_receiver);
+        let cost_update_service = CostUpdateService::new(blockstore.clone(), cost_update_receiver);
 
         let drop_bank_service = DropBankService::new(drop_bank_receiver);
 
@@ -374,8 +404,20 @@ impl Tvu {
             )?)
         };
 
-        let blockstore_cleanup_service = tvu_config.max_ledger_shreds.map(|max_ledger_shreds| {
-            BlockstoreCleanupService::new(blockstore.clone(), max_ledger_shreds, exit.clone())
+        let blockstore_cleanup_service = (tvu_config.max_ledger_shreds.is_some()
+            || !tvu_config.blockstore_retention.is_empty())
+        .then(|| {
+            let mut retention = tvu_config.blockstore_retention.clone();
+            if let Some(max_ledger_shreds) = tvu_config.max_ledger_shreds {
+                retention
+                    .entry(ColumnGroup::Shreds)
+                    .or_insert(RetentionPolicy::MaxShreds(max_ledger_shreds));
+            }
+            BlockstoreCleanupService::new_with_retention(
+                blockstore.clone(),
+                retention,
+                exit.clone(),
+            )
         });
 
         let duplicate_shred_listener = DuplicateShredListener::new(
@@ -10,12 +10,15 @@ use {
         consensus::{
             reconcile_blockstore_roots_with_external_source,
             tower_storage::{NullTowerStorage, TowerStorage},
+            vote_decision_history::{VoteDecisionHistory, VOTE_DECISION_HISTORY_CAPACITY},
             ExternalRootSource, Tower,
         },
         repair::{
             self,
+            local_repair_source::LocalRepairSource,
             quic_endpoint::{RepairQuicAsyncSenders, RepairQuicSenders, RepairQuicSockets},
             repair_handler::RepairHandlerType,
+            repair_peer_selection::RepairPeerSelectionType,
             serve_repair_service::ServeRepairService,
         },
         sample_performance_service::SamplePerformanceService,
@@ -62,9 +65,10 @@ use {
     solana_ledger::{
         bank_forks_utils,
         blockstore::{
-            Blockstore, BlockstoreError, PurgeType, MAX_COMPLETED_SLOTS_IN_CHANNEL,
-            MAX_REPLAY_WAKE_UP_SIGNALS,
+            shred_archive::ShredArchiveError, Blockstore, BlockstoreError, ColumnGroup, PurgeType,
+            MAX_COMPLETED_SLOTS_IN_CHANNEL, MAX_REPLAY_WAKE_UP_SIGNALS,
         },
+        blockstore_cleanup_service::RetentionPolicy,
         blockstore_metric_report_service::BlockstoreMetricReportService,
         blockstore_options::{BlockstoreOptions, BLOCKSTORE_DIRECTORY_ROCKS_LEVEL},
         blockstore_processor::{self, TransactionStatusSender},
@@ -125,6 +129,7 @@ use {
     solana_turbine::{
         self,
         broadcast_stage::BroadcastStageType,
+        shred_arrivals::{ShredArrivals, SHRED_ARRIVALS_NUM_SLOTS},
         xdp::{XdpConfig, XdpRetransmitter},
     },
     solana_unified_scheduler_pool::DefaultSchedulerPool,
@@ -134,6 +139,8 @@ use {
     std::{
         borrow::Cow,
         collections::{HashMap, HashSet},
+        fs::File,
+        io::BufReader,
         net::SocketAddr,
         num::NonZeroUsize,
         path::{Path, PathBuf},
@@ -238,6 +245,8 @@ pub struct ValidatorConfig {
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: SnapshotConfig,
     pub max_ledger_shreds: Option<u64>,
+    /// Column groups retained separately from `max_ledger_shreds`
+    pub blockstore_retention: HashMap<ColumnGroup, RetentionPolicy>,
     pub blockstore_options: BlockstoreOptions,
     pub broadcast_stage_type: BroadcastStageType,
     pub turbine_disabled: Arc<AtomicBool>,
@@ -296,6 +305,19 @@ pub struct ValidatorConfig {
     pub use_tpu_client_next: bool,
     pub retransmit_xdp: Option<XdpConfig>,
     pub repair_handler_type: RepairHandlerType,
+    /// Programs which are never evicted from the program cache
+    pub program_cache_pinned_programs: Vec<Pubkey>,
+    /// Programs which are loaded into the program cache at startup
+    pub program_cache_prewarm_programs: Vec<Pubkey>,
+    /// Shred archives inserted into the blockstore at startup
+    pub shred_archives_to_import: Vec<PathBuf>,
+    pub repair_peer_selection: RepairPeerSelectionType,
+    /// Record shred arrival times and turbine tree positions of recent slots
+    pub shred_arrival_telemetry: bool,
+    /// Ledger of another node on the same host to repair from before the network
+    pub local_repair_ledger_path: Option<PathBuf>,
+    /// File the voting decisions of replay are appended to as JSON lines
+    pub vote_decision_log_path: Option<PathBuf>,
 }
 
 impl ValidatorConfig {
@@ -310,6 +332,7 @@ impl ValidatorConfig {
             expected_shred_version: None,
             voting_disabled: false,
             max_ledger_shreds: None,
+            blockstore_retention: HashMap::default(),
             blockstore_options: BlockstoreOptions::default_for_tests(),
             account_paths: Vec::new(),
             account_snapshot_paths: Vec::new(),
@@ -376,6 +399,13 @@ impl ValidatorConfig {
             use_tpu_client_next: true,
             retransmit_xdp: None,
             repair_handler_type: RepairHandlerType::default(),
+            program_cache_pinned_programs: Vec::new(),
+            program_cache_prewarm_programs: Vec::new(),
+            shred_archives_to_import: Vec::new(),
+            repair_peer_selection: RepairPeerSelectionType::default(),
+            shred_arrival_telemetry: false,
+            local_repair_ledger_path: None,
+            vote_decision_log_path: None,
         }
     }
 
@@ -780,6 +810,8 @@ impl Validator {
             check_poh_speed(&bank_forks.read().unwrap().root_bank(), None)?;
         }
 
+        prepare_program_cache(config, &bank_forks.read().unwrap().root_bank());
+
         let (root_slot, hard_forks) = {
             let root_bank = bank_forks.read().unwrap().root_bank();
             (root_bank.slot(), root_bank.hard_forks())
@@ -892,6 +924,12 @@ impl Validator {
         let prioritization_fee_cache = Arc::new(PrioritizationFeeCache::default());
 
         let leader_schedule_cache = Arc::new(leader_schedule_cache);
+        import_shred_archives(
+            config,
+            &blockstore,
+            &leader_schedule_cache,
+            &bank_forks.read().unwrap().root_bank(),
+        )?;
         let startup_verification_complete;
         let (mut poh_recorder, entry_receiver) = {
             let bank = &bank_forks.read().unwrap().working_bank();
@@ -1293,6 +1331,19 @@ impl Validator {
         );
         let (repair_request_quic_sender, repair_request_quic_receiver) = unbounded();
         let (repair_response_quic_sender, repair_response_quic_receiver) = unbounded();
+        let local_repair_source = config
+            .local_repair_ledger_path
+            .as_deref()
+            .map(|ledger_path| {
+                LocalRepairSource::new(ledger_path, repair_response_quic_sender.clone())
+                    .map(Arc::new)
+                    .map_err(|err| {
+                        ValidatorError::Other(format!(
+                            "Failed to open the local repair ledger at {ledger_path:?}: {err}"
+                        ))
+                    })
+            })
+            .transpose()?;
         let (ancestor_hashes_response_quic_sender, ancestor_hashes_response_quic_receiver) =
             unbounded();
 
@@ -1464,6 +1515,20 @@ impl Validator {
                 (None, None)
             };
 
+        let shred_arrivals = config
+            .shred_arrival_telemetry
+            .then(|| Arc::new(ShredArrivals::new(SHRED_ARRIVALS_NUM_SLOTS)));
+        let vote_decision_history = Arc::new(match &config.vote_decision_log_path {
+            Some(log_path) => {
+                VoteDecisionHistory::new_with_log_file(VOTE_DECISION_HISTORY_CAPACITY, log_path)
+                    .map_err(|err| {
+                        ValidatorError::Other(format!(
+                            "Failed to open the vote decision log {log_path:?}: {err}"
+                        ))
+                    })?
+            }
+            None => VoteDecisionHistory::default(),
+        });
         let tvu = Tvu::new(
             vote_account,
             authorized_voter_keypairs,
@@ -1497,14 +1562,19 @@ impl Validator {
             duplicate_confirmed_slots_receiver,
             TvuConfig {
                 max_ledger_shreds: config.max_ledger_shreds,
+                blockstore_retention: config.blockstore_retention.clone(),
                 shred_version: node.info.shred_version(),
                 repair_validators: config.repair_validators.clone(),
                 repair_whitelist: config.repair_whitelist.clone(),
+                repair_peer_selection: config.repair_peer_selection.clone(),
+                local_repair_source,
                 wait_for_vote_to_start_leader,
                 replay_forks_threads: config.replay_forks_threads,
                 replay_transactions_threads: config.replay_transactions_threads,
                 shred_sigverify_threads: config.tvu_shred_sigverify_threads,
                 xdp_sender: xdp_sender.clone(),
+                shred_arrivals: shred_arrivals.clone(),
+                vote_decision_history: vote_decision_history.clone(),
             },
             &max_slots,
             block_metadata_notifier,
@@ -1651,6 +1721,17 @@ impl Validator {
             outstanding_repair_requests,
             cluster_slots,
             gossip_socket: Some(node.sockets.gossip.clone()),
+            blockstore: blockstore.clone(),
+            leader_schedule_cache: leader_schedule_cache.clone(),
+            shred_arrivals,
+            rpc_request_count: json_rpc_service
+                .as_ref()
+                .map(|json_rpc_service| json_rpc_service.request_count()),
+            rpc_health: json_rpc_service
+                .as_ref()
+                .map(|json_rpc_service| json_rpc_service.health()),
+            snapshot_controller,
+            vote_decision_history,
         });
 
         Ok(Self {
@@ -1846,6 +1927,62 @@ fn active_vote_account_exists_in_bank(bank: &Bank, vote_account: &Pubkey) -> boo
     false
 }
 
+fn prepare_program_cache(config: &ValidatorConfig, root_bank: &Bank) {
+    {
+        let program_cache = root_bank.program_cache();
+        let mut program_cache = program_cache.write().unwrap();
+        for program_id in &config.program_cache_pinned_programs {
+            program_cache.pin_program(*program_id);
+        }
+    }
+    if !config.program_cache_prewarm_programs.is_empty() {
+        let mut timer = Measure::start("prewarm program cache");
+        let num_loaded = root_bank.prewarm_program_cache(&config.program_cache_prewarm_programs);
+        timer.stop();
+        info!(
+            "Prewarmed {num_loaded} of {} programs in the program cache. {timer}",
+            config.program_cache_prewarm_programs.len()
+        );
+    }
+}
+
+fn import_shred_archives(
+    config: &ValidatorConfig,
+    blockstore: &Blockstore,
+    leader_schedule_cache: &LeaderScheduleCache,
+    root_bank: &Bank,
+) -> Result<(), ValidatorError> {
+    for path in &config.shred_archives_to_import {
+        let mut timer = Measure::start("import shred archive");
+        let stats = File::open(path)
+            .map_err(ShredArchiveError::from)
+            .and_then(|file| {
+                blockstore.import_shred_archive(BufReader::new(file), |slot| {
+                    leader_schedule_cache.slot_leader_at(slot, Some(root_bank))
+                })
+            })
+            .map_err(|err| {
+                ValidatorError::Other(format!(
+                    "failed to import shred archive {}: {err}",
+                    path.display()
+                ))
+            })?;
+        timer.stop();
+        info!(
+            "Imported shred archive {}: {stats:?}. {timer}",
+            path.display()
+        );
+        if stats.num_rejected_slots > 0 {
+            warn!(
+                "Rejected {} slots from shred archive {} which failed verification",
+                stats.num_rejected_slots,
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
 fn check_poh_speed(bank: &Bank, maybe_hash_samples: Option<u64>) -> Result<(), ValidatorError> {
     let Some(hashes_per_tick) = bank.hashes_per_tick() else {
         warn!("Unable to read hashes per tick from Bank, skipping PoH speed check");
@@ -110,14 +110,28 @@ impl VotingService {
         vote_op: VoteOp,
         connection_cache: Arc<ConnectionCache>,
     ) {
-        if let VoteOp::PushVote { saved_tower, .. } = &vote_op {
-            let mut measure = Measure::start("tower storage save");
-            if let Err(err) = tower_storage.store(saved_tower) {
-                error!("Unable to save tower to storage: {err:?}");
-                std::process::exit(1);
+        match &vote_op {
+            VoteOp::PushVote { saved_tower, .. } => {
+                let mut measure = Measure::start("tower storage save");
+                if let Err(err) = tower_storage.store(saved_tower) {
+                    if err.is_lease_error() {
+                        // Another instance may have taken over this identity, it must not vote
+                        warn!("Not voting, unable to save tower: {err}");
+                        return;
+                    }
+                    error!("Unable to save tower to storage: {err:?}");
+                    std::process::exit(1);
+                }
+                measure.stop();
+                trace!("{measure}");
+            }
+            VoteOp::RefreshVote { tx, .. } => {
+                // The vote is signed by the identity it was made with
+                let node_pubkey = tx.message.account_keys[0];
+                if !tower_storage.holds_lease(&node_pubkey) {
+                    return;
+                }
             }
-            measure.stop();
-            trace!("{measure}");
         }
 
         // Attempt to send our vote transaction to the leaders for the next few
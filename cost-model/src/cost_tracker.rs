@@ -57,6 +57,21 @@ pub struct UpdatedCosts {
     pub updated_costliest_account_cost: u64,
 }
 
+/// Read-only outcome of checking a transaction against the tracker, see
+/// `CostTracker::estimate()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostTrackerEstimate {
+    /// `Err` if `try_add()` would currently reject the transaction
+    pub result: Result<(), CostTrackerError>,
+    /// block cost accumulated so far, before adding the transaction
+    pub block_cost: u64,
+    /// vote cost accumulated so far, before adding the transaction
+    pub vote_cost: u64,
+    /// cost accumulated so far by each of the transaction's write-locked
+    /// accounts, before adding the transaction
+    pub writable_account_costs: Vec<(Pubkey, u64)>,
+}
+
 #[cfg_attr(feature = "frozen-abi", derive(AbiExample))]
 #[derive(Debug)]
 pub struct CostTracker {
@@ -182,6 +197,31 @@ impl CostTracker {
         })
     }
 
+    /// Report whether `tx_cost` would fit into the block, and how much cost
+    /// its write-locked accounts have already accumulated, without adding it
+    pub fn estimate(
+        &self,
+        tx_cost: &TransactionCost<impl TransactionWithMeta>,
+    ) -> CostTrackerEstimate {
+        CostTrackerEstimate {
+            result: self.would_fit(tx_cost),
+            block_cost: self.block_cost,
+            vote_cost: self.vote_cost,
+            writable_account_costs: tx_cost
+                .writable_accounts()
+                .map(|account_key| (*account_key, self.writable_account_cost(account_key)))
+                .collect(),
+        }
+    }
+
+    /// Cost accumulated by write-locking `account_key` in this block
+    fn writable_account_cost(&self, account_key: &Pubkey) -> u64 {
+        self.cost_by_writable_accounts
+            .get(account_key)
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn update_execution_cost(
         &mut self,
         estimated_tx_cost: &TransactionCost<impl TransactionWithMeta>,
@@ -285,6 +325,23 @@ impl CostTracker {
         );
     }
 
+    /// Returns up to `n` write-locked accounts with the highest accumulated
+    /// cost, sorted by descending cost
+    pub fn top_writable_accounts(&self, n: usize) -> Vec<(Pubkey, u64)> {
+        let mut accounts: Vec<_> = self
+            .cost_by_writable_accounts
+            .iter()
+            .filter(|(_, &cost)| cost > 0)
+            .map(|(&pubkey, &cost)| (pubkey, cost))
+            .collect();
+        if accounts.len() > n {
+            accounts.select_nth_unstable_by(n, |a, b| b.1.cmp(&a.1));
+            accounts.truncate(n);
+        }
+        accounts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        accounts
+    }
+
     fn find_costliest_account(&self) -> (Pubkey, u64) {
         self.cost_by_writable_accounts
             .iter()
@@ -545,6 +602,66 @@ mod tests {
         assert_eq!(old.0 + 1, testee.allocated_accounts_data_size.0);
     }
 
+    #[test]
+    fn test_cost_tracker_estimate() {
+        let mint_keypair = test_setup();
+        let tx1 = build_simple_transaction(&mint_keypair);
+        let tx_cost1 = simple_transaction_cost(&tx1, 5);
+        let cost1 = tx_cost1.sum();
+        let tx2 = build_simple_transaction(&mint_keypair);
+        let tx_cost2 = simple_transaction_cost(&tx2, 5);
+
+        // account limit only leaves room for one transaction on mint's account
+        let mut testee = CostTracker::new(cost1, cost1 * 2, cost1);
+        let estimate = testee.estimate(&tx_cost1);
+        assert_eq!(estimate.result, Ok(()));
+        assert_eq!(estimate.block_cost, 0);
+        assert_eq!(
+            estimate.writable_account_costs,
+            vec![(mint_keypair.pubkey(), 0)]
+        );
+
+        assert!(testee.try_add(&tx_cost1).is_ok());
+        let estimate = testee.estimate(&tx_cost2);
+        assert_eq!(
+            estimate.result,
+            Err(CostTrackerError::WouldExceedAccountMaxLimit)
+        );
+        assert_eq!(estimate.block_cost, cost1);
+        assert_eq!(
+            estimate.writable_account_costs,
+            vec![(mint_keypair.pubkey(), cost1)]
+        );
+
+        // estimating never changes the tracker
+        assert_eq!(testee.block_cost(), cost1);
+        assert_eq!(testee.transaction_count(), 1);
+    }
+
+    #[test]
+    fn test_cost_tracker_top_writable_accounts() {
+        let mut testee = CostTracker::default();
+        let keys: Vec<_> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        for (i, key) in keys.iter().enumerate() {
+            let tx = WritableKeysTransaction(vec![*key]);
+            let tx_cost = simple_transaction_cost(&tx, 100 * (i as u64 + 1));
+            testee.try_add(&tx_cost).unwrap();
+        }
+        let cost_of = |key: &Pubkey| testee.cost_by_writable_accounts[key];
+
+        let top = testee.top_writable_accounts(3);
+        assert_eq!(
+            top,
+            vec![
+                (keys[4], cost_of(&keys[4])),
+                (keys[3], cost_of(&keys[3])),
+                (keys[2], cost_of(&keys[2])),
+            ]
+        );
+        assert_eq!(testee.top_writable_accounts(10).len(), 5);
+        assert!(testee.top_writable_accounts(0).is_empty());
+    }
+
     #[test]
     fn test_cost_tracker_ok_add_two_same_accounts() {
         let mint_keypair = test_setup();
@@ -1,4 +1,5 @@
-pub use solana_file_download::DownloadProgressRecord;
+mod parallel_download;
+
 use {
     log::*,
     solana_clock::Slot,
@@ -16,6 +17,13 @@ use {
         path::{Path, PathBuf},
     },
 };
+pub use {
+    parallel_download::{
+        ParallelDownloadConfig, ParallelDownloadError, DEFAULT_CONNECTIONS_PER_PEER,
+        DEFAULT_DOWNLOAD_CHUNK_SIZE,
+    },
+    solana_file_download::DownloadProgressRecord,
+};
 
 pub fn download_genesis_if_missing(
     rpc_addr: &SocketAddr,
@@ -40,19 +48,16 @@ pub fn download_genesis_if_missing(
     }
 }
 
-/// Download a snapshot archive from `rpc_addr`.  Use `snapshot_kind` to specify downloading either
-/// a full snapshot or an incremental snapshot.
-pub fn download_snapshot_archive(
-    rpc_addr: &SocketAddr,
+/// Purges old snapshot archives and returns the remote archive paths to try, one per archive
+/// format, for the desired snapshot.
+fn prepare_snapshot_archive_download(
     full_snapshot_archives_dir: &Path,
     incremental_snapshot_archives_dir: &Path,
     desired_snapshot_hash: (Slot, SnapshotHash),
     snapshot_kind: SnapshotKind,
     maximum_full_snapshot_archives_to_retain: NonZeroUsize,
     maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
-    use_progress_bar: bool,
-    progress_notify_callback: &mut DownloadProgressCallbackOption<'_>,
-) -> Result<(), String> {
+) -> Vec<PathBuf> {
     snapshot_utils::purge_old_snapshot_archives(
         full_snapshot_archives_dir,
         incremental_snapshot_archives_dir,
@@ -67,30 +72,54 @@ pub fn download_snapshot_archive(
         });
     fs::create_dir_all(&snapshot_archives_remote_dir).unwrap();
 
-    for archive_format in [
+    [
         ArchiveFormat::TarZstd {
             config: ZstdConfig::default(),
         },
         ArchiveFormat::TarLz4,
-    ] {
-        let destination_path = match snapshot_kind {
-            SnapshotKind::FullSnapshot => snapshot_utils::build_full_snapshot_archive_path(
+    ]
+    .into_iter()
+    .map(|archive_format| match snapshot_kind {
+        SnapshotKind::FullSnapshot => snapshot_utils::build_full_snapshot_archive_path(
+            &snapshot_archives_remote_dir,
+            desired_snapshot_hash.0,
+            &desired_snapshot_hash.1,
+            archive_format,
+        ),
+        SnapshotKind::IncrementalSnapshot(base_slot) => {
+            snapshot_utils::build_incremental_snapshot_archive_path(
                 &snapshot_archives_remote_dir,
+                base_slot,
                 desired_snapshot_hash.0,
                 &desired_snapshot_hash.1,
                 archive_format,
-            ),
-            SnapshotKind::IncrementalSnapshot(base_slot) => {
-                snapshot_utils::build_incremental_snapshot_archive_path(
-                    &snapshot_archives_remote_dir,
-                    base_slot,
-                    desired_snapshot_hash.0,
-                    &desired_snapshot_hash.1,
-                    archive_format,
-                )
-            }
-        };
+            )
+        }
+    })
+    .collect()
+}
 
+/// Download a snapshot archive from `rpc_addr`.  Use `snapshot_kind` to specify downloading either
+/// a full snapshot or an incremental snapshot.
+pub fn download_snapshot_archive(
+    rpc_addr: &SocketAddr,
+    full_snapshot_archives_dir: &Path,
+    incremental_snapshot_archives_dir: &Path,
+    desired_snapshot_hash: (Slot, SnapshotHash),
+    snapshot_kind: SnapshotKind,
+    maximum_full_snapshot_archives_to_retain: NonZeroUsize,
+    maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
+    use_progress_bar: bool,
+    progress_notify_callback: &mut DownloadProgressCallbackOption<'_>,
+) -> Result<(), String> {
+    for destination_path in prepare_snapshot_archive_download(
+        full_snapshot_archives_dir,
+        incremental_snapshot_archives_dir,
+        desired_snapshot_hash,
+        snapshot_kind,
+        maximum_full_snapshot_archives_to_retain,
+        maximum_incremental_snapshot_archives_to_retain,
+    ) {
         if destination_path.is_file() {
             return Ok(());
         }
@@ -114,3 +143,49 @@ pub fn download_snapshot_archive(
         desired_snapshot_hash.0, rpc_addr
     ))
 }
+
+/// Download a snapshot archive in byte ranges over several connections to one of `rpc_addrs`,
+/// all of which advertise the desired snapshot.  The peers are tried in order, and the download
+/// is resumed if a previous attempt from the same peer was interrupted.
+///
+/// Returns `ParallelDownloadError::RangesNotServed` if none of the peers serve byte ranges of the
+/// archive, in which case `download_snapshot_archive` can still be used.
+pub fn download_snapshot_archive_from_peers(
+    rpc_addrs: &[SocketAddr],
+    full_snapshot_archives_dir: &Path,
+    incremental_snapshot_archives_dir: &Path,
+    desired_snapshot_hash: (Slot, SnapshotHash),
+    snapshot_kind: SnapshotKind,
+    maximum_full_snapshot_archives_to_retain: NonZeroUsize,
+    maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
+    config: &ParallelDownloadConfig,
+) -> Result<(), ParallelDownloadError> {
+    for destination_path in prepare_snapshot_archive_download(
+        full_snapshot_archives_dir,
+        incremental_snapshot_archives_dir,
+        desired_snapshot_hash,
+        snapshot_kind,
+        maximum_full_snapshot_archives_to_retain,
+        maximum_incremental_snapshot_archives_to_retain,
+    ) {
+        if destination_path.is_file() {
+            return Ok(());
+        }
+
+        match parallel_download::download_archive_from_peers(
+            rpc_addrs,
+            destination_path.file_name().unwrap().to_str().unwrap(),
+            &destination_path,
+            config,
+        ) {
+            Err(ParallelDownloadError::RangesNotServed(file_name)) => {
+                info!("No peer serves byte ranges of {file_name}")
+            }
+            result => return result,
+        }
+    }
+    Err(ParallelDownloadError::RangesNotServed(format!(
+        "a snapshot archive for slot {}",
+        desired_snapshot_hash.0
+    )))
+}
@@ -0,0 +1,578 @@
+//! Ranged, resumable downloads of a snapshot archive over several connections to an RPC peer.
+//!
+//! The archive is downloaded in fixed size chunks into a partial file next to its final
+//! location. Completed chunks are recorded in a state file, so that an interrupted download,
+//! e.g. by a validator restart, resumes with the remaining chunks. Chunks that fail or download
+//! too slowly are retried.
+//!
+//! All chunks of an archive come from the same peer. Archives of the same snapshot made by
+//! different nodes are not byte-identical, so a download only resumes from the peer it started
+//! with, and moving on to another peer starts over.
+
+use {
+    log::*,
+    reqwest::{
+        blocking::Client,
+        header::{CONTENT_RANGE, RANGE},
+        StatusCode,
+    },
+    std::{
+        collections::VecDeque,
+        fs::{self, File, OpenOptions},
+        io::{Read, Seek, SeekFrom, Write},
+        net::SocketAddr,
+        ops::Range,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+            Mutex,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+};
+
+pub const DEFAULT_DOWNLOAD_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+pub const DEFAULT_CONNECTIONS_PER_PEER: usize = 4;
+
+const PARTIAL_DOWNLOAD_EXTENSION: &str = "partial";
+const DOWNLOAD_STATE_EXTENSION: &str = "partial-state";
+// Number of failed chunks after which a peer is no longer used.
+const MAX_PEER_FAILURES: usize = 3;
+// Chunk throughput is only checked once a chunk has been downloading for this long.
+const THROUGHPUT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const PROGRESS_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+const IDLE_WORKER_SLEEP: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug)]
+pub struct ParallelDownloadConfig {
+    /// Size of the byte ranges the archive is split into
+    pub chunk_size: u64,
+    /// Number of concurrent connections to the peer
+    pub connections_per_peer: usize,
+    /// Chunks downloading slower than this, in bytes/second, are abandoned and retried
+    pub minimal_download_speed: f32,
+}
+
+impl Default for ParallelDownloadConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_DOWNLOAD_CHUNK_SIZE,
+            connections_per_peer: DEFAULT_CONNECTIONS_PER_PEER,
+            minimal_download_speed: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ParallelDownloadError {
+    #[error("no peer serves byte ranges of {0}")]
+    RangesNotServed(String),
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// Progress of a partial download, persisted next to it so that the download can be resumed
+///
+/// The state file holds a header line with the peer and the archive and chunk sizes, followed by
+/// the index of each completed chunk on its own line.
+#[derive(Debug, PartialEq, Eq)]
+struct DownloadState {
+    peer: SocketAddr,
+    archive_size: u64,
+    chunk_size: u64,
+    completed: Vec<bool>,
+}
+
+impl DownloadState {
+    fn new(peer: SocketAddr, archive_size: u64, chunk_size: u64) -> Self {
+        let num_chunks = archive_size.div_ceil(chunk_size) as usize;
+        Self {
+            peer,
+            archive_size,
+            chunk_size,
+            completed: vec![false; num_chunks],
+        }
+    }
+
+    /// Loads the state of a previous download of the same archive from `peer`, if any
+    fn load(path: &Path, peer: SocketAddr, archive_size: u64, chunk_size: u64) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        // Only newline terminated lines are complete, the last line may be truncated if the
+        // process stopped while appending to it.
+        let (contents, _truncated) = contents.rsplit_once('\n')?;
+        let mut lines = contents.split('\n');
+        if lines.next()? != Self::header(peer, archive_size, chunk_size) {
+            return None;
+        }
+        let mut state = Self::new(peer, archive_size, chunk_size);
+        for chunk in lines {
+            if let Some(completed) = chunk
+                .parse::<usize>()
+                .ok()
+                .and_then(|chunk| state.completed.get_mut(chunk))
+            {
+                *completed = true;
+            }
+        }
+        Some(state)
+    }
+
+    /// Returns the peer a previous download was started from, if any
+    fn load_peer(path: &Path) -> Option<SocketAddr> {
+        let contents = fs::read_to_string(path).ok()?;
+        let (header, _) = contents.split_once('\n')?;
+        header.split(' ').next()?.parse().ok()
+    }
+
+    fn header(peer: SocketAddr, archive_size: u64, chunk_size: u64) -> String {
+        format!("{peer} {archive_size} {chunk_size}")
+    }
+
+    fn chunk_range(&self, chunk: usize) -> Range<u64> {
+        let start = chunk as u64 * self.chunk_size;
+        start..self.archive_size.min(start + self.chunk_size)
+    }
+
+    fn pending_chunks(&self) -> VecDeque<usize> {
+        (0..self.completed.len())
+            .filter(|&chunk| !self.completed[chunk])
+            .collect()
+    }
+
+    fn completed_bytes(&self) -> u64 {
+        (0..self.completed.len())
+            .filter(|&chunk| self.completed[chunk])
+            .map(|chunk| {
+                let range = self.chunk_range(chunk);
+                range.end - range.start
+            })
+            .sum()
+    }
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+/// Removes partial downloads in `dir` other than those of `keep_file_name`
+fn purge_stale_partial_downloads(dir: &Path, keep_file_name: &str) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let Some(file_name) = path.file_name().and_then(|file_name| file_name.to_str()) else {
+            continue;
+        };
+        let is_partial = [PARTIAL_DOWNLOAD_EXTENSION, DOWNLOAD_STATE_EXTENSION]
+            .iter()
+            .any(|extension| file_name.ends_with(&format!(".{extension}")));
+        if is_partial && !file_name.starts_with(&format!("{keep_file_name}.")) {
+            info!("Removing stale partial download {}", path.display());
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+/// Returns the size of the archive at `url` if the peer serves byte ranges of it
+fn probe_peer(client: &Client, url: &str) -> Result<u64, String> {
+    let response = client
+        .get(url)
+        .header(RANGE, "bytes=0-0")
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .map_err(|err| err.to_string())?;
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return Err(format!("byte ranges not served: {}", response.status()));
+    }
+    response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|content_range| content_range.to_str().ok())
+        .and_then(|content_range| content_range.strip_prefix("bytes 0-0/"))
+        .and_then(|archive_size| archive_size.parse().ok())
+        .ok_or_else(|| "invalid Content-Range".to_string())
+}
+
+/// Downloads `range` of the archive at `url` into the same range of `partial_path`
+fn download_chunk(
+    client: &Client,
+    url: &str,
+    partial_path: &Path,
+    range: &Range<u64>,
+    minimal_download_speed: f32,
+    downloaded_bytes: &AtomicU64,
+) -> Result<(), String> {
+    let chunk_size = range.end - range.start;
+    let mut request = client
+        .get(url)
+        .header(RANGE, format!("bytes={}-{}", range.start, range.end - 1));
+    if minimal_download_speed > 0.0 {
+        // Stalled connections would never fail the throughput check below.
+        request = request.timeout(
+            THROUGHPUT_GRACE_PERIOD
+                + Duration::from_secs_f32(chunk_size as f32 / minimal_download_speed),
+        );
+    }
+    let mut response = request.send().map_err(|err| err.to_string())?;
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return Err(format!("unexpected response status: {}", response.status()));
+    }
+    let expected_content_range = format!("bytes {}-{}/", range.start, range.end - 1);
+    if !response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|content_range| content_range.to_str().ok())
+        .is_some_and(|content_range| content_range.starts_with(&expected_content_range))
+    {
+        return Err("unexpected Content-Range".to_string());
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(partial_path)
+        .map_err(|err| err.to_string())?;
+    file.seek(SeekFrom::Start(range.start))
+        .map_err(|err| err.to_string())?;
+    let start = Instant::now();
+    let mut received = 0;
+    let mut buffer = vec![0; 64 * 1024];
+    let result = loop {
+        let size = match response.read(&mut buffer) {
+            Ok(0) => break Ok(()),
+            Ok(size) => size,
+            Err(err) => break Err(err.to_string()),
+        };
+        if received + size as u64 > chunk_size {
+            break Err("received more data than requested".to_string());
+        }
+        if let Err(err) = file.write_all(&buffer[..size]) {
+            break Err(err.to_string());
+        }
+        received += size as u64;
+        downloaded_bytes.fetch_add(size as u64, Ordering::Relaxed);
+        let elapsed = start.elapsed();
+        if elapsed > THROUGHPUT_GRACE_PERIOD {
+            let throughput = received as f32 / elapsed.as_secs_f32();
+            if throughput < minimal_download_speed {
+                break Err(format!(
+                    "download too slow: {throughput} < {minimal_download_speed} bytes/sec"
+                ));
+            }
+        }
+    }
+    .and_then(|()| {
+        if received == chunk_size {
+            file.sync_data().map_err(|err| err.to_string())
+        } else {
+            Err(format!("received {received} of {chunk_size} bytes"))
+        }
+    });
+    if result.is_err() {
+        downloaded_bytes.fetch_sub(received, Ordering::Relaxed);
+    }
+    result
+}
+
+/// Downloads the archive `file_name` from one of `peers` to `destination_path`
+///
+/// Peers are tried in order, except that an interrupted download is resumed from the peer it
+/// was started from. A failed download can be resumed by a later call, from the chunks
+/// completed so far.
+pub(crate) fn download_archive_from_peers(
+    peers: &[SocketAddr],
+    file_name: &str,
+    destination_path: &Path,
+    config: &ParallelDownloadConfig,
+) -> Result<(), ParallelDownloadError> {
+    let client = Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(None)
+        .build()
+        .map_err(|err| ParallelDownloadError::Failed(err.to_string()))?;
+    let resume_peer =
+        DownloadState::load_peer(&with_extension(destination_path, DOWNLOAD_STATE_EXTENSION));
+    let peers = probe_peers(&client, &order_peers(peers, resume_peer), file_name);
+    if peers.is_empty() {
+        return Err(ParallelDownloadError::RangesNotServed(
+            file_name.to_string(),
+        ));
+    }
+    let mut errors = Vec::with_capacity(peers.len());
+    for (peer, archive_size) in peers {
+        match download_chunks(
+            &client,
+            peer,
+            file_name,
+            destination_path,
+            archive_size,
+            config,
+        ) {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                warn!("{err}");
+                errors.push(err);
+            }
+        }
+    }
+    Err(ParallelDownloadError::Failed(errors.join("; ")))
+}
+
+fn archive_url(peer: &SocketAddr, file_name: &str) -> String {
+    format!("http://{peer}/{file_name}")
+}
+
+/// Moves `resume_peer` to the front of `peers`, if it is one of them
+fn order_peers(peers: &[SocketAddr], resume_peer: Option<SocketAddr>) -> Vec<SocketAddr> {
+    let mut peers = peers.to_vec();
+    if let Some(index) = peers.iter().position(|peer| Some(*peer) == resume_peer) {
+        peers[..=index].rotate_right(1);
+    }
+    peers
+}
+
+/// Returns the peers which serve byte ranges of the archive `file_name`, in the order of
+/// `peers`, along with the size of their archive
+fn probe_peers(client: &Client, peers: &[SocketAddr], file_name: &str) -> Vec<(SocketAddr, u64)> {
+    let probes: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = peers
+            .iter()
+            .map(|peer| {
+                let url = archive_url(peer, file_name);
+                scope.spawn(move || (*peer, probe_peer(client, &url)))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    probes
+        .into_iter()
+        .filter_map(|(peer, probe)| match probe {
+            Ok(archive_size) => Some((peer, archive_size)),
+            Err(err) => {
+                info!("Not downloading {file_name} from {peer}: {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Downloads the archive `file_name` from `peer`, resuming a previous download from it
+fn download_chunks(
+    client: &Client,
+    peer: SocketAddr,
+    file_name: &str,
+    destination_path: &Path,
+    archive_size: u64,
+    config: &ParallelDownloadConfig,
+) -> Result<(), String> {
+    let snapshot_archives_remote_dir = destination_path.parent().unwrap();
+    purge_stale_partial_downloads(snapshot_archives_remote_dir, file_name);
+    let partial_path = with_extension(destination_path, PARTIAL_DOWNLOAD_EXTENSION);
+    let state_path = with_extension(destination_path, DOWNLOAD_STATE_EXTENSION);
+    let chunk_size = config.chunk_size.max(1);
+    // Bytes downloaded from another peer are discarded by recreating the partial file.
+    let state = DownloadState::load(&state_path, peer, archive_size, chunk_size)
+        .filter(|_| {
+            fs::metadata(&partial_path).is_ok_and(|metadata| metadata.len() == archive_size)
+        })
+        .map(Ok)
+        .unwrap_or_else(|| {
+            File::create(&partial_path)
+                .and_then(|file| file.set_len(archive_size))
+                .and_then(|()| {
+                    fs::write(
+                        &state_path,
+                        format!(
+                            "{}\n",
+                            DownloadState::header(peer, archive_size, chunk_size)
+                        ),
+                    )
+                })
+                .map(|()| DownloadState::new(peer, archive_size, chunk_size))
+                .map_err(|err| format!("Failed to create {}: {err}", partial_path.display()))
+        })?;
+    let state_file = OpenOptions::new()
+        .append(true)
+        .open(&state_path)
+        .map_err(|err| format!("Failed to open {}: {err}", state_path.display()))?;
+
+    let completed_bytes = state.completed_bytes();
+    let pending = Mutex::new(state.pending_chunks());
+    info!(
+        "Downloading {file_name} ({archive_size} bytes, {} chunks remaining, {completed_bytes} \
+         bytes already downloaded) from {peer}",
+        pending.lock().unwrap().len(),
+    );
+    let url = archive_url(&peer, file_name);
+    let state_file = Mutex::new(state_file);
+    let in_flight = AtomicUsize::new(0);
+    let downloaded_bytes = AtomicU64::new(completed_bytes);
+    let done = AtomicBool::new(false);
+    let failures = AtomicUsize::new(0);
+
+    let worker = || {
+        while failures.load(Ordering::Relaxed) < MAX_PEER_FAILURES {
+            let chunk = {
+                let mut pending = pending.lock().unwrap();
+                let chunk = pending.pop_front();
+                if chunk.is_some() {
+                    in_flight.fetch_add(1, Ordering::Relaxed);
+                }
+                chunk
+            };
+            let Some(chunk) = chunk else {
+                // Chunks in flight on other connections may still fail and need a retry.
+                if in_flight.load(Ordering::Relaxed) == 0 {
+                    break;
+                }
+                thread::sleep(IDLE_WORKER_SLEEP);
+                continue;
+            };
+            let range = state.chunk_range(chunk);
+            let result = download_chunk(
+                client,
+                &url,
+                &partial_path,
+                &range,
+                config.minimal_download_speed,
+                &downloaded_bytes,
+            )
+            .and_then(|()| {
+                writeln!(state_file.lock().unwrap(), "{chunk}").map_err(|err| err.to_string())
+            });
+            if let Err(err) = result {
+                warn!("Failed to download chunk {chunk} of {file_name} from {peer}: {err}");
+                failures.fetch_add(1, Ordering::Relaxed);
+                pending.lock().unwrap().push_back(chunk);
+            }
+            in_flight.fetch_sub(1, Ordering::Relaxed);
+        }
+    };
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.connections_per_peer.max(1))
+            .map(|_| scope.spawn(worker))
+            .collect();
+        scope.spawn(|| {
+            let start = Instant::now();
+            while !done.load(Ordering::Relaxed) {
+                thread::sleep(PROGRESS_REPORT_INTERVAL);
+                let downloaded_bytes = downloaded_bytes.load(Ordering::Relaxed);
+                info!(
+                    "Downloaded {downloaded_bytes} of {archive_size} bytes of {file_name} \
+                     ({:.1}%) at {:.1} MB/s from {peer}",
+                    100.0 * downloaded_bytes as f32 / archive_size.max(1) as f32,
+                    downloaded_bytes.saturating_sub(completed_bytes) as f32
+                        / start.elapsed().as_secs_f32()
+                        / 1_000_000.0,
+                );
+            }
+        });
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    let remaining = pending.into_inner().unwrap().len();
+    if remaining > 0 {
+        return Err(format!(
+            "Failed to download {remaining} chunks of {file_name} from {peer}"
+        ));
+    }
+    fs::rename(&partial_path, destination_path).map_err(|err| {
+        format!(
+            "Failed to rename {} to {}: {err}",
+            partial_path.display(),
+            destination_path.display()
+        )
+    })?;
+    let _ = fs::remove_file(&state_path);
+    info!("Downloaded {file_name} from {peer}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_download_state() {
+        let peer = SocketAddr::from(([127, 0, 0, 1], 8899));
+        let state = DownloadState::new(peer, 250, 100);
+        assert_eq!(state.completed.len(), 3);
+        assert_eq!(state.chunk_range(0), 0..100);
+        assert_eq!(state.chunk_range(2), 200..250);
+        assert_eq!(state.pending_chunks(), VecDeque::from([0, 1, 2]));
+        assert_eq!(state.completed_bytes(), 0);
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.tar.zst.partial-state");
+        assert_eq!(DownloadState::load(&path, peer, 250, 100), None);
+        assert_eq!(DownloadState::load_peer(&path), None);
+
+        // Out of range and truncated entries are ignored.
+        fs::write(&path, "127.0.0.1:8899 250 100\n2\n7\n1").unwrap();
+        let state = DownloadState::load(&path, peer, 250, 100).unwrap();
+        assert_eq!(state.completed, vec![false, false, true]);
+        assert_eq!(state.pending_chunks(), VecDeque::from([0, 1]));
+        assert_eq!(state.completed_bytes(), 50);
+        assert_eq!(DownloadState::load_peer(&path), Some(peer));
+
+        // A different peer, archive or chunk size starts over.
+        let other_peer = SocketAddr::from(([127, 0, 0, 2], 8899));
+        assert_eq!(DownloadState::load(&path, other_peer, 250, 100), None);
+        assert_eq!(DownloadState::load(&path, peer, 300, 100), None);
+        assert_eq!(DownloadState::load(&path, peer, 250, 50), None);
+    }
+
+    #[test]
+    fn test_order_peers() {
+        let peers: Vec<_> = (1..=4)
+            .map(|i| SocketAddr::from(([127, 0, 0, i], 8899)))
+            .collect();
+        assert_eq!(order_peers(&peers, None), peers);
+        assert_eq!(
+            order_peers(&peers, Some(peers[2])),
+            vec![peers[2], peers[0], peers[1], peers[3]]
+        );
+        let unknown_peer = SocketAddr::from(([127, 0, 0, 5], 8899));
+        assert_eq!(order_peers(&peers, Some(unknown_peer)), peers);
+    }
+
+    #[test]
+    fn test_purge_stale_partial_downloads() {
+        let dir = TempDir::new().unwrap();
+        for file_name in [
+            "snapshot-100-a.tar.zst.partial",
+            "snapshot-100-a.tar.zst.partial-state",
+            "snapshot-200-b.tar.zst.partial",
+            "snapshot-200-b.tar.zst.partial-state",
+            "snapshot-50-c.tar.zst",
+        ] {
+            fs::write(dir.path().join(file_name), "").unwrap();
+        }
+        purge_stale_partial_downloads(dir.path(), "snapshot-200-b.tar.zst");
+        let mut file_names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        file_names.sort();
+        assert_eq!(
+            file_names,
+            vec![
+                "snapshot-200-b.tar.zst.partial",
+                "snapshot-200-b.tar.zst.partial-state",
+                "snapshot-50-c.tar.zst",
+            ]
+        );
+    }
+}
@@ -5,10 +5,11 @@
 use {
     solana_clock::{Slot, UnixTimestamp},
     solana_hash::Hash,
+    solana_pubkey::Pubkey,
     solana_signature::Signature,
     solana_transaction::{sanitized::SanitizedTransaction, versioned::VersionedTransaction},
     solana_transaction_status::{Reward, RewardsAndNumPartitions, TransactionStatusMeta},
-    std::{any::Any, error, io},
+    std::{any::Any, collections::HashSet, error, io, ops::RangeInclusive},
     thiserror::Error,
 };
 
@@ -297,6 +298,98 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_4(&'a ReplicaBlockInfoV4<'a>),
 }
 
+/// Describes the account updates a plugin wants to receive. The validator evaluates the
+/// filter before calling [`GeyserPlugin::update_account`], so updates which don't pass never
+/// reach the plugin.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountFilter {
+    /// Accounts owned by one of these programs pass the filter.
+    pub owners: HashSet<Pubkey>,
+
+    /// These accounts pass the filter, regardless of their owner.
+    /// If both `owners` and `pubkeys` are empty, every account passes.
+    pub pubkeys: HashSet<Pubkey>,
+
+    /// If set, only accounts whose data length is in this range pass the filter.
+    pub data_size: Option<RangeInclusive<usize>>,
+}
+
+impl AccountFilter {
+    /// Returns true if an update to this account should be sent to the plugin
+    pub fn matches(&self, pubkey: &Pubkey, owner: &Pubkey, data_len: usize) -> bool {
+        let selected = (self.owners.is_empty() && self.pubkeys.is_empty())
+            || self.pubkeys.contains(pubkey)
+            || self.owners.contains(owner);
+        selected
+            && self
+                .data_size
+                .as_ref()
+                .is_none_or(|data_size| data_size.contains(&data_len))
+    }
+}
+
+/// Describes the transactions a plugin wants to receive. The validator evaluates the filter
+/// before calling [`GeyserPlugin::notify_transaction`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionFilter {
+    /// Whether simple vote transactions pass the filter.
+    pub include_votes: bool,
+
+    /// Whether transactions other than simple votes pass the filter.
+    pub include_non_votes: bool,
+
+    /// Whether transactions which failed pass the filter.
+    pub include_failed: bool,
+
+    /// If not empty, only transactions which reference one of these accounts, either
+    /// directly or through an address lookup table, pass the filter.
+    pub mentioned_accounts: HashSet<Pubkey>,
+}
+
+impl Default for TransactionFilter {
+    fn default() -> Self {
+        Self {
+            include_votes: true,
+            include_non_votes: true,
+            include_failed: true,
+            mentioned_accounts: HashSet::default(),
+        }
+    }
+}
+
+impl TransactionFilter {
+    /// Returns true if this transaction should be sent to the plugin
+    pub fn matches(
+        &self,
+        is_vote: bool,
+        transaction: &VersionedTransaction,
+        transaction_status_meta: &TransactionStatusMeta,
+    ) -> bool {
+        let included = if is_vote {
+            self.include_votes
+        } else {
+            self.include_non_votes
+        };
+        if !included {
+            return false;
+        }
+        if transaction_status_meta.status.is_err() && !self.include_failed {
+            return false;
+        }
+        if self.mentioned_accounts.is_empty() {
+            return true;
+        }
+        let loaded_addresses = &transaction_status_meta.loaded_addresses;
+        transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .chain(&loaded_addresses.writable)
+            .chain(&loaded_addresses.readonly)
+            .any(|account| self.mentioned_accounts.contains(account))
+    }
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -501,4 +594,18 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// The account updates the plugin is interested in. Called after
+    /// `on_load`, the validator only calls `update_account` for accounts
+    /// which pass the filter. Default is None -- every account update is sent.
+    fn account_filter(&self) -> Option<AccountFilter> {
+        None
+    }
+
+    /// The transactions the plugin is interested in. Called after `on_load`,
+    /// the validator only calls `notify_transaction` for transactions which
+    /// pass the filter. Default is None -- every transaction is sent.
+    fn transaction_filter(&self) -> Option<TransactionFilter> {
+        None
+    }
 }
@@ -1,6 +1,9 @@
 /// Module responsible for notifying plugins of account updates
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        async_dispatcher::{AccountUpdate, Notification},
+        geyser_plugin_manager::GeyserPluginManager,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
     },
@@ -79,6 +82,10 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
+            if let Some(dispatcher) = plugin.async_dispatcher() {
+                dispatcher.send(Notification::EndOfStartup);
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-end-of-restore-from-snapshot");
             match plugin.notify_end_of_startup() {
                 Err(err) => {
@@ -162,7 +169,20 @@ impl AccountsUpdateNotifierImpl {
         if plugin_manager.plugins.is_empty() {
             return;
         }
+        let pubkey = Pubkey::try_from(account.pubkey).unwrap_or_default();
+        let owner = Pubkey::try_from(account.owner).unwrap_or_default();
         for plugin in plugin_manager.plugins.iter() {
+            if !plugin.account_update_selected(&pubkey, &owner, account.data.len()) {
+                continue;
+            }
+            if let Some(dispatcher) = plugin.async_dispatcher() {
+                dispatcher.send(Notification::Account {
+                    account: AccountUpdate::new(&account),
+                    slot,
+                    is_startup,
+                });
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-account");
             match plugin.update_account(
                 ReplicaAccountInfoVersions::V0_0_3(&account),
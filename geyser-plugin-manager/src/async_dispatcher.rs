@@ -0,0 +1,594 @@
+/// Module responsible for delivering notifications to plugins from a dedicated thread
+///
+/// Plugins opt in through their config file:
+///
+/// ```json
+/// {
+///     "libpath": "/path/to/plugin.so",
+///     "async_dispatch": {
+///         "queue_capacity": 100000,
+///         "overflow_policy": "drop_oldest"
+///     }
+/// }
+/// ```
+///
+/// Notifications are copied into a bounded queue, so the validator threads producing them no
+/// longer wait for the plugin. What happens when the queue is full is decided by the
+/// [`OverflowPolicy`].
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, ReplicaAccountInfoV3, ReplicaAccountInfoVersions, ReplicaBlockInfoV4,
+        ReplicaBlockInfoVersions, ReplicaEntryInfoV2, ReplicaEntryInfoVersions,
+        ReplicaTransactionInfoV3, ReplicaTransactionInfoVersions, SlotStatus,
+    },
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
+    log::*,
+    serde_derive::Deserialize,
+    solana_clock::{Slot, UnixTimestamp},
+    solana_hash::Hash,
+    solana_metrics::datapoint_info,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_transaction::{sanitized::SanitizedTransaction, versioned::VersionedTransaction},
+    solana_transaction_status::{RewardsAndNumPartitions, TransactionStatusMeta},
+    std::{
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+pub const DEFAULT_QUEUE_CAPACITY: usize = 100_000;
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+const METRICS_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What to do with a notification when the plugin's queue is full
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait for the plugin to make room, stalling the notifying thread
+    #[default]
+    Block,
+    /// Discard the oldest queued notification to make room for the new one
+    DropOldest,
+    /// Stop sending notifications to the plugin
+    Disconnect,
+}
+
+/// The `async_dispatch` section of a plugin config file
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AsyncDispatchConfig {
+    pub queue_capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for AsyncDispatchConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AccountUpdate {
+    pub pubkey: Pubkey,
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: Vec<u8>,
+    pub write_version: u64,
+    pub txn: Option<SanitizedTransaction>,
+}
+
+impl AccountUpdate {
+    pub fn new(account: &ReplicaAccountInfoV3) -> Self {
+        Self {
+            pubkey: Pubkey::try_from(account.pubkey).unwrap_or_default(),
+            lamports: account.lamports,
+            owner: Pubkey::try_from(account.owner).unwrap_or_default(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data.to_vec(),
+            write_version: account.write_version,
+            txn: account.txn.cloned(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TransactionUpdate {
+    pub signature: Signature,
+    pub message_hash: Hash,
+    pub is_vote: bool,
+    pub transaction: VersionedTransaction,
+    pub transaction_status_meta: TransactionStatusMeta,
+    pub index: usize,
+}
+
+impl TransactionUpdate {
+    pub fn new(transaction: &ReplicaTransactionInfoV3) -> Self {
+        Self {
+            signature: *transaction.signature,
+            message_hash: *transaction.message_hash,
+            is_vote: transaction.is_vote,
+            transaction: transaction.transaction.clone(),
+            transaction_status_meta: transaction.transaction_status_meta.clone(),
+            index: transaction.index,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct EntryUpdate {
+    pub slot: Slot,
+    pub index: usize,
+    pub num_hashes: u64,
+    pub hash: Vec<u8>,
+    pub executed_transaction_count: u64,
+    pub starting_transaction_index: usize,
+}
+
+impl EntryUpdate {
+    pub fn new(entry: &ReplicaEntryInfoV2) -> Self {
+        Self {
+            slot: entry.slot,
+            index: entry.index,
+            num_hashes: entry.num_hashes,
+            hash: entry.hash.to_vec(),
+            executed_transaction_count: entry.executed_transaction_count,
+            starting_transaction_index: entry.starting_transaction_index,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BlockMetadataUpdate {
+    pub parent_slot: Slot,
+    pub parent_blockhash: String,
+    pub slot: Slot,
+    pub blockhash: String,
+    pub rewards: RewardsAndNumPartitions,
+    pub block_time: Option<UnixTimestamp>,
+    pub block_height: Option<u64>,
+    pub executed_transaction_count: u64,
+    pub entry_count: u64,
+}
+
+impl BlockMetadataUpdate {
+    pub fn new(block_info: &ReplicaBlockInfoV4) -> Self {
+        Self {
+            parent_slot: block_info.parent_slot,
+            parent_blockhash: block_info.parent_blockhash.to_string(),
+            slot: block_info.slot,
+            blockhash: block_info.blockhash.to_string(),
+            rewards: block_info.rewards.clone(),
+            block_time: block_info.block_time,
+            block_height: block_info.block_height,
+            executed_transaction_count: block_info.executed_transaction_count,
+            entry_count: block_info.entry_count,
+        }
+    }
+}
+
+/// An owned copy of a notification, waiting in a plugin's queue
+#[derive(Debug)]
+pub(crate) enum Notification {
+    Account {
+        account: AccountUpdate,
+        slot: Slot,
+        is_startup: bool,
+    },
+    EndOfStartup,
+    SlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatus,
+    },
+    Transaction {
+        transaction: TransactionUpdate,
+        slot: Slot,
+    },
+    Entry(EntryUpdate),
+    BlockMetadata(BlockMetadataUpdate),
+}
+
+impl Notification {
+    fn dispatch(&self, plugin: &dyn GeyserPlugin) {
+        let result = match self {
+            Notification::Account {
+                account,
+                slot,
+                is_startup,
+            } => {
+                let account_info = ReplicaAccountInfoV3 {
+                    pubkey: account.pubkey.as_ref(),
+                    lamports: account.lamports,
+                    owner: account.owner.as_ref(),
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                    data: &account.data,
+                    write_version: account.write_version,
+                    txn: account.txn.as_ref(),
+                };
+                plugin.update_account(
+                    ReplicaAccountInfoVersions::V0_0_3(&account_info),
+                    *slot,
+                    *is_startup,
+                )
+            }
+            Notification::EndOfStartup => plugin.notify_end_of_startup(),
+            Notification::SlotStatus {
+                slot,
+                parent,
+                status,
+            } => plugin.update_slot_status(*slot, *parent, status),
+            Notification::Transaction { transaction, slot } => {
+                let transaction_info = ReplicaTransactionInfoV3 {
+                    signature: &transaction.signature,
+                    message_hash: &transaction.message_hash,
+                    is_vote: transaction.is_vote,
+                    transaction: &transaction.transaction,
+                    transaction_status_meta: &transaction.transaction_status_meta,
+                    index: transaction.index,
+                };
+                plugin.notify_transaction(
+                    ReplicaTransactionInfoVersions::V0_0_3(&transaction_info),
+                    *slot,
+                )
+            }
+            Notification::Entry(entry) => {
+                let entry_info = ReplicaEntryInfoV2 {
+                    slot: entry.slot,
+                    index: entry.index,
+                    num_hashes: entry.num_hashes,
+                    hash: &entry.hash,
+                    executed_transaction_count: entry.executed_transaction_count,
+                    starting_transaction_index: entry.starting_transaction_index,
+                };
+                plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry_info))
+            }
+            Notification::BlockMetadata(block) => {
+                let block_info = ReplicaBlockInfoV4 {
+                    parent_slot: block.parent_slot,
+                    parent_blockhash: &block.parent_blockhash,
+                    slot: block.slot,
+                    blockhash: &block.blockhash,
+                    rewards: &block.rewards,
+                    block_time: block.block_time,
+                    block_height: block.block_height,
+                    executed_transaction_count: block.executed_transaction_count,
+                    entry_count: block.entry_count,
+                };
+                plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_4(&block_info))
+            }
+        };
+        if let Err(err) = result {
+            error!(
+                "Failed to deliver {} notification, error: {err} to plugin {}",
+                self.kind(),
+                plugin.name()
+            );
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Notification::Account { .. } => "account",
+            Notification::EndOfStartup => "end of startup",
+            Notification::SlotStatus { .. } => "slot status",
+            Notification::Transaction { .. } => "transaction",
+            Notification::Entry(_) => "entry",
+            Notification::BlockMetadata(_) => "block metadata",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct AsyncDispatchStats {
+    dispatched: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Owns the queue and the thread delivering notifications to a single plugin
+#[derive(Debug)]
+pub struct AsyncDispatcher {
+    config: AsyncDispatchConfig,
+    sender: Sender<Notification>,
+    // Kept to discard the oldest notification under `OverflowPolicy::DropOldest`
+    receiver: Receiver<Notification>,
+    disconnected: Arc<AtomicBool>,
+    stats: Arc<AsyncDispatchStats>,
+    exit: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AsyncDispatcher {
+    pub fn new(name: String, plugin: Arc<dyn GeyserPlugin>, config: AsyncDispatchConfig) -> Self {
+        let (sender, receiver) = bounded(config.queue_capacity.max(1));
+        let disconnected = Arc::new(AtomicBool::new(false));
+        let stats = Arc::new(AsyncDispatchStats::default());
+        let exit = Arc::new(AtomicBool::new(false));
+        info!("Starting async dispatch for plugin {name} with {config:?}");
+        let thread = Builder::new()
+            .name("solGeyserAsync".to_string())
+            .spawn({
+                let receiver = receiver.clone();
+                let disconnected = disconnected.clone();
+                let stats = stats.clone();
+                let exit = exit.clone();
+                move || Self::run(name, plugin, receiver, disconnected, stats, exit)
+            })
+            .unwrap();
+        Self {
+            config,
+            sender,
+            receiver,
+            disconnected,
+            stats,
+            exit,
+            thread: Some(thread),
+        }
+    }
+
+    /// Queues a notification, applying the overflow policy if the queue is full
+    pub(crate) fn send(&self, notification: Notification) {
+        if self.disconnected.load(Ordering::Relaxed) {
+            self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        match self.config.overflow_policy {
+            OverflowPolicy::Block => {
+                // Only fails once the dispatch thread is gone
+                let _ = self.sender.send(notification);
+            }
+            OverflowPolicy::DropOldest => {
+                let mut notification = notification;
+                loop {
+                    match self.sender.try_send(notification) {
+                        Ok(()) | Err(TrySendError::Disconnected(_)) => break,
+                        Err(TrySendError::Full(rejected)) => {
+                            if self.receiver.try_recv().is_ok() {
+                                self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                            notification = rejected;
+                        }
+                    }
+                }
+            }
+            OverflowPolicy::Disconnect => match self.sender.try_send(notification) {
+                Ok(()) | Err(TrySendError::Disconnected(_)) => {}
+                Err(TrySendError::Full(_)) => {
+                    if !self.disconnected.swap(true, Ordering::Relaxed) {
+                        error!(
+                            "Notification queue of a plugin is full (capacity {}), it will not \
+                             receive any more notifications",
+                            self.config.queue_capacity
+                        );
+                    }
+                    self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            },
+        }
+    }
+
+    /// Returns true if the plugin stopped receiving notifications because its queue overflowed
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Relaxed)
+    }
+
+    pub fn queue_depth(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Delivers the notifications already queued and stops the dispatch thread
+    pub fn join(&mut self) -> thread::Result<()> {
+        self.exit.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(()),
+        }
+    }
+
+    fn run(
+        name: String,
+        plugin: Arc<dyn GeyserPlugin>,
+        receiver: Receiver<Notification>,
+        disconnected: Arc<AtomicBool>,
+        stats: Arc<AsyncDispatchStats>,
+        exit: Arc<AtomicBool>,
+    ) {
+        let mut last_report = Instant::now();
+        loop {
+            match receiver.recv_timeout(RECV_TIMEOUT) {
+                Ok(notification) => {
+                    notification.dispatch(plugin.as_ref());
+                    stats.dispatched.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if last_report.elapsed() >= METRICS_REPORT_INTERVAL {
+                Self::report_metrics(&name, &receiver, &disconnected, &stats);
+                last_report = Instant::now();
+            }
+        }
+        Self::report_metrics(&name, &receiver, &disconnected, &stats);
+        info!("Stopped async dispatch for plugin {name}");
+    }
+
+    fn report_metrics(
+        name: &str,
+        receiver: &Receiver<Notification>,
+        disconnected: &AtomicBool,
+        stats: &AsyncDispatchStats,
+    ) {
+        datapoint_info!(
+            "geyser-plugin-async-dispatch",
+            "plugin" => name,
+            ("queue_depth", receiver.len(), i64),
+            ("dispatched", stats.dispatched.swap(0, Ordering::Relaxed), i64),
+            ("dropped", stats.dropped.swap(0, Ordering::Relaxed), i64),
+            ("disconnected", disconnected.load(Ordering::Relaxed), bool),
+        );
+    }
+}
+
+impl Drop for AsyncDispatcher {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        agave_geyser_plugin_interface::geyser_plugin_interface::Result as PluginResult,
+        std::sync::{Condvar, Mutex},
+    };
+
+    /// Records the slots it was notified of, waiting while `blocked` is set
+    #[derive(Debug, Default)]
+    struct SlowPlugin {
+        blocked: Mutex<bool>,
+        unblocked: Condvar,
+        slots: Mutex<Vec<Slot>>,
+    }
+
+    impl SlowPlugin {
+        fn set_blocked(&self, blocked: bool) {
+            *self.blocked.lock().unwrap() = blocked;
+            self.unblocked.notify_all();
+        }
+    }
+
+    impl GeyserPlugin for SlowPlugin {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn update_slot_status(
+            &self,
+            slot: Slot,
+            _parent: Option<u64>,
+            _status: &SlotStatus,
+        ) -> PluginResult<()> {
+            let _unblocked = self
+                .unblocked
+                .wait_while(self.blocked.lock().unwrap(), |blocked| *blocked)
+                .unwrap();
+            self.slots.lock().unwrap().push(slot);
+            Ok(())
+        }
+    }
+
+    fn slot_status(slot: Slot) -> Notification {
+        Notification::SlotStatus {
+            slot,
+            parent: None,
+            status: SlotStatus::Processed,
+        }
+    }
+
+    /// Queues slot 0, which blocks the dispatch thread, then fills the queue with slots 1..=n
+    fn fill_queue(plugin: &Arc<SlowPlugin>, overflow_policy: OverflowPolicy) -> AsyncDispatcher {
+        plugin.set_blocked(true);
+        let dispatcher = AsyncDispatcher::new(
+            "slow".to_string(),
+            plugin.clone(),
+            AsyncDispatchConfig {
+                queue_capacity: 2,
+                overflow_policy,
+            },
+        );
+        dispatcher.send(slot_status(0));
+        while dispatcher.queue_depth() > 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        for slot in 1..=4 {
+            dispatcher.send(slot_status(slot));
+        }
+        dispatcher
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let plugin = Arc::new(SlowPlugin::default());
+        let mut dispatcher = fill_queue(&plugin, OverflowPolicy::DropOldest);
+        assert_eq!(dispatcher.queue_depth(), 2);
+        assert_eq!(dispatcher.stats.dropped.load(Ordering::Relaxed), 2);
+
+        plugin.set_blocked(false);
+        dispatcher.join().unwrap();
+        assert_eq!(*plugin.slots.lock().unwrap(), vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let plugin = Arc::new(SlowPlugin::default());
+        let mut dispatcher = fill_queue(&plugin, OverflowPolicy::Disconnect);
+        assert!(dispatcher.is_disconnected());
+        dispatcher.send(slot_status(5));
+        assert_eq!(dispatcher.stats.dropped.load(Ordering::Relaxed), 3);
+
+        plugin.set_blocked(false);
+        dispatcher.join().unwrap();
+        assert_eq!(*plugin.slots.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_block() {
+        let plugin = Arc::new(SlowPlugin::default());
+        plugin.set_blocked(true);
+        let mut dispatcher = AsyncDispatcher::new(
+            "slow".to_string(),
+            plugin.clone(),
+            AsyncDispatchConfig {
+                queue_capacity: 1,
+                overflow_policy: OverflowPolicy::Block,
+            },
+        );
+        thread::scope(|scope| {
+            let sender = scope.spawn(|| {
+                for slot in 0..4 {
+                    dispatcher.send(slot_status(slot));
+                }
+            });
+            thread::sleep(Duration::from_millis(50));
+            assert!(!sender.is_finished());
+            plugin.set_blocked(false);
+        });
+        dispatcher.join().unwrap();
+        assert_eq!(*plugin.slots.lock().unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(dispatcher.stats.dropped.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_config_from_json() {
+        let config: AsyncDispatchConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, AsyncDispatchConfig::default());
+        let config: AsyncDispatchConfig =
+            serde_json::from_str(r#"{"queue_capacity": 10, "overflow_policy": "disconnect"}"#)
+                .unwrap();
+        assert_eq!(
+            config,
+            AsyncDispatchConfig {
+                queue_capacity: 10,
+                overflow_policy: OverflowPolicy::Disconnect,
+            }
+        );
+        assert!(serde_json::from_str::<AsyncDispatchConfig>(r#"{"policy": "block"}"#).is_err());
+    }
+}
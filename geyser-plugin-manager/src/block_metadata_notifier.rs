@@ -1,5 +1,6 @@
 use {
     crate::{
+        async_dispatcher::{BlockMetadataUpdate, Notification},
         block_metadata_notifier_interface::BlockMetadataNotifier,
         geyser_plugin_manager::GeyserPluginManager,
     },
@@ -52,6 +53,12 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
         );
 
         for plugin in plugin_manager.plugins.iter() {
+            if let Some(dispatcher) = plugin.async_dispatcher() {
+                dispatcher.send(Notification::BlockMetadata(BlockMetadataUpdate::new(
+                    &block_info,
+                )));
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-slot");
             let block_info = ReplicaBlockInfoVersions::V0_0_4(&block_info);
             match plugin.notify_block_metadata(block_info) {
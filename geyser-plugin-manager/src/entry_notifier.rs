@@ -1,6 +1,9 @@
 /// Module responsible for notifying plugins about entries
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        async_dispatcher::{EntryUpdate, Notification},
+        geyser_plugin_manager::GeyserPluginManager,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaEntryInfoV2, ReplicaEntryInfoVersions,
     },
@@ -39,6 +42,10 @@ impl EntryNotifier for EntryNotifierImpl {
             if !plugin.entry_notifications_enabled() {
                 continue;
             }
+            if let Some(dispatcher) = plugin.async_dispatcher() {
+                dispatcher.send(Notification::Entry(EntryUpdate::new(&entry_info)));
+                continue;
+            }
             match plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry_info)) {
                 Err(err) => {
                     error!(
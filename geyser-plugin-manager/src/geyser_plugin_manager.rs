@@ -1,11 +1,18 @@
 use {
-    agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+    crate::async_dispatcher::{AsyncDispatchConfig, AsyncDispatcher},
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        AccountFilter, GeyserPlugin, TransactionFilter,
+    },
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
+    solana_pubkey::Pubkey,
+    solana_transaction::versioned::VersionedTransaction,
+    solana_transaction_status::TransactionStatusMeta,
     std::{
         ops::{Deref, DerefMut},
         path::Path,
+        sync::Arc,
     },
     tokio::sync::oneshot::Sender as OneShotSender,
 };
@@ -13,7 +20,15 @@ use {
 #[derive(Debug)]
 pub struct LoadedGeyserPlugin {
     name: String,
-    plugin: Box<dyn GeyserPlugin>,
+    // Declared before the plugin, so the dispatch thread is joined and releases its
+    // reference before the plugin is dropped
+    dispatcher: Option<AsyncDispatcher>,
+    async_dispatch_config: Option<AsyncDispatchConfig>,
+    plugin: Arc<dyn GeyserPlugin>,
+    // Filters declared by the plugin, cached so they are not fetched across the
+    // plugin boundary for every notification
+    account_filter: Option<AccountFilter>,
+    transaction_filter: Option<TransactionFilter>,
     // NOTE: While we do not access the library, the plugin we have loaded most
     // certainly does. To ensure we don't SIGSEGV we must declare the library
     // after the plugin so the plugin is dropped first.
@@ -30,27 +45,100 @@ impl LoadedGeyserPlugin {
     pub fn new(library: Library, plugin: Box<dyn GeyserPlugin>, name: Option<String>) -> Self {
         Self {
             name: name.unwrap_or_else(|| plugin.name().to_owned()),
-            plugin,
+            dispatcher: None,
+            async_dispatch_config: None,
+            plugin: Arc::from(plugin),
+            account_filter: None,
+            transaction_filter: None,
             library,
         }
     }
 
+    /// Deliver notifications to the plugin from a dedicated thread, see [`AsyncDispatcher`]
+    pub fn with_async_dispatch(mut self, config: Option<AsyncDispatchConfig>) -> Self {
+        self.async_dispatch_config = config;
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Starts the dispatch thread if the plugin opted in, must be called after `on_load`
+    pub fn start_async_dispatch(&mut self) {
+        if let Some(config) = self.async_dispatch_config.clone() {
+            self.dispatcher = Some(AsyncDispatcher::new(
+                self.name.clone(),
+                self.plugin.clone(),
+                config,
+            ));
+        }
+    }
+
+    /// Delivers the queued notifications and stops the dispatch thread, must be called
+    /// before `on_unload`
+    pub fn stop_async_dispatch(&mut self) {
+        if let Some(mut dispatcher) = self.dispatcher.take() {
+            if dispatcher.join().is_err() {
+                error!("Async dispatch thread of plugin {} panicked", self.name);
+            }
+        }
+    }
+
+    /// The dispatcher which notifications must be sent to, if the plugin opted in to async dispatch
+    pub fn async_dispatcher(&self) -> Option<&AsyncDispatcher> {
+        self.dispatcher.as_ref()
+    }
+
+    /// Fetches the filters declared by the plugin, must be called after `on_load`
+    pub fn load_filters(&mut self) {
+        self.account_filter = self.plugin.account_filter();
+        self.transaction_filter = self.plugin.transaction_filter();
+        if self.account_filter.is_some() || self.transaction_filter.is_some() {
+            info!(
+                "Plugin {} declared filters, accounts: {:?}, transactions: {:?}",
+                self.name, self.account_filter, self.transaction_filter
+            );
+        }
+    }
+
+    /// Check if the plugin wants to be notified of an update to this account
+    pub fn account_update_selected(
+        &self,
+        pubkey: &Pubkey,
+        owner: &Pubkey,
+        data_len: usize,
+    ) -> bool {
+        self.account_filter
+            .as_ref()
+            .is_none_or(|filter| filter.matches(pubkey, owner, data_len))
+    }
+
+    /// Check if the plugin wants to be notified of this transaction
+    pub fn transaction_selected(
+        &self,
+        is_vote: bool,
+        transaction: &VersionedTransaction,
+        transaction_status_meta: &TransactionStatusMeta,
+    ) -> bool {
+        self.transaction_filter
+            .as_ref()
+            .is_none_or(|filter| filter.matches(is_vote, transaction, transaction_status_meta))
+    }
 }
 
 impl Deref for LoadedGeyserPlugin {
-    type Target = Box<dyn GeyserPlugin>;
+    type Target = dyn GeyserPlugin;
 
     fn deref(&self) -> &Self::Target {
-        &self.plugin
+        self.plugin.as_ref()
     }
 }
 
 impl DerefMut for LoadedGeyserPlugin {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.plugin
+        Arc::get_mut(&mut self.plugin)
+            .expect("the plugin is only shared with its dispatch thread, which must be stopped")
     }
 }
 
@@ -71,6 +159,7 @@ impl GeyserPluginManager {
     pub fn unload(&mut self) {
         for mut plugin in self.plugins.drain(..) {
             info!("Unloading plugin for {:?}", plugin.name());
+            plugin.stop_async_dispatch();
             plugin.on_unload();
         }
     }
@@ -171,6 +260,8 @@ impl GeyserPluginManager {
                 ),
                 data: None,
             })?;
+        new_plugin.load_filters();
+        new_plugin.start_async_dispatch();
         let name = new_plugin.name().to_string();
         self.plugins.push(new_plugin);
 
@@ -252,6 +343,8 @@ impl GeyserPluginManager {
         match new_plugin.on_load(new_parsed_config_file, true) {
             // On success, push plugin and library
             Ok(()) => {
+                new_plugin.load_filters();
+                new_plugin.start_async_dispatch();
                 self.plugins.push(new_plugin);
             }
 
@@ -273,6 +366,7 @@ impl GeyserPluginManager {
     fn _drop_plugin(&mut self, idx: usize) {
         let mut current_plugin = self.plugins.remove(idx);
         let name = current_plugin.name().to_string();
+        current_plugin.stop_async_dispatch();
         current_plugin.on_unload();
         info!("Unloaded plugin {name} at idx {idx}");
     }
@@ -396,6 +490,20 @@ pub(crate) fn load_plugin_from_config(
 
     let plugin_name = result["name"].as_str().map(|s| s.to_owned());
 
+    let async_dispatch_config = match &result["async_dispatch"] {
+        serde_json::Value::Null => None,
+        async_dispatch => Some(
+            serde_json::from_value::<AsyncDispatchConfig>(async_dispatch.clone()).map_err(
+                |err| {
+                    GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                        "The async_dispatch section of the config file \
+                         {geyser_plugin_config_file:?} is invalid, error: {err:?}"
+                    ))
+                },
+            )?,
+        ),
+    };
+
     let config_file = geyser_plugin_config_file
         .as_os_str()
         .to_str()
@@ -411,7 +519,8 @@ pub(crate) fn load_plugin_from_config(
         (Box::from_raw(plugin_raw), lib)
     };
     Ok((
-        LoadedGeyserPlugin::new(lib, plugin, plugin_name),
+        LoadedGeyserPlugin::new(lib, plugin, plugin_name)
+            .with_async_dispatch(async_dispatch_config),
         config_file,
     ))
 }
@@ -453,9 +562,17 @@ mod tests {
         crate::geyser_plugin_manager::{
             GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
         },
-        agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+        agave_geyser_plugin_interface::geyser_plugin_interface::{
+            AccountFilter, GeyserPlugin, TransactionFilter,
+        },
         libloading::Library,
-        std::sync::{Arc, RwLock},
+        solana_pubkey::Pubkey,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::TransactionStatusMeta,
+        std::{
+            collections::HashSet,
+            sync::{Arc, RwLock},
+        },
     };
 
     pub(super) fn dummy_plugin_and_library<P: GeyserPlugin>(
@@ -494,6 +611,75 @@ mod tests {
         }
     }
 
+    #[derive(Debug)]
+    struct FilteredPlugin {
+        account_filter: AccountFilter,
+        transaction_filter: TransactionFilter,
+    }
+
+    impl GeyserPlugin for FilteredPlugin {
+        fn name(&self) -> &'static str {
+            "filtered"
+        }
+
+        fn account_filter(&self) -> Option<AccountFilter> {
+            Some(self.account_filter.clone())
+        }
+
+        fn transaction_filter(&self) -> Option<TransactionFilter> {
+            Some(self.transaction_filter.clone())
+        }
+    }
+
+    #[test]
+    fn test_plugin_filters() {
+        let owner = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mentioned = Pubkey::new_unique();
+        let (mut plugin, _config) = dummy_plugin_and_library(
+            FilteredPlugin {
+                account_filter: AccountFilter {
+                    owners: HashSet::from([owner]),
+                    data_size: Some(0..=128),
+                    ..AccountFilter::default()
+                },
+                transaction_filter: TransactionFilter {
+                    include_votes: false,
+                    include_failed: false,
+                    mentioned_accounts: HashSet::from([mentioned]),
+                    ..TransactionFilter::default()
+                },
+            },
+            DUMMY_CONFIG,
+        );
+        let transaction = VersionedTransaction::default();
+        let mut meta = TransactionStatusMeta::default();
+        meta.loaded_addresses.readonly.push(mentioned);
+
+        // Filters only apply once fetched after on_load
+        assert!(plugin.account_update_selected(&other, &other, 0));
+        assert!(plugin.transaction_selected(true, &transaction, &meta));
+
+        plugin.load_filters();
+        assert!(plugin.account_update_selected(&other, &owner, 128));
+        assert!(!plugin.account_update_selected(&other, &owner, 129));
+        assert!(!plugin.account_update_selected(&owner, &other, 0));
+
+        assert!(plugin.transaction_selected(false, &transaction, &meta));
+        assert!(!plugin.transaction_selected(true, &transaction, &meta));
+        meta.status = Err(solana_transaction_error::TransactionError::AccountNotFound);
+        assert!(!plugin.transaction_selected(false, &transaction, &meta));
+        meta.status = Ok(());
+        meta.loaded_addresses.readonly.clear();
+        assert!(!plugin.transaction_selected(false, &transaction, &meta));
+
+        // Plugins without filters receive everything
+        let (mut plugin, _config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        plugin.load_filters();
+        assert!(plugin.account_update_selected(&other, &other, usize::MAX));
+        assert!(plugin.transaction_selected(true, &transaction, &meta));
+    }
+
     #[test]
     fn test_geyser_reload() {
         // Initialize empty manager
@@ -1,4 +1,5 @@
 pub mod accounts_update_notifier;
+pub mod async_dispatcher;
 pub mod block_metadata_notifier;
 pub mod block_metadata_notifier_interface;
 pub mod entry_notifier;
@@ -1,5 +1,5 @@
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{async_dispatcher::Notification, geyser_plugin_manager::GeyserPluginManager},
     agave_geyser_plugin_interface::geyser_plugin_interface::SlotStatus,
     log::*,
     solana_clock::Slot,
@@ -55,6 +55,14 @@ impl SlotStatusNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
+            if let Some(dispatcher) = plugin.async_dispatcher() {
+                dispatcher.send(Notification::SlotStatus {
+                    slot,
+                    parent,
+                    status: slot_status.clone(),
+                });
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-slot");
             match plugin.update_slot_status(slot, parent, &slot_status) {
                 Err(err) => {
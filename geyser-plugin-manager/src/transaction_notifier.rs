@@ -1,6 +1,9 @@
 /// Module responsible for notifying plugins of transactions
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        async_dispatcher::{Notification, TransactionUpdate},
+        geyser_plugin_manager::GeyserPluginManager,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaTransactionInfoV3, ReplicaTransactionInfoVersions,
     },
@@ -52,7 +55,16 @@ impl TransactionNotifier for TransactionNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
-            if !plugin.transaction_notifications_enabled() {
+            if !plugin.transaction_notifications_enabled()
+                || !plugin.transaction_selected(is_vote, transaction, transaction_status_meta)
+            {
+                continue;
+            }
+            if let Some(dispatcher) = plugin.async_dispatcher() {
+                dispatcher.send(Notification::Transaction {
+                    transaction: TransactionUpdate::new(&transaction_log_info),
+                    slot,
+                });
                 continue;
             }
             match plugin.notify_transaction(
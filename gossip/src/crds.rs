@@ -164,6 +164,11 @@ impl VersionedCrdsValue {
             num_push_recv,
         }
     }
+
+    #[inline]
+    pub(crate) fn num_push_recv(&self) -> Option<u8> {
+        self.num_push_recv
+    }
 }
 
 impl Default for Crds {
@@ -0,0 +1,262 @@
+//! Read-only views of the CRDS table for debugging gossip propagation.
+//!
+//! Entries are summarized into [`CrdsEntryInfo`], which carries the origin,
+//! wallclock and serialized size of every value along with a short
+//! type-specific description. Iteration is driven by a [`Cursor`], so callers
+//! can dump the whole table with a default cursor and then keep polling with
+//! the same cursor to stream values as they are inserted or updated.
+
+use {
+    crate::{
+        crds::{Crds, Cursor, VersionedCrdsValue},
+        crds_data::CrdsData,
+    },
+    serde::{Serialize, Serializer},
+    solana_pubkey::Pubkey,
+    std::{collections::HashSet, fmt::Display},
+};
+
+/// Names of the `CrdsData` variants accepted by [`CrdsInspectFilter::kinds`].
+pub const CRDS_DATA_KINDS: &[&str] = &[
+    "LegacyContactInfo",
+    "Vote",
+    "LowestSlot",
+    "LegacySnapshotHashes",
+    "AccountsHashes",
+    "EpochSlots",
+    "LegacyVersion",
+    "Version",
+    "NodeInstance",
+    "DuplicateShred",
+    "SnapshotHashes",
+    "ContactInfo",
+    "RestartLastVotedForkSlots",
+    "RestartHeaviestFork",
+];
+
+/// Selects which CRDS entries are returned by [`get_crds_entries`].
+#[derive(Clone, Debug, Default)]
+pub struct CrdsInspectFilter {
+    /// Only return entries of these `CrdsData` kinds; all kinds if `None`.
+    pub kinds: Option<HashSet<&'static str>>,
+    /// Only return entries originated by these nodes; all nodes if `None`.
+    pub origins: Option<HashSet<Pubkey>>,
+}
+
+impl CrdsInspectFilter {
+    fn matches(&self, kind: &str, origin: &Pubkey) -> bool {
+        self.kinds.as_ref().is_none_or(|kinds| kinds.contains(kind))
+            && self
+                .origins
+                .as_ref()
+                .is_none_or(|origins| origins.contains(origin))
+    }
+}
+
+/// Summary of a single value in the CRDS table.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrdsEntryInfo {
+    /// Name of the `CrdsData` variant.
+    pub kind: &'static str,
+    /// Index of the value for kinds a node may publish several of.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<u8>,
+    #[serde(serialize_with = "serialize_display")]
+    pub origin: Pubkey,
+    /// Wallclock set by the origin when the value was signed.
+    pub wallclock: u64,
+    /// Local time at which this node inserted or last updated the value.
+    pub local_timestamp: u64,
+    /// How the value reached this node: `None` if inserted locally or from a
+    /// pull request, `Some(0)` if received in a pull response and `Some(k)` if
+    /// pushed, with `k - 1` duplicate pushes received since.
+    pub num_push_recv: Option<u8>,
+    /// Size of the serialized value in bytes.
+    pub size: usize,
+    #[serde(serialize_with = "serialize_display")]
+    pub hash: solana_hash::Hash,
+    /// Short human readable description of the value.
+    pub details: String,
+}
+
+fn serialize_display<T: Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// Returns the entries inserted or updated since `cursor` which match
+/// `filter`, in insertion order, and advances `cursor` past them.
+///
+/// A default cursor returns the whole table.
+pub fn get_crds_entries(
+    crds: &Crds,
+    cursor: &mut Cursor,
+    filter: &CrdsInspectFilter,
+) -> Vec<CrdsEntryInfo> {
+    crds.get_entries(cursor)
+        .filter_map(|entry| {
+            let info = CrdsEntryInfo::from(entry);
+            filter.matches(info.kind, &info.origin).then_some(info)
+        })
+        .collect()
+}
+
+impl From<&VersionedCrdsValue> for CrdsEntryInfo {
+    fn from(entry: &VersionedCrdsValue) -> Self {
+        let value = &entry.value;
+        let (kind, index, details) = describe(value.data());
+        Self {
+            kind,
+            index,
+            origin: value.pubkey(),
+            wallclock: value.wallclock(),
+            local_timestamp: entry.local_timestamp,
+            num_push_recv: entry.num_push_recv(),
+            size: value.bincode_serialized_size(),
+            hash: *value.hash(),
+            details,
+        }
+    }
+}
+
+fn describe(data: &CrdsData) -> (&'static str, Option<u8>, String) {
+    match data {
+        CrdsData::LegacyContactInfo(_) => ("LegacyContactInfo", None, String::default()),
+        CrdsData::Vote(index, vote) => (
+            "Vote",
+            Some(*index),
+            vote.slot()
+                .map(|slot| format!("slot={slot}"))
+                .unwrap_or_default(),
+        ),
+        CrdsData::LowestSlot(_, lowest_slot) => {
+            ("LowestSlot", None, format!("lowest={}", lowest_slot.lowest))
+        }
+        CrdsData::LegacySnapshotHashes(_) => ("LegacySnapshotHashes", None, String::default()),
+        CrdsData::AccountsHashes(_) => ("AccountsHashes", None, String::default()),
+        CrdsData::EpochSlots(index, epoch_slots) => (
+            "EpochSlots",
+            Some(*index),
+            epoch_slots
+                .first_slot()
+                .map(|slot| format!("first_slot={slot}"))
+                .unwrap_or_default(),
+        ),
+        CrdsData::LegacyVersion(_) => ("LegacyVersion", None, String::default()),
+        CrdsData::Version(_) => ("Version", None, String::default()),
+        CrdsData::NodeInstance(_) => ("NodeInstance", None, String::default()),
+        CrdsData::DuplicateShred(index, shred) => (
+            "DuplicateShred",
+            Some(*index),
+            format!(
+                "slot={} chunk={}/{}",
+                shred.slot,
+                shred.chunk_index(),
+                shred.num_chunks()
+            ),
+        ),
+        CrdsData::SnapshotHashes(hashes) => (
+            "SnapshotHashes",
+            None,
+            format!(
+                "full={} incremental={}",
+                hashes.full.0,
+                hashes
+                    .incremental
+                    .iter()
+                    .map(|(slot, _)| *slot)
+                    .max()
+                    .map(|slot| slot.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+        ),
+        CrdsData::ContactInfo(node) => (
+            "ContactInfo",
+            None,
+            format!(
+                "gossip={} shred_version={} version={}",
+                node.gossip()
+                    .map(|addr| addr.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+                node.shred_version(),
+                node.version()
+            ),
+        ),
+        CrdsData::RestartLastVotedForkSlots(slots) => (
+            "RestartLastVotedForkSlots",
+            None,
+            format!(
+                "last_voted_slot={} last_voted_hash={} shred_version={}",
+                slots.last_voted_slot, slots.last_voted_hash, slots.shred_version
+            ),
+        ),
+        CrdsData::RestartHeaviestFork(fork) => (
+            "RestartHeaviestFork",
+            None,
+            format!(
+                "last_slot={} last_slot_hash={} observed_stake={} shred_version={}",
+                fork.last_slot, fork.last_slot_hash, fork.observed_stake, fork.shred_version
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{contact_info::ContactInfo, crds::GossipRoute, crds_value::CrdsValue},
+        solana_keypair::Keypair,
+        solana_signer::Signer,
+        solana_time_utils::timestamp,
+    };
+
+    #[test]
+    fn test_get_crds_entries() {
+        let mut crds = Crds::default();
+        let keypairs: Vec<_> = std::iter::repeat_with(Keypair::new).take(3).collect();
+        for keypair in &keypairs {
+            let node = ContactInfo::new_localhost(&keypair.pubkey(), timestamp());
+            let value = CrdsValue::new(CrdsData::ContactInfo(node), keypair);
+            crds.insert(value, timestamp(), GossipRoute::LocalMessage)
+                .unwrap();
+        }
+
+        let mut cursor = Cursor::default();
+        let entries = get_crds_entries(&crds, &mut cursor, &CrdsInspectFilter::default());
+        assert_eq!(entries.len(), 3);
+        for (entry, keypair) in entries.iter().zip(&keypairs) {
+            assert_eq!(entry.kind, "ContactInfo");
+            assert_eq!(entry.origin, keypair.pubkey());
+            assert!(entry.size > 0);
+        }
+        // Nothing new since the last call.
+        assert!(get_crds_entries(&crds, &mut cursor, &CrdsInspectFilter::default()).is_empty());
+
+        let filter = CrdsInspectFilter {
+            kinds: None,
+            origins: Some(HashSet::from([keypairs[1].pubkey()])),
+        };
+        let entries = get_crds_entries(&crds, &mut Cursor::default(), &filter);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].origin, keypairs[1].pubkey());
+
+        let filter = CrdsInspectFilter {
+            kinds: Some(HashSet::from(["Vote"])),
+            origins: None,
+        };
+        assert!(get_crds_entries(&crds, &mut Cursor::default(), &filter).is_empty());
+
+        // Updated values are streamed again.
+        let node = ContactInfo::new_localhost(&keypairs[2].pubkey(), timestamp() + 1);
+        let value = CrdsValue::new(CrdsData::ContactInfo(node), &keypairs[2]);
+        crds.insert(value, timestamp(), GossipRoute::LocalMessage)
+            .unwrap();
+        let entries = get_crds_entries(&crds, &mut cursor, &CrdsInspectFilter::default());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].origin, keypairs[2].pubkey());
+    }
+}
@@ -9,19 +9,34 @@ use {
     solana_clap_utils::{
         hidden_unless_forced,
         input_parsers::{keypair_of, pubkeys_of},
-        input_validators::{is_keypair_or_ask_keyword, is_port, is_pubkey},
+        input_validators::{is_keypair_or_ask_keyword, is_parsable, is_port, is_pubkey},
     },
-    solana_gossip::{contact_info::ContactInfo, gossip_service::discover},
+    solana_gossip::{
+        contact_info::ContactInfo,
+        crds::Cursor,
+        crds_inspect::{get_crds_entries, CrdsEntryInfo, CrdsInspectFilter, CRDS_DATA_KINDS},
+        gossip_service::{discover, make_gossip_node},
+    },
+    solana_keypair::Keypair,
+    solana_net_utils::DEFAULT_IP_ECHO_SERVER_THREADS,
     solana_pubkey::Pubkey,
     solana_streamer::socket::SocketAddrSpace,
     std::{
+        collections::HashSet,
         error,
         net::{IpAddr, Ipv4Addr, SocketAddr},
         process::exit,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::sleep,
         time::Duration,
     },
 };
 
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 fn parse_matches() -> ArgMatches<'static> {
     let shred_version_arg = Arg::with_name("shred_version")
         .long("shred-version")
@@ -163,6 +178,79 @@ fn parse_matches() -> ArgMatches<'static> {
                         .help("Maximum time to wait in seconds [default: wait forever]"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("inspect")
+                .about("Join the cluster as a spy and dump its CRDS table")
+                .setting(AppSettings::DisableVersion)
+                .arg(
+                    Arg::with_name("entrypoint")
+                        .short("n")
+                        .long("entrypoint")
+                        .value_name("HOST:PORT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(solana_net_utils::is_host_port)
+                        .help("Rendezvous with the cluster at this entrypoint"),
+                )
+                .arg(
+                    Arg::with_name("identity")
+                        .short("i")
+                        .long("identity")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .validator(is_keypair_or_ask_keyword)
+                        .help("Identity keypair [default: ephemeral keypair]"),
+                )
+                .arg(
+                    Arg::with_name("kind")
+                        .long("type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .multiple(true)
+                        .possible_values(CRDS_DATA_KINDS)
+                        .help("Only show values of this CrdsData type"),
+                )
+                .arg(
+                    Arg::with_name("origin")
+                        .long("origin")
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .multiple(true)
+                        .help("Only show values originated by this node"),
+                )
+                .arg(
+                    Arg::with_name("wait")
+                        .long("wait")
+                        .value_name("SECONDS")
+                        .takes_value(true)
+                        .default_value("15")
+                        .validator(is_parsable::<u64>)
+                        .help("Time to let the table fill up through gossip before dumping it"),
+                )
+                .arg(
+                    Arg::with_name("follow")
+                        .long("follow")
+                        .takes_value(false)
+                        .help(
+                            "After dumping the table, keep running and print values as they are \
+                             inserted or updated",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(&["text", "json"])
+                        .default_value("text")
+                        .help("Output format. With --follow, json prints one value per line"),
+                )
+                .arg(&shred_version_arg)
+                .arg(&gossip_port_arg)
+                .arg(&gossip_host_arg)
+                .arg(&bind_address_arg),
+        )
         .get_matches()
 }
 
@@ -360,6 +448,107 @@ fn process_rpc_url(
     Ok(())
 }
 
+fn print_crds_entries(entries: &[CrdsEntryInfo], json: bool) {
+    for entry in entries {
+        if json {
+            println!("{}", serde_json::to_string(entry).unwrap());
+        } else {
+            println!(
+                "{:<26} {:>3} {:<44} {:>13} {:>5} {}",
+                entry.kind,
+                entry
+                    .index
+                    .map(|index| index.to_string())
+                    .unwrap_or_default(),
+                entry.origin,
+                entry.wallclock,
+                entry.size,
+                entry.details,
+            );
+        }
+    }
+}
+
+fn process_inspect(
+    matches: &ArgMatches,
+    socket_addr_space: SocketAddrSpace,
+) -> std::io::Result<()> {
+    let identity_keypair = keypair_of(matches, "identity").unwrap_or_else(Keypair::new);
+    let entrypoint_addr = parse_entrypoint(matches);
+    let gossip_addr = get_gossip_address(matches, entrypoint_addr);
+    let wait = Duration::from_secs(value_t_or_exit!(matches, "wait", u64));
+    let follow = matches.is_present("follow");
+    let json = matches.value_of("output") == Some("json");
+    let filter = CrdsInspectFilter {
+        kinds: matches.values_of("kind").map(|kinds| {
+            kinds
+                .filter_map(|kind| CRDS_DATA_KINDS.iter().find(|k| **k == kind).copied())
+                .collect()
+        }),
+        origins: pubkeys_of(matches, "origin").map(HashSet::from_iter),
+    };
+
+    let mut shred_version = value_t_or_exit!(matches, "shred_version", u16);
+    if shred_version == 0 {
+        shred_version = get_entrypoint_shred_version(&entrypoint_addr)
+            .expect("need non-zero shred-version to join the cluster");
+    }
+
+    let exit = Arc::new(AtomicBool::new(false));
+    let (gossip_service, ip_echo, cluster_info) = make_gossip_node(
+        identity_keypair,
+        entrypoint_addr.as_ref(),
+        exit.clone(),
+        Some(&gossip_addr),
+        shred_version,
+        true, // should_check_duplicate_instance
+        socket_addr_space,
+    );
+    let _ip_echo_server = ip_echo.map(|tcp_listener| {
+        solana_net_utils::ip_echo_server(
+            tcp_listener,
+            DEFAULT_IP_ECHO_SERVER_THREADS,
+            Some(shred_version),
+        )
+    });
+    info!("Node Id: {}", cluster_info.id());
+
+    sleep(wait);
+    let mut cursor = Cursor::default();
+    let entries = get_crds_entries(
+        &cluster_info.gossip.crds.read().unwrap(),
+        &mut cursor,
+        &filter,
+    );
+    if json && !follow {
+        println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+    } else {
+        if !json {
+            println!(
+                "{:<26} {:>3} {:<44} {:>13} {:>5} Details",
+                "Type", "Idx", "Origin", "Wallclock", "Size"
+            );
+        }
+        print_crds_entries(&entries, json);
+    }
+
+    if follow {
+        loop {
+            sleep(FOLLOW_POLL_INTERVAL);
+            let entries = get_crds_entries(
+                &cluster_info.gossip.crds.read().unwrap(),
+                &mut cursor,
+                &filter,
+            );
+            print_crds_entries(&entries, json);
+        }
+    }
+
+    exit.store(true, Ordering::Relaxed);
+    gossip_service.join().unwrap();
+    Ok(())
+}
+
 fn get_gossip_address(matches: &ArgMatches, entrypoint_addr: Option<SocketAddr>) -> SocketAddr {
     let bind_address = parse_bind_address(matches, entrypoint_addr);
     SocketAddr::new(
@@ -386,6 +575,9 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         ("rpc-url", Some(matches)) => {
             process_rpc_url(matches, socket_addr_space)?;
         }
+        ("inspect", Some(matches)) => {
+            process_inspect(matches, socket_addr_space)?;
+        }
         _ => unreachable!(),
     }
 
@@ -5,7 +5,10 @@ use {
         error::{LedgerToolError, Result},
         ledger_path::canonicalize_ledger_path,
         ledger_utils::get_program_ids,
-        output::{output_ledger, output_slot, CliDuplicateSlotProof, SlotBounds, SlotInfo},
+        output::{
+            output_ledger, output_slot, CliBlockCostDetails, CliDuplicateSlotProof, SlotBounds,
+            SlotInfo,
+        },
     },
     chrono::{DateTime, Utc},
     clap::{
@@ -26,13 +29,14 @@ use {
             Blockstore, PurgeType,
         },
         blockstore_options::AccessType,
-        shred::Shred,
+        shred::{self, Shred, ShredType},
     },
+    solana_turbine::shred_arrivals::SlotShredArrivals,
     std::{
         borrow::Cow,
-        collections::{BTreeMap, BTreeSet, HashMap},
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
         fs::File,
-        io::{stdout, BufRead, BufReader, Write},
+        io::{stdout, BufRead, BufReader, BufWriter, Write},
         path::{Path, PathBuf},
         sync::atomic::AtomicBool,
         time::{Duration, UNIX_EPOCH},
@@ -130,7 +134,8 @@ fn analyze_storage(blockstore: &Blockstore) -> Result<()> {
     analyze_column(blockstore, Blocktime::NAME)?;
     analyze_column(blockstore, PerfSamples::NAME)?;
     analyze_column(blockstore, BlockHeight::NAME)?;
-    analyze_column(blockstore, OptimisticSlots::NAME)
+    analyze_column(blockstore, OptimisticSlots::NAME)?;
+    analyze_column(blockstore, BlockCost::NAME)
 }
 
 fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
@@ -161,6 +166,7 @@ fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
         cf::OptimisticSlots::NAME => {
             Some(cf::OptimisticSlots::slot(cf::OptimisticSlots::index(key)))
         }
+        cf::BlockCost::NAME => Some(cf::BlockCost::slot(cf::BlockCost::index(key))),
         &_ => None,
     }
 }
@@ -263,6 +269,72 @@ fn print_blockstore_file_metadata(blockstore: &Blockstore, file_name: &Option<&s
     Ok(())
 }
 
+// Compares shred arrival records captured by a validator with the shreds of
+// the slot in the blockstore.
+fn print_shred_arrivals(blockstore: &Blockstore, slot_arrivals: &SlotShredArrivals) -> Result<()> {
+    let slot = slot_arrivals.slot;
+    println!("{slot_arrivals}");
+    let Some(meta) = blockstore.meta(slot)? else {
+        println!("Slot {slot} is not in the blockstore");
+        return Ok(());
+    };
+    let received: HashSet<(ShredType, u64)> = slot_arrivals
+        .arrivals
+        .iter()
+        .map(|arrival| (arrival.shred_type, u64::from(arrival.index)))
+        .collect();
+    let num_coding_shreds = blockstore.slot_coding_iterator(slot, 0)?.count();
+    let mut num_data_shreds = 0;
+    // Data shreds in the blockstore which were never received were recovered
+    // from the other shreds of their erasure batch.
+    let mut recovered = BTreeMap::<u32, usize>::new();
+    let mut stored = HashSet::new();
+    for ((_, index), shred) in blockstore.slot_data_iterator(slot, 0)? {
+        num_data_shreds += 1;
+        stored.insert(index);
+        if !received.contains(&(ShredType::Data, index)) {
+            let fec_set_index = shred::layout::get_fec_set_index(&shred).unwrap_or_default();
+            *recovered.entry(fec_set_index).or_default() += 1;
+        }
+    }
+    let num_missing = received
+        .iter()
+        .filter(|(shred_type, index)| *shred_type == ShredType::Data && !stored.contains(index))
+        .count();
+
+    println!(
+        "Blockstore: {num_data_shreds} data shreds, {num_coding_shreds} coding shreds, {}",
+        if meta.is_full() {
+            "full".to_string()
+        } else {
+            format!(
+                "incomplete ({} of {} data shreds consecutive)",
+                meta.consumed,
+                meta.last_index
+                    .map(|index| (index + 1).to_string())
+                    .unwrap_or_else(|| "?".to_string())
+            )
+        }
+    );
+    if let Some(first_arrival) = slot_arrivals.first_arrival() {
+        println!(
+            "First shred inserted into the blockstore {}ms after the first shred arrived",
+            meta.first_shred_timestamp as i64 - first_arrival as i64
+        );
+    }
+    println!(
+        "Data shreds recovered from erasure coding: {}",
+        recovered.values().sum::<usize>()
+    );
+    for (fec_set_index, count) in recovered {
+        println!("  FEC set {fec_set_index}: {count}");
+    }
+    if num_missing > 0 {
+        println!("Received data shreds missing from the blockstore: {num_missing}");
+    }
+    Ok(())
+}
+
 pub trait BlockstoreSubCommand {
     fn blockstore_subcommand(self) -> Self;
 }
@@ -308,6 +380,13 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
                 "Output statistics in JSON format about all column families in the ledger rocksdb",
             )
             .settings(&hidden),
+        SubCommand::with_name("block-cost")
+            .about(
+                "Print the recorded block cost and most contended writable accounts of each slot",
+            )
+            .settings(&hidden)
+            .arg(&starting_slot_arg)
+            .arg(&ending_slot_arg),
         SubCommand::with_name("bounds")
             .about(
                 "Print lowest and highest non-empty slots. Note that there may be empty slots \
@@ -333,6 +412,22 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
                     .takes_value(true)
                     .help("Target ledger directory to write inner \"rocksdb\" within."),
             ),
+        SubCommand::with_name("export-shred-archive")
+            .about(
+                "Export the shreds and shred metadata of a range of slots to a shred archive \
+                 that a validator can import with --import-shred-archive",
+            )
+            .settings(&hidden)
+            .arg(&starting_slot_arg)
+            .arg(&ending_slot_arg)
+            .arg(
+                Arg::with_name("output_file")
+                    .long("output-file")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Path of the shred archive to write"),
+            ),
         SubCommand::with_name("dead-slots")
             .about("Print all the dead slots in the ledger")
             .settings(&hidden)
@@ -562,6 +657,20 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
                     .required(true)
                     .help("Slots to mark dead"),
             ),
+        SubCommand::with_name("shred-arrivals")
+            .about(
+                "Compare shred arrival records captured with `agave-validator shred-arrivals` \
+                 against the ledger",
+            )
+            .settings(&hidden)
+            .arg(
+                Arg::with_name("arrivals_file")
+                    .long("arrivals-file")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Json output of `agave-validator shred-arrivals <SLOT> --output json`"),
+            ),
         SubCommand::with_name("shred-meta")
             .about("Prints raw shred metadata")
             .settings(&hidden)
@@ -601,6 +710,24 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
             arg_matches,
             AccessType::Secondary,
         ))?,
+        ("block-cost", Some(arg_matches)) => {
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+            let output_format =
+                OutputFormat::from_matches(arg_matches, "output_format", verbose_level > 1);
+            for (slot, details) in blockstore
+                .block_cost_details_iterator(starting_slot)?
+                .take_while(|(slot, _)| *slot <= ending_slot)
+            {
+                let cli_block_cost_details = CliBlockCostDetails::new(slot, details);
+                println!(
+                    "{}",
+                    output_format.formatted_string(&cli_block_cost_details)
+                );
+            }
+        }
         ("bounds", Some(arg_matches)) => {
             let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
             let all = arg_matches.is_present("all");
@@ -683,6 +810,20 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                 }
             }
         }
+        ("export-shred-archive", Some(arg_matches)) => {
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+            let output_file = value_t_or_exit!(arg_matches, "output_file", String);
+
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let writer = BufWriter::new(File::create(&output_file)?);
+            let stats = blockstore.export_shred_archive(writer, starting_slot, ending_slot)?;
+            println!(
+                "Exported {} slots ({} data shreds, {} coding shreds) to {output_file}",
+                stats.num_slots, stats.num_data_shreds, stats.num_coding_shreds,
+            );
+        }
         ("dead-slots", Some(arg_matches)) => {
             let blockstore =
                 crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
@@ -983,6 +1124,14 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                     .map(|_| println!("Slot {slot} marked dead"))?;
             }
         }
+        ("shred-arrivals", Some(arg_matches)) => {
+            let arrivals_file = value_t_or_exit!(arg_matches, "arrivals_file", String);
+            let slot_arrivals: SlotShredArrivals =
+                serde_json::from_reader(BufReader::new(File::open(&arrivals_file)?))?;
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            print_shred_arrivals(&blockstore, &slot_arrivals)?;
+        }
         ("shred-meta", Some(arg_matches)) => {
             #[derive(Debug)]
             #[allow(dead_code)]
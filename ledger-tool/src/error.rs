@@ -1,4 +1,9 @@
-use {solana_ledger::blockstore::BlockstoreError, thiserror::Error};
+use {
+    solana_core::consensus::TowerError,
+    solana_ledger::blockstore::{shred_archive::ShredArchiveError, BlockstoreError},
+    solana_runtime::snapshot_utils::SnapshotError,
+    thiserror::Error,
+};
 
 pub type Result<T> = std::result::Result<T, LedgerToolError>;
 
@@ -7,6 +12,15 @@ pub enum LedgerToolError {
     #[error("{0}")]
     Blockstore(#[from] BlockstoreError),
 
+    #[error("{0}")]
+    ShredArchive(#[from] ShredArchiveError),
+
+    #[error("{0}")]
+    Snapshot(#[from] SnapshotError),
+
+    #[error("{0}")]
+    Tower(#[from] TowerError),
+
     #[error("{0}")]
     SerdeJson(#[from] serde_json::Error),
 
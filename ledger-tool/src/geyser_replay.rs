@@ -0,0 +1,371 @@
+//! Helpers to drive Geyser plugins from ledger replay.
+//!
+//! The `geyser-replay` subcommand hands these to the replay machinery through
+//! `load_and_replay_ledger_to_geyser_or_exit()` so that plugins observe the
+//! same sequence of notifications that a live validator would produce, which
+//! lets them backfill their data stores. Other commands accepting a Geyser
+//! plugin config only notify plugins of account and transaction updates.
+
+use {
+    solana_account::AccountSharedData,
+    solana_accounts_db::accounts_update_notifier_interface::{
+        AccountForGeyser, AccountsUpdateNotifierInterface,
+    },
+    solana_clock::Slot,
+    solana_entry::entry::EntrySummary,
+    solana_geyser_plugin_manager::block_metadata_notifier_interface::BlockMetadataNotifierArc,
+    solana_hash::Hash,
+    solana_ledger::{
+        blockstore::Blockstore, blockstore_processor::ProcessSlotCallback,
+        entry_notifier_interface::EntryNotifier,
+    },
+    solana_pubkey::Pubkey,
+    solana_rpc::{
+        slot_status_notifier::SlotStatusNotifier,
+        transaction_notifier_interface::TransactionNotifier,
+    },
+    solana_runtime::bank::Bank,
+    solana_signature::Signature,
+    solana_transaction::{sanitized::SanitizedTransaction, versioned::VersionedTransaction},
+    solana_transaction_status::TransactionStatusMeta,
+    std::sync::Arc,
+};
+
+/// Options specific to replaying the ledger for the `geyser-replay` subcommand
+#[derive(Debug, Default)]
+pub struct GeyserReplayConfig {
+    /// Only notify plugins of slots at or after this slot, defaults to the
+    /// slot replay starts from
+    pub start_slot: Option<Slot>,
+}
+
+/// Forwards notifications to `inner` only for slots at or after `start_slot`.
+///
+/// Slots between the starting snapshot and `start_slot` must still be
+/// replayed to reach `start_slot`, but plugins being backfilled are not
+/// interested in them. Notifications for accounts restored from the snapshot
+/// are always forwarded since they describe the state replay begins from.
+#[derive(Debug)]
+pub struct StartSlotFilter<T: ?Sized> {
+    inner: Arc<T>,
+    start_slot: Slot,
+}
+
+impl<T: ?Sized> StartSlotFilter<T> {
+    pub fn new(inner: Arc<T>, start_slot: Slot) -> Self {
+        Self { inner, start_slot }
+    }
+}
+
+impl AccountsUpdateNotifierInterface
+    for StartSlotFilter<dyn AccountsUpdateNotifierInterface + Sync + Send>
+{
+    fn snapshot_notifications_enabled(&self) -> bool {
+        self.inner.snapshot_notifications_enabled()
+    }
+
+    fn notify_account_update(
+        &self,
+        slot: Slot,
+        account: &AccountSharedData,
+        txn: &Option<&SanitizedTransaction>,
+        pubkey: &Pubkey,
+        write_version: u64,
+    ) {
+        if slot >= self.start_slot {
+            self.inner
+                .notify_account_update(slot, account, txn, pubkey, write_version);
+        }
+    }
+
+    fn notify_account_restore_from_snapshot(
+        &self,
+        slot: Slot,
+        write_version: u64,
+        account: &AccountForGeyser<'_>,
+    ) {
+        self.inner
+            .notify_account_restore_from_snapshot(slot, write_version, account);
+    }
+
+    fn notify_end_of_restore_from_snapshot(&self) {
+        self.inner.notify_end_of_restore_from_snapshot();
+    }
+}
+
+impl TransactionNotifier for StartSlotFilter<dyn TransactionNotifier + Sync + Send> {
+    fn notify_transaction(
+        &self,
+        slot: Slot,
+        transaction_slot_index: usize,
+        signature: &Signature,
+        message_hash: &Hash,
+        is_vote: bool,
+        transaction_status_meta: &TransactionStatusMeta,
+        transaction: &VersionedTransaction,
+    ) {
+        if slot >= self.start_slot {
+            self.inner.notify_transaction(
+                slot,
+                transaction_slot_index,
+                signature,
+                message_hash,
+                is_vote,
+                transaction_status_meta,
+                transaction,
+            );
+        }
+    }
+}
+
+impl EntryNotifier for StartSlotFilter<dyn EntryNotifier + Sync + Send> {
+    fn notify_entry(
+        &self,
+        slot: Slot,
+        index: usize,
+        entry: &EntrySummary,
+        starting_transaction_index: usize,
+    ) {
+        if slot >= self.start_slot {
+            self.inner
+                .notify_entry(slot, index, entry, starting_transaction_index);
+        }
+    }
+}
+
+/// Returns a slot callback that emits slot status and block metadata
+/// notifications for every bank replayed at or after `start_slot`.
+///
+/// ReplayStage reports `CreatedBank` when a bank is created and block metadata
+/// once it is frozen; the callback runs after the bank is frozen so both are
+/// reported together, followed by `Processed`. Replay from the ledger never
+/// observes votes, so slots the blockstore recorded as optimistically
+/// confirmed or rooted are then reported as `Confirmed`, and rooted slots as
+/// `Rooted`, in the order a live validator reports them.
+pub fn geyser_slot_callback(
+    blockstore: Arc<Blockstore>,
+    block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+    slot_status_notifier: Option<SlotStatusNotifier>,
+    start_slot: Slot,
+) -> ProcessSlotCallback {
+    Arc::new(move |bank: &Bank| {
+        let slot = bank.slot();
+        if slot < start_slot {
+            return;
+        }
+        let parent_slot = bank.parent_slot();
+
+        if let Some(slot_status_notifier) = &slot_status_notifier {
+            slot_status_notifier
+                .read()
+                .unwrap()
+                .notify_created_bank(slot, parent_slot);
+        }
+
+        if let Some(block_metadata_notifier) = &block_metadata_notifier {
+            let parent_blockhash = bank
+                .parent()
+                .map(|bank| bank.last_blockhash())
+                .unwrap_or_default();
+            let entry_count = blockstore
+                .get_slot_entries(slot, 0)
+                .map(|entries| entries.len() as u64)
+                .unwrap_or_default();
+            block_metadata_notifier.notify_block_metadata(
+                parent_slot,
+                &parent_blockhash.to_string(),
+                slot,
+                &bank.last_blockhash().to_string(),
+                &bank.get_rewards_and_num_partitions(),
+                Some(bank.clock().unix_timestamp),
+                Some(bank.block_height()),
+                bank.executed_transaction_count(),
+                entry_count,
+            );
+        }
+
+        if let Some(slot_status_notifier) = &slot_status_notifier {
+            let slot_status_notifier = slot_status_notifier.read().unwrap();
+            slot_status_notifier.notify_slot_processed(slot, Some(parent_slot));
+            let is_root = blockstore.is_root(slot);
+            let is_optimistic = matches!(
+                blockstore.get_optimistic_slot(slot),
+                Ok(Some((hash, _))) if hash == bank.hash()
+            );
+            if is_root || is_optimistic {
+                slot_status_notifier.notify_slot_confirmed(slot, Some(parent_slot));
+            }
+            if is_root {
+                slot_status_notifier.notify_slot_rooted(slot, Some(parent_slot));
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_ledger::get_tmp_ledger_path_auto_delete,
+        solana_rpc::slot_status_notifier::SlotStatusNotifierInterface,
+        solana_runtime::genesis_utils::create_genesis_config,
+        std::sync::{Mutex, RwLock},
+    };
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        notifications: Mutex<Vec<(&'static str, Slot)>>,
+    }
+
+    impl RecordingNotifier {
+        fn record(&self, kind: &'static str, slot: Slot) {
+            self.notifications.lock().unwrap().push((kind, slot));
+        }
+
+        fn take(&self) -> Vec<(&'static str, Slot)> {
+            std::mem::take(&mut self.notifications.lock().unwrap())
+        }
+    }
+
+    impl EntryNotifier for RecordingNotifier {
+        fn notify_entry(
+            &self,
+            slot: Slot,
+            _index: usize,
+            _entry: &EntrySummary,
+            _starting_transaction_index: usize,
+        ) {
+            self.record("entry", slot);
+        }
+    }
+
+    impl TransactionNotifier for RecordingNotifier {
+        fn notify_transaction(
+            &self,
+            slot: Slot,
+            _transaction_slot_index: usize,
+            _signature: &Signature,
+            _message_hash: &Hash,
+            _is_vote: bool,
+            _transaction_status_meta: &TransactionStatusMeta,
+            _transaction: &VersionedTransaction,
+        ) {
+            self.record("transaction", slot);
+        }
+    }
+
+    impl SlotStatusNotifierInterface for RecordingNotifier {
+        fn notify_slot_confirmed(&self, slot: Slot, _parent: Option<Slot>) {
+            self.record("confirmed", slot);
+        }
+
+        fn notify_slot_processed(&self, slot: Slot, _parent: Option<Slot>) {
+            self.record("processed", slot);
+        }
+
+        fn notify_slot_rooted(&self, slot: Slot, _parent: Option<Slot>) {
+            self.record("rooted", slot);
+        }
+
+        fn notify_first_shred_received(&self, slot: Slot) {
+            self.record("first_shred_received", slot);
+        }
+
+        fn notify_completed(&self, slot: Slot) {
+            self.record("completed", slot);
+        }
+
+        fn notify_created_bank(&self, slot: Slot, _parent: Slot) {
+            self.record("created_bank", slot);
+        }
+
+        fn notify_slot_dead(&self, slot: Slot, _parent: Slot, _error: String) {
+            self.record("dead", slot);
+        }
+    }
+
+    #[test]
+    fn test_start_slot_filter() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let entry_notifier: Arc<dyn EntryNotifier + Sync + Send> = notifier.clone();
+        let entry_filter = StartSlotFilter::new(entry_notifier, 10);
+        let transaction_notifier: Arc<dyn TransactionNotifier + Sync + Send> = notifier.clone();
+        let transaction_filter = StartSlotFilter::new(transaction_notifier, 10);
+
+        let entry = EntrySummary {
+            num_hashes: 0,
+            hash: Hash::default(),
+            num_transactions: 0,
+        };
+        for slot in [9, 10, 11] {
+            entry_filter.notify_entry(slot, 0, &entry, 0);
+            transaction_filter.notify_transaction(
+                slot,
+                0,
+                &Signature::default(),
+                &Hash::default(),
+                false,
+                &TransactionStatusMeta::default(),
+                &VersionedTransaction::default(),
+            );
+        }
+        assert_eq!(
+            notifier.take(),
+            vec![
+                ("entry", 10),
+                ("transaction", 10),
+                ("entry", 11),
+                ("transaction", 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_geyser_slot_callback_status_order() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let genesis_config = create_genesis_config(1_000_000).genesis_config;
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        let new_frozen_bank = |slot| {
+            let bank = Bank::new_from_parent(bank0.clone(), &Pubkey::default(), slot);
+            bank.freeze();
+            bank
+        };
+        let (bank1, bank2, bank3, bank4) = (
+            new_frozen_bank(1),
+            new_frozen_bank(2),
+            new_frozen_bank(3),
+            new_frozen_bank(4),
+        );
+        // Slot 2 is optimistically confirmed, slot 3 is rooted and slot 4 was
+        // optimistically confirmed on another fork
+        blockstore
+            .insert_optimistic_slot(2, &bank2.hash(), 0)
+            .unwrap();
+        blockstore.set_roots(std::iter::once(&3)).unwrap();
+        blockstore
+            .insert_optimistic_slot(4, &Hash::new_unique(), 0)
+            .unwrap();
+
+        let notifier = Arc::new(RwLock::new(RecordingNotifier::default()));
+        let slot_status_notifier: SlotStatusNotifier = notifier.clone();
+        let callback = geyser_slot_callback(blockstore, None, Some(slot_status_notifier), 2);
+        for bank in [&bank1, &bank2, &bank3, &bank4] {
+            callback(bank);
+        }
+        assert_eq!(
+            notifier.read().unwrap().take(),
+            vec![
+                ("created_bank", 2),
+                ("processed", 2),
+                ("confirmed", 2),
+                ("created_bank", 3),
+                ("processed", 3),
+                ("confirmed", 3),
+                ("rooted", 3),
+                ("created_bank", 4),
+                ("processed", 4),
+            ]
+        );
+    }
+}
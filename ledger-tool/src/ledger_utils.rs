@@ -1,9 +1,13 @@
 use {
-    crate::LEDGER_TOOL_DIRECTORY,
+    crate::{
+        geyser_replay::{geyser_slot_callback, GeyserReplayConfig, StartSlotFilter},
+        LEDGER_TOOL_DIRECTORY,
+    },
     clap::{value_t, value_t_or_exit, values_t_or_exit, ArgMatches},
     crossbeam_channel::unbounded,
     log::*,
     solana_accounts_db::{
+        accounts_update_notifier_interface::AccountsUpdateNotifier,
         hardened_unpack::open_genesis_config,
         utils::{create_all_accounts_run_and_snapshot_dirs, move_and_async_delete_path_contents},
     },
@@ -18,18 +22,25 @@ use {
         blockstore::{Blockstore, BlockstoreError},
         blockstore_options::{AccessType, BlockstoreOptions, BlockstoreRecoveryMode},
         blockstore_processor::{
-            self, BlockstoreProcessorError, ProcessOptions, TransactionStatusSender,
+            self, BlockstoreProcessorError, ProcessOptions, ProcessSlotCallback,
+            TransactionStatusSender,
         },
+        entry_notifier_interface::EntryNotifierArc,
+        entry_notifier_service::EntryNotifierService,
         use_snapshot_archives_at_startup::UseSnapshotArchivesAtStartup,
     },
     solana_measure::measure_time,
     solana_pubkey::Pubkey,
-    solana_rpc::transaction_status_service::TransactionStatusService,
+    solana_rpc::{
+        transaction_notifier_interface::TransactionNotifierArc,
+        transaction_status_service::TransactionStatusService,
+    },
     solana_runtime::{
         accounts_background_service::{
             AbsRequestHandlers, AccountsBackgroundService, PendingSnapshotPackages,
             PrunedBanksRequestHandler, SnapshotRequestHandler,
         },
+        bank::Bank,
         bank_forks::BankForks,
         prioritization_fee_cache::PrioritizationFeeCache,
         snapshot_config::{SnapshotConfig, SnapshotUsage},
@@ -92,6 +103,11 @@ pub(crate) enum LoadAndProcessLedgerError {
     )]
     EndingSlotNotReachableFromStartingSlot(Slot, Slot, String),
 
+    #[error(
+        "failed to notify geyser plugins from slot {1}; replay begins at starting slot {0}. {2}"
+    )]
+    GeyserStartSlotLessThanStartingSlot(Slot, Slot, String),
+
     #[error("failed to setup geyser service: {0}")]
     GeyserServiceSetup(#[source] GeyserPluginServiceError),
 
@@ -122,12 +138,53 @@ pub fn load_and_process_ledger_or_exit(
     })
 }
 
+/// Like `load_and_process_ledger_or_exit()`, but also notifies the Geyser
+/// plugins of entries, block metadata and slot status as a live validator would
+pub fn load_and_replay_ledger_to_geyser_or_exit(
+    arg_matches: &ArgMatches,
+    genesis_config: &GenesisConfig,
+    blockstore: Arc<Blockstore>,
+    process_options: ProcessOptions,
+    geyser_replay_config: GeyserReplayConfig,
+) -> LoadAndProcessLedgerOutput {
+    do_load_and_process_ledger(
+        arg_matches,
+        genesis_config,
+        blockstore,
+        process_options,
+        None,
+        Some(geyser_replay_config),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Exiting. Failed to load and process ledger: {err}");
+        exit(1);
+    })
+}
+
 pub fn load_and_process_ledger(
     arg_matches: &ArgMatches,
     genesis_config: &GenesisConfig,
     blockstore: Arc<Blockstore>,
     process_options: ProcessOptions,
     transaction_status_sender: Option<TransactionStatusSender>,
+) -> Result<LoadAndProcessLedgerOutput, LoadAndProcessLedgerError> {
+    do_load_and_process_ledger(
+        arg_matches,
+        genesis_config,
+        blockstore,
+        process_options,
+        transaction_status_sender,
+        None,
+    )
+}
+
+fn do_load_and_process_ledger(
+    arg_matches: &ArgMatches,
+    genesis_config: &GenesisConfig,
+    blockstore: Arc<Blockstore>,
+    mut process_options: ProcessOptions,
+    transaction_status_sender: Option<TransactionStatusSender>,
+    geyser_replay_config: Option<GeyserReplayConfig>,
 ) -> Result<LoadAndProcessLedgerOutput, LoadAndProcessLedgerError> {
     let bank_snapshots_dir = if blockstore.is_primary_access() {
         blockstore.ledger_path().join("snapshot")
@@ -263,7 +320,22 @@ pub fn load_and_process_ledger(
         .map_err(LoadAndProcessLedgerError::CleanOrphanedAccountSnapshotDirectories)?;
 
     let geyser_plugin_active = arg_matches.is_present("geyser_plugin_config");
-    let (accounts_update_notifier, transaction_notifier) = if geyser_plugin_active {
+    let geyser_start_slot = match geyser_replay_config {
+        Some(GeyserReplayConfig {
+            start_slot: Some(start_slot),
+        }) if start_slot < starting_slot => {
+            return Err(
+                LoadAndProcessLedgerError::GeyserStartSlotLessThanStartingSlot(
+                    starting_slot,
+                    start_slot,
+                    PROCESS_SLOTS_HELP_STRING.to_string(),
+                ),
+            );
+        }
+        Some(GeyserReplayConfig { start_slot }) => Some(start_slot.unwrap_or(starting_slot)),
+        None => None,
+    };
+    let geyser_service = if geyser_plugin_active {
         let geyser_config_files = values_t_or_exit!(arg_matches, "geyser_plugin_config", String)
             .into_iter()
             .map(PathBuf::from)
@@ -274,18 +346,61 @@ pub fn load_and_process_ledger(
         let geyser_service =
             GeyserPluginService::new(confirmed_bank_receiver, false, &geyser_config_files)
                 .map_err(LoadAndProcessLedgerError::GeyserServiceSetup)?;
-        (
-            geyser_service.get_accounts_update_notifier(),
-            geyser_service.get_transaction_notifier(),
-        )
+        Some(geyser_service)
     } else {
-        (None, None)
+        None
     };
+    let (accounts_update_notifier, transaction_notifier, entry_notifier) =
+        match (&geyser_service, geyser_start_slot) {
+            (Some(geyser_service), Some(start_slot)) => (
+                geyser_service.get_accounts_update_notifier().map(
+                    |notifier| -> AccountsUpdateNotifier {
+                        Arc::new(StartSlotFilter::new(notifier, start_slot))
+                    },
+                ),
+                geyser_service.get_transaction_notifier().map(
+                    |notifier| -> TransactionNotifierArc {
+                        Arc::new(StartSlotFilter::new(notifier, start_slot))
+                    },
+                ),
+                geyser_service
+                    .get_entry_notifier()
+                    .map(|notifier| -> EntryNotifierArc {
+                        Arc::new(StartSlotFilter::new(notifier, start_slot))
+                    }),
+            ),
+            (Some(geyser_service), None) => (
+                geyser_service.get_accounts_update_notifier(),
+                geyser_service.get_transaction_notifier(),
+                None,
+            ),
+            (None, _) => (None, None, None),
+        };
+    if let (Some(geyser_service), Some(start_slot)) = (&geyser_service, geyser_start_slot) {
+        let geyser_callback = geyser_slot_callback(
+            blockstore.clone(),
+            geyser_service.get_block_metadata_notifier(),
+            geyser_service.get_slot_status_notifier(),
+            start_slot,
+        );
+        process_options.slot_callback = Some(match process_options.slot_callback.take() {
+            Some(slot_callback) => Arc::new(move |bank: &Bank| {
+                slot_callback(bank);
+                geyser_callback(bank);
+            }) as ProcessSlotCallback,
+            None => geyser_callback,
+        });
+    }
 
     let exit = Arc::new(AtomicBool::new(false));
     // Separate TSS exit flag as it needs to get set at a later point than
     // the common exit flag. This is coupled to draining TSS receiver queue first.
     let tss_exit = Arc::new(AtomicBool::new(false));
+    // The entry notifier service is never signaled to exit: joining it waits
+    // for it to drain its queue of notifications instead.
+    let entry_notifier_service = entry_notifier.map(|entry_notifier| {
+        EntryNotifierService::new(entry_notifier, Arc::new(AtomicBool::new(false)))
+    });
 
     let enable_rpc_transaction_history = arg_matches.is_present("enable_rpc_transaction_history");
 
@@ -402,7 +517,9 @@ pub fn load_and_process_ledger(
         &leader_schedule_cache,
         &process_options,
         transaction_status_sender.as_ref(),
-        None, // entry_notification_sender
+        entry_notifier_service
+            .as_ref()
+            .map(|service| service.sender()),
         Some(&snapshot_controller),
     )
     .map(|_| LoadAndProcessLedgerOutput {
@@ -416,6 +533,13 @@ pub fn load_and_process_ledger(
     if let Some(service) = transaction_status_service {
         service.quiesce_and_join_for_tests(tss_exit);
     }
+    if let Some(service) = entry_notifier_service {
+        service.join().expect("entry notifier service");
+    }
+    // Unload the plugins so they can flush any buffered notifications
+    if let (Some(geyser_service), Some(_)) = (geyser_service, geyser_start_slot) {
+        geyser_service.join().expect("geyser plugin service");
+    }
 
     result
 }
@@ -4,6 +4,7 @@ use {
         args::*,
         bigtable::*,
         blockstore::*,
+        geyser_replay::GeyserReplayConfig,
         ledger_path::*,
         ledger_utils::*,
         output::{
@@ -11,6 +12,8 @@ use {
             SlotBankHash,
         },
         program::*,
+        snapshot::*,
+        tower::*,
     },
     agave_feature_set::{self as feature_set, FeatureSet},
     agave_reserved_account_keys::ReservedAccountKeys,
@@ -82,6 +85,7 @@ use {
         self,
         vote_state::{self, VoteStateV3},
     },
+    solana_wen_restart::dry_run::{run_dry_run, RecordedRestartMessages},
     std::{
         collections::{HashMap, HashSet},
         ffi::{OsStr, OsString},
@@ -103,10 +107,13 @@ mod args;
 mod bigtable;
 mod blockstore;
 mod error;
+mod geyser_replay;
 mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod snapshot;
+mod tower;
 
 fn render_dot(dot: String, output_file: &str, output_format: &str) -> io::Result<()> {
     let mut child = Command::new("dot")
@@ -1237,6 +1244,53 @@ fn main() {
                         ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("geyser-replay")
+                .about("Replay the ledger and notify Geyser plugins as a live validator would")
+                .long_about(
+                    "Replay the ledger from the latest snapshot and deliver account, \
+                     transaction, entry, block metadata and slot status notifications to the \
+                     given Geyser plugins. Useful to backfill a plugin's data store for a slot \
+                     range without running a validator.",
+                )
+                .arg(&load_genesis_config_arg)
+                .args(&accounts_db_config_args)
+                .args(&snapshot_config_args)
+                .arg(&hard_forks_arg)
+                .arg(&log_messages_bytes_limit_arg)
+                .arg(
+                    Arg::with_name("geyser_plugin_config")
+                        .long("plugin-config")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Configuration file for the Geyser plugin to notify"),
+                )
+                .arg(
+                    Arg::with_name("geyser_start_slot")
+                        .long("start-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .help(
+                            "Only notify plugins of slots at or after this slot. Must not be \
+                             less than the slot of the snapshot replay starts from \
+                             [default: the snapshot slot]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("halt_at_slot")
+                        .long("end-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .help(
+                            "Stop replaying after this slot [default: the highest replayable \
+                             slot in the blockstore]",
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("graph")
                 .about("Create a Graphviz rendering of the ledger")
@@ -1659,7 +1713,48 @@ fn main() {
                 )
                 .arg(&allow_dead_slots_arg),
         )
+        .subcommand(
+            SubCommand::with_name("wen-restart-dry-run")
+                .about("Rehearse a wen_restart using recorded restart messages")
+                .long_about(
+                    "Aggregate the RestartLastVotedForkSlots and RestartHeaviestFork messages \
+                     recorded in wen_restart progress files against the local ledger, and print \
+                     the heaviest fork this validator would select, the blocks it is missing and \
+                     the stake behind each step. The ledger is opened read-only, nothing is sent \
+                     to gossip and no snapshot is generated.",
+                )
+                .arg(&load_genesis_config_arg)
+                .args(&accounts_db_config_args)
+                .args(&snapshot_config_args)
+                .arg(&halt_at_slot_arg)
+                .arg(&hard_forks_arg)
+                .arg(
+                    Arg::with_name("wen_restart_progress")
+                        .long("progress-file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help(
+                            "wen_restart progress file recorded by a validator. Pass the files \
+                             of several validators to cover the messages of the whole cluster",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("shred_version")
+                        .long("shred-version")
+                        .value_name("VERSION")
+                        .takes_value(true)
+                        .validator(is_parsable::<u16>)
+                        .help(
+                            "Shred version of the restarting cluster [default: the shred \
+                             version of the local root]",
+                        ),
+                ),
+        )
         .program_subcommand()
+        .snapshot_subcommand()
+        .tower_subcommand()
         .get_matches();
 
     info!("{} {}", crate_name!(), solana_version::version!());
@@ -1677,6 +1772,8 @@ fn main() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("snapshot", Some(arg_matches)) => snapshot_process_command(&ledger_path, arg_matches),
+        ("tower", Some(arg_matches)) => tower_process_command(&ledger_path, arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         ("analyze-storage", Some(_))
@@ -1893,6 +1990,41 @@ fn main() {
                     exit_signal.store(true, Ordering::Relaxed);
                     system_monitor_service.join().unwrap();
                 }
+                ("geyser-replay", Some(arg_matches)) => {
+                    let start_slot = value_t!(arg_matches, "geyser_start_slot", Slot).ok();
+                    let end_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
+                    if let (Some(start_slot), Some(end_slot)) = (start_slot, end_slot) {
+                        if end_slot < start_slot {
+                            eprintln!(
+                                "--end-slot {end_slot} must not be less than --start-slot \
+                                 {start_slot}"
+                            );
+                            exit(1);
+                        }
+                    }
+
+                    let process_options = parse_process_options(&ledger_path, arg_matches);
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        get_access_type(&process_options),
+                    );
+                    let LoadAndProcessLedgerOutput { bank_forks, .. } =
+                        load_and_replay_ledger_to_geyser_or_exit(
+                            arg_matches,
+                            &genesis_config,
+                            Arc::new(blockstore),
+                            process_options,
+                            GeyserReplayConfig { start_slot },
+                        );
+
+                    let working_bank = bank_forks.read().unwrap().working_bank();
+                    println!(
+                        "Notified Geyser plugins through slot {}",
+                        working_bank.slot()
+                    );
+                }
                 ("graph", Some(arg_matches)) => {
                     let output_file = value_t_or_exit!(arg_matches, "graph_filename", String);
                     let graph_config = GraphConfig {
@@ -3157,6 +3289,52 @@ fn main() {
                         }
                     }
                 }
+                ("wen-restart-dry-run", Some(arg_matches)) => {
+                    let progress_files =
+                        values_t_or_exit!(arg_matches, "wen_restart_progress", PathBuf);
+                    let messages =
+                        RecordedRestartMessages::load(&progress_files).unwrap_or_else(|err| {
+                            eprintln!("Failed to load wen_restart progress files: {err}");
+                            exit(1);
+                        });
+                    println!(
+                        "Loaded {} RestartLastVotedForkSlots and {} RestartHeaviestFork messages",
+                        messages.num_last_voted_fork_slots(),
+                        messages.num_heaviest_forks(),
+                    );
+
+                    let process_options = parse_process_options(&ledger_path, arg_matches);
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    // Secondary access keeps replay from marking dead slots in the ledger.
+                    let blockstore = Arc::new(open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        AccessType::Secondary,
+                    ));
+                    let LoadAndProcessLedgerOutput { bank_forks, .. } =
+                        load_and_process_ledger_or_exit(
+                            arg_matches,
+                            &genesis_config,
+                            blockstore.clone(),
+                            process_options,
+                            None,
+                        );
+                    let shred_version =
+                        value_t!(arg_matches, "shred_version", u16).unwrap_or_else(|_| {
+                            compute_shred_version(
+                                &genesis_config.hash(),
+                                Some(&bank_forks.read().unwrap().root_bank().hard_forks()),
+                            )
+                        });
+
+                    match run_dry_run(bank_forks, blockstore, shred_version, &messages) {
+                        Ok(report) => print!("{report}"),
+                        Err(err) => {
+                            eprintln!("wen_restart dry run failed: {err}");
+                            exit(1);
+                        }
+                    }
+                }
                 ("", _) => {
                     eprintln!("{}", matches.usage());
                     exit(1);
@@ -17,16 +17,19 @@ use {
         display::writeln_transaction, CliAccount, CliAccountNewConfig, OutputFormat, QuietDisplay,
         VerboseDisplay,
     },
-    solana_clock::{Slot, UnixTimestamp},
+    solana_clock::{Epoch, Slot, UnixTimestamp},
     solana_hash::Hash,
     solana_ledger::{
         blockstore::{Blockstore, BlockstoreError},
-        blockstore_meta::{DuplicateSlotProof, ErasureMeta},
+        blockstore_meta::{BlockCostDetails, DuplicateSlotProof, ErasureMeta},
         shred::{self, Shred, ShredType},
     },
     solana_native_token::lamports_to_sol,
     solana_pubkey::Pubkey,
-    solana_runtime::bank::Bank,
+    solana_runtime::{
+        bank::Bank, snapshot_archive_info::SnapshotArchiveInfoGetter,
+        snapshot_utils::SnapshotArchiveManifest,
+    },
     solana_transaction::versioned::VersionedTransaction,
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, Encodable, EncodedConfirmedBlock,
@@ -304,6 +307,68 @@ impl fmt::Display for CliBlockWithEntries {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliBlockCostDetails {
+    slot: Slot,
+    block_cost: u64,
+    block_cost_limit: u64,
+    vote_cost: u64,
+    transaction_count: u64,
+    is_leader_block: bool,
+    top_writable_accounts: Vec<CliWritableAccountCost>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliWritableAccountCost {
+    pubkey: String,
+    block_cost: u64,
+}
+
+impl CliBlockCostDetails {
+    pub fn new(slot: Slot, details: BlockCostDetails) -> Self {
+        Self {
+            slot,
+            block_cost: details.block_cost,
+            block_cost_limit: details.block_cost_limit,
+            vote_cost: details.vote_cost,
+            transaction_count: details.transaction_count,
+            is_leader_block: details.is_leader_block,
+            top_writable_accounts: details
+                .top_writable_accounts
+                .into_iter()
+                .map(|(pubkey, block_cost)| CliWritableAccountCost {
+                    pubkey: pubkey.to_string(),
+                    block_cost,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl QuietDisplay for CliBlockCostDetails {}
+impl VerboseDisplay for CliBlockCostDetails {}
+
+impl fmt::Display for CliBlockCostDetails {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Slot {}: block cost {}/{}, vote cost {}, {} transactions{}",
+            self.slot,
+            self.block_cost,
+            self.block_cost_limit,
+            self.vote_cost,
+            self.transaction_count,
+            if self.is_leader_block { ", leader" } else { "" },
+        )?;
+        for account in &self.top_writable_accounts {
+            write!(f, "\n  {:<44} {:>12}", account.pubkey, account.block_cost)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliDuplicateSlotProof {
@@ -963,3 +1028,390 @@ impl fmt::Display for CliAccounts {
 }
 impl QuietDisplay for CliAccounts {}
 impl VerboseDisplay for CliAccounts {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotManifest {
+    archive: String,
+    /// Only for incremental snapshots, the slot of the full snapshot they are relative to
+    base_slot: Option<Slot>,
+    snapshot_version: String,
+    slot: Slot,
+    parent_slot: Slot,
+    epoch: Epoch,
+    block_height: u64,
+    bank_hash: String,
+    parent_bank_hash: String,
+    capitalization: u64,
+    transaction_count: u64,
+    signature_count: u64,
+    accounts_data_len: u64,
+    accounts_lt_hash_checksum: String,
+    hard_forks: Vec<Slot>,
+    vote_accounts: usize,
+    total_stake: u64,
+    epoch_stakes: Vec<Epoch>,
+    status_cache_slots: usize,
+    status_cache_entries: usize,
+    storages: usize,
+    storages_len: u64,
+}
+
+impl CliSnapshotManifest {
+    pub fn new(
+        snapshot_archive_info: &impl SnapshotArchiveInfoGetter,
+        base_slot: Option<Slot>,
+        manifest: SnapshotArchiveManifest,
+    ) -> Self {
+        Self {
+            archive: snapshot_archive_info.path().display().to_string(),
+            base_slot,
+            snapshot_version: manifest.snapshot_version.as_str().to_string(),
+            slot: manifest.slot,
+            parent_slot: manifest.parent_slot,
+            epoch: manifest.epoch,
+            block_height: manifest.block_height,
+            bank_hash: manifest.hash.to_string(),
+            parent_bank_hash: manifest.parent_hash.to_string(),
+            capitalization: manifest.capitalization,
+            transaction_count: manifest.transaction_count,
+            signature_count: manifest.signature_count,
+            accounts_data_len: manifest.accounts_data_len,
+            accounts_lt_hash_checksum: manifest.accounts_lt_hash_checksum.to_string(),
+            hard_forks: manifest.hard_forks,
+            vote_accounts: manifest.vote_accounts_count,
+            total_stake: manifest.total_stake,
+            epoch_stakes: manifest.epoch_stakes_epochs,
+            status_cache_slots: manifest.status_cache_slots,
+            status_cache_entries: manifest.status_cache_entries,
+            storages: manifest.storages_count,
+            storages_len: manifest.storages_len,
+        }
+    }
+}
+
+impl QuietDisplay for CliSnapshotManifest {}
+impl VerboseDisplay for CliSnapshotManifest {}
+
+impl fmt::Display for CliSnapshotManifest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Archive: {}", self.archive)?;
+        if let Some(base_slot) = self.base_slot {
+            writeln!(f, "Incremental snapshot based on slot {base_slot}")?;
+        }
+        writeln!(f, "Snapshot version: {}", self.snapshot_version)?;
+        writeln!(
+            f,
+            "Slot: {}, parent slot: {}, epoch: {}, block height: {}",
+            self.slot, self.parent_slot, self.epoch, self.block_height,
+        )?;
+        writeln!(f, "Bank hash: {}", self.bank_hash)?;
+        writeln!(f, "Parent bank hash: {}", self.parent_bank_hash)?;
+        writeln!(
+            f,
+            "Capitalization: ◎{}",
+            lamports_to_sol(self.capitalization)
+        )?;
+        writeln!(
+            f,
+            "Transaction count: {}, signature count: {}",
+            self.transaction_count, self.signature_count,
+        )?;
+        writeln!(f, "Accounts data size: {} bytes", self.accounts_data_len)?;
+        writeln!(
+            f,
+            "Accounts lt hash checksum: {}",
+            self.accounts_lt_hash_checksum
+        )?;
+        writeln!(f, "Hard forks: {:?}", self.hard_forks)?;
+        writeln!(
+            f,
+            "Vote accounts: {}, total stake: ◎{}",
+            self.vote_accounts,
+            lamports_to_sol(self.total_stake),
+        )?;
+        writeln!(f, "Epoch stakes for epochs: {:?}", self.epoch_stakes)?;
+        writeln!(
+            f,
+            "Status cache: {} slots, {} entries",
+            self.status_cache_slots, self.status_cache_entries,
+        )?;
+        write!(
+            f,
+            "Storages: {}, {} bytes",
+            self.storages, self.storages_len
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliBankSummary {
+    slot: Slot,
+    bank_hash: String,
+    capitalization: u64,
+    vote_accounts: usize,
+    total_stake: u64,
+    accounts_lt_hash_checksum: String,
+}
+
+impl CliBankSummary {
+    pub fn new(bank: &Bank) -> Self {
+        let vote_accounts = bank.vote_accounts();
+        Self {
+            slot: bank.slot(),
+            bank_hash: bank.hash().to_string(),
+            capitalization: bank.capitalization(),
+            vote_accounts: vote_accounts.len(),
+            total_stake: vote_accounts.values().map(|(stake, _)| stake).sum(),
+            accounts_lt_hash_checksum: bank.accounts_lt_hash_checksum().to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountChange {
+    pub pubkey: String,
+    pub change: AccountChange,
+    pub owner: String,
+    pub base_lamports: Option<u64>,
+    pub other_lamports: Option<u64>,
+    pub base_data_len: Option<usize>,
+    pub other_data_len: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliVoteAccountStakeChange {
+    pub vote_account: String,
+    pub base_stake: u64,
+    pub other_stake: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiff {
+    pub base: CliBankSummary,
+    pub other: CliBankSummary,
+    /// Programs the compared accounts are owned by, all accounts are compared if empty
+    pub owners: Vec<String>,
+    pub accounts: Vec<CliAccountChange>,
+    pub stake_changes: Vec<CliVoteAccountStakeChange>,
+}
+
+impl QuietDisplay for CliSnapshotDiff {}
+impl VerboseDisplay for CliSnapshotDiff {}
+
+fn write_comparison<T: PartialEq + Display>(
+    f: &mut Formatter<'_>,
+    name: &str,
+    base: T,
+    other: T,
+) -> fmt::Result {
+    if base == other {
+        writeln!(f, "{name}: {base}")
+    } else {
+        writeln!(f, "{name}: {base} -> {other}")
+    }
+}
+
+impl fmt::Display for CliSnapshotDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (base, other) = (&self.base, &self.other);
+        write_comparison(f, "Slot", base.slot, other.slot)?;
+        write_comparison(f, "Bank hash", &base.bank_hash, &other.bank_hash)?;
+        write_comparison(
+            f,
+            "Capitalization",
+            format!("◎{}", lamports_to_sol(base.capitalization)),
+            format!("◎{}", lamports_to_sol(other.capitalization)),
+        )?;
+        write_comparison(
+            f,
+            "Accounts lt hash checksum",
+            &base.accounts_lt_hash_checksum,
+            &other.accounts_lt_hash_checksum,
+        )?;
+        write_comparison(f, "Vote accounts", base.vote_accounts, other.vote_accounts)?;
+        write_comparison(
+            f,
+            "Total stake",
+            format!("◎{}", lamports_to_sol(base.total_stake)),
+            format!("◎{}", lamports_to_sol(other.total_stake)),
+        )?;
+        if !self.stake_changes.is_empty() {
+            writeln!(
+                f,
+                "Stake changed for {} vote accounts:",
+                self.stake_changes.len()
+            )?;
+            for change in &self.stake_changes {
+                writeln!(
+                    f,
+                    "  {:<44} ◎{} -> ◎{}",
+                    change.vote_account,
+                    lamports_to_sol(change.base_stake),
+                    lamports_to_sol(change.other_stake),
+                )?;
+            }
+        }
+
+        if self.owners.is_empty() {
+            writeln!(f, "Accounts:")?;
+        } else {
+            writeln!(f, "Accounts owned by {}:", self.owners.join(", "))?;
+        }
+        let count = |change| {
+            self.accounts
+                .iter()
+                .filter(|account| account.change == change)
+                .count()
+        };
+        write!(
+            f,
+            "  {} added, {} removed, {} modified",
+            count(AccountChange::Added),
+            count(AccountChange::Removed),
+            count(AccountChange::Modified),
+        )?;
+        for account in &self.accounts {
+            let lamports = |lamports: Option<u64>| {
+                lamports.map_or("-".to_string(), |lamports| lamports.to_string())
+            };
+            let data_len = |data_len: Option<usize>| {
+                data_len.map_or("-".to_string(), |data_len| data_len.to_string())
+            };
+            write!(
+                f,
+                "\n  {:<8} {:<44} owner: {:<44} lamports: {} -> {}, data len: {} -> {}",
+                format!("{:?}", account.change).to_lowercase(),
+                account.pubkey,
+                account.owner,
+                lamports(account.base_lamports),
+                lamports(account.other_lamports),
+                data_len(account.base_data_len),
+                data_len(account.other_data_len),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TowerSlotStatus {
+    Rooted,
+    Full,
+    Incomplete,
+    Dead,
+    Missing,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTowerVote {
+    pub slot: Slot,
+    pub confirmation_count: u32,
+    pub lockout: u64,
+    pub last_locked_out_slot: Slot,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blockstore_status: Option<TowerSlotStatus>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTowerBlockstoreCheck {
+    pub max_root: Slot,
+    pub lowest_slot: Slot,
+    pub problems: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTower {
+    pub tower_file: String,
+    pub identity: String,
+    /// Node the tower data was saved by, which differs from `identity` for a misplaced tower
+    pub node_pubkey: String,
+    pub signature_valid: bool,
+    pub root: Slot,
+    pub last_vote_slot: Option<Slot>,
+    pub last_vote_hash: Option<String>,
+    /// Oldest vote first
+    pub votes: Vec<CliTowerVote>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blockstore_check: Option<CliTowerBlockstoreCheck>,
+}
+
+impl QuietDisplay for CliTower {}
+impl VerboseDisplay for CliTower {}
+
+impl fmt::Display for CliTower {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Tower file: {}", self.tower_file)?;
+        writeln!(f, "Identity: {}", self.identity)?;
+        if self.node_pubkey != self.identity {
+            writeln!(f, "Saved by node: {} (does not match)", self.node_pubkey)?;
+        }
+        writeln!(
+            f,
+            "Signature: {}",
+            if self.signature_valid {
+                "valid"
+            } else {
+                "invalid, the validator will refuse to load this tower"
+            }
+        )?;
+        writeln!(f, "Root: {}", self.root)?;
+        match (self.last_vote_slot, &self.last_vote_hash) {
+            (Some(slot), Some(hash)) => writeln!(f, "Last vote: slot {slot}, hash {hash}")?,
+            _ => writeln!(f, "Last vote: none")?,
+        }
+
+        writeln!(f, "Vote stack:")?;
+        writeln!(
+            f,
+            "  {:>12} {:>13} {:>12} {:>16} {:>10}",
+            "Slot", "Confirmations", "Lockout", "Locked out until", "Blockstore"
+        )?;
+        for vote in self.votes.iter().rev() {
+            writeln!(
+                f,
+                "  {:>12} {:>13} {:>12} {:>16} {:>10}",
+                vote.slot,
+                vote.confirmation_count,
+                vote.lockout,
+                vote.last_locked_out_slot,
+                vote.blockstore_status.map_or("-".to_string(), |status| {
+                    format!("{status:?}").to_lowercase()
+                }),
+            )?;
+        }
+
+        if let Some(check) = &self.blockstore_check {
+            writeln!(
+                f,
+                "Blockstore: root {}, lowest slot {}",
+                check.max_root, check.lowest_slot
+            )?;
+            if check.problems.is_empty() {
+                write!(f, "The tower is consistent with the blockstore")?;
+            } else {
+                write!(f, "Problems:")?;
+                for problem in &check.problems {
+                    write!(f, "\n  {problem}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
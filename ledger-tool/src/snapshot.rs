@@ -0,0 +1,626 @@
+use {
+    crate::{
+        args::*,
+        canonicalize_ledger_path,
+        error::{LedgerToolError, Result},
+        ledger_utils::*,
+        output::{
+            AccountChange, CliAccountChange, CliBankSummary, CliSnapshotDiff, CliSnapshotManifest,
+            CliVoteAccountStakeChange,
+        },
+        LEDGER_TOOL_DIRECTORY,
+    },
+    clap::{value_t, App, AppSettings, Arg, ArgMatches, SubCommand},
+    log::*,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_accounts_db::{
+        accounts_db::AccountsDb, accounts_index::ScanConfig,
+        utils::create_accounts_run_and_snapshot_dirs,
+    },
+    solana_clap_utils::{
+        input_parsers::pubkeys_of,
+        input_validators::{is_pubkey, is_slot},
+    },
+    solana_cli_output::OutputFormat,
+    solana_genesis_config::GenesisConfig,
+    solana_pubkey::Pubkey,
+    solana_runtime::{
+        bank::Bank,
+        snapshot_archive_info::{
+            FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
+        },
+        snapshot_bank_utils, snapshot_utils,
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+};
+
+pub trait SnapshotSubCommand {
+    fn snapshot_subcommand(self) -> Self;
+}
+
+impl SnapshotSubCommand for App<'_, '_> {
+    fn snapshot_subcommand(self) -> Self {
+        let snapshot_archive_arg = |name, index| {
+            Arg::with_name(name)
+                .index(index)
+                .value_name("SNAPSHOT_ARCHIVE")
+                .takes_value(true)
+        };
+
+        self.subcommand(
+            SubCommand::with_name("snapshot")
+                .about("Commands to inspect and compare snapshot archives")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("inspect")
+                        .about("Print the manifest of a snapshot archive")
+                        .long_about(
+                            "Print the bank fields, status cache size and storages of a full or \
+                             incremental snapshot archive. Only the manifest at the beginning of \
+                             the archive is read, the accounts are not loaded.",
+                        )
+                        .arg(
+                            snapshot_archive_arg("snapshot_archive", 1)
+                                .required(true)
+                                .help("Snapshot archive to inspect"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("diff")
+                        .about("Compare the accounts and stakes of two snapshot archives")
+                        .long_about(
+                            "Load two snapshot archives and report the accounts added, removed \
+                             and modified between them, along with their capitalization, stakes \
+                             and accounts lattice hash. Without a second snapshot archive, the \
+                             snapshot is compared with the bank at --halt-at-slot after \
+                             processing the ledger from the snapshot archives in --snapshots. \
+                             The full snapshot an incremental snapshot archive is based on is \
+                             looked up next to it and in --snapshots. Comparing all the accounts \
+                             of large snapshots needs a lot of memory, use --owner to compare \
+                             the accounts of some programs only.",
+                        )
+                        .arg(&load_genesis_arg())
+                        .args(&accounts_db_args())
+                        .args(&snapshot_args())
+                        .arg(
+                            snapshot_archive_arg("base_snapshot_archive", 1)
+                                .required(true)
+                                .help("Snapshot archive to compare from"),
+                        )
+                        .arg(
+                            snapshot_archive_arg("other_snapshot_archive", 2)
+                                .help("Snapshot archive to compare to"),
+                        )
+                        .arg(
+                            Arg::with_name("halt_at_slot")
+                                .long("halt-at-slot")
+                                .value_name("SLOT")
+                                .validator(is_slot)
+                                .takes_value(true)
+                                .conflicts_with("other_snapshot_archive")
+                                .help(
+                                    "Compare with the bank at this slot instead of a second \
+                                     snapshot archive",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("owner")
+                                .long("owner")
+                                .value_name("PUBKEY")
+                                .takes_value(true)
+                                .multiple(true)
+                                .validator(is_pubkey)
+                                .help(
+                                    "Only compare the accounts owned by this program, may be \
+                                     specified multiple times",
+                                ),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn snapshot_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    do_snapshot_process_command(ledger_path, matches).unwrap_or_else(|err| {
+        eprintln!("Failed to complete command: {err}");
+        std::process::exit(1);
+    });
+}
+
+fn do_snapshot_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -> Result<()> {
+    match matches.subcommand() {
+        ("inspect", Some(arg_matches)) => {
+            let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+            let archive_path = PathBuf::from(arg_matches.value_of("snapshot_archive").unwrap());
+            let manifest = if let Ok(full_snapshot_archive_info) =
+                FullSnapshotArchiveInfo::new_from_path(archive_path.clone())
+            {
+                CliSnapshotManifest::new(
+                    &full_snapshot_archive_info,
+                    None,
+                    snapshot_utils::read_snapshot_archive_manifest(&full_snapshot_archive_info)?,
+                )
+            } else {
+                let incremental_snapshot_archive_info =
+                    parse_incremental_snapshot_archive(&archive_path)?;
+                CliSnapshotManifest::new(
+                    &incremental_snapshot_archive_info,
+                    Some(incremental_snapshot_archive_info.base_slot()),
+                    snapshot_utils::read_snapshot_archive_manifest(
+                        &incremental_snapshot_archive_info,
+                    )?,
+                )
+            };
+            println!("{}", output_format.formatted_string(&manifest));
+        }
+        ("diff", Some(arg_matches)) => {
+            let ledger_path = canonicalize_ledger_path(ledger_path);
+            let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+            let owners = pubkeys_of(arg_matches, "owner");
+            let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+
+            let tmp_dir_parent = ledger_path.join(LEDGER_TOOL_DIRECTORY);
+            fs::create_dir_all(&tmp_dir_parent)?;
+            let base_tmp_dir = tempfile::Builder::new()
+                .prefix("snapshot-diff-base-")
+                .tempdir_in(&tmp_dir_parent)?;
+            let base_bank = Arc::new(load_bank_from_snapshot_archive(
+                &ledger_path,
+                arg_matches,
+                &genesis_config,
+                Path::new(arg_matches.value_of("base_snapshot_archive").unwrap()),
+                base_tmp_dir.path(),
+            )?);
+
+            let other_tmp_dir = tempfile::Builder::new()
+                .prefix("snapshot-diff-other-")
+                .tempdir_in(&tmp_dir_parent)?;
+            let other_bank = if let Some(other_snapshot_archive) =
+                arg_matches.value_of("other_snapshot_archive")
+            {
+                Arc::new(load_bank_from_snapshot_archive(
+                    &ledger_path,
+                    arg_matches,
+                    &genesis_config,
+                    Path::new(other_snapshot_archive),
+                    other_tmp_dir.path(),
+                )?)
+            } else {
+                let process_options = parse_process_options(&ledger_path, arg_matches);
+                let blockstore =
+                    open_blockstore(&ledger_path, arg_matches, get_access_type(&process_options));
+                let LoadAndProcessLedgerOutput { bank_forks, .. } = load_and_process_ledger_or_exit(
+                    arg_matches,
+                    &genesis_config,
+                    Arc::new(blockstore),
+                    process_options,
+                    None,
+                );
+                let bank = bank_forks.read().unwrap().working_bank();
+                bank
+            };
+
+            let diff = diff_banks(&base_bank, &other_bank, owners.as_deref())?;
+            println!("{}", output_format.formatted_string(&diff));
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn parse_incremental_snapshot_archive(
+    archive_path: &Path,
+) -> Result<IncrementalSnapshotArchiveInfo> {
+    IncrementalSnapshotArchiveInfo::new_from_path(archive_path.to_path_buf()).map_err(|_| {
+        LedgerToolError::BadArgument(format!(
+            "{} is not a snapshot archive",
+            archive_path.display()
+        ))
+    })
+}
+
+/// Returns the full snapshot archive to load for `archive_path`, and the incremental snapshot
+/// archive if `archive_path` is one
+fn parse_snapshot_archive(
+    archive_path: &Path,
+    arg_matches: &ArgMatches<'_>,
+) -> Result<(
+    FullSnapshotArchiveInfo,
+    Option<IncrementalSnapshotArchiveInfo>,
+)> {
+    if let Ok(full_snapshot_archive_info) =
+        FullSnapshotArchiveInfo::new_from_path(archive_path.to_path_buf())
+    {
+        return Ok((full_snapshot_archive_info, None));
+    }
+    let incremental_snapshot_archive_info = parse_incremental_snapshot_archive(archive_path)?;
+    let base_slot = incremental_snapshot_archive_info.base_slot();
+    let archive_dir = archive_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut archive_dirs = vec![archive_dir.to_path_buf()];
+    archive_dirs.extend(
+        value_t!(arg_matches, "snapshots", String)
+            .ok()
+            .map(PathBuf::from),
+    );
+    let full_snapshot_archive_info = archive_dirs
+        .iter()
+        .flat_map(snapshot_utils::get_full_snapshot_archives)
+        .find(|full_snapshot_archive_info| full_snapshot_archive_info.slot() == base_slot)
+        .ok_or_else(|| {
+            LedgerToolError::BadArgument(format!(
+                "{} is based on the full snapshot archive of slot {base_slot}, which was not \
+                 found",
+                archive_path.display(),
+            ))
+        })?;
+    Ok((
+        full_snapshot_archive_info,
+        Some(incremental_snapshot_archive_info),
+    ))
+}
+
+fn load_bank_from_snapshot_archive(
+    ledger_path: &Path,
+    arg_matches: &ArgMatches<'_>,
+    genesis_config: &GenesisConfig,
+    archive_path: &Path,
+    tmp_dir: &Path,
+) -> Result<Bank> {
+    let (full_snapshot_archive_info, incremental_snapshot_archive_info) =
+        parse_snapshot_archive(archive_path, arg_matches)?;
+    let process_options = parse_process_options(ledger_path, arg_matches);
+    let (account_run_path, _account_snapshot_path) =
+        create_accounts_run_and_snapshot_dirs(tmp_dir.join("accounts"))?;
+    let bank_snapshots_dir = tmp_dir.join("snapshot");
+    fs::create_dir_all(&bank_snapshots_dir)?;
+
+    info!("Loading bank from {}", archive_path.display());
+    let (bank, _) = snapshot_bank_utils::bank_from_snapshot_archives(
+        &[account_run_path],
+        &bank_snapshots_dir,
+        &full_snapshot_archive_info,
+        incremental_snapshot_archive_info.as_ref(),
+        genesis_config,
+        &process_options.runtime_config,
+        process_options.debug_keys,
+        None,
+        None,
+        process_options.accounts_db_skip_shrink,
+        false,
+        process_options.verify_index,
+        process_options.accounts_db_config,
+        None,
+        Arc::default(),
+    )?;
+    Ok(bank)
+}
+
+/// What is compared of an account: a checksum of its lt hash covers all of its fields
+struct AccountSummary {
+    lamports: u64,
+    owner: Pubkey,
+    data_len: usize,
+    lt_hash_checksum: [u8; 32],
+}
+
+impl AccountSummary {
+    fn new(pubkey: &Pubkey, account: &AccountSharedData) -> Self {
+        Self {
+            lamports: account.lamports(),
+            owner: *account.owner(),
+            data_len: account.data().len(),
+            lt_hash_checksum: AccountsDb::lt_hash_account(account, pubkey).0.checksum().0,
+        }
+    }
+}
+
+/// Collects the accounts of `bank`, only those owned by `owners` if given. Zero lamport accounts
+/// are deleted accounts not yet cleaned up, so they are skipped either way.
+fn collect_accounts(
+    bank: &Bank,
+    owners: Option<&[Pubkey]>,
+) -> Result<HashMap<Pubkey, AccountSummary>> {
+    let mut accounts = HashMap::new();
+    let mut insert = |pubkey: &Pubkey, account: &AccountSharedData| {
+        if account.lamports() != 0 {
+            accounts.insert(*pubkey, AccountSummary::new(pubkey, account));
+        }
+    };
+    if let Some(owners) = owners {
+        for owner in owners {
+            let program_accounts = bank
+                .get_program_accounts(owner, &ScanConfig::default())
+                .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+            for (pubkey, account) in &program_accounts {
+                insert(pubkey, account);
+            }
+        }
+    } else {
+        bank.scan_all_accounts(
+            |item| {
+                if let Some((pubkey, account, _slot)) = item {
+                    insert(pubkey, &account);
+                }
+            },
+            false,
+        )
+        .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+    }
+    Ok(accounts)
+}
+
+/// Adds the accounts of `bank` at `pubkeys` to `accounts`, skipping zero lamport accounts as
+/// `collect_accounts` does
+fn lookup_accounts(
+    bank: &Bank,
+    pubkeys: &[Pubkey],
+    accounts: &mut HashMap<Pubkey, AccountSummary>,
+) {
+    for pubkey in pubkeys {
+        if let Some(account) = bank.get_account(pubkey) {
+            if account.lamports() != 0 {
+                accounts.insert(*pubkey, AccountSummary::new(pubkey, &account));
+            }
+        }
+    }
+}
+
+fn diff_banks(
+    base_bank: &Bank,
+    other_bank: &Bank,
+    owners: Option<&[Pubkey]>,
+) -> Result<CliSnapshotDiff> {
+    info!("Scanning the accounts of slot {}", base_bank.slot());
+    let mut base_accounts = collect_accounts(base_bank, owners)?;
+    info!("Scanning the accounts of slot {}", other_bank.slot());
+    let mut other_accounts = collect_accounts(other_bank, owners)?;
+    if owners.is_some() {
+        // An account whose owner changed is only found by one of the owner filtered scans, so
+        // look up its other version by key to report it as modified
+        let base_only: Vec<_> = base_accounts
+            .keys()
+            .filter(|pubkey| !other_accounts.contains_key(pubkey))
+            .copied()
+            .collect();
+        let other_only: Vec<_> = other_accounts
+            .keys()
+            .filter(|pubkey| !base_accounts.contains_key(pubkey))
+            .copied()
+            .collect();
+        lookup_accounts(other_bank, &base_only, &mut other_accounts);
+        lookup_accounts(base_bank, &other_only, &mut base_accounts);
+    }
+
+    let mut accounts = vec![];
+    for (pubkey, base) in base_accounts {
+        match other_accounts.remove(&pubkey) {
+            None => accounts.push(CliAccountChange {
+                pubkey: pubkey.to_string(),
+                change: AccountChange::Removed,
+                owner: base.owner.to_string(),
+                base_lamports: Some(base.lamports),
+                other_lamports: None,
+                base_data_len: Some(base.data_len),
+                other_data_len: None,
+            }),
+            Some(other) if other.lt_hash_checksum != base.lt_hash_checksum => {
+                accounts.push(CliAccountChange {
+                    pubkey: pubkey.to_string(),
+                    change: AccountChange::Modified,
+                    owner: other.owner.to_string(),
+                    base_lamports: Some(base.lamports),
+                    other_lamports: Some(other.lamports),
+                    base_data_len: Some(base.data_len),
+                    other_data_len: Some(other.data_len),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    accounts.extend(
+        other_accounts
+            .into_iter()
+            .map(|(pubkey, other)| CliAccountChange {
+                pubkey: pubkey.to_string(),
+                change: AccountChange::Added,
+                owner: other.owner.to_string(),
+                base_lamports: None,
+                other_lamports: Some(other.lamports),
+                base_data_len: None,
+                other_data_len: Some(other.data_len),
+            }),
+    );
+    accounts.sort_by(|a, b| (a.change, &a.pubkey).cmp(&(b.change, &b.pubkey)));
+
+    let base_vote_accounts = base_bank.vote_accounts();
+    let other_vote_accounts = other_bank.vote_accounts();
+    let vote_account_pubkeys: HashSet<_> = base_vote_accounts
+        .keys()
+        .chain(other_vote_accounts.keys())
+        .collect();
+    let mut stake_changes: Vec<_> = vote_account_pubkeys
+        .into_iter()
+        .filter_map(|vote_account| {
+            let base_stake = base_vote_accounts
+                .get(vote_account)
+                .map_or(0, |(stake, _)| *stake);
+            let other_stake = other_vote_accounts
+                .get(vote_account)
+                .map_or(0, |(stake, _)| *stake);
+            (base_stake != other_stake).then(|| CliVoteAccountStakeChange {
+                vote_account: vote_account.to_string(),
+                base_stake,
+                other_stake,
+            })
+        })
+        .collect();
+    stake_changes.sort_by(|a, b| a.vote_account.cmp(&b.vote_account));
+
+    Ok(CliSnapshotDiff {
+        base: CliBankSummary::new(base_bank),
+        other: CliBankSummary::new(other_bank),
+        owners: owners
+            .unwrap_or_default()
+            .iter()
+            .map(Pubkey::to_string)
+            .collect(),
+        accounts,
+        stake_changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_runtime::genesis_utils::{create_genesis_config, create_genesis_config_with_leader},
+    };
+
+    fn account_changes(
+        diff: &CliSnapshotDiff,
+        pubkeys: &[Pubkey],
+    ) -> Vec<(String, AccountChange, Option<u64>, Option<u64>)> {
+        let pubkeys: Vec<_> = pubkeys.iter().map(Pubkey::to_string).collect();
+        diff.accounts
+            .iter()
+            .filter(|account| pubkeys.contains(&account.pubkey))
+            .map(|account| {
+                (
+                    account.pubkey.clone(),
+                    account.change,
+                    account.base_lamports,
+                    account.other_lamports,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_banks_accounts() {
+        let genesis_config = create_genesis_config(1_000_000_000).genesis_config;
+        let owner = Pubkey::new_unique();
+        let pubkeys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let [added, removed, modified, unchanged, deleted] = pubkeys;
+
+        let base_bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        for pubkey in [removed, modified, unchanged] {
+            base_bank.store_account(&pubkey, &AccountSharedData::new(10, 0, &owner));
+        }
+        let other_bank = Bank::new_from_parent(base_bank.clone(), &Pubkey::default(), 1);
+        other_bank.store_account(&added, &AccountSharedData::new(30, 0, &owner));
+        other_bank.store_account(&removed, &AccountSharedData::new(0, 0, &owner));
+        other_bank.store_account(&modified, &AccountSharedData::new(20, 0, &owner));
+        // Never existed with lamports in either bank
+        other_bank.store_account(&deleted, &AccountSharedData::new(0, 0, &owner));
+
+        let expected = vec![
+            (added.to_string(), AccountChange::Added, None, Some(30)),
+            (removed.to_string(), AccountChange::Removed, Some(10), None),
+            (
+                modified.to_string(),
+                AccountChange::Modified,
+                Some(10),
+                Some(20),
+            ),
+        ];
+        let diff = diff_banks(&base_bank, &other_bank, None).unwrap();
+        assert_eq!(account_changes(&diff, &pubkeys), expected);
+        assert!(diff.owners.is_empty());
+
+        // Scanning by owner classifies the accounts the same way, and only finds theirs
+        let diff = diff_banks(&base_bank, &other_bank, Some(&[owner])).unwrap();
+        assert_eq!(account_changes(&diff, &pubkeys), expected);
+        assert_eq!(diff.accounts.len(), expected.len());
+        assert_eq!(diff.owners, vec![owner.to_string()]);
+        assert!(diff.stake_changes.is_empty());
+    }
+
+    #[test]
+    fn test_diff_banks_owner_changed() {
+        let genesis_config = create_genesis_config(1_000_000_000).genesis_config;
+        let old_owner = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+
+        let base_bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        base_bank.store_account(&pubkey, &AccountSharedData::new(10, 0, &old_owner));
+        let other_bank = Bank::new_from_parent(base_bank.clone(), &Pubkey::default(), 1);
+        other_bank.store_account(&pubkey, &AccountSharedData::new(10, 0, &new_owner));
+
+        let expected = vec![(
+            pubkey.to_string(),
+            AccountChange::Modified,
+            Some(10),
+            Some(10),
+        )];
+        for owners in [
+            None,
+            Some(&[old_owner][..]),
+            Some(&[new_owner][..]),
+            Some(&[old_owner, new_owner][..]),
+        ] {
+            let diff = diff_banks(&base_bank, &other_bank, owners).unwrap();
+            assert_eq!(account_changes(&diff, &[pubkey]), expected);
+            let account = diff
+                .accounts
+                .iter()
+                .find(|account| account.pubkey == pubkey.to_string())
+                .unwrap();
+            assert_eq!(account.owner, new_owner.to_string());
+        }
+    }
+
+    #[test]
+    fn test_diff_banks_stake_changes() {
+        let new_bank = |stake| {
+            let genesis_config =
+                create_genesis_config_with_leader(1_000_000_000, &Pubkey::new_unique(), stake)
+                    .genesis_config;
+            let bank = Bank::new_for_tests(&genesis_config);
+            let vote_accounts = bank.vote_accounts();
+            let (vote_account, (stake, _)) = vote_accounts.iter().next().unwrap();
+            assert!(*stake > 0);
+            (bank, *vote_account, *stake)
+        };
+        let (base_bank, base_vote_account, base_stake) = new_bank(1_000_000_000);
+        let (other_bank, other_vote_account, other_stake) = new_bank(2_000_000_000);
+
+        // Comparing no accounts only leaves the stake changes
+        let diff = diff_banks(&base_bank, &other_bank, Some(&[])).unwrap();
+        assert!(diff.accounts.is_empty());
+        let mut expected = vec![
+            (base_vote_account.to_string(), base_stake, 0),
+            (other_vote_account.to_string(), 0, other_stake),
+        ];
+        expected.sort();
+        assert_eq!(
+            diff.stake_changes
+                .iter()
+                .map(|change| (
+                    change.vote_account.clone(),
+                    change.base_stake,
+                    change.other_stake
+                ))
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        let diff = diff_banks(&base_bank, &base_bank, Some(&[])).unwrap();
+        assert!(diff.stake_changes.is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+use {
+    crate::{
+        error::{LedgerToolError, Result},
+        ledger_path::canonicalize_ledger_path,
+        output::{CliTower, CliTowerBlockstoreCheck, CliTowerVote, TowerSlotStatus},
+    },
+    clap::{value_t, App, AppSettings, Arg, ArgMatches, SubCommand},
+    solana_clap_utils::{input_parsers::pubkey_of, input_validators::is_pubkey_or_keypair},
+    solana_cli_output::OutputFormat,
+    solana_clock::Slot,
+    solana_core::consensus::{tower_storage::FileTowerStorage, Tower},
+    solana_ledger::{
+        ancestor_iterator::AncestorIterator, blockstore::Blockstore, blockstore_options::AccessType,
+    },
+    solana_pubkey::Pubkey,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
+};
+
+pub trait TowerSubCommand {
+    fn tower_subcommand(self) -> Self;
+}
+
+impl TowerSubCommand for App<'_, '_> {
+    fn tower_subcommand(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("tower")
+                .about("Commands to inspect a validator's saved tower")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("inspect")
+                        .about("Print the vote stack of a saved tower")
+                        .long_about(
+                            "Decode a saved tower file, verify its signature against the \
+                             validator identity and print its root, last vote and vote stack \
+                             with lockouts. Unless --no-blockstore-check is given, the votes are \
+                             also checked against the ledger's blockstore: whether each voted \
+                             slot is present and on the rooted fork, whether the votes descend \
+                             from each other, and which votes lock the validator out of the \
+                             rooted fork.",
+                        )
+                        .arg(
+                            Arg::with_name("identity")
+                                .long("identity")
+                                .value_name("KEYPAIR or PUBKEY")
+                                .takes_value(true)
+                                .validator(is_pubkey_or_keypair)
+                                .help(
+                                    "Validator identity the tower belongs to [default: the \
+                                     identity of the only tower file in the tower directory]",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("tower")
+                                .long("tower")
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .help("Directory containing the tower file [default: --ledger]"),
+                        )
+                        .arg(
+                            Arg::with_name("no_blockstore_check")
+                                .long("no-blockstore-check")
+                                .takes_value(false)
+                                .help("Do not check the tower against the blockstore"),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn tower_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    do_tower_process_command(ledger_path, matches).unwrap_or_else(|err| {
+        eprintln!("Failed to complete command: {err}");
+        std::process::exit(1);
+    });
+}
+
+fn do_tower_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -> Result<()> {
+    match matches.subcommand() {
+        ("inspect", Some(arg_matches)) => {
+            let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+            let ledger_path = canonicalize_ledger_path(ledger_path);
+            let tower_path = value_t!(arg_matches, "tower", PathBuf)
+                .ok()
+                .unwrap_or_else(|| ledger_path.clone());
+            let tower_storage = FileTowerStorage::new(tower_path);
+            let identity = match pubkey_of(arg_matches, "identity") {
+                Some(identity) => identity,
+                None => find_tower_identity(&tower_storage)?,
+            };
+
+            let saved_tower = tower_storage.load_saved_tower(&identity)?;
+            let tower_file = if tower_storage.filename(&identity).exists() {
+                tower_storage.filename(&identity)
+            } else {
+                tower_storage.old_filename(&identity)
+            };
+            let signature_valid = saved_tower.verify_signature(&identity);
+            let tower = saved_tower.deserialize_tower()?;
+
+            let (vote_statuses, blockstore_check) = if arg_matches.is_present("no_blockstore_check")
+            {
+                (vec![], None)
+            } else {
+                let blockstore =
+                    crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+                let (vote_statuses, blockstore_check) =
+                    check_tower_with_blockstore(&tower, &blockstore);
+                (vote_statuses, Some(blockstore_check))
+            };
+
+            let cli_tower = CliTower {
+                tower_file: tower_file.display().to_string(),
+                identity: identity.to_string(),
+                node_pubkey: tower.node_pubkey.to_string(),
+                signature_valid,
+                root: tower.root(),
+                last_vote_slot: tower.last_voted_slot(),
+                last_vote_hash: tower
+                    .last_voted_slot_hash()
+                    .map(|(_slot, hash)| hash.to_string()),
+                votes: tower
+                    .tower_lockouts()
+                    .enumerate()
+                    .map(|(i, lockout)| CliTowerVote {
+                        slot: lockout.slot(),
+                        confirmation_count: lockout.confirmation_count(),
+                        lockout: lockout.lockout(),
+                        last_locked_out_slot: lockout.last_locked_out_slot(),
+                        blockstore_status: vote_statuses.get(i).copied(),
+                    })
+                    .collect(),
+                blockstore_check,
+            };
+            println!("{}", output_format.formatted_string(&cli_tower));
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Returns the identity of the only tower file in the tower directory
+fn find_tower_identity(tower_storage: &FileTowerStorage) -> Result<Pubkey> {
+    let mut identities = vec![];
+    for entry in fs::read_dir(&tower_storage.tower_path)? {
+        let file_name = entry?.file_name();
+        let identity = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_suffix(".bin"))
+            .and_then(|file_name| {
+                file_name
+                    .strip_prefix("tower-1_9-")
+                    .or_else(|| file_name.strip_prefix("tower-"))
+            })
+            .and_then(|identity| Pubkey::from_str(identity).ok());
+        if let Some(identity) = identity {
+            if !identities.contains(&identity) {
+                identities.push(identity);
+            }
+        }
+    }
+    match identities.as_slice() {
+        [identity] => Ok(*identity),
+        [] => Err(LedgerToolError::BadArgument(format!(
+            "no tower file found in {}",
+            tower_storage.tower_path.display()
+        ))),
+        _ => Err(LedgerToolError::BadArgument(format!(
+            "{} contains the towers of several identities, use --identity to select one of: {}",
+            tower_storage.tower_path.display(),
+            identities
+                .iter()
+                .map(|identity| identity.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ))),
+    }
+}
+
+/// Returns whether `ancestor` is `slot` or one of its ancestors in the blockstore, or `None` if
+/// the chain of parents of `slot` ends before reaching `ancestor`
+fn is_ancestor(blockstore: &Blockstore, ancestor: Slot, slot: Slot) -> Option<bool> {
+    if ancestor >= slot {
+        return Some(ancestor == slot);
+    }
+    for parent in AncestorIterator::new(slot, blockstore) {
+        if parent <= ancestor {
+            return Some(parent == ancestor);
+        }
+    }
+    None
+}
+
+fn slot_status(blockstore: &Blockstore, slot: Slot) -> TowerSlotStatus {
+    if blockstore.is_root(slot) {
+        TowerSlotStatus::Rooted
+    } else if blockstore.is_dead(slot) {
+        TowerSlotStatus::Dead
+    } else {
+        match blockstore.meta(slot).ok().flatten() {
+            Some(slot_meta) if slot_meta.is_full() => TowerSlotStatus::Full,
+            Some(_) => TowerSlotStatus::Incomplete,
+            None => TowerSlotStatus::Missing,
+        }
+    }
+}
+
+/// Returns the blockstore status of each vote in the tower and the problems found with them
+fn check_tower_with_blockstore(
+    tower: &Tower,
+    blockstore: &Blockstore,
+) -> (Vec<TowerSlotStatus>, CliTowerBlockstoreCheck) {
+    let max_root = blockstore.max_root();
+    let lowest_slot = blockstore.lowest_slot();
+    let mut problems = vec![];
+    let mut vote_statuses = vec![];
+
+    let tower_root = tower.root();
+    if tower_root > max_root {
+        problems.push(format!(
+            "The tower root {tower_root} is newer than the blockstore root {max_root}, the \
+             blockstore roots will be reconciled with the tower at startup"
+        ));
+    }
+
+    // Every vote must descend from the previous vote, starting from the tower root
+    let mut previous_slot = tower_root;
+    for lockout in tower.tower_lockouts() {
+        let slot = lockout.slot();
+        vote_statuses.push(slot_status(blockstore, slot));
+        if is_ancestor(blockstore, previous_slot, slot) == Some(false) {
+            problems.push(format!(
+                "The vote for slot {slot} does not descend from slot {previous_slot} in the \
+                 blockstore"
+            ));
+        }
+        previous_slot = slot;
+    }
+
+    if let Some(last_vote_slot) = tower.last_voted_slot() {
+        if last_vote_slot >= lowest_slot && blockstore.meta(last_vote_slot).ok().flatten().is_none()
+        {
+            problems.push(format!(
+                "The last voted slot {last_vote_slot} is not in the blockstore, the validator \
+                 will treat it as a stray vote after restart"
+            ));
+        }
+    }
+
+    // A vote on another fork than the rooted one locks the validator out of the rooted fork
+    // until the vote's lockout expires
+    let conflicting_votes: Vec<_> = tower
+        .tower_lockouts()
+        .filter(|lockout| {
+            let slot = lockout.slot();
+            is_ancestor(blockstore, slot, max_root) == Some(false)
+                && is_ancestor(blockstore, max_root, slot) == Some(false)
+        })
+        .collect();
+    if let Some(locked_out_until) = conflicting_votes
+        .iter()
+        .map(|lockout| lockout.last_locked_out_slot())
+        .max()
+    {
+        problems.push(format!(
+            "The votes for slots {:?} are not on the fork of the blockstore root {max_root}, the \
+             validator cannot vote on that fork until after slot {locked_out_until}",
+            conflicting_votes
+                .iter()
+                .map(|lockout| lockout.slot())
+                .collect::<Vec<_>>(),
+        ));
+    }
+
+    (
+        vote_statuses,
+        CliTowerBlockstoreCheck {
+            max_root,
+            lowest_slot,
+            problems,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_hash::Hash,
+        solana_ledger::{blockstore::make_slot_entries, get_tmp_ledger_path_auto_delete},
+    };
+
+    fn tower_with_votes(slots: &[Slot]) -> Tower {
+        let mut tower = Tower::default();
+        for slot in slots {
+            tower.record_vote(*slot, Hash::new_unique());
+        }
+        tower
+    }
+
+    #[test]
+    fn test_check_tower_with_blockstore() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        // 0 <- 1 <- 2 <- 4
+        //        \
+        //         3 <- 5
+        for (slot, parent_slot) in [(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 3)] {
+            let (shreds, _) = make_slot_entries(slot, parent_slot, 1);
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+        }
+        blockstore.set_roots([0, 1, 3].iter()).unwrap();
+
+        // Votes on the rooted fork
+        let (vote_statuses, check) =
+            check_tower_with_blockstore(&tower_with_votes(&[1, 3, 5]), &blockstore);
+        assert_eq!(check.max_root, 3);
+        assert_eq!(
+            vote_statuses,
+            vec![
+                TowerSlotStatus::Rooted,
+                TowerSlotStatus::Rooted,
+                TowerSlotStatus::Full
+            ]
+        );
+        assert!(check.problems.is_empty(), "{:?}", check.problems);
+
+        // Votes on the fork that was not rooted lock the validator out
+        let (_, check) = check_tower_with_blockstore(&tower_with_votes(&[1, 2, 4]), &blockstore);
+        assert_eq!(check.problems.len(), 1, "{:?}", check.problems);
+        assert!(check.problems[0].contains("[2, 4]"));
+
+        // The last vote is missing from the blockstore
+        let (vote_statuses, check) =
+            check_tower_with_blockstore(&tower_with_votes(&[1, 3, 5, 6]), &blockstore);
+        assert_eq!(vote_statuses.last(), Some(&TowerSlotStatus::Missing));
+        assert_eq!(check.problems.len(), 1, "{:?}", check.problems);
+        assert!(check.problems[0].contains("stray vote"));
+    }
+}
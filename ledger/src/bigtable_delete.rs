@@ -1,10 +1,16 @@
-use {log::*, solana_clock::Slot, solana_measure::measure::Measure, std::result::Result};
+use {
+    log::*,
+    solana_clock::Slot,
+    solana_measure::measure::Measure,
+    solana_storage_history::HistoryStorage,
+    std::{result::Result, sync::Arc},
+};
 
 // Attempt to delete this many blocks in parallel
 const NUM_BLOCKS_TO_DELETE_IN_PARALLEL: usize = 32;
 
 pub async fn delete_confirmed_blocks(
-    bigtable: solana_storage_bigtable::LedgerStorage,
+    history_storage: Arc<dyn HistoryStorage>,
     blocks_to_delete: Vec<Slot>,
     dry_run: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -23,7 +29,7 @@ pub async fn delete_confirmed_blocks(
 
         let deletion_futures = blocks
             .iter()
-            .map(|block| bigtable.delete_confirmed_block(*block, dry_run));
+            .map(|block| history_storage.delete_confirmed_block(*block, dry_run));
 
         for (block, result) in blocks
             .iter()
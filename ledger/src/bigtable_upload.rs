@@ -4,6 +4,7 @@ use {
     log::*,
     solana_clock::Slot,
     solana_measure::measure::Measure,
+    solana_storage_history::HistoryStorage,
     std::{
         cmp::{max, min},
         collections::HashSet,
@@ -41,12 +42,12 @@ struct BlockstoreLoadStats {
     pub elapsed: Duration,
 }
 
-/// Uploads a range of blocks from a Blockstore to bigtable LedgerStorage
+/// Uploads a range of blocks from a Blockstore to long-term history storage, such as bigtable
 /// Returns the Slot of the last block checked. If no blocks in the range `[staring_slot,
 /// ending_slot]` are found in Blockstore, this value is equal to `ending_slot`.
 pub async fn upload_confirmed_blocks(
     blockstore: Arc<Blockstore>,
-    bigtable: solana_storage_bigtable::LedgerStorage,
+    history_storage: Arc<dyn HistoryStorage>,
     starting_slot: Slot,
     ending_slot: Slot,
     config: ConfirmedBlockUploadConfig,
@@ -77,20 +78,20 @@ pub async fn upload_confirmed_blocks(
         last_blockstore_slot,
     );
 
-    // Gather the blocks that are already present in bigtable, by slot
-    let bigtable_slots = if !config.force_reupload {
-        let mut bigtable_slots = vec![];
+    // Gather the blocks that are already present in history storage, by slot
+    let uploaded_slots = if !config.force_reupload {
+        let mut uploaded_slots = vec![];
         info!(
-            "Loading list of bigtable blocks between slots {first_blockstore_slot} and \
+            "Loading list of history storage blocks between slots {first_blockstore_slot} and \
              {last_blockstore_slot}..."
         );
 
         let mut start_slot = first_blockstore_slot;
         while start_slot <= last_blockstore_slot {
-            let mut next_bigtable_slots = loop {
-                let num_bigtable_blocks = min(1000, config.max_num_slots_to_check * 2);
-                match bigtable
-                    .get_confirmed_blocks(start_slot, num_bigtable_blocks)
+            let mut next_uploaded_slots = loop {
+                let num_blocks = min(1000, config.max_num_slots_to_check * 2);
+                match history_storage
+                    .get_confirmed_blocks(start_slot, num_blocks)
                     .await
                 {
                     Ok(slots) => break slots,
@@ -101,13 +102,13 @@ pub async fn upload_confirmed_blocks(
                     }
                 }
             };
-            if next_bigtable_slots.is_empty() {
+            if next_uploaded_slots.is_empty() {
                 break;
             }
-            bigtable_slots.append(&mut next_bigtable_slots);
-            start_slot = bigtable_slots.last().unwrap() + 1;
+            uploaded_slots.append(&mut next_uploaded_slots);
+            start_slot = uploaded_slots.last().unwrap() + 1;
         }
-        bigtable_slots
+        uploaded_slots
             .into_iter()
             .filter(|slot| *slot <= last_blockstore_slot)
             .collect::<Vec<_>>()
@@ -116,13 +117,13 @@ pub async fn upload_confirmed_blocks(
     };
 
     // The blocks that still need to be uploaded is the difference between what's already in the
-    // bigtable and what's in blockstore...
+    // history storage and what's in blockstore...
     let blocks_to_upload = {
         let blockstore_slots = blockstore_slots.into_iter().collect::<HashSet<_>>();
-        let bigtable_slots = bigtable_slots.into_iter().collect::<HashSet<_>>();
+        let uploaded_slots = uploaded_slots.into_iter().collect::<HashSet<_>>();
 
         let mut blocks_to_upload = blockstore_slots
-            .difference(&bigtable_slots)
+            .difference(&uploaded_slots)
             .cloned()
             .collect::<Vec<_>>();
         blocks_to_upload.sort_unstable();
@@ -132,7 +133,8 @@ pub async fn upload_confirmed_blocks(
 
     if blocks_to_upload.is_empty() {
         info!(
-            "No blocks between {starting_slot} and {ending_slot} need to be uploaded to bigtable"
+            "No blocks between {starting_slot} and {ending_slot} need to be uploaded to history \
+             storage"
         );
         return Ok(ending_slot);
     }
@@ -144,7 +146,8 @@ pub async fn upload_confirmed_blocks(
         last_slot
     );
 
-    // Distribute the blockstore reading across a few background threads to speed up the bigtable uploading
+    // Distribute the blockstore reading across a few background threads to speed up the
+    // history storage uploading
     let (loader_threads, receiver): (Vec<_>, _) = {
         let exit = exit.clone();
 
@@ -221,9 +224,10 @@ pub async fn upload_confirmed_blocks(
                 None
             }
             Some(confirmed_block) => {
-                let bt = bigtable.clone();
+                let history_storage = history_storage.clone();
                 Some(tokio::spawn(async move {
-                    bt.upload_confirmed_block_with_entries(slot, confirmed_block)
+                    history_storage
+                        .upload_confirmed_block_with_entries(slot, confirmed_block)
                         .await
                 }))
             }
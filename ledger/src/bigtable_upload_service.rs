@@ -4,6 +4,7 @@ use {
         blockstore::Blockstore,
     },
     solana_runtime::commitment::BlockCommitmentCache,
+    solana_storage_history::HistoryStorage,
     std::{
         cmp::min,
         sync::{
@@ -22,7 +23,7 @@ pub struct BigTableUploadService {
 impl BigTableUploadService {
     pub fn new(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        history_storage: Arc<dyn HistoryStorage>,
         blockstore: Arc<Blockstore>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
@@ -30,7 +31,7 @@ impl BigTableUploadService {
     ) -> Self {
         Self::new_with_config(
             runtime,
-            bigtable_ledger_storage,
+            history_storage,
             blockstore,
             block_commitment_cache,
             max_complete_transaction_status_slot,
@@ -41,20 +42,20 @@ impl BigTableUploadService {
 
     pub fn new_with_config(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        history_storage: Arc<dyn HistoryStorage>,
         blockstore: Arc<Blockstore>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         config: ConfirmedBlockUploadConfig,
         exit: Arc<AtomicBool>,
     ) -> Self {
-        info!("Starting BigTable upload service");
+        info!("Starting history storage upload service");
         let thread = Builder::new()
             .name("solBigTUpload".to_string())
             .spawn(move || {
                 Self::run(
                     runtime,
-                    bigtable_ledger_storage,
+                    history_storage,
                     blockstore,
                     block_commitment_cache,
                     max_complete_transaction_status_slot,
@@ -69,7 +70,7 @@ impl BigTableUploadService {
 
     fn run(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        history_storage: Arc<dyn HistoryStorage>,
         blockstore: Arc<Blockstore>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
@@ -100,7 +101,7 @@ impl BigTableUploadService {
 
             let result = runtime.block_on(bigtable_upload::upload_confirmed_blocks(
                 blockstore.clone(),
-                bigtable_ledger_storage.clone(),
+                history_storage.clone(),
                 start_slot,
                 end_slot,
                 config.clone(),
@@ -110,7 +111,7 @@ impl BigTableUploadService {
             match result {
                 Ok(last_slot_uploaded) => start_slot = last_slot_uploaded.saturating_add(1),
                 Err(err) => {
-                    warn!("bigtable: upload_confirmed_blocks: {err}");
+                    warn!("history storage: upload_confirmed_blocks: {err}");
                     std::thread::sleep(std::time::Duration::from_secs(2));
                     if start_slot == 0 {
                         start_slot = blockstore.get_first_available_block().unwrap_or_default();
@@ -86,6 +86,7 @@ use {
 pub mod blockstore_purge;
 pub mod column;
 pub mod error;
+pub mod shred_archive;
 #[cfg(test)]
 use static_assertions::const_assert_eq;
 pub use {
@@ -95,7 +96,7 @@ pub use {
         blockstore_meta::{OptimisticSlotMetaVersioned, SlotMeta},
         blockstore_metrics::BlockstoreInsertionMetrics,
     },
-    blockstore_purge::PurgeType,
+    blockstore_purge::{ColumnGroup, PurgeType},
     rocksdb::properties as RocksProperties,
 };
 
@@ -250,6 +251,7 @@ pub struct Blockstore {
     // Column families
     address_signatures_cf: LedgerColumn<cf::AddressSignatures>,
     bank_hash_cf: LedgerColumn<cf::BankHash>,
+    block_cost_cf: LedgerColumn<cf::BlockCost>,
     block_height_cf: LedgerColumn<cf::BlockHeight>,
     blocktime_cf: LedgerColumn<cf::Blocktime>,
     code_shred_cf: LedgerColumn<cf::ShredCode>,
@@ -266,6 +268,7 @@ pub struct Blockstore {
     rewards_cf: LedgerColumn<cf::Rewards>,
     roots_cf: LedgerColumn<cf::Root>,
     transaction_memos_cf: LedgerColumn<cf::TransactionMemos>,
+    transactions_cf: LedgerColumn<cf::Transactions>,
     transaction_status_cf: LedgerColumn<cf::TransactionStatus>,
     transaction_status_index_cf: LedgerColumn<cf::TransactionStatusIndex>,
 
@@ -275,6 +278,10 @@ pub struct Blockstore {
     new_shreds_signals: Mutex<Vec<Sender<bool>>>,
     completed_slots_senders: Mutex<Vec<CompletedSlotsSender>>,
     pub lowest_cleanup_slot: RwLock<Slot>,
+    /// Last slot cleaned up for column groups which are retained separately
+    /// from the rest of the ledger. Updated while holding the write lock of
+    /// `lowest_cleanup_slot`.
+    pub(crate) column_group_cleanup_slots: RwLock<HashMap<ColumnGroup, Slot>>,
     pub slots_stats: SlotsStats,
     rpc_api_metrics: BlockstoreRpcApiMetrics,
 }
@@ -397,6 +404,7 @@ impl Blockstore {
 
         let address_signatures_cf = db.column();
         let bank_hash_cf = db.column();
+        let block_cost_cf = db.column();
         let block_height_cf = db.column();
         let blocktime_cf = db.column();
         let code_shred_cf = db.column();
@@ -415,6 +423,7 @@ impl Blockstore {
         let transaction_memos_cf = db.column();
         let transaction_status_cf = db.column();
         let transaction_status_index_cf = db.column();
+        let transactions_cf = db.column();
 
         // Get max root or 0 if it doesn't exist
         let max_root = roots_cf
@@ -431,6 +440,7 @@ impl Blockstore {
             db,
             address_signatures_cf,
             bank_hash_cf,
+            block_cost_cf,
             block_height_cf,
             blocktime_cf,
             code_shred_cf,
@@ -449,12 +459,14 @@ impl Blockstore {
             transaction_memos_cf,
             transaction_status_cf,
             transaction_status_index_cf,
+            transactions_cf,
             highest_primary_index_slot: RwLock::<Option<Slot>>::default(),
             new_shreds_signals: Mutex::default(),
             completed_slots_senders: Mutex::default(),
             insert_shreds_lock: Mutex::<()>::default(),
             max_root,
             lowest_cleanup_slot: RwLock::<Slot>::default(),
+            column_group_cleanup_slots: RwLock::default(),
             slots_stats: SlotsStats::default(),
             rpc_api_metrics: BlockstoreRpcApiMetrics::default(),
         };
@@ -858,11 +870,13 @@ impl Blockstore {
         self.transaction_status_cf.submit_rocksdb_cf_metrics();
         self.address_signatures_cf.submit_rocksdb_cf_metrics();
         self.transaction_memos_cf.submit_rocksdb_cf_metrics();
+        self.transactions_cf.submit_rocksdb_cf_metrics();
         self.transaction_status_index_cf.submit_rocksdb_cf_metrics();
         self.rewards_cf.submit_rocksdb_cf_metrics();
         self.blocktime_cf.submit_rocksdb_cf_metrics();
         self.perf_samples_cf.submit_rocksdb_cf_metrics();
         self.block_height_cf.submit_rocksdb_cf_metrics();
+        self.block_cost_cf.submit_rocksdb_cf_metrics();
         self.bank_hash_cf.submit_rocksdb_cf_metrics();
         self.optimistic_slots_cf.submit_rocksdb_cf_metrics();
         self.merkle_root_meta_cf.submit_rocksdb_cf_metrics();
@@ -2570,7 +2584,7 @@ impl Blockstore {
     }
 
     fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTimestamp>> {
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = self.check_column_group_cleanup_slot(ColumnGroup::BlockTime, slot)?;
         self.blocktime_cf.get(slot)
     }
 
@@ -2578,7 +2592,7 @@ impl Blockstore {
         self.rpc_api_metrics
             .num_get_rooted_block_time
             .fetch_add(1, Ordering::Relaxed);
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = self.check_column_group_cleanup_slot(ColumnGroup::BlockTime, slot)?;
 
         if self.is_root(slot) {
             return self
@@ -2597,7 +2611,7 @@ impl Blockstore {
         self.rpc_api_metrics
             .num_get_block_height
             .fetch_add(1, Ordering::Relaxed);
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = self.check_column_group_cleanup_slot(ColumnGroup::BlockTime, slot)?;
 
         self.block_height_cf.get(slot)
     }
@@ -2606,6 +2620,38 @@ impl Blockstore {
         self.block_height_cf.put(slot, &block_height)
     }
 
+    pub fn get_block_cost_details(&self, slot: Slot) -> Result<Option<BlockCostDetails>> {
+        let _lock = self.check_lowest_cleanup_slot(slot)?;
+
+        Ok(self.block_cost_cf.get(slot)?.map(BlockCostDetails::from))
+    }
+
+    pub fn write_block_cost_details(
+        &self,
+        slot: Slot,
+        block_cost_details: BlockCostDetails,
+    ) -> Result<()> {
+        self.block_cost_cf
+            .put(slot, &BlockCostDetailsVersioned::from(block_cost_details))
+    }
+
+    /// Returns the recorded block cost details in ascending slot order,
+    /// starting at `slot`
+    pub fn block_cost_details_iterator(
+        &self,
+        slot: Slot,
+    ) -> Result<impl Iterator<Item = (Slot, BlockCostDetails)> + '_> {
+        let iter = self
+            .block_cost_cf
+            .iter(IteratorMode::From(slot, IteratorDirection::Forward))?;
+        Ok(iter.map(|(slot, bytes)| {
+            let details = cf::BlockCost::deserialize(&bytes).unwrap_or_else(|err| {
+                panic!("Could not deserialize BlockCostDetails for slot {slot}: {err:?}")
+            });
+            (slot, details.into())
+        }))
+    }
+
     /// The first complete block that is available in the Blockstore ledger
     pub fn get_first_available_block(&self) -> Result<Slot> {
         let mut root_iterator = self.rooted_slot_iterator(self.lowest_slot_with_genesis())?;
@@ -2621,6 +2667,21 @@ impl Blockstore {
         Ok(root_iterator.next().unwrap_or_default())
     }
 
+    /// Returns the first slot for which transaction history can be looked up,
+    /// which differs from `get_first_available_block()` if the transaction
+    /// history columns are retained separately from shreds.
+    fn get_first_available_transaction_history_slot(&self) -> Result<Slot> {
+        match self
+            .column_group_cleanup_slots
+            .read()
+            .unwrap()
+            .get(&ColumnGroup::TransactionHistory)
+        {
+            Some(cleanup_slot) => Ok(cleanup_slot.saturating_add(1)),
+            None => self.get_first_available_block(),
+        }
+    }
+
     pub fn get_rooted_block(
         &self,
         slot: Slot,
@@ -3037,6 +3098,27 @@ impl Blockstore {
             .put_in_batch(db_write_batch, (*signature, slot), &memos)
     }
 
+    /// Returns true if transactions should be written with
+    /// `add_transaction_to_batch()`, which is the case when the transaction
+    /// history is retained separately from the shreds.
+    pub fn stores_transactions(&self) -> bool {
+        self.column_group_cleanup_slots
+            .read()
+            .unwrap()
+            .contains_key(&ColumnGroup::TransactionHistory)
+    }
+
+    pub fn add_transaction_to_batch(
+        &self,
+        signature: &Signature,
+        slot: Slot,
+        transaction: &VersionedTransaction,
+        db_write_batch: &mut WriteBatch,
+    ) -> Result<()> {
+        self.transactions_cf
+            .put_in_batch(db_write_batch, (*signature, slot), transaction)
+    }
+
     /// Acquires the `lowest_cleanup_slot` lock and returns a tuple of the held lock
     /// and lowest available slot.
     ///
@@ -3053,15 +3135,35 @@ impl Blockstore {
         Ok(lowest_cleanup_slot)
     }
 
+    /// Like `check_lowest_cleanup_slot()`, but for the columns in `group`,
+    /// which may be retained for a different number of slots than the rest of
+    /// the ledger.
+    fn check_column_group_cleanup_slot(
+        &self,
+        group: ColumnGroup,
+        slot: Slot,
+    ) -> Result<std::sync::RwLockReadGuard<Slot>> {
+        let lowest_cleanup_slot = self.lowest_cleanup_slot.read().unwrap();
+        let cleanup_slot = self.column_group_cleanup_slot_locked(group, *lowest_cleanup_slot);
+        if cleanup_slot > 0 && cleanup_slot >= slot {
+            return Err(BlockstoreError::SlotCleanedUp);
+        }
+        Ok(lowest_cleanup_slot)
+    }
+
     /// Acquires the lock of `lowest_cleanup_slot` and returns the tuple of
-    /// the held lock and the lowest available slot.
+    /// the held lock and the lowest available slot for the columns in `group`.
     ///
-    /// This function ensures a consistent result by using lowest_cleanup_slot
-    /// as the lower bound for reading columns that do not employ strong read
-    /// consistency with slot-based delete_range.
-    fn ensure_lowest_cleanup_slot(&self) -> (std::sync::RwLockReadGuard<Slot>, Slot) {
+    /// This function ensures a consistent result by using the cleanup slot of
+    /// `group` as the lower bound for reading columns that do not employ
+    /// strong read consistency with slot-based delete_range.
+    fn ensure_column_group_cleanup_slot(
+        &self,
+        group: ColumnGroup,
+    ) -> (std::sync::RwLockReadGuard<Slot>, Slot) {
         let lowest_cleanup_slot = self.lowest_cleanup_slot.read().unwrap();
-        let lowest_available_slot = (*lowest_cleanup_slot)
+        let lowest_available_slot = self
+            .column_group_cleanup_slot_locked(group, *lowest_cleanup_slot)
             .checked_add(1)
             .expect("overflow from trusted value");
 
@@ -3071,6 +3173,19 @@ impl Blockstore {
         (lowest_cleanup_slot, lowest_available_slot)
     }
 
+    fn column_group_cleanup_slot_locked(
+        &self,
+        group: ColumnGroup,
+        lowest_cleanup_slot: Slot,
+    ) -> Slot {
+        self.column_group_cleanup_slots
+            .read()
+            .unwrap()
+            .get(&group)
+            .copied()
+            .unwrap_or(lowest_cleanup_slot)
+    }
+
     // Returns a transaction status, as well as a loop counter for unit testing
     fn get_transaction_status_with_counter(
         &self,
@@ -3078,8 +3193,8 @@ impl Blockstore {
         confirmed_unrooted_slots: &HashSet<Slot>,
     ) -> Result<(Option<(Slot, TransactionStatusMeta)>, u64)> {
         let mut counter = 0;
-        let (lock, _) = self.ensure_lowest_cleanup_slot();
-        let first_available_block = self.get_first_available_block()?;
+        let (lock, _) = self.ensure_column_group_cleanup_slot(ColumnGroup::TransactionHistory);
+        let first_available_block = self.get_first_available_transaction_history_slot()?;
 
         let iterator =
             self.transaction_status_cf
@@ -3205,11 +3320,19 @@ impl Blockstore {
         if let Some((slot, meta)) =
             self.get_transaction_status(signature, confirmed_unrooted_slots)?
         {
-            let transaction = self
-                .find_transaction_in_slot(slot, signature)?
-                .ok_or(BlockstoreError::TransactionStatusSlotMismatch)?; // Should not happen
+            // Transactions are only stored separately while the shreds may be
+            // purged before the transaction history
+            let transaction = match self.transactions_cf.get((signature, slot))? {
+                Some(transaction) => transaction,
+                None => self
+                    .find_transaction_in_slot(slot, signature)?
+                    .ok_or(BlockstoreError::TransactionStatusSlotMismatch)?, // Should not happen
+            };
 
-            let block_time = self.get_block_time(slot)?;
+            let block_time = match self.get_block_time(slot) {
+                Err(BlockstoreError::SlotCleanedUp) => None,
+                block_time => block_time?,
+            };
             Ok(Some(ConfirmedTransactionWithStatusMeta {
                 slot,
                 tx_with_meta: TransactionWithStatusMeta::Complete(
@@ -3262,7 +3385,8 @@ impl Blockstore {
         pubkey: Pubkey,
         slot: Slot,
     ) -> Result<Vec<(Slot, Signature)>> {
-        let (lock, lowest_available_slot) = self.ensure_lowest_cleanup_slot();
+        let (lock, lowest_available_slot) =
+            self.ensure_column_group_cleanup_slot(ColumnGroup::TransactionHistory);
         let mut signatures: Vec<(Slot, Signature)> = vec![];
         if slot < lowest_available_slot {
             return Ok(signatures);
@@ -3368,7 +3492,7 @@ impl Blockstore {
         };
         get_before_slot_timer.stop();
 
-        let first_available_block = self.get_first_available_block()?;
+        let first_available_block = self.get_first_available_transaction_history_slot()?;
         // Generate a HashSet of signatures that should be excluded from the results based on
         // `until` signature
         let mut get_until_slot_timer = Measure::start("get_until_slot_timer");
@@ -4159,6 +4283,13 @@ impl Blockstore {
         *self.lowest_cleanup_slot.read().unwrap()
     }
 
+    /// Returns the last slot cleaned up for the columns in `group`, which is
+    /// `lowest_cleanup_slot()` unless `group` is retained separately.
+    pub fn column_group_cleanup_slot(&self, group: ColumnGroup) -> Slot {
+        let lowest_cleanup_slot = self.lowest_cleanup_slot.read().unwrap();
+        self.column_group_cleanup_slot_locked(group, *lowest_cleanup_slot)
+    }
+
     pub fn storage_size(&self) -> Result<u64> {
         self.db.storage_size()
     }
@@ -4186,6 +4317,12 @@ impl Blockstore {
         self.db.is_primary_access()
     }
 
+    /// Brings a blockstore opened with secondary access up to date with the
+    /// writes of the process holding primary access.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.db.try_catch_up_with_primary()
+    }
+
     /// Scan for any ancestors of the supplied `start_root` that are not
     /// marked as roots themselves. Mark any found slots as roots since
     /// the ancestor of a root is also inherently a root. Returns the
@@ -9099,6 +9236,83 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_get_transaction_after_shreds_purged() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        // As set up by BlockstoreCleanupService for a separate transaction
+        // history retention policy
+        blockstore
+            .column_group_cleanup_slots
+            .write()
+            .unwrap()
+            .insert(ColumnGroup::TransactionHistory, 0);
+        assert!(blockstore.stores_transactions());
+
+        let slot = 2;
+        let entries = make_slot_entries_with_transactions(5);
+        let shreds = entries_to_test_shreds(
+            &entries,
+            slot,
+            slot - 1, // parent_slot
+            true,     // is_full_slot
+            0,        // version
+        );
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        blockstore.set_roots(std::iter::once(&slot)).unwrap();
+
+        let transactions: Vec<VersionedTransaction> = entries
+            .into_iter()
+            .flat_map(|entry| entry.transactions)
+            .collect();
+        let mut write_batch = blockstore.get_write_batch().unwrap();
+        for (transaction_index, transaction) in transactions.iter().enumerate() {
+            let signature = transaction.signatures[0];
+            blockstore
+                .add_transaction_status_to_batch(
+                    slot,
+                    signature,
+                    std::iter::empty(),
+                    TransactionStatusMeta::default(),
+                    transaction_index,
+                    &mut write_batch,
+                )
+                .unwrap();
+            blockstore
+                .add_transaction_to_batch(&signature, slot, transaction, &mut write_batch)
+                .unwrap();
+        }
+        blockstore.write_batch(write_batch).unwrap();
+
+        blockstore.purge_column_group(ColumnGroup::Shreds, 0, slot);
+        *blockstore.lowest_cleanup_slot.write().unwrap() = slot;
+        assert!(blockstore.meta(slot).unwrap().is_none());
+
+        for transaction in transactions {
+            let signature = transaction.signatures[0];
+            let expected = Some(ConfirmedTransactionWithStatusMeta {
+                slot,
+                tx_with_meta: TransactionWithStatusMeta::Complete(
+                    VersionedTransactionWithStatusMeta {
+                        transaction,
+                        meta: TransactionStatusMeta::default(),
+                    },
+                ),
+                block_time: None,
+            });
+            assert_eq!(
+                blockstore.get_rooted_transaction(signature).unwrap(),
+                expected
+            );
+            assert_eq!(
+                blockstore
+                    .get_complete_transaction(signature, slot)
+                    .unwrap(),
+                expected
+            );
+        }
+    }
+
     #[test]
     fn test_empty_transaction_status() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
@@ -9916,6 +10130,52 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_write_block_cost_details() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let details: Vec<_> = (1..=5)
+            .map(|slot| {
+                let details = BlockCostDetails {
+                    block_cost: slot * 1000,
+                    block_cost_limit: 48_000_000,
+                    vote_cost: slot * 100,
+                    transaction_count: slot,
+                    is_leader_block: slot % 2 == 0,
+                    top_writable_accounts: vec![(Pubkey::new_unique(), slot * 10)],
+                };
+                blockstore
+                    .write_block_cost_details(slot, details.clone())
+                    .unwrap();
+                (slot, details)
+            })
+            .collect();
+
+        assert_eq!(blockstore.get_block_cost_details(0).unwrap(), None);
+        assert_eq!(
+            blockstore.get_block_cost_details(3).unwrap().as_ref(),
+            Some(&details[2].1)
+        );
+        assert_eq!(
+            blockstore
+                .block_cost_details_iterator(2)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            details[1..].to_vec()
+        );
+
+        blockstore.purge_and_compact_slots(0, 3);
+        assert_eq!(blockstore.get_block_cost_details(3).unwrap(), None);
+        assert_eq!(
+            blockstore
+                .block_cost_details_iterator(0)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            details[3..].to_vec()
+        );
+    }
+
     #[test]
     fn test_lowest_slot() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
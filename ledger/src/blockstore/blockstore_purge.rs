@@ -1,7 +1,9 @@
 use {
     super::*,
+    crate::blockstore::column::ColumnName,
     solana_message::AccountKeys,
     std::{cmp::max, time::Instant},
+    strum::{Display, EnumString, EnumVariantNames, IntoStaticStr},
 };
 
 #[derive(Default)]
@@ -22,6 +24,84 @@ pub enum PurgeType {
     CompactionFilter,
 }
 
+/// Groups of columns that `BlockstoreCleanupService` can retain for different
+/// lengths of time.
+///
+/// The `Root` column does not belong to any group; it is kept until every
+/// group has purged a slot since transaction history lookups only return
+/// rooted slots.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Display,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ColumnGroup {
+    /// Data and coding shreds along with the slot metadata describing them,
+    /// and every other slot-keyed column not in one of the groups below.
+    Shreds,
+    /// `TransactionStatus`, `AddressSignatures`, `TransactionMemos` and
+    /// `Transactions`.
+    TransactionHistory,
+    /// `Rewards`.
+    Rewards,
+    /// `Blocktime` and `BlockHeight`.
+    BlockTime,
+}
+
+impl ColumnGroup {
+    pub const ALL: [Self; 4] = [
+        Self::Shreds,
+        Self::TransactionHistory,
+        Self::Rewards,
+        Self::BlockTime,
+    ];
+
+    /// Whether the columns in this group are keyed by slot first, and so can
+    /// be purged with range deletes. The others are only cleaned up by the
+    /// compaction filter.
+    pub fn is_slot_keyed(self) -> bool {
+        self != Self::TransactionHistory
+    }
+}
+
+/// The purge operations shared by every column of a [`ColumnGroup`], so that
+/// the columns of each group are listed only once, in
+/// `Blockstore::column_group_columns()`.
+trait GroupColumn {
+    fn name(&self) -> &'static str;
+    fn delete_range_in_batch(&self, batch: &mut WriteBatch, from: Slot, to: Slot) -> Result<()>;
+    fn delete_file_in_range(&self, from: Slot, to: Slot) -> Result<()>;
+    fn compact(&self);
+}
+
+impl<C: Column + ColumnName> GroupColumn for LedgerColumn<C> {
+    fn name(&self) -> &'static str {
+        C::NAME
+    }
+
+    fn delete_range_in_batch(&self, batch: &mut WriteBatch, from: Slot, to: Slot) -> Result<()> {
+        LedgerColumn::<C>::delete_range_in_batch(self, batch, from, to)
+    }
+
+    fn delete_file_in_range(&self, from: Slot, to: Slot) -> Result<()> {
+        LedgerColumn::<C>::delete_file_in_range(self, from, to)
+    }
+
+    fn compact(&self) {
+        LedgerColumn::<C>::compact(self)
+    }
+}
+
 impl Blockstore {
     /// Performs cleanup based on the specified deletion range.  After this
     /// function call, entries within \[`from_slot`, `to_slot`\] will become
@@ -77,6 +157,100 @@ impl Blockstore {
         }
     }
 
+    /// Purges the columns in `group` within \[`from_slot`, `to_slot`\] and
+    /// leaves every other column untouched.
+    ///
+    /// Slot-keyed columns are purged by range deletion. The transaction
+    /// history columns are not keyed by slot first; they are only cleaned up
+    /// by the compaction filter once `set_column_group_max_expired_slot()` is
+    /// called, in the same way as `PurgeType::CompactionFilter`.
+    pub fn purge_column_group(&self, group: ColumnGroup, from_slot: Slot, to_slot: Slot) {
+        let mut purge_stats = PurgeStats::default();
+        let purge_result =
+            self.run_purge_column_group_with_stats(group, from_slot, to_slot, &mut purge_stats);
+
+        datapoint_info!(
+            "blockstore-purge-column-group",
+            "column_group" => <&str>::from(group),
+            ("from_slot", from_slot as i64, i64),
+            ("to_slot", to_slot as i64, i64),
+            ("delete_range_us", purge_stats.delete_range as i64, i64),
+            ("write_batch_us", purge_stats.write_batch as i64, i64),
+            (
+                "delete_file_in_range_us",
+                purge_stats.delete_file_in_range as i64,
+                i64
+            )
+        );
+        if let Err(e) = purge_result {
+            error!(
+                "Error: {e:?}; Purge of column group {group} failed in range {from_slot:?} to \
+                 {to_slot:?}"
+            );
+        }
+    }
+
+    /// Like `set_max_expired_slot()`, but only for the columns in `group`.
+    ///
+    /// From then on, the compaction filter keeps the columns in `group` based
+    /// on this value instead of the one from `set_max_expired_slot()`.
+    pub fn set_column_group_max_expired_slot(&self, group: ColumnGroup, to_slot: Slot) {
+        let to_slot = to_slot.checked_add(1).unwrap();
+        for column_name in self.column_group_column_names(group) {
+            self.db.set_oldest_slot_for_column(column_name, to_slot);
+        }
+    }
+
+    /// Names of the columns in `group`.
+    pub fn column_group_column_names(&self, group: ColumnGroup) -> Vec<&'static str> {
+        self.column_group_columns(group)
+            .into_iter()
+            .map(GroupColumn::name)
+            .collect()
+    }
+
+    fn column_group_columns(&self, group: ColumnGroup) -> Vec<&dyn GroupColumn> {
+        match group {
+            ColumnGroup::Shreds => vec![
+                &self.meta_cf,
+                &self.bank_hash_cf,
+                &self.data_shred_cf,
+                &self.code_shred_cf,
+                &self.dead_slots_cf,
+                &self.duplicate_slots_cf,
+                &self.erasure_meta_cf,
+                &self.orphans_cf,
+                &self.index_cf,
+                &self.perf_samples_cf,
+                &self.block_cost_cf,
+                &self.optimistic_slots_cf,
+                &self.merkle_root_meta_cf,
+            ],
+            ColumnGroup::TransactionHistory => vec![
+                &self.transaction_status_cf,
+                &self.address_signatures_cf,
+                &self.transaction_memos_cf,
+                &self.transactions_cf,
+            ],
+            ColumnGroup::Rewards => vec![&self.rewards_cf],
+            ColumnGroup::BlockTime => vec![&self.blocktime_cf, &self.block_height_cf],
+        }
+    }
+
+    /// Compacts the columns in `group` that are only cleaned up by the
+    /// compaction filter, so that slots purged by `purge_column_group()` are
+    /// removed from disk right away instead of at the next periodic
+    /// compaction.
+    ///
+    /// **This rewrites the whole columns and is slow.**
+    pub fn compact_column_group(&self, group: ColumnGroup) {
+        if !group.is_slot_keyed() {
+            for column in self.column_group_columns(group) {
+                column.compact();
+            }
+        }
+    }
+
     pub fn purge_and_compact_slots(&self, from_slot: Slot, to_slot: Slot) {
         self.purge_slots(from_slot, to_slot, PurgeType::Exact);
     }
@@ -234,6 +408,80 @@ impl Blockstore {
         Ok(columns_purged)
     }
 
+    fn run_purge_column_group_with_stats(
+        &self,
+        group: ColumnGroup,
+        from_slot: Slot,
+        to_slot: Slot,
+        purge_stats: &mut PurgeStats,
+    ) -> Result<bool> {
+        let mut write_batch = self.get_write_batch()?;
+
+        let mut delete_range_timer = Measure::start("delete_range");
+        let columns_purged =
+            self.purge_column_group_range(&mut write_batch, group, from_slot, to_slot);
+        delete_range_timer.stop();
+
+        let mut write_timer = Measure::start("write_batch");
+        self.write_batch(write_batch).inspect_err(|e| {
+            error!(
+                "Error: {e:?} while submitting write batch for purge of column group {group} \
+                 from_slot {from_slot} to_slot {to_slot}"
+            )
+        })?;
+        write_timer.stop();
+
+        // See run_purge_with_stats() for why this is only safe when from_slot
+        // is 0.
+        let mut purge_files_in_range_timer = Measure::start("delete_file_in_range");
+        if columns_purged && from_slot == 0 {
+            self.purge_column_group_files_in_range(group, from_slot, to_slot);
+        }
+        purge_files_in_range_timer.stop();
+
+        purge_stats.delete_range += delete_range_timer.as_us();
+        purge_stats.write_batch += write_timer.as_us();
+        purge_stats.delete_file_in_range += purge_files_in_range_timer.as_us();
+
+        Ok(columns_purged)
+    }
+
+    fn purge_column_group_range(
+        &self,
+        write_batch: &mut WriteBatch,
+        group: ColumnGroup,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> bool {
+        // Groups that aren't keyed by slot rely entirely on the compaction
+        // filter, see purge_range().
+        !group.is_slot_keyed()
+            || self
+                .column_group_columns(group)
+                .into_iter()
+                .fold(true, |purged, column| {
+                    column
+                        .delete_range_in_batch(write_batch, from_slot, to_slot)
+                        .is_ok()
+                        & purged
+                })
+    }
+
+    fn purge_column_group_files_in_range(
+        &self,
+        group: ColumnGroup,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> bool {
+        !group.is_slot_keyed()
+            || self
+                .column_group_columns(group)
+                .into_iter()
+                .fold(true, |purged, column| {
+                    column.delete_file_in_range(from_slot, to_slot).is_ok() & purged
+                })
+    }
+
     fn purge_range(
         &self,
         write_batch: &mut WriteBatch,
@@ -297,6 +545,10 @@ impl Blockstore {
                 .block_height_cf
                 .delete_range_in_batch(write_batch, from_slot, to_slot)
                 .is_ok()
+            & self
+                .block_cost_cf
+                .delete_range_in_batch(write_batch, from_slot, to_slot)
+                .is_ok()
             & self
                 .optimistic_slots_cf
                 .delete_range_in_batch(write_batch, from_slot, to_slot)
@@ -377,6 +629,10 @@ impl Blockstore {
                 .block_height_cf
                 .delete_file_in_range(from_slot, to_slot)
                 .is_ok()
+            & self
+                .block_cost_cf
+                .delete_file_in_range(from_slot, to_slot)
+                .is_ok()
             & self
                 .optimistic_slots_cf
                 .delete_file_in_range(from_slot, to_slot)
@@ -455,6 +711,8 @@ impl Blockstore {
                         .delete_in_batch(batch, (signature, slot))?;
                     self.transaction_memos_cf
                         .delete_in_batch(batch, (signature, slot))?;
+                    self.transactions_cf
+                        .delete_in_batch(batch, (signature, slot))?;
                     if !primary_indexes.is_empty() {
                         self.transaction_memos_cf
                             .delete_deprecated_in_batch(batch, signature)?;
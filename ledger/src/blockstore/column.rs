@@ -10,6 +10,7 @@ use {
     solana_pubkey::{Pubkey, PUBKEY_BYTES},
     solana_signature::{Signature, SIGNATURE_BYTES},
     solana_storage_proto::convert::generated,
+    solana_transaction::versioned::VersionedTransaction,
 };
 
 pub(crate) const DEPRECATED_PROGRAM_COSTS_COLUMN_NAME: &str = "program_costs";
@@ -154,6 +155,17 @@ pub mod columns {
     /// * value type: [`String`]
     pub struct TransactionMemos;
 
+    #[derive(Debug)]
+    /// The transactions column
+    ///
+    /// This column family is only written while the transaction history is
+    /// retained separately from the shreds, so that transactions can still be
+    /// looked up once the shreds they were read from are purged.
+    ///
+    /// * index type: `(`[`Signature`]`, `[`Slot`])`
+    /// * value type: `VersionedTransaction`
+    pub struct Transactions;
+
     #[derive(Debug)]
     /// The transaction status index column.
     ///
@@ -208,6 +220,17 @@ pub mod columns {
     /// * index type: `crate::shred::ErasureSetId` `(Slot, fec_set_index: u32)`
     /// * value type: [`blockstore_meta::MerkleRootMeta`]`
     pub struct MerkleRootMeta;
+
+    #[derive(Debug)]
+    /// The block cost column
+    ///
+    /// This column family stores the cost tracker summary of a block once its
+    /// bank has been frozen: total and vote cost, transaction count, and the
+    /// most contended writable accounts.
+    ///
+    /// * index type: `u64` (see [`SlotColumn`])
+    /// * value type: [`blockstore_meta::BlockCostDetailsVersioned`]
+    pub struct BlockCost;
 }
 
 macro_rules! convert_column_index_to_key_bytes {
@@ -548,6 +571,42 @@ impl ColumnIndexDeprecation for columns::TransactionMemos {
     }
 }
 
+impl Column for columns::Transactions {
+    type Index = (Signature, Slot);
+    type Key = [u8; SIGNATURE_BYTES + std::mem::size_of::<Slot>()];
+
+    #[inline]
+    fn key((signature, slot): &Self::Index) -> Self::Key {
+        convert_column_index_to_key_bytes!(Key,
+            ..64 => signature.as_ref(),
+            64.. => &slot.to_be_bytes(),
+        )
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        convert_column_key_bytes_to_index!(key,
+             0..64 => Signature::from,
+            64..72 => Slot::from_be_bytes,
+        )
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.1
+    }
+
+    // The Transactions column is not keyed by slot so this method is meaningless
+    // See Column::as_index() declaration for more details
+    fn as_index(_index: u64) -> Self::Index {
+        (Signature::default(), 0)
+    }
+}
+impl ColumnName for columns::Transactions {
+    const NAME: &'static str = "transactions";
+}
+impl TypedColumn for columns::Transactions {
+    type Type = VersionedTransaction;
+}
+
 impl Column for columns::TransactionStatusIndex {
     type Index = u64;
     type Key = [u8; std::mem::size_of::<u64>()];
@@ -803,6 +862,14 @@ impl TypedColumn for columns::OptimisticSlots {
     type Type = blockstore_meta::OptimisticSlotMetaVersioned;
 }
 
+impl SlotColumn for columns::BlockCost {}
+impl ColumnName for columns::BlockCost {
+    const NAME: &'static str = "block_cost";
+}
+impl TypedColumn for columns::BlockCost {
+    type Type = blockstore_meta::BlockCostDetailsVersioned;
+}
+
 impl Column for columns::MerkleRootMeta {
     type Index = (Slot, /*fec_set_index:*/ u32);
     type Key = [u8; std::mem::size_of::<Slot>() + std::mem::size_of::<u32>()];
@@ -0,0 +1,484 @@
+//! Export and import of shreds through a self-contained archive file.
+//!
+//! A shred archive lets a node be seeded with the shreds for a range of slots
+//! from disk instead of repairing them over the network. The archive is a
+//! bincode stream made of a [`ShredArchiveHeader`] followed by one
+//! [`ArchivedSlot`] per slot and terminated by `None`. Each slot carries the
+//! stored data and coding shred payloads along with the `SlotMeta`,
+//! `ErasureMeta` and `MerkleRootMeta` of the exporting blockstore.
+//!
+//! Neither the shreds nor the metadata are trusted on import. Every shred must
+//! be signed by the slot leader, which signs the merkle root of its erasure
+//! set. The merkle root and erasure config of each erasure set are rebuilt from
+//! its shreds, which must all agree, and the archived metadata must match what
+//! was rebuilt. Slots that fail verification are skipped while the rest of the
+//! archive is imported.
+
+use {
+    super::*,
+    crate::shred::MAX_CODE_SHREDS_PER_SLOT,
+    bincode::Options,
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    solana_packet::PACKET_DATA_SIZE,
+    std::io::{Read, Write},
+};
+
+/// Identifies a file as a shred archive.
+pub const SHRED_ARCHIVE_MAGIC: [u8; 8] = *b"SHREDARC";
+/// Version of the archive format written by this build.
+pub const SHRED_ARCHIVE_VERSION: u32 = 1;
+
+const MAX_HEADER_SIZE: u64 = 1024;
+/// Upper bound on the serialized size of an [`ArchivedSlot`], so that a corrupt
+/// archive can't make the import allocate without bound. Every shred a slot can
+/// have is given room for its payload, length prefix and share of the metadata.
+const MAX_ARCHIVED_SLOT_SIZE: u64 =
+    ((MAX_DATA_SHREDS_PER_SLOT + MAX_CODE_SHREDS_PER_SLOT) * (PACKET_DATA_SIZE + 128)) as u64;
+
+#[derive(Error, Debug)]
+pub enum ShredArchiveError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serialize(#[from] bincode::Error),
+    #[error("blockstore error: {0}")]
+    Blockstore(#[from] BlockstoreError),
+    #[error("not a shred archive")]
+    InvalidMagic,
+    #[error("unsupported shred archive version {0}")]
+    UnsupportedVersion(u32),
+    #[error("start slot {0} is greater than end slot {1}")]
+    InvalidSlotRange(Slot, Slot),
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ShredArchiveHeader {
+    pub magic: [u8; 8],
+    pub version: u32,
+    /// First slot of the range the archive was exported for.
+    pub start_slot: Slot,
+    /// Last slot, inclusive, of the range the archive was exported for.
+    pub end_slot: Slot,
+}
+
+/// The shreds and shred metadata of a single slot.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchivedSlot {
+    pub slot_meta: SlotMeta,
+    /// `ErasureMeta` of every erasure set, keyed by fec_set_index.
+    pub erasure_metas: Vec<(u32, ErasureMeta)>,
+    /// `MerkleRootMeta` of every erasure set, keyed by fec_set_index.
+    pub merkle_root_metas: Vec<(u32, MerkleRootMeta)>,
+    /// Data shred payloads, as stored in the blockstore.
+    pub data_shreds: Vec<Vec<u8>>,
+    /// Coding shred payloads, as stored in the blockstore.
+    pub coding_shreds: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShredArchiveStats {
+    /// Number of slots exported or imported.
+    pub num_slots: usize,
+    /// Number of slots skipped on import because they failed verification.
+    pub num_rejected_slots: usize,
+    pub num_data_shreds: usize,
+    pub num_coding_shreds: usize,
+}
+
+/// Reason a slot from an archive was rejected on import.
+#[derive(Error, Debug, PartialEq, Eq)]
+enum SlotVerificationError {
+    #[error("leader is unknown")]
+    UnknownLeader,
+    #[error("invalid shred at index {0}: {1}")]
+    InvalidShred(usize, String),
+    #[error("found shred for slot {0}")]
+    SlotMismatch(Slot),
+    #[error("invalid signature on {0:?} shred {1}")]
+    InvalidSignature(ShredType, u32),
+    #[error("{0:?} shred {1} does not match the merkle root of erasure set {2}")]
+    MerkleRootMismatch(ShredType, u32, u32),
+    #[error("coding shred {0} does not match the erasure config of erasure set {1}")]
+    ErasureConfigMismatch(u32, u32),
+    #[error("archived shred metadata does not match the shreds")]
+    ArchivedMetaMismatch,
+}
+
+impl ArchivedSlot {
+    /// Deserializes and verifies the archived shreds, returning them if all of
+    /// them were signed by `leader` and the archived shred metadata matches
+    /// the shreds.
+    fn verify(
+        &self,
+        leader: Option<Pubkey>,
+    ) -> std::result::Result<Vec<Shred>, SlotVerificationError> {
+        let slot = self.slot_meta.slot;
+        let leader = leader.ok_or(SlotVerificationError::UnknownLeader)?;
+        // Rebuilt from the first shred of each erasure set; the other shreds
+        // of the set must agree with it.
+        let mut merkle_roots: HashMap<u32, Hash> = HashMap::new();
+        let mut erasure_metas: HashMap<u32, ErasureMeta> = HashMap::new();
+
+        let shreds = self
+            .data_shreds
+            .iter()
+            .chain(&self.coding_shreds)
+            .enumerate()
+            .map(|(i, payload)| {
+                let shred = Shred::new_from_serialized_shred(payload.clone())
+                    .map_err(|err| SlotVerificationError::InvalidShred(i, err.to_string()))?;
+                if shred.slot() != slot {
+                    return Err(SlotVerificationError::SlotMismatch(shred.slot()));
+                }
+                if !shred.verify(&leader) {
+                    return Err(SlotVerificationError::InvalidSignature(
+                        shred.shred_type(),
+                        shred.index(),
+                    ));
+                }
+                let fec_set_index = shred.fec_set_index();
+                let merkle_root_mismatch = || {
+                    SlotVerificationError::MerkleRootMismatch(
+                        shred.shred_type(),
+                        shred.index(),
+                        fec_set_index,
+                    )
+                };
+                let merkle_root = shred.merkle_root().map_err(|_| merkle_root_mismatch())?;
+                if *merkle_roots.entry(fec_set_index).or_insert(merkle_root) != merkle_root {
+                    return Err(merkle_root_mismatch());
+                }
+                if shred.is_code() {
+                    let erasure_meta = ErasureMeta::from_coding_shred(&shred).map(|erasure_meta| {
+                        *erasure_metas.entry(fec_set_index).or_insert(erasure_meta)
+                    });
+                    if !erasure_meta
+                        .is_some_and(|erasure_meta| erasure_meta.check_coding_shred(&shred))
+                    {
+                        return Err(SlotVerificationError::ErasureConfigMismatch(
+                            shred.index(),
+                            fec_set_index,
+                        ));
+                    }
+                }
+                Ok(shred)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // The archived metadata must describe exactly the erasure sets rebuilt
+        // from the shreds.
+        let archived_merkle_roots: HashMap<u32, Option<Hash>> = self
+            .merkle_root_metas
+            .iter()
+            .map(|(fec_set_index, merkle_root_meta)| {
+                (*fec_set_index, merkle_root_meta.merkle_root())
+            })
+            .collect();
+        let archived_erasure_metas: HashMap<u32, &ErasureMeta> = self
+            .erasure_metas
+            .iter()
+            .map(|(fec_set_index, erasure_meta)| (*fec_set_index, erasure_meta))
+            .collect();
+        let merkle_roots_match = archived_merkle_roots.len() == self.merkle_root_metas.len()
+            && archived_merkle_roots.len() == merkle_roots.len()
+            && merkle_roots.iter().all(|(fec_set_index, merkle_root)| {
+                archived_merkle_roots.get(fec_set_index) == Some(&Some(*merkle_root))
+            });
+        let erasure_metas_match = archived_erasure_metas.len() == self.erasure_metas.len()
+            && archived_erasure_metas.len() == erasure_metas.len()
+            && shreds.iter().filter(|shred| shred.is_code()).all(|shred| {
+                archived_erasure_metas
+                    .get(&shred.fec_set_index())
+                    .is_some_and(|erasure_meta| erasure_meta.check_coding_shred(shred))
+            });
+        if !merkle_roots_match || !erasure_metas_match {
+            return Err(SlotVerificationError::ArchivedMetaMismatch);
+        }
+        Ok(shreds)
+    }
+}
+
+/// Like `bincode::deserialize_from()`, which archives are written with, but
+/// fails instead of reading more than `limit` bytes.
+fn deserialize_from_with_limit<R: Read, T: DeserializeOwned>(
+    reader: R,
+    limit: u64,
+) -> bincode::Result<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(limit)
+        .deserialize_from(reader)
+}
+
+impl Blockstore {
+    /// Writes the shreds of every slot in `[start_slot, end_slot]` to `writer`
+    /// as a shred archive. Slots without any shreds are skipped.
+    pub fn export_shred_archive<W: Write>(
+        &self,
+        mut writer: W,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> std::result::Result<ShredArchiveStats, ShredArchiveError> {
+        if start_slot > end_slot {
+            return Err(ShredArchiveError::InvalidSlotRange(start_slot, end_slot));
+        }
+        let header = ShredArchiveHeader {
+            magic: SHRED_ARCHIVE_MAGIC,
+            version: SHRED_ARCHIVE_VERSION,
+            start_slot,
+            end_slot,
+        };
+        bincode::serialize_into(&mut writer, &header)?;
+
+        let mut stats = ShredArchiveStats::default();
+        for (slot, slot_meta) in self
+            .slot_meta_iterator(start_slot)?
+            .take_while(|(slot, _)| *slot <= end_slot)
+        {
+            let data_shreds: Vec<_> = self
+                .slot_data_iterator(slot, 0)?
+                .map(|(_, payload)| payload.into_vec())
+                .collect();
+            let coding_shreds: Vec<_> = self
+                .slot_coding_iterator(slot, 0)?
+                .map(|(_, payload)| payload.into_vec())
+                .collect();
+            if data_shreds.is_empty() && coding_shreds.is_empty() {
+                continue;
+            }
+            let erasure_metas = self
+                .erasure_meta_cf
+                .iter(IteratorMode::From((slot, 0), IteratorDirection::Forward))?
+                .take_while(|((erasure_slot, _), _)| *erasure_slot == slot)
+                .map(|((_, fec_set_index), bytes)| {
+                    let fec_set_index = u32::try_from(fec_set_index)
+                        .expect("fec_set_index from a previously inserted shred should fit in u32");
+                    Ok((fec_set_index, deserialize(&bytes)?))
+                })
+                .collect::<bincode::Result<Vec<_>>>()?;
+            let merkle_root_metas = self
+                .merkle_root_meta_cf
+                .iter(IteratorMode::From((slot, 0), IteratorDirection::Forward))?
+                .take_while(|((merkle_root_slot, _), _)| *merkle_root_slot == slot)
+                .map(|((_, fec_set_index), bytes)| Ok((fec_set_index, deserialize(&bytes)?)))
+                .collect::<bincode::Result<Vec<_>>>()?;
+
+            stats.num_slots += 1;
+            stats.num_data_shreds += data_shreds.len();
+            stats.num_coding_shreds += coding_shreds.len();
+            let archived_slot = ArchivedSlot {
+                slot_meta,
+                erasure_metas,
+                merkle_root_metas,
+                data_shreds,
+                coding_shreds,
+            };
+            bincode::serialize_into(&mut writer, &Some(archived_slot))?;
+        }
+        bincode::serialize_into(&mut writer, &None::<ArchivedSlot>)?;
+        writer.flush()?;
+        Ok(stats)
+    }
+
+    /// Reads a shred archive from `reader`, verifies it and inserts its shreds.
+    ///
+    /// `slot_leader` returns the leader of a slot, which every shred of that
+    /// slot must be signed by. Slots whose leader is unknown or which fail
+    /// verification are skipped and counted in
+    /// [`ShredArchiveStats::num_rejected_slots`].
+    pub fn import_shred_archive<R: Read>(
+        &self,
+        mut reader: R,
+        slot_leader: impl Fn(Slot) -> Option<Pubkey>,
+    ) -> std::result::Result<ShredArchiveStats, ShredArchiveError> {
+        let header: ShredArchiveHeader = deserialize_from_with_limit(&mut reader, MAX_HEADER_SIZE)?;
+        if header.magic != SHRED_ARCHIVE_MAGIC {
+            return Err(ShredArchiveError::InvalidMagic);
+        }
+        if header.version != SHRED_ARCHIVE_VERSION {
+            return Err(ShredArchiveError::UnsupportedVersion(header.version));
+        }
+        info!(
+            "Importing shred archive for slots [{}, {}]",
+            header.start_slot, header.end_slot
+        );
+
+        let mut stats = ShredArchiveStats::default();
+        while let Some(archived_slot) = deserialize_from_with_limit::<_, Option<ArchivedSlot>>(
+            &mut reader,
+            MAX_ARCHIVED_SLOT_SIZE,
+        )? {
+            let slot = archived_slot.slot_meta.slot;
+            let shreds = match archived_slot.verify(slot_leader(slot)) {
+                Ok(shreds) => shreds,
+                Err(err) => {
+                    warn!("Rejecting slot {slot} from shred archive: {err}");
+                    stats.num_rejected_slots += 1;
+                    continue;
+                }
+            };
+            self.do_insert_shreds(
+                shreds
+                    .iter()
+                    .map(|shred| (Cow::Borrowed(shred), /*is_repaired:*/ true)),
+                None,  // leader_schedule
+                false, // is_trusted
+                None,  // (reed_solomon_cache, retransmit_sender)
+                &mut BlockstoreInsertionMetrics::default(),
+            )?;
+            stats.num_slots += 1;
+            stats.num_data_shreds += archived_slot.data_shreds.len();
+            stats.num_coding_shreds += archived_slot.coding_shreds.len();
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::get_tmp_ledger_path_auto_delete, assert_matches::assert_matches};
+
+    fn make_slot_shreds(keypair: &Keypair, slot: Slot, parent_slot: Slot) -> Vec<Shred> {
+        let entries = create_ticks(64, 1, Hash::new_unique());
+        Shredder::new(slot, parent_slot, 0, 0)
+            .unwrap()
+            .make_merkle_shreds_from_entries(
+                keypair,
+                &entries,
+                true, // is_last_in_slot
+                Some(Hash::new_unique()),
+                0, // next_shred_index
+                0, // next_code_index
+                &ReedSolomonCache::default(),
+                &mut ProcessShredsStats::default(),
+            )
+            .collect()
+    }
+
+    #[test]
+    fn test_shred_archive_round_trip() {
+        let leader = Keypair::new();
+        let source_path = get_tmp_ledger_path_auto_delete!();
+        let source = Blockstore::open(source_path.path()).unwrap();
+        for slot in 1..=3 {
+            source
+                .insert_shreds(make_slot_shreds(&leader, slot, slot - 1), None, false)
+                .unwrap();
+        }
+
+        let mut archive = Vec::new();
+        let stats = source.export_shred_archive(&mut archive, 1, 2).unwrap();
+        assert_eq!(stats.num_slots, 2);
+        assert!(stats.num_data_shreds > 0);
+        assert!(stats.num_coding_shreds > 0);
+
+        let dest_path = get_tmp_ledger_path_auto_delete!();
+        let dest = Blockstore::open(dest_path.path()).unwrap();
+        let leader_pubkey = leader.pubkey();
+        let import_stats = dest
+            .import_shred_archive(archive.as_slice(), |_| Some(leader_pubkey))
+            .unwrap();
+        assert_eq!(import_stats, stats);
+        for slot in 1..=2 {
+            assert!(dest.is_full(slot));
+            assert_eq!(
+                dest.get_data_shreds_for_slot(slot, 0).unwrap(),
+                source.get_data_shreds_for_slot(slot, 0).unwrap()
+            );
+            assert_eq!(
+                dest.get_coding_shreds_for_slot(slot, 0).unwrap(),
+                source.get_coding_shreds_for_slot(slot, 0).unwrap()
+            );
+        }
+        assert!(dest.meta(3).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_deserialize_from_with_limit() {
+        let data_shreds = vec![vec![0u8; 1000]];
+        let bytes = bincode::serialize(&data_shreds).unwrap();
+        assert_eq!(
+            deserialize_from_with_limit::<_, Vec<Vec<u8>>>(bytes.as_slice(), 2000).unwrap(),
+            data_shreds
+        );
+        assert!(deserialize_from_with_limit::<_, Vec<Vec<u8>>>(bytes.as_slice(), 500).is_err());
+    }
+
+    #[test]
+    fn test_shred_archive_rejects_unverified_slots() {
+        let leader = Keypair::new();
+        let source_path = get_tmp_ledger_path_auto_delete!();
+        let source = Blockstore::open(source_path.path()).unwrap();
+        for slot in 1..=2 {
+            source
+                .insert_shreds(make_slot_shreds(&leader, slot, slot - 1), None, false)
+                .unwrap();
+        }
+        let mut archive = Vec::new();
+        source.export_shred_archive(&mut archive, 1, 2).unwrap();
+
+        // Slot 1 has the wrong leader and slot 2 has no known leader.
+        let dest_path = get_tmp_ledger_path_auto_delete!();
+        let dest = Blockstore::open(dest_path.path()).unwrap();
+        let other = Pubkey::new_unique();
+        let stats = dest
+            .import_shred_archive(archive.as_slice(), |slot| (slot == 1).then_some(other))
+            .unwrap();
+        assert_eq!(stats.num_slots, 0);
+        assert_eq!(stats.num_rejected_slots, 2);
+        assert!(dest.meta(1).unwrap().is_none());
+        assert!(dest.meta(2).unwrap().is_none());
+
+        // Merkle roots recorded for another slot don't match the shreds even
+        // though they are signed by the right leader.
+        let mut reader = archive.as_slice();
+        let header: ShredArchiveHeader = bincode::deserialize_from(&mut reader).unwrap();
+        let mut slot_1: ArchivedSlot = bincode::deserialize_from::<_, Option<_>>(&mut reader)
+            .unwrap()
+            .unwrap();
+        let slot_2: ArchivedSlot = bincode::deserialize_from::<_, Option<_>>(&mut reader)
+            .unwrap()
+            .unwrap();
+        assert!(slot_1.verify(Some(leader.pubkey())).is_ok());
+        let merkle_root_metas =
+            std::mem::replace(&mut slot_1.merkle_root_metas, slot_2.merkle_root_metas);
+        assert_matches!(
+            slot_1.verify(Some(leader.pubkey())),
+            Err(SlotVerificationError::ArchivedMetaMismatch)
+        );
+
+        // Shreds from another shredding of the slot by the same leader don't
+        // belong to the same erasure set, whatever the archived metadata says.
+        slot_1.merkle_root_metas = merkle_root_metas;
+        slot_1.data_shreds[1] = make_slot_shreds(&leader, 1, 0)
+            .into_iter()
+            .find(|shred| shred.is_data() && shred.index() == 1)
+            .unwrap()
+            .payload()
+            .to_vec();
+        assert_matches!(
+            slot_1.verify(Some(leader.pubkey())),
+            Err(SlotVerificationError::MerkleRootMismatch(
+                ShredType::Data,
+                1,
+                0
+            ))
+        );
+
+        let mut bad_magic = Vec::new();
+        bincode::serialize_into(
+            &mut bad_magic,
+            &ShredArchiveHeader {
+                magic: *b"NOTSHRED",
+                ..header
+            },
+        )
+        .unwrap();
+        assert_matches!(
+            dest.import_shred_archive(bad_magic.as_slice(), |_| None),
+            Err(ShredArchiveError::InvalidMagic)
+        );
+    }
+}
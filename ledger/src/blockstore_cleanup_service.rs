@@ -3,16 +3,23 @@
 //! can be done quickly and should have a fairly stable correlation to actual bytes.
 //! Once the shred count (and thus roughly the byte count) reaches a threshold,
 //! the services begins removing data in FIFO order.
+//!
+//! Some of the columns can also be retained for a different length of time
+//! than the shreds, by slot count, age or disk size; see [`ColumnGroup`] and
+//! [`RetentionPolicy`]. This lets RPC nodes keep transaction history long after
+//! the shreds it was derived from are gone.
 
 use {
     crate::blockstore::{
         self,
         column::{columns, ColumnName},
-        Blockstore, PurgeType,
+        Blockstore, ColumnGroup, PurgeType,
     },
     solana_clock::{Slot, DEFAULT_MS_PER_SLOT},
     solana_measure::measure::Measure,
     std::{
+        collections::HashMap,
+        str::FromStr,
         string::ToString,
         sync::{
             atomic::{AtomicBool, Ordering},
@@ -48,30 +55,128 @@ const DEFAULT_CLEANUP_SLOT_INTERVAL: u64 = 512;
 const LOOP_LIMITER: Duration =
     Duration::from_millis(DEFAULT_CLEANUP_SLOT_INTERVAL * DEFAULT_MS_PER_SLOT / 10);
 
+/// How long [`BlockstoreCleanupService`] keeps the columns of a [`ColumnGroup`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// Keep at most this many data shreds, which is how `--limit-ledger-size`
+    /// is measured. The slots to purge are derived from the number of data
+    /// shreds whichever group this applies to.
+    MaxShreds(u64),
+    /// Keep this many slots before the latest root.
+    Slots(u64),
+    /// Keep the slots younger than this, converted to a number of slots
+    /// using `DEFAULT_MS_PER_SLOT`.
+    Age(Duration),
+    /// Keep the columns of the group under this many bytes on disk.
+    DiskSize(u64),
+}
+
+impl FromStr for RetentionPolicy {
+    type Err = String;
+
+    /// Parses `slots:<N>`, `age:<N>{s,m,h,d}`, `size:<N>{K,M,G,T}` or
+    /// `shreds:<N>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, value) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected <kind>:<value>, got {s}"))?;
+        let parse_number = |value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|err| format!("invalid number {value}: {err}"))
+        };
+        let parse_with_unit = |value: &str, units: &[(char, u64)]| {
+            let (number, multiplier) = match value.chars().last() {
+                Some(unit) if unit.is_ascii_alphabetic() => {
+                    let multiplier = units
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(&unit))
+                        .map(|(_, multiplier)| *multiplier)
+                        .ok_or_else(|| format!("invalid unit {unit} in {value}"))?;
+                    (&value[..value.len() - 1], multiplier)
+                }
+                _ => (value, 1),
+            };
+            parse_number(number)?
+                .checked_mul(multiplier)
+                .ok_or_else(|| format!("{value} is too large"))
+        };
+        match kind {
+            "shreds" => parse_number(value).map(Self::MaxShreds),
+            "slots" => parse_number(value).map(Self::Slots),
+            "age" => parse_with_unit(
+                value,
+                &[('s', 1), ('m', 60), ('h', 60 * 60), ('d', 24 * 60 * 60)],
+            )
+            .map(|secs| Self::Age(Duration::from_secs(secs))),
+            "size" => parse_with_unit(
+                value,
+                &[
+                    ('k', 1 << 10),
+                    ('m', 1 << 20),
+                    ('g', 1 << 30),
+                    ('t', 1 << 40),
+                ],
+            )
+            .map(Self::DiskSize),
+            _ => Err(format!(
+                "unknown retention kind {kind}, expected one of shreds, slots, age or size"
+            )),
+        }
+    }
+}
+
 pub struct BlockstoreCleanupService {
     t_cleanup: JoinHandle<()>,
 }
 
 impl BlockstoreCleanupService {
     pub fn new(blockstore: Arc<Blockstore>, max_ledger_shreds: u64, exit: Arc<AtomicBool>) -> Self {
+        let retention = HashMap::from([(
+            ColumnGroup::Shreds,
+            RetentionPolicy::MaxShreds(max_ledger_shreds),
+        )]);
+        Self::new_with_retention(blockstore, retention, exit)
+    }
+
+    /// Creates a service which purges each [`ColumnGroup`] according to its
+    /// entry in `retention`.
+    ///
+    /// Groups without an entry follow `ColumnGroup::Shreds`, which is never
+    /// purged if it doesn't have an entry either.
+    pub fn new_with_retention(
+        blockstore: Arc<Blockstore>,
+        retention: HashMap<ColumnGroup, RetentionPolicy>,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
         let mut last_purge_slot = 0;
         let mut last_check_time = Instant::now();
 
+        // Until the first purge, reads of separately retained groups should
+        // not be limited by the cleanup of the shreds.
+        {
+            let mut column_group_cleanup_slots =
+                blockstore.column_group_cleanup_slots.write().unwrap();
+            for group in retention
+                .keys()
+                .filter(|group| **group != ColumnGroup::Shreds)
+            {
+                column_group_cleanup_slots.entry(*group).or_default();
+            }
+        }
+
         let t_cleanup = Builder::new()
             .name("solBstoreClean".to_string())
             .spawn(move || {
-                info!(
-                    "BlockstoreCleanupService has started with max ledger \
-                     shreds={max_ledger_shreds}",
-                );
+                info!("BlockstoreCleanupService has started with retention={retention:?}");
                 loop {
                     if exit.load(Ordering::Relaxed) {
                         break;
                     }
                     if last_check_time.elapsed() > LOOP_LIMITER {
-                        Self::cleanup_ledger(
+                        Self::cleanup_ledger_with_retention(
                             &blockstore,
-                            max_ledger_shreds,
+                            &retention,
                             &mut last_purge_slot,
                             DEFAULT_CLEANUP_SLOT_INTERVAL,
                         );
@@ -106,14 +211,7 @@ impl BlockstoreCleanupService {
         root: Slot,
         max_ledger_shreds: u64,
     ) -> (bool, Slot, u64) {
-        let live_files = blockstore
-            .live_files_metadata()
-            .expect("Blockstore::live_files_metadata()");
-        let num_shreds = live_files
-            .iter()
-            .filter(|live_file| live_file.column_family_name == columns::ShredData::NAME)
-            .map(|file_meta| file_meta.num_entries)
-            .sum();
+        let num_shreds = Self::num_data_shreds(blockstore);
 
         // Using the difference between the lowest and highest slot seen will
         // result in overestimating the number of slots in the blockstore since
@@ -174,6 +272,93 @@ impl BlockstoreCleanupService {
         }
     }
 
+    fn num_data_shreds(blockstore: &Blockstore) -> u64 {
+        blockstore
+            .live_files_metadata()
+            .expect("Blockstore::live_files_metadata()")
+            .iter()
+            .filter(|live_file| live_file.column_family_name == columns::ShredData::NAME)
+            .map(|file_meta| file_meta.num_entries)
+            .sum()
+    }
+
+    /// Returns the highest slot to purge from the columns in `group` so that
+    /// they satisfy `policy`, or `None` if nothing needs to be purged. Slots
+    /// newer than `root` are never purged.
+    fn find_column_group_slots_to_clean(
+        blockstore: &Blockstore,
+        group: ColumnGroup,
+        policy: &RetentionPolicy,
+        root: Slot,
+    ) -> Option<Slot> {
+        match policy {
+            RetentionPolicy::MaxShreds(max_ledger_shreds) => {
+                let (slots_to_clean, lowest_cleanup_slot, _) =
+                    Self::find_slots_to_clean(blockstore, root, *max_ledger_shreds);
+                slots_to_clean.then_some(lowest_cleanup_slot)
+            }
+            RetentionPolicy::Slots(num_slots) => root.checked_sub(*num_slots),
+            RetentionPolicy::Age(age) => {
+                let num_slots = (age.as_millis() / u128::from(DEFAULT_MS_PER_SLOT)) as u64;
+                root.checked_sub(num_slots)
+            }
+            RetentionPolicy::DiskSize(max_size) => {
+                Self::find_column_group_slots_to_clean_by_size(blockstore, group, *max_size, root)
+            }
+        }
+    }
+
+    /// Like `find_slots_to_clean()`, but estimates the number of slots to
+    /// purge from the on-disk size of the columns in `group`.
+    fn find_column_group_slots_to_clean_by_size(
+        blockstore: &Blockstore,
+        group: ColumnGroup,
+        max_size: u64,
+        root: Slot,
+    ) -> Option<Slot> {
+        let column_names = blockstore.column_group_column_names(group);
+        let size: u64 = blockstore
+            .live_files_metadata()
+            .expect("Blockstore::live_files_metadata()")
+            .iter()
+            .filter(|live_file| column_names.contains(&live_file.column_family_name.as_str()))
+            .map(|live_file| live_file.size as u64)
+            .sum();
+        if size <= max_size {
+            return None;
+        }
+
+        // Roots are kept until every group has purged them, so the lowest
+        // root is no newer than the oldest data of any group.
+        let lowest_root = blockstore
+            .rooted_slot_iterator(0)
+            .ok()
+            .and_then(|mut roots| roots.next())
+            .unwrap_or_default();
+        let lowest_slot = blockstore
+            .column_group_cleanup_slot(group)
+            .saturating_add(1)
+            .max(lowest_root);
+        let highest_slot = blockstore
+            .highest_slot()
+            .expect("Blockstore::highest_slot()")
+            .unwrap_or(lowest_slot)
+            .max(lowest_slot);
+        let num_slots = highest_slot - lowest_slot + 1;
+        let mean_size_per_slot = size / num_slots;
+        info!(
+            "Blockstore column group {group} uses {size} bytes in slots [{lowest_slot}, \
+             {highest_slot}], mean of {mean_size_per_slot} bytes per slot",
+        );
+
+        if mean_size_per_slot == 0 {
+            error!("Skipping cleanup of column group {group}: calculated mean of 0 bytes per slot");
+            return None;
+        }
+        let num_slots_to_clean = (size - max_size).div_ceil(mean_size_per_slot);
+        Some(std::cmp::min(lowest_slot + num_slots_to_clean - 1, root))
+    }
+
     /// Checks for new roots and initiates a cleanup if the last cleanup was at
     /// least `purge_interval` slots ago. A cleanup will no-op if the ledger
     /// already has fewer than `max_ledger_shreds`; otherwise, the cleanup will
@@ -199,6 +384,26 @@ impl BlockstoreCleanupService {
         max_ledger_shreds: u64,
         last_purge_slot: &mut u64,
         purge_interval: u64,
+    ) {
+        let retention = HashMap::from([(
+            ColumnGroup::Shreds,
+            RetentionPolicy::MaxShreds(max_ledger_shreds),
+        )]);
+        Self::cleanup_ledger_with_retention(
+            blockstore,
+            &retention,
+            last_purge_slot,
+            purge_interval,
+        );
+    }
+
+    /// Like `cleanup_ledger()`, but purges each [`ColumnGroup`] according to
+    /// its entry in `retention`; see `new_with_retention()`.
+    pub fn cleanup_ledger_with_retention(
+        blockstore: &Arc<Blockstore>,
+        retention: &HashMap<ColumnGroup, RetentionPolicy>,
+        last_purge_slot: &mut u64,
+        purge_interval: u64,
     ) {
         let root = blockstore.max_root();
         if root - *last_purge_slot <= purge_interval {
@@ -208,10 +413,14 @@ impl BlockstoreCleanupService {
         info!("Looking for Blockstore data to cleanup, latest root: {root}");
 
         let disk_utilization_pre = blockstore.storage_size();
-        let (slots_to_clean, lowest_cleanup_slot, total_shreds) =
-            Self::find_slots_to_clean(blockstore, root, max_ledger_shreds);
-
-        if slots_to_clean {
+        let total_shreds = Self::num_data_shreds(blockstore);
+        let shreds_cleanup_slot = retention.get(&ColumnGroup::Shreds).and_then(|policy| {
+            Self::find_column_group_slots_to_clean(blockstore, ColumnGroup::Shreds, policy, root)
+        });
+
+        if retention.keys().any(|group| *group != ColumnGroup::Shreds) {
+            Self::cleanup_column_groups(blockstore, retention, root, shreds_cleanup_slot);
+        } else if let Some(lowest_cleanup_slot) = shreds_cleanup_slot {
             *blockstore.lowest_cleanup_slot.write().unwrap() = lowest_cleanup_slot;
 
             let mut purge_time = Measure::start("purge_slots()");
@@ -237,6 +446,85 @@ impl BlockstoreCleanupService {
         Self::report_disk_metrics(disk_utilization_pre, disk_utilization_post, total_shreds);
     }
 
+    /// Purges every [`ColumnGroup`] up to its own cleanup slot, for when some
+    /// groups are retained separately from the shreds.
+    ///
+    /// The `Root` column and the legacy `TransactionStatusIndex` column do not
+    /// belong to a group and are purged once every group has purged a slot.
+    fn cleanup_column_groups(
+        blockstore: &Blockstore,
+        retention: &HashMap<ColumnGroup, RetentionPolicy>,
+        root: Slot,
+        shreds_cleanup_slot: Option<Slot>,
+    ) {
+        let previous_cleanup_slots: HashMap<_, _> = ColumnGroup::ALL
+            .into_iter()
+            .map(|group| (group, blockstore.column_group_cleanup_slot(group)))
+            .collect();
+        let cleanup_slots: HashMap<_, _> = ColumnGroup::ALL
+            .into_iter()
+            .map(|group| {
+                let cleanup_slot = match retention.get(&group) {
+                    Some(policy) if group != ColumnGroup::Shreds => {
+                        Self::find_column_group_slots_to_clean(blockstore, group, policy, root)
+                    }
+                    _ => shreds_cleanup_slot,
+                };
+                // Never move a cleanup slot backwards, e.g. if the root stalls
+                let cleanup_slot = cleanup_slot
+                    .unwrap_or_default()
+                    .max(previous_cleanup_slots[&group]);
+                (group, cleanup_slot)
+            })
+            .collect();
+
+        {
+            let mut lowest_cleanup_slot = blockstore.lowest_cleanup_slot.write().unwrap();
+            let mut column_group_cleanup_slots =
+                blockstore.column_group_cleanup_slots.write().unwrap();
+            *lowest_cleanup_slot = cleanup_slots[&ColumnGroup::Shreds];
+            for group in retention
+                .keys()
+                .filter(|group| **group != ColumnGroup::Shreds)
+            {
+                column_group_cleanup_slots.insert(*group, cleanup_slots[group]);
+            }
+        }
+
+        for group in ColumnGroup::ALL {
+            let cleanup_slot = cleanup_slots[&group];
+            if cleanup_slot <= previous_cleanup_slots[&group] {
+                continue;
+            }
+            let mut purge_time = Measure::start("purge_column_group()");
+            blockstore.purge_column_group(group, 0, cleanup_slot);
+            blockstore.set_column_group_max_expired_slot(group, cleanup_slot);
+            // Until the purged slots are compacted away, the size of the group
+            // on disk would still count them and the next cleanup would purge
+            // that many slots again.
+            if matches!(retention.get(&group), Some(RetentionPolicy::DiskSize(_))) {
+                blockstore.compact_column_group(group);
+            }
+            purge_time.stop();
+            info!(
+                "Cleaned up Blockstore {group} data older than slot {cleanup_slot}. {purge_time}"
+            );
+        }
+
+        let min_cleanup_slot = cleanup_slots.values().copied().min().unwrap_or_default();
+        let previous_min_cleanup_slot = previous_cleanup_slots
+            .values()
+            .copied()
+            .min()
+            .unwrap_or_default();
+        if min_cleanup_slot > previous_min_cleanup_slot {
+            // Every group has already purged these slots; this only removes
+            // the columns outside of any group.
+            blockstore.purge_slots(0, min_cleanup_slot, PurgeType::CompactionFilter);
+            blockstore.set_max_expired_slot(min_cleanup_slot);
+        }
+    }
+
     fn report_disk_metrics(
         pre: blockstore::Result<u64>,
         post: blockstore::Result<u64>,
@@ -261,7 +549,13 @@ impl BlockstoreCleanupService {
 mod tests {
     use {
         super::*,
-        crate::{blockstore::make_many_slot_entries, get_tmp_ledger_path_auto_delete},
+        crate::{
+            blockstore::{make_many_slot_entries, BlockstoreError},
+            get_tmp_ledger_path_auto_delete,
+        },
+        assert_matches::assert_matches,
+        solana_signature::Signature,
+        solana_transaction_status::TransactionStatusMeta,
     };
 
     fn flush_blockstore_contents_to_disk(blockstore: Blockstore) -> Blockstore {
@@ -375,6 +669,142 @@ mod tests {
             .for_each(|(slot, _)| assert!(slot > 40));
     }
 
+    #[test]
+    fn test_retention_policy_from_str() {
+        assert_eq!(
+            "shreds:1000".parse::<RetentionPolicy>(),
+            Ok(RetentionPolicy::MaxShreds(1000))
+        );
+        assert_eq!(
+            "slots:432000".parse::<RetentionPolicy>(),
+            Ok(RetentionPolicy::Slots(432_000))
+        );
+        assert_eq!(
+            "age:90d".parse::<RetentionPolicy>(),
+            Ok(RetentionPolicy::Age(Duration::from_secs(90 * 24 * 60 * 60)))
+        );
+        assert_eq!(
+            "age:30".parse::<RetentionPolicy>(),
+            Ok(RetentionPolicy::Age(Duration::from_secs(30)))
+        );
+        assert_eq!(
+            "size:500G".parse::<RetentionPolicy>(),
+            Ok(RetentionPolicy::DiskSize(500 << 30))
+        );
+        assert!("slots".parse::<RetentionPolicy>().is_err());
+        assert!("slots:abc".parse::<RetentionPolicy>().is_err());
+        assert!("age:10w".parse::<RetentionPolicy>().is_err());
+        assert!("bytes:10".parse::<RetentionPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_cleanup_with_retention() {
+        solana_logger::setup();
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let (shreds, _) = make_many_slot_entries(0, 51, 5);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        for slot in 0..=50 {
+            blockstore.set_block_time(slot, slot as i64).unwrap();
+        }
+        blockstore
+            .set_roots((0..=50).collect::<Vec<_>>().iter())
+            .unwrap();
+        let blockstore = Arc::new(blockstore);
+
+        // Keep 10 slots of shreds but 40 slots of block times
+        let retention = HashMap::from([
+            (ColumnGroup::Shreds, RetentionPolicy::Slots(10)),
+            (ColumnGroup::BlockTime, RetentionPolicy::Slots(40)),
+        ]);
+        let mut last_purge_slot = 0;
+        BlockstoreCleanupService::cleanup_ledger_with_retention(
+            &blockstore,
+            &retention,
+            &mut last_purge_slot,
+            10,
+        );
+        assert_eq!(last_purge_slot, 50);
+        assert_eq!(blockstore.lowest_cleanup_slot(), 40);
+        assert_eq!(
+            blockstore.column_group_cleanup_slot(ColumnGroup::BlockTime),
+            10
+        );
+        // Rewards follow the shreds since they have no policy of their own
+        assert_eq!(
+            blockstore.column_group_cleanup_slot(ColumnGroup::Rewards),
+            40
+        );
+
+        blockstore
+            .slot_meta_iterator(0)
+            .unwrap()
+            .for_each(|(slot, _)| assert!(slot > 40));
+        assert_eq!(blockstore.get_rooted_block_time(20).unwrap(), 20);
+        assert_matches!(
+            blockstore.get_rooted_block_time(10),
+            Err(BlockstoreError::SlotCleanedUp)
+        );
+        // Roots are kept as long as any group still has data for the slot
+        assert!(blockstore.is_root(20));
+        assert!(!blockstore.is_root(10));
+    }
+
+    #[test]
+    fn test_cleanup_transaction_history_by_size() {
+        solana_logger::setup();
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let (shreds, _) = make_many_slot_entries(0, 51, 1);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        for slot in 1..=50 {
+            blockstore
+                .write_transaction_status(
+                    slot,
+                    Signature::new_unique(),
+                    std::iter::empty(),
+                    TransactionStatusMeta::default(),
+                    0,
+                )
+                .unwrap();
+        }
+        blockstore
+            .set_roots((0..=50).collect::<Vec<_>>().iter())
+            .unwrap();
+        let blockstore = Arc::new(flush_blockstore_contents_to_disk(blockstore));
+        let transaction_history_size = || {
+            let column_names =
+                blockstore.column_group_column_names(ColumnGroup::TransactionHistory);
+            blockstore
+                .live_files_metadata()
+                .unwrap()
+                .iter()
+                .filter(|live_file| column_names.contains(&live_file.column_family_name.as_str()))
+                .map(|live_file| live_file.size)
+                .sum::<usize>()
+        };
+        assert!(transaction_history_size() > 0);
+
+        let retention = HashMap::from([(
+            ColumnGroup::TransactionHistory,
+            RetentionPolicy::DiskSize(1),
+        )]);
+        let mut last_purge_slot = 0;
+        BlockstoreCleanupService::cleanup_ledger_with_retention(
+            &blockstore,
+            &retention,
+            &mut last_purge_slot,
+            10,
+        );
+        assert_eq!(
+            blockstore.column_group_cleanup_slot(ColumnGroup::TransactionHistory),
+            50
+        );
+        // The purged statuses are already gone from disk, so the next cleanup
+        // doesn't measure them again.
+        assert_eq!(transaction_history_size(), 0);
+    }
+
     #[test]
     fn test_cleanup_speed() {
         solana_logger::setup();
@@ -30,7 +30,7 @@ use {
     serde::de::DeserializeOwned,
     solana_clock::Slot,
     std::{
-        collections::HashSet,
+        collections::{HashMap, HashSet},
         ffi::{CStr, CString},
         fs,
         marker::PhantomData,
@@ -38,7 +38,7 @@ use {
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
-            Arc,
+            Arc, RwLock,
         },
     },
 };
@@ -65,6 +65,9 @@ pub enum IteratorMode<Index> {
 struct OldestSlot {
     slot: Arc<AtomicU64>,
     clean_slot_0: Arc<AtomicBool>,
+    /// Columns that are retained independently of `slot`; see
+    /// `Blockstore::set_column_group_max_expired_slot()`.
+    column_slots: Arc<RwLock<HashMap<&'static str, Slot>>>,
 }
 
 impl OldestSlot {
@@ -85,6 +88,24 @@ impl OldestSlot {
         self.slot.load(Ordering::Relaxed)
     }
 
+    pub fn set_for_column(&self, column_name: &'static str, oldest_slot: Slot) {
+        self.column_slots
+            .write()
+            .unwrap()
+            .insert(column_name, oldest_slot);
+    }
+
+    /// Returns the oldest slot to keep for `column_name`, which is the value
+    /// from `set_for_column()` if there is one and `get()` otherwise.
+    pub fn get_for_column(&self, column_name: &str) -> Slot {
+        self.column_slots
+            .read()
+            .unwrap()
+            .get(column_name)
+            .copied()
+            .unwrap_or_else(|| self.get())
+    }
+
     pub(crate) fn set_clean_slot_0(&self, clean_slot_0: bool) {
         self.clean_slot_0.store(clean_slot_0, Ordering::Relaxed);
     }
@@ -187,6 +208,7 @@ impl Rocks {
             new_cf_descriptor::<columns::TransactionStatus>(options, oldest_slot),
             new_cf_descriptor::<columns::AddressSignatures>(options, oldest_slot),
             new_cf_descriptor::<columns::TransactionMemos>(options, oldest_slot),
+            new_cf_descriptor::<columns::Transactions>(options, oldest_slot),
             new_cf_descriptor::<columns::TransactionStatusIndex>(options, oldest_slot),
             new_cf_descriptor::<columns::Rewards>(options, oldest_slot),
             new_cf_descriptor::<columns::Blocktime>(options, oldest_slot),
@@ -194,6 +216,7 @@ impl Rocks {
             new_cf_descriptor::<columns::BlockHeight>(options, oldest_slot),
             new_cf_descriptor::<columns::OptimisticSlots>(options, oldest_slot),
             new_cf_descriptor::<columns::MerkleRootMeta>(options, oldest_slot),
+            new_cf_descriptor::<columns::BlockCost>(options, oldest_slot),
         ];
 
         // If the access type is Secondary, we don't need to open all of the
@@ -242,7 +265,7 @@ impl Rocks {
         cf_descriptors
     }
 
-    const fn columns() -> [&'static str; 20] {
+    const fn columns() -> [&'static str; 22] {
         [
             columns::ErasureMeta::NAME,
             columns::DeadSlots::NAME,
@@ -257,6 +280,7 @@ impl Rocks {
             columns::TransactionStatus::NAME,
             columns::AddressSignatures::NAME,
             columns::TransactionMemos::NAME,
+            columns::Transactions::NAME,
             columns::TransactionStatusIndex::NAME,
             columns::Rewards::NAME,
             columns::Blocktime::NAME,
@@ -264,6 +288,7 @@ impl Rocks {
             columns::BlockHeight::NAME,
             columns::OptimisticSlots::NAME,
             columns::MerkleRootMeta::NAME,
+            columns::BlockCost::NAME,
         ]
     }
 
@@ -433,6 +458,14 @@ impl Rocks {
             || self.access_type == AccessType::PrimaryForMaintenance
     }
 
+    /// Replays the primary instance's logs so that a secondary instance
+    /// observes writes made since it was opened or last caught up.
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.db
+            .try_catch_up_with_primary()
+            .map_err(BlockstoreError::RocksDb)
+    }
+
     /// Retrieves the specified RocksDB integer property of the current
     /// column family.
     ///
@@ -461,6 +494,12 @@ impl Rocks {
         self.oldest_slot.set(oldest_slot);
     }
 
+    /// Sets the oldest slot to keep for `column_name`, overriding the value
+    /// from `set_oldest_slot()` for that column from now on.
+    pub(crate) fn set_oldest_slot_for_column(&self, column_name: &'static str, oldest_slot: Slot) {
+        self.oldest_slot.set_for_column(column_name, oldest_slot);
+    }
+
     pub(crate) fn set_clean_slot_0(&self, clean_slot_0: bool) {
         self.oldest_slot.set_clean_slot_0(clean_slot_0);
     }
@@ -663,9 +702,9 @@ where
         }))
     }
 
-    #[cfg(test)]
     // The validator performs compactions asynchronously, this method is
-    // provided to force a synchronous compaction to test our compaction filter
+    // provided to force a synchronous compaction, e.g. to apply our compaction
+    // filter immediately
     pub fn compact(&self) {
         // compact_range_cf() optionally takes a start and end key to limit
         // compaction. Providing values will result in a different method
@@ -1069,7 +1108,7 @@ impl<C: Column + ColumnName> CompactionFilterFactory for PurgedSlotFilterFactory
     type Filter = PurgedSlotFilter<C>;
 
     fn create(&mut self, _context: CompactionFilterContext) -> Self::Filter {
-        let copied_oldest_slot = self.oldest_slot.get();
+        let copied_oldest_slot = self.oldest_slot.get_for_column(C::NAME);
         let copied_clean_slot_0 = self.oldest_slot.get_clean_slot_0();
         PurgedSlotFilter::<C> {
             oldest_slot: copied_oldest_slot,
@@ -1234,6 +1273,7 @@ fn should_enable_cf_compaction(cf_name: &str) -> bool {
         cf_name,
         columns::TransactionStatus::NAME
             | columns::TransactionMemos::NAME
+            | columns::Transactions::NAME
             | columns::AddressSignatures::NAME
     )
 }
@@ -8,6 +8,7 @@ use {
     serde::{Deserialize, Deserializer, Serialize, Serializer},
     solana_clock::{Slot, UnixTimestamp},
     solana_hash::Hash,
+    solana_pubkey::Pubkey,
     std::{
         collections::BTreeSet,
         ops::{Range, RangeBounds},
@@ -879,6 +880,39 @@ impl OptimisticSlotMetaVersioned {
     }
 }
 
+/// Summary of a block's `CostTracker` taken once its bank was frozen.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BlockCostDetails {
+    pub block_cost: u64,
+    pub block_cost_limit: u64,
+    pub vote_cost: u64,
+    pub transaction_count: u64,
+    /// Whether this validator produced the block
+    pub is_leader_block: bool,
+    /// The most contended writable accounts and their accumulated cost,
+    /// sorted by descending cost
+    pub top_writable_accounts: Vec<(Pubkey, u64)>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub enum BlockCostDetailsVersioned {
+    V0(BlockCostDetails),
+}
+
+impl From<BlockCostDetails> for BlockCostDetailsVersioned {
+    fn from(details: BlockCostDetails) -> Self {
+        BlockCostDetailsVersioned::V0(details)
+    }
+}
+
+impl From<BlockCostDetailsVersioned> for BlockCostDetails {
+    fn from(versioned: BlockCostDetailsVersioned) -> Self {
+        match versioned {
+            BlockCostDetailsVersioned::V0(details) => details,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use {
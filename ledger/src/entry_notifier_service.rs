@@ -73,7 +73,11 @@ impl EntryNotifierService {
         self.sender.clone()
     }
 
+    /// Waits for the service to exit, which happens once `exit` is set or,
+    /// after every other sender has been dropped, once all the pending
+    /// notifications have been delivered
     pub fn join(self) -> thread::Result<()> {
+        drop(self.sender);
         self.thread_hdl.join()
     }
 }
@@ -115,6 +115,12 @@ pub(super) fn get_version(shred: &[u8]) -> Option<u16> {
     Some(u16::from_le_bytes(bytes))
 }
 
+#[inline]
+pub fn get_fec_set_index(shred: &[u8]) -> Option<u32> {
+    let bytes = <[u8; 4]>::try_from(shred.get(79..79 + 4)?).unwrap();
+    Some(u32::from_le_bytes(bytes))
+}
+
 // The caller should verify first that the shred is data and not code!
 #[inline]
 pub(super) fn get_parent_offset(shred: &[u8]) -> Option<u16> {
@@ -551,6 +557,10 @@ mod tests {
             assert_eq!(get_slot(bytes).unwrap(), shred_common_header.slot);
             assert_eq!(get_index(bytes).unwrap(), shred_common_header.index);
             assert_eq!(get_version(bytes).unwrap(), shred_common_header.version);
+            assert_eq!(
+                get_fec_set_index(bytes).unwrap(),
+                shred_common_header.fec_set_index
+            );
             assert_eq!(get_shred_id(bytes).unwrap(), {
                 let shred_type = ShredType::from(shred_common_header.shred_variant);
                 ShredId::new(
@@ -20,6 +20,7 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         pubsub_config: config.pubsub_config.clone(),
         snapshot_config: config.snapshot_config.clone(),
         max_ledger_shreds: config.max_ledger_shreds,
+        blockstore_retention: config.blockstore_retention.clone(),
         blockstore_options: config.blockstore_options.clone(),
         broadcast_stage_type: config.broadcast_stage_type.clone(),
         turbine_disabled: config.turbine_disabled.clone(),
@@ -80,6 +81,13 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         use_tpu_client_next: config.use_tpu_client_next,
         retransmit_xdp: config.retransmit_xdp.clone(),
         repair_handler_type: config.repair_handler_type.clone(),
+        program_cache_pinned_programs: config.program_cache_pinned_programs.clone(),
+        program_cache_prewarm_programs: config.program_cache_prewarm_programs.clone(),
+        shred_archives_to_import: config.shred_archives_to_import.clone(),
+        repair_peer_selection: config.repair_peer_selection.clone(),
+        shred_arrival_telemetry: config.shred_arrival_telemetry,
+        local_repair_ledger_path: config.local_repair_ledger_path.clone(),
+        vote_decision_log_path: config.vote_decision_log_path.clone(),
     }
 }
 
@@ -19,7 +19,7 @@ use {
         thread,
     },
     std::{
-        collections::{hash_map::Entry, HashMap},
+        collections::{hash_map::Entry, HashMap, HashSet},
         fmt::{Debug, Formatter},
         sync::Weak,
     },
@@ -515,6 +515,23 @@ impl ProgramCacheEntry {
     pub fn account_owner(&self) -> Pubkey {
         self.account_owner.into()
     }
+
+    /// Returns true if this entry is still hidden behind its delay visibility period at `slot`.
+    pub fn is_delay_visibility(&self, slot: Slot) -> bool {
+        matches!(self.program, ProgramCacheEntryType::DelayVisibility)
+            || self.is_implicit_delay_visibility_tombstone(slot)
+    }
+
+    /// Returns the size in bytes of the JIT compiled machine code, if the program is compiled.
+    pub fn jit_size(&self) -> Option<usize> {
+        match &self.program {
+            #[cfg(all(not(target_os = "windows"), target_arch = "x86_64"))]
+            ProgramCacheEntryType::Loaded(executable) => executable
+                .get_compiled_program()
+                .map(|compiled_program| compiled_program.mem_size()),
+            _ => None,
+        }
+    }
 }
 
 /// Globally shared RBPF config and syscall registry
@@ -645,6 +662,8 @@ pub struct ProgramCache<FG: ForkGraph> {
     pub fork_graph: Option<Weak<RwLock<FG>>>,
     /// Coordinates TX batches waiting for others to complete their task during cooperative loading
     pub loading_task_waiter: Arc<LoadingTaskWaiter>,
+    /// Programs which are never evicted, regardless of their usage statistics
+    pinned_programs: HashSet<Pubkey>,
 }
 
 impl<FG: ForkGraph> Debug for ProgramCache<FG> {
@@ -824,6 +843,7 @@ impl<FG: ForkGraph> ProgramCache<FG> {
             stats: ProgramCacheStats::default(),
             fork_graph: None,
             loading_task_waiter: Arc::new(LoadingTaskWaiter::default()),
+            pinned_programs: HashSet::new(),
         }
     }
 
@@ -1223,6 +1243,57 @@ impl<FG: ForkGraph> ProgramCache<FG> {
         }
     }
 
+    /// Returns the list of all entries in the cache, including tombstones and unloaded entries.
+    pub fn get_all_entries(&self) -> Vec<(Pubkey, Arc<ProgramCacheEntry>)> {
+        match &self.index {
+            IndexImplementation::V1 { entries, .. } => entries
+                .iter()
+                .flat_map(|(id, second_level)| {
+                    second_level.iter().map(|program| (*id, program.clone()))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns true if any version of the program is verified and compiled.
+    pub fn has_loaded_entry(&self, key: &Pubkey) -> bool {
+        match &self.index {
+            IndexImplementation::V1 { entries, .. } => {
+                entries.get(key).is_some_and(|second_level| {
+                    second_level
+                        .iter()
+                        .any(|program| matches!(program.program, ProgramCacheEntryType::Loaded(_)))
+                })
+            }
+        }
+    }
+
+    /// Excludes the program from eviction. Returns false if it was already pinned.
+    pub fn pin_program(&mut self, program_id: Pubkey) -> bool {
+        self.pinned_programs.insert(program_id)
+    }
+
+    /// Makes the program eligible for eviction again. Returns false if it was not pinned.
+    pub fn unpin_program(&mut self, program_id: &Pubkey) -> bool {
+        self.pinned_programs.remove(program_id)
+    }
+
+    pub fn is_pinned(&self, program_id: &Pubkey) -> bool {
+        self.pinned_programs.contains(program_id)
+    }
+
+    pub fn pinned_programs(&self) -> &HashSet<Pubkey> {
+        &self.pinned_programs
+    }
+
+    /// Returns the loaded entries which may be evicted, and the total number of loaded entries.
+    fn get_eviction_candidates(&self) -> (Vec<(Pubkey, Arc<ProgramCacheEntry>)>, usize) {
+        let mut candidates = self.get_flattened_entries(true, true);
+        let num_loaded = candidates.len();
+        candidates.retain(|(id, _program)| !self.pinned_programs.contains(id));
+        (candidates, num_loaded)
+    }
+
     /// Returns the list of all entries in the cache.
     pub fn get_flattened_entries_for_tests(&self) -> Vec<(Pubkey, Arc<ProgramCacheEntry>)> {
         match &self.index {
@@ -1247,25 +1318,24 @@ impl<FG: ForkGraph> ProgramCache<FG> {
 
     /// Unloads programs which were used infrequently
     pub fn sort_and_unload(&mut self, shrink_to: PercentageInteger) {
-        let mut sorted_candidates = self.get_flattened_entries(true, true);
+        let (mut sorted_candidates, num_loaded) = self.get_eviction_candidates();
         sorted_candidates
             .sort_by_cached_key(|(_id, program)| program.tx_usage_counter.load(Ordering::Relaxed));
-        let num_to_unload = sorted_candidates
-            .len()
-            .saturating_sub(shrink_to.apply_to(MAX_LOADED_ENTRY_COUNT));
+        let num_to_unload = num_loaded.saturating_sub(shrink_to.apply_to(MAX_LOADED_ENTRY_COUNT));
         self.unload_program_entries(sorted_candidates.iter().take(num_to_unload));
     }
 
     /// Evicts programs using 2's random selection, choosing the least used program out of the two entries.
     /// The eviction is performed enough number of times to reduce the cache usage to the given percentage.
     pub fn evict_using_2s_random_selection(&mut self, shrink_to: PercentageInteger, now: Slot) {
-        let mut candidates = self.get_flattened_entries(true, true);
+        let (mut candidates, num_loaded) = self.get_eviction_candidates();
         self.stats
             .water_level
-            .store(candidates.len() as u64, Ordering::Relaxed);
-        let num_to_unload = candidates
-            .len()
-            .saturating_sub(shrink_to.apply_to(MAX_LOADED_ENTRY_COUNT));
+            .store(num_loaded as u64, Ordering::Relaxed);
+        // Pinned programs still count towards the water level, but are never picked for eviction
+        let num_to_unload = num_loaded
+            .saturating_sub(shrink_to.apply_to(MAX_LOADED_ENTRY_COUNT))
+            .min(candidates.len());
         fn random_index_and_usage_counter(
             candidates: &[(Pubkey, Arc<ProgramCacheEntry>)],
             now: Slot,
@@ -1662,6 +1732,54 @@ mod tests {
         assert_eq!(num_tombstones, num_tombstones_expected);
     }
 
+    #[test]
+    fn test_eviction_skips_pinned_programs() {
+        for random_eviction in [false, true] {
+            let mut programs = vec![];
+            let mut cache = new_mock_cache::<TestForkGraph>();
+
+            let program1 = Pubkey::new_unique();
+            program_deploy_test_helper(
+                &mut cache,
+                program1,
+                vec![0, 10, 20],
+                vec![4, 5, 25],
+                &mut programs,
+            );
+            let pinned_program = Pubkey::new_unique();
+            program_deploy_test_helper(
+                &mut cache,
+                pinned_program,
+                vec![5, 11],
+                vec![0, 2],
+                &mut programs,
+            );
+            assert!(cache.pin_program(pinned_program));
+            assert!(!cache.pin_program(pinned_program));
+            assert!(cache.is_pinned(&pinned_program));
+
+            // Shrinking to zero unloads everything except for the pinned program
+            if random_eviction {
+                cache.evict_using_2s_random_selection(Percentage::from(0), 21);
+                assert_eq!(cache.stats.water_level.load(Ordering::Relaxed), 5);
+            } else {
+                cache.sort_and_unload(Percentage::from(0));
+            }
+            let loaded = cache.get_flattened_entries(true, true);
+            assert_eq!(loaded.len(), 2);
+            assert!(loaded.iter().all(|(id, _entry)| *id == pinned_program));
+            assert_eq!(cache.stats.evictions.get(&program1), Some(&3));
+            assert!(!cache.stats.evictions.contains_key(&pinned_program));
+
+            // Once unpinned, the program can be evicted again
+            assert!(cache.unpin_program(&pinned_program));
+            cache.sort_and_unload(Percentage::from(0));
+            assert!(cache.get_flattened_entries(true, true).is_empty());
+            // Tombstones and unloaded entries are still reported
+            assert_eq!(cache.get_all_entries().len(), 45);
+        }
+    }
+
     #[test]
     fn test_eviction() {
         let mut programs = vec![];
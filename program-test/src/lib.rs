@@ -12,8 +12,8 @@ use {
     solana_account::{create_account_shared_data_for_test, Account, AccountSharedData},
     solana_account_info::AccountInfo,
     solana_banks_client::start_client,
-    solana_banks_server::banks_server::start_local_server,
-    solana_clock::{Epoch, Slot},
+    solana_banks_server::banks_server::{start_local_server_with_test_controls, TestControls},
+    solana_clock::{Clock, Epoch, Slot, UnixTimestamp},
     solana_compute_budget::compute_budget::ComputeBudget,
     solana_fee_calculator::{FeeRateGovernor, DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE},
     solana_genesis_config::{ClusterType, GenesisConfig},
@@ -921,10 +921,12 @@ impl ProgramTest {
         let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
         let target_slot_duration = target_tick_duration * gci.genesis_config.ticks_per_slot as u32;
-        let transport = start_local_server(
+        let test_controls = TestControls::new(bank_forks.clone(), block_commitment_cache.clone());
+        let transport = start_local_server_with_test_controls(
             bank_forks.clone(),
             block_commitment_cache.clone(),
             target_tick_duration,
+            Some(test_controls),
         )
         .await;
         let banks_client = start_client(transport)
@@ -955,10 +957,12 @@ impl ProgramTest {
     pub async fn start_with_context(mut self) -> ProgramTestContext {
         let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
-        let transport = start_local_server(
+        let test_controls = TestControls::new(bank_forks.clone(), block_commitment_cache.clone());
+        let transport = start_local_server_with_test_controls(
             bank_forks.clone(),
             block_commitment_cache.clone(),
             target_tick_duration,
+            Some(test_controls.clone()),
         )
         .await;
         let banks_client = start_client(transport)
@@ -968,6 +972,7 @@ impl ProgramTest {
         ProgramTestContext::new(
             bank_forks,
             block_commitment_cache,
+            test_controls,
             banks_client,
             last_blockhash,
             gci,
@@ -1029,6 +1034,7 @@ pub struct ProgramTestContext {
     genesis_config: GenesisConfig,
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    test_controls: TestControls,
     _bank_task: DroppableTask<()>,
 }
 
@@ -1036,6 +1042,7 @@ impl ProgramTestContext {
     fn new(
         bank_forks: Arc<RwLock<BankForks>>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+        test_controls: TestControls,
         banks_client: BanksClient,
         last_blockhash: Hash,
         genesis_config_info: GenesisConfigInfo,
@@ -1075,6 +1082,7 @@ impl ProgramTestContext {
             genesis_config: genesis_config_info.genesis_config,
             bank_forks,
             block_commitment_cache,
+            test_controls,
             _bank_task: bank_task,
         }
     }
@@ -1170,11 +1178,9 @@ impl ProgramTestContext {
             .unwrap();
 
         // warp_bank is frozen so go forward to get unfrozen bank at warp_slot
-        bank_forks.insert(Bank::new_from_parent(
-            warp_bank,
-            &Pubkey::default(),
-            warp_slot,
-        ));
+        let warp_bank = Bank::new_from_parent(warp_bank, &Pubkey::default(), warp_slot);
+        self.test_controls.apply_clock_override(&warp_bank);
+        bank_forks.insert(warp_bank);
 
         // Update block commitment cache, otherwise banks server will poll at
         // the wrong slot
@@ -1221,6 +1227,7 @@ impl ProgramTestContext {
         let mut warp_bank = Bank::new_from_parent(bank, &Pubkey::default(), warp_slot);
 
         warp_bank.force_reward_interval_end_for_tests();
+        self.test_controls.apply_clock_override(&warp_bank);
         bank_forks.insert(warp_bank);
 
         // Update block commitment cache, otherwise banks server will poll at
@@ -1237,6 +1244,45 @@ impl ProgramTestContext {
         Ok(())
     }
 
+    /// Move `Clock::unix_timestamp` of the working bank forward by `seconds`.
+    ///
+    /// If the clock was frozen with `set_clock()`, it stays frozen at the new
+    /// value. The slot is left untouched.
+    pub fn advance_time(&mut self, seconds: u64) -> Clock {
+        self.test_controls.advance_time(seconds)
+    }
+
+    /// Set `Clock::unix_timestamp` of the working bank and freeze it there.
+    ///
+    /// The timestamp survives `step_slots()` and warps until `advance_time()`
+    /// moves it or `unfreeze_clock()` releases it, which makes time-dependent
+    /// program logic deterministic.
+    pub fn set_clock(&mut self, unix_timestamp: UnixTimestamp) -> Clock {
+        self.test_controls.set_clock(unix_timestamp)
+    }
+
+    /// Let `Clock::unix_timestamp` follow the runtime's estimate again
+    pub fn unfreeze_clock(&mut self) -> Clock {
+        self.test_controls.unfreeze_clock()
+    }
+
+    /// Advance the working bank by `slots` consecutive slots.
+    ///
+    /// Unlike `warp_to_slot()`, each slot is created from its parent and
+    /// registers one new blockhash, so `last_blockhash` and blockhashes of
+    /// in-flight transactions remain valid as long as fewer than
+    /// `MAX_PROCESSING_AGE` slots are stepped. Returns the new working slot.
+    pub fn step_slots(&mut self, slots: u64) -> Slot {
+        let slot = self.test_controls.step_slots(slots);
+        self.last_blockhash = self
+            .bank_forks
+            .read()
+            .unwrap()
+            .working_bank()
+            .last_blockhash();
+        slot
+    }
+
     /// Get a new latest blockhash, similar in spirit to RpcClient::get_latest_blockhash()
     pub async fn get_new_latest_blockhash(&mut self) -> io::Result<Hash> {
         let blockhash = self
@@ -0,0 +1,87 @@
+use {
+    solana_clock::Clock, solana_program_test::ProgramTest, solana_pubkey::Pubkey,
+    solana_signer::Signer, solana_system_interface::instruction as system_instruction,
+    solana_transaction::Transaction,
+};
+
+#[tokio::test]
+async fn advance_time_moves_unix_timestamp() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let before = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+
+    let clock = context.advance_time(3_600);
+    assert_eq!(clock.unix_timestamp, before.unix_timestamp + 3_600);
+    assert_eq!(clock.slot, before.slot);
+
+    let after = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(after, clock);
+}
+
+#[tokio::test]
+async fn set_clock_freezes_unix_timestamp_across_slots() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let frozen_timestamp = 1_700_000_000;
+
+    context.set_clock(frozen_timestamp);
+    let slot = context.step_slots(5);
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(clock.slot, slot);
+    assert_eq!(clock.unix_timestamp, frozen_timestamp);
+
+    context.warp_to_slot(slot + 100).unwrap();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(clock.unix_timestamp, frozen_timestamp);
+
+    // advancing a frozen clock keeps it frozen at the new value
+    context.advance_time(60);
+    context.step_slots(1);
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(clock.unix_timestamp, frozen_timestamp + 60);
+
+    context.unfreeze_clock();
+    context.step_slots(1);
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert!(clock.unix_timestamp >= frozen_timestamp + 60);
+}
+
+#[tokio::test]
+async fn step_slots_keeps_blockhash_valid() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let start_slot = context.banks_client.get_root_slot().await.unwrap();
+    let blockhash = context.last_blockhash;
+
+    let slot = context.step_slots(10);
+    assert_eq!(slot, start_slot + 10);
+    assert_ne!(context.last_blockhash, blockhash);
+
+    // a transaction signed before stepping still lands
+    let transfer =
+        system_instruction::transfer(&context.payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+    let transaction = Transaction::new_signed_with_payer(
+        &[transfer],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn banks_client_test_controls() {
+    let (banks_client, _payer, _blockhash) = ProgramTest::default().start().await;
+
+    let clock = banks_client.set_clock(42).await.unwrap();
+    assert_eq!(clock.unix_timestamp, 42);
+
+    let slot = banks_client.step_slots(3).await.unwrap();
+    let clock = banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(clock.slot, slot);
+    assert_eq!(clock.unix_timestamp, 42);
+
+    let clock = banks_client.advance_time(8).await.unwrap();
+    assert_eq!(clock.unix_timestamp, 50);
+}
@@ -1102,6 +1102,19 @@ impl MergeKind {
         stake_lamports: u64,
         clock: &Clock,
         stake_history: &StakeHistory,
+    ) -> Result<Self, InstructionError> {
+        Self::classify(stake_state, stake_lamports, clock, stake_history).inspect_err(|err| {
+            if *err == StakeError::MergeTransientStake.into() {
+                ic_msg!(invoke_context, "{}", StakeError::MergeTransientStake);
+            }
+        })
+    }
+
+    fn classify(
+        stake_state: &StakeStateV2,
+        stake_lamports: u64,
+        clock: &Clock,
+        stake_history: &StakeHistory,
     ) -> Result<Self, InstructionError> {
         match stake_state {
             StakeStateV2::Stake(meta, stake, stake_flags) => {
@@ -1117,11 +1130,7 @@ impl MergeKind {
                     (0, 0, 0) => Ok(Self::Inactive(*meta, stake_lamports, *stake_flags)),
                     (0, _, _) => Ok(Self::ActivationEpoch(*meta, *stake, *stake_flags)),
                     (_, 0, 0) => Ok(Self::FullyActive(*meta, *stake)),
-                    _ => {
-                        let err = StakeError::MergeTransientStake;
-                        ic_msg!(invoke_context, "{}", err);
-                        Err(err.into())
-                    }
+                    _ => Err(StakeError::MergeTransientStake.into()),
                 }
             }
             StakeStateV2::Initialized(meta) => {
@@ -1137,6 +1146,13 @@ impl MergeKind {
         source: &Meta,
         clock: &Clock,
     ) -> Result<(), InstructionError> {
+        Self::check_metas(stake, source, clock).map_err(|msg| {
+            ic_msg!(invoke_context, "{}", msg);
+            StakeError::MergeMismatch.into()
+        })
+    }
+
+    fn check_metas(stake: &Meta, source: &Meta, clock: &Clock) -> Result<(), &'static str> {
         // lockups may mismatch so long as both have expired
         let can_merge_lockups = stake.lockup == source.lockup
             || (!stake.lockup.is_in_force(clock, None) && !source.lockup.is_in_force(clock, None));
@@ -1148,8 +1164,7 @@ impl MergeKind {
         if stake.authorized == source.authorized && can_merge_lockups {
             Ok(())
         } else {
-            ic_msg!(invoke_context, "Unable to merge due to metadata mismatch");
-            Err(StakeError::MergeMismatch.into())
+            Err("Unable to merge due to metadata mismatch")
         }
     }
 
@@ -1158,15 +1173,23 @@ impl MergeKind {
         stake: &Delegation,
         source: &Delegation,
     ) -> Result<(), InstructionError> {
+        Self::check_active_delegations(stake, source).map_err(|msg| {
+            ic_msg!(invoke_context, "{}", msg);
+            StakeError::MergeMismatch.into()
+        })
+    }
+
+    fn check_active_delegations(
+        stake: &Delegation,
+        source: &Delegation,
+    ) -> Result<(), &'static str> {
         if stake.voter_pubkey != source.voter_pubkey {
-            ic_msg!(invoke_context, "Unable to merge due to voter mismatch");
-            Err(StakeError::MergeMismatch.into())
+            Err("Unable to merge due to voter mismatch")
         } else if stake.deactivation_epoch == Epoch::MAX && source.deactivation_epoch == Epoch::MAX
         {
             Ok(())
         } else {
-            ic_msg!(invoke_context, "Unable to merge due to stake deactivation");
-            Err(StakeError::MergeMismatch.into())
+            Err("Unable to merge due to stake deactivation")
         }
     }
 
@@ -1187,6 +1210,10 @@ impl MergeKind {
                 )
             })
             .unwrap_or(Ok(()))?;
+        self.merged_state(source)
+    }
+
+    fn merged_state(self, source: Self) -> Result<Option<StakeStateV2>, InstructionError> {
         let merged_state = match (self, source) {
             (Self::Inactive(_, _, _), Self::Inactive(_, _, _)) => None,
             (Self::Inactive(_, _, _), Self::ActivationEpoch(_, _, _)) => None,
@@ -1238,6 +1265,42 @@ impl MergeKind {
     }
 }
 
+/// Checks whether the stake account `source` could be merged into
+/// `destination` by the `Merge` instruction at `clock`, returning the reason
+/// the program would reject it otherwise. Lets clients plan merges without
+/// submitting transactions that are bound to fail.
+pub fn check_merge(
+    destination_state: &StakeStateV2,
+    destination_lamports: u64,
+    source_state: &StakeStateV2,
+    source_lamports: u64,
+    clock: &Clock,
+    stake_history: &StakeHistory,
+) -> Result<(), &'static str> {
+    let classify = |state: &StakeStateV2, lamports: u64| {
+        MergeKind::classify(state, lamports, clock, stake_history).map_err(|err| {
+            if err == StakeError::MergeTransientStake.into() {
+                "Unable to merge due to transient stake"
+            } else {
+                "Unable to merge an uninitialized stake account"
+            }
+        })
+    };
+    let destination = classify(destination_state, destination_lamports)?;
+    let source = classify(source_state, source_lamports)?;
+    MergeKind::check_metas(destination.meta(), source.meta(), clock)?;
+    if let Some((stake, source_stake)) = destination.active_stake().zip(source.active_stake()) {
+        MergeKind::check_active_delegations(&stake.delegation, &source_stake.delegation)?;
+    }
+    destination.merged_state(source).map(|_| ()).map_err(|err| {
+        if err == StakeError::MergeMismatch.into() {
+            "Unable to merge due to activation state mismatch"
+        } else {
+            "Unable to merge due to arithmetic overflow"
+        }
+    })
+}
+
 fn merge_delegation_stake_and_credits_observed(
     stake: &mut Stake,
     absorbed_lamports: u64,
@@ -2781,6 +2844,112 @@ mod tests {
         assert_eq!(delegation.stake, 2 * stake.delegation.stake);
     }
 
+    #[test]
+    fn test_check_merge() {
+        let stake_history = StakeHistory::default();
+        let mut clock = Clock::default();
+        let lamports = 424242;
+        let meta = Meta::auto(&Pubkey::new_unique());
+        let active_stake = |voter_pubkey| Stake {
+            delegation: Delegation {
+                voter_pubkey,
+                stake: 4242,
+                ..Delegation::default()
+            },
+            ..Stake::default()
+        };
+        let voter_pubkey = Pubkey::new_unique();
+        let initialized = StakeStateV2::Initialized(meta);
+        let delegated = StakeStateV2::Stake(meta, active_stake(voter_pubkey), StakeFlags::empty());
+        let other_voter = StakeStateV2::Stake(
+            meta,
+            active_stake(Pubkey::new_unique()),
+            StakeFlags::empty(),
+        );
+
+        assert_eq!(
+            check_merge(
+                &initialized,
+                lamports,
+                &initialized,
+                lamports,
+                &clock,
+                &stake_history
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_merge(
+                &initialized,
+                lamports,
+                &StakeStateV2::Initialized(Meta::auto(&Pubkey::new_unique())),
+                lamports,
+                &clock,
+                &stake_history
+            ),
+            Err("Unable to merge due to metadata mismatch")
+        );
+        assert_eq!(
+            check_merge(
+                &StakeStateV2::Uninitialized,
+                lamports,
+                &initialized,
+                lamports,
+                &clock,
+                &stake_history
+            ),
+            Err("Unable to merge an uninitialized stake account")
+        );
+
+        // Both delegations are in their activation epoch
+        assert_eq!(
+            check_merge(
+                &delegated,
+                lamports,
+                &delegated,
+                lamports,
+                &clock,
+                &stake_history
+            ),
+            Ok(())
+        );
+
+        clock.epoch = 10;
+        assert_eq!(
+            check_merge(
+                &delegated,
+                lamports,
+                &delegated,
+                lamports,
+                &clock,
+                &stake_history
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_merge(
+                &delegated,
+                lamports,
+                &other_voter,
+                lamports,
+                &clock,
+                &stake_history
+            ),
+            Err("Unable to merge due to voter mismatch")
+        );
+        assert_eq!(
+            check_merge(
+                &delegated,
+                lamports,
+                &initialized,
+                lamports,
+                &clock,
+                &stake_history
+            ),
+            Err("Unable to merge due to activation state mismatch")
+        );
+    }
+
     #[test]
     fn test_active_stake_merge() {
         let transaction_accounts = vec![(
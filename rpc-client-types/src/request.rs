@@ -18,6 +18,7 @@ pub enum RpcRequest {
     GetBlockProduction,
     GetBlocks,
     GetBlocksWithLimit,
+    GetBlockCostDetails,
     GetBlockTime,
     GetClusterNodes,
     GetEpochInfo,
@@ -58,8 +59,10 @@ pub enum RpcRequest {
     GetTokenLargestAccounts,
     GetTokenSupply,
     GetTransaction,
+    GetTransactionCost,
     GetTransactionCount,
     GetVersion,
+    GetVoteAccountAnalytics,
     GetVoteAccounts,
     IsBlockhashValid,
     MinimumLedgerSlot,
@@ -83,6 +86,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetBlockProduction => "getBlockProduction",
             RpcRequest::GetBlocks => "getBlocks",
             RpcRequest::GetBlocksWithLimit => "getBlocksWithLimit",
+            RpcRequest::GetBlockCostDetails => "getBlockCostDetails",
             RpcRequest::GetBlockTime => "getBlockTime",
             RpcRequest::GetClusterNodes => "getClusterNodes",
             RpcRequest::GetEpochInfo => "getEpochInfo",
@@ -123,8 +127,10 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetTokenSupply => "getTokenSupply",
             RpcRequest::GetTokenLargestAccounts => "getTokenLargestAccounts",
             RpcRequest::GetTransaction => "getTransaction",
+            RpcRequest::GetTransactionCost => "getTransactionCost",
             RpcRequest::GetTransactionCount => "getTransactionCount",
             RpcRequest::GetVersion => "getVersion",
+            RpcRequest::GetVoteAccountAnalytics => "getVoteAccountAnalytics",
             RpcRequest::GetVoteAccounts => "getVoteAccounts",
             RpcRequest::IsBlockhashValid => "isBlockhashValid",
             RpcRequest::MinimumLedgerSlot => "minimumLedgerSlot",
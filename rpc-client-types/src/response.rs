@@ -407,6 +407,85 @@ pub struct RpcSimulateTransactionResult {
     pub replacement_blockhash: Option<RpcBlockhash>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionCost {
+    /// Estimated cost of the transaction, split by component
+    pub signature_cost: u64,
+    pub write_lock_cost: u64,
+    pub data_bytes_cost: u64,
+    pub programs_execution_cost: u64,
+    pub loaded_accounts_data_size_cost: u64,
+    pub total_cost: u64,
+    pub is_simple_vote: bool,
+    /// Cost already accumulated by the block, and its limits
+    pub block_cost: u64,
+    pub block_cost_limit: u64,
+    pub vote_cost: u64,
+    pub vote_cost_limit: u64,
+    pub account_cost_limit: u64,
+    /// Cost already accumulated in the block by each write-locked account
+    pub writable_accounts: Vec<RpcWritableAccountCost>,
+    /// Set if the block's cost tracker would reject the transaction
+    pub err: Option<UiTransactionError>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcWritableAccountCost {
+    pub pubkey: String,
+    pub block_cost: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockCostDetails {
+    pub slot: Slot,
+    pub block_cost: u64,
+    pub block_cost_limit: u64,
+    pub vote_cost: u64,
+    pub transaction_count: u64,
+    pub is_leader_block: bool,
+    /// Most contended write-locked accounts in the block, by descending cost
+    pub top_writable_accounts: Vec<RpcWritableAccountCost>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVoteAccountAnalytics {
+    pub vote_pubkey: String,
+    pub node_pubkey: String,
+    pub commission: u8,
+    pub activated_stake: u64,
+    /// Credits earned in recent epochs, oldest first
+    pub epochs: Vec<RpcVoteEpochAnalytics>,
+    /// Number of votes in the tower that landed at each latency, in slots
+    pub vote_latencies: Vec<RpcVoteLatencyCount>,
+    /// Credits the votes in the tower earned below the maximum for a timely vote
+    pub late_vote_credits_missed: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVoteEpochAnalytics {
+    pub epoch: Epoch,
+    pub credits: u64,
+    /// Most credits earned in the epoch by any vote account of the cluster
+    pub cluster_max_credits: u64,
+    pub missed_credits: u64,
+    /// Credits as a percentage of `cluster_max_credits`
+    pub efficiency: f64,
+    /// Commission charged on the epoch's voting rewards, if known
+    pub commission: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVoteLatencyCount {
+    pub latency: u8,
+    pub votes: usize,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcStorageTurn {
@@ -2249,6 +2249,23 @@ impl RpcClient {
             .await
     }
 
+    /// Returns the vote credits earned by a vote account in recent epochs
+    /// compared with the best performing vote account of the cluster, and the
+    /// latency of the votes in its tower.
+    pub async fn get_vote_account_analytics(
+        &self,
+        vote_pubkey: &Pubkey,
+        config: RpcVoteAccountAnalyticsConfig,
+    ) -> ClientResult<RpcVoteAccountAnalytics> {
+        Ok(self
+            .send::<Response<RpcVoteAccountAnalytics>>(
+                RpcRequest::GetVoteAccountAnalytics,
+                json!([vote_pubkey.to_string(), config]),
+            )
+            .await?
+            .value)
+    }
+
     pub async fn wait_for_max_stake(
         &self,
         commitment: CommitmentConfig,
@@ -2944,6 +2961,16 @@ impl RpcClient {
             .map_err(|err| err.into_with_request(request))?
     }
 
+    /// Returns the cost recorded for a finalized block and its most contended
+    /// writable accounts, or `None` if the node has no record of the slot.
+    pub async fn get_block_cost_details(
+        &self,
+        slot: Slot,
+    ) -> ClientResult<Option<RpcBlockCostDetails>> {
+        self.send(RpcRequest::GetBlockCostDetails, json!([slot]))
+            .await
+    }
+
     /// Returns information about the current epoch.
     ///
     /// This method uses the configured default [commitment level][cl].
@@ -4683,6 +4710,38 @@ impl RpcClient {
             .ok_or_else(|| ClientErrorKind::Custom("Invalid blockhash".to_string()).into())
     }
 
+    /// Estimate the cost of a transaction and check it against the cost
+    /// already accumulated by the block the node is currently building.
+    ///
+    /// The transaction is not executed, and its signatures are not verified.
+    pub async fn get_transaction_cost(
+        &self,
+        transaction: &impl SerializableTransaction,
+    ) -> ClientResult<RpcTransactionCost> {
+        self.get_transaction_cost_with_commitment(transaction, CommitmentConfig::processed())
+            .await
+    }
+
+    pub async fn get_transaction_cost_with_commitment(
+        &self,
+        transaction: &impl SerializableTransaction,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<RpcTransactionCost> {
+        let serialized_encoded = serialize_and_encode(transaction, UiTransactionEncoding::Base64)?;
+        let config = RpcTransactionCostConfig {
+            commitment: Some(commitment),
+            encoding: Some(UiTransactionEncoding::Base64),
+            min_context_slot: None,
+        };
+        Ok(self
+            .send::<Response<RpcTransactionCost>>(
+                RpcRequest::GetTransactionCost,
+                json!([serialized_encoded, config]),
+            )
+            .await?
+            .value)
+    }
+
     pub async fn get_new_latest_blockhash(&self, blockhash: &Hash) -> ClientResult<Hash> {
         let mut num_retries = 0;
         let start = Instant::now();
@@ -1960,6 +1960,17 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_vote_accounts_with_config(config))
     }
 
+    /// Returns the vote credits earned by a vote account in recent epochs
+    /// compared with the best performing vote account of the cluster, and the
+    /// latency of the votes in its tower.
+    pub fn get_vote_account_analytics(
+        &self,
+        vote_pubkey: &Pubkey,
+        config: RpcVoteAccountAnalyticsConfig,
+    ) -> ClientResult<RpcVoteAccountAnalytics> {
+        self.invoke((self.rpc_client.as_ref()).get_vote_account_analytics(vote_pubkey, config))
+    }
+
     pub fn wait_for_max_stake(
         &self,
         commitment: CommitmentConfig,
@@ -2519,6 +2530,12 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_block_time(slot))
     }
 
+    /// Returns the cost recorded for a finalized block and its most contended
+    /// writable accounts, or `None` if the node has no record of the slot.
+    pub fn get_block_cost_details(&self, slot: Slot) -> ClientResult<Option<RpcBlockCostDetails>> {
+        self.invoke((self.rpc_client.as_ref()).get_block_cost_details(slot))
+    }
+
     /// Returns information about the current epoch.
     ///
     /// This method uses the configured default [commitment level][cl].
@@ -3727,6 +3744,28 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_fee_for_message(message))
     }
 
+    /// Estimate the cost of a transaction and check it against the cost
+    /// already accumulated by the block the node is currently building.
+    ///
+    /// The transaction is not executed, and its signatures are not verified.
+    pub fn get_transaction_cost(
+        &self,
+        transaction: &impl SerializableTransaction,
+    ) -> ClientResult<RpcTransactionCost> {
+        self.invoke((self.rpc_client.as_ref()).get_transaction_cost(transaction))
+    }
+
+    pub fn get_transaction_cost_with_commitment(
+        &self,
+        transaction: &impl SerializableTransaction,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<RpcTransactionCost> {
+        self.invoke(
+            (self.rpc_client.as_ref())
+                .get_transaction_cost_with_commitment(transaction, commitment),
+        )
+    }
+
     pub fn get_new_latest_blockhash(&self, blockhash: &Hash) -> ClientResult<Hash> {
         self.invoke((self.rpc_client.as_ref()).get_new_latest_blockhash(blockhash))
     }
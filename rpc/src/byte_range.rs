@@ -0,0 +1,89 @@
+//! Serving single byte ranges of files, so that downloads of snapshot and genesis archives can be
+//! resumed and split across several nodes.
+
+use {
+    jsonrpc_http_server::hyper::{header, http::response::Builder, Body, Response, StatusCode},
+    std::io::SeekFrom,
+    tokio::{fs::File, io::AsyncSeekExt},
+};
+
+/// Parses a `Range` header value requesting a single byte range, e.g. `bytes=0-1023`,
+/// `bytes=1024-` or `bytes=-1024`. Returns the inclusive start and end offsets, or None if the
+/// range is malformed or cannot be satisfied for a file of `file_length` bytes.
+pub fn parse_byte_range(range: &str, file_length: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix_length) => {
+            let suffix_length = suffix_length.parse::<u64>().ok()?;
+            (
+                file_length.saturating_sub(suffix_length),
+                file_length.checked_sub(1)?,
+            )
+        }
+        (start, "") => (start.parse().ok()?, file_length.checked_sub(1)?),
+        (start, end) => {
+            let end = end.parse::<u64>().ok()?;
+            (start.parse().ok()?, end.min(file_length.checked_sub(1)?))
+        }
+    };
+    (start <= end && start < file_length).then_some((start, end))
+}
+
+/// Prepares the response serving `file`, of `file_length` bytes, for a request with the `Range`
+/// header value `range`, if any.
+///
+/// Seeks `file` to the start of the requested range and returns the response builder with the
+/// status and headers for the range, along with the number of bytes of `file` to send. Returns
+/// the complete response instead if the range cannot be served.
+pub async fn seek_to_byte_range(
+    file: &mut File,
+    file_length: u64,
+    range: Option<&str>,
+) -> Result<(Builder, u64), Response<Body>> {
+    let builder = Response::builder().header(header::ACCEPT_RANGES, "bytes");
+    let Some(range) = range else {
+        return Ok((builder, file_length));
+    };
+    let Some((start, end)) = parse_byte_range(range, file_length) else {
+        return Err(Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{file_length}"))
+            .body(Body::empty())
+            .unwrap());
+    };
+    if file.seek(SeekFrom::Start(start)).await.is_err() {
+        return Err(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::empty())
+            .unwrap());
+    }
+    Ok((
+        builder.status(StatusCode::PARTIAL_CONTENT).header(
+            header::CONTENT_RANGE,
+            format!("bytes {start}-{end}/{file_length}"),
+        ),
+        end - start + 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_byte_range("bytes=900-", 1000), Some((900, 999)));
+        assert_eq!(parse_byte_range("bytes=900-2000", 1000), Some((900, 999)));
+        assert_eq!(parse_byte_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_byte_range("bytes=-2000", 1000), Some((0, 999)));
+
+        assert_eq!(parse_byte_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_byte_range("bytes=99-0", 1000), None);
+        assert_eq!(parse_byte_range("bytes=-0", 1000), None);
+        assert_eq!(parse_byte_range("bytes=0-", 0), None);
+        assert_eq!(parse_byte_range("bytes=0-1,5-6", 1000), None);
+        assert_eq!(parse_byte_range("items=0-99", 1000), None);
+        assert_eq!(parse_byte_range("bytes=a-b", 1000), None);
+    }
+}
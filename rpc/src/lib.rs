@@ -1,4 +1,5 @@
 #![allow(clippy::arithmetic_side_effects)]
+pub mod byte_range;
 mod cluster_tpu_info;
 pub mod filter;
 pub mod max_slots;
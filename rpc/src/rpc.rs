@@ -30,8 +30,9 @@ use {
         },
     },
     solana_client::connection_cache::Protocol,
-    solana_clock::{Slot, UnixTimestamp, MAX_PROCESSING_AGE},
+    solana_clock::{Epoch, Slot, UnixTimestamp, MAX_PROCESSING_AGE},
     solana_commitment_config::{CommitmentConfig, CommitmentLevel},
+    solana_cost_model::cost_model::CostModel,
     solana_entry::entry::Entry,
     solana_epoch_info::EpochInfo,
     solana_epoch_rewards_hasher::EpochRewardsHasher,
@@ -77,7 +78,7 @@ use {
     solana_signature::Signature,
     solana_signer::Signer,
     solana_stake_program,
-    solana_storage_bigtable::Error as StorageError,
+    solana_storage_history::{Error as StorageError, HistoryStorage, HistoryStorageConfig},
     solana_transaction::{
         sanitized::{MessageHash, SanitizedTransaction, MAX_TX_ACCOUNT_LOCKS},
         versioned::VersionedTransaction,
@@ -92,7 +93,9 @@ use {
         UiConfirmedBlock, UiTransactionEncoding,
     },
     solana_validator_exit::Exit,
-    solana_vote_program::vote_state::MAX_LOCKOUT_HISTORY,
+    solana_vote_program::vote_state::{
+        VoteState, MAX_EPOCH_CREDITS_HISTORY, MAX_LOCKOUT_HISTORY, VOTE_CREDITS_MAXIMUM_PER_SLOT,
+    },
     spl_generic_token::{
         token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
         token_2022::{self, ACCOUNTTYPE_ACCOUNT},
@@ -107,7 +110,7 @@ use {
     std::{
         any::type_name,
         cmp::{max, min, Reverse},
-        collections::{BinaryHeap, HashMap, HashSet},
+        collections::{BTreeMap, BinaryHeap, HashMap, HashSet},
         convert::TryFrom,
         net::SocketAddr,
         str::FromStr,
@@ -141,6 +144,9 @@ type RpcCustomResult<T> = std::result::Result<T, RpcCustomError>;
 
 pub const MAX_REQUEST_BODY_SIZE: usize = 50 * (1 << 10); // 50kB
 pub const PERFORMANCE_SAMPLES_LIMIT: usize = 720;
+const DEFAULT_VOTE_ANALYTICS_EPOCHS: usize = 10;
+// Each epoch of commission history costs a block lookup
+const MAX_COMMISSION_HISTORY_EPOCHS: Epoch = 5;
 
 fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
     RpcResponse {
@@ -167,6 +173,9 @@ pub struct JsonRpcConfig {
     pub health_check_slot_distance: u64,
     pub skip_preflight_health_check: bool,
     pub rpc_bigtable_config: Option<RpcBigtableConfig>,
+    /// Long-term transaction history kept outside of BigTable, ignored if
+    /// `rpc_bigtable_config` is set
+    pub rpc_history_storage_config: Option<RpcHistoryStorageConfig>,
     pub max_multiple_accounts: Option<usize>,
     pub account_indexes: AccountSecondaryIndexes,
     pub rpc_threads: usize,
@@ -188,6 +197,7 @@ impl Default for JsonRpcConfig {
             health_check_slot_distance: Default::default(),
             skip_preflight_health_check: bool::default(),
             rpc_bigtable_config: Option::default(),
+            rpc_history_storage_config: Option::default(),
             max_multiple_accounts: Option::default(),
             account_indexes: AccountSecondaryIndexes::default(),
             rpc_threads: 1,
@@ -234,6 +244,12 @@ impl Default for RpcBigtableConfig {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct RpcHistoryStorageConfig {
+    pub enable_history_storage_upload: bool,
+    pub storage: HistoryStorageConfig,
+}
+
 #[derive(Clone)]
 pub struct JsonRpcRequestProcessor {
     bank_forks: Arc<RwLock<BankForks>>,
@@ -247,7 +263,7 @@ pub struct JsonRpcRequestProcessor {
     cluster_info: Arc<ClusterInfo>,
     genesis_hash: Hash,
     transaction_sender: Sender<TransactionInfo>,
-    bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+    history_storage: Option<Arc<dyn HistoryStorage>>,
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
     max_slots: Arc<MaxSlots>,
@@ -259,9 +275,9 @@ pub struct JsonRpcRequestProcessor {
 impl Metadata for JsonRpcRequestProcessor {}
 
 impl JsonRpcRequestProcessor {
-    pub fn clone_without_bigtable(&self) -> JsonRpcRequestProcessor {
+    pub fn clone_without_history_storage(&self) -> JsonRpcRequestProcessor {
         Self {
-            bigtable_ledger_storage: None, // Disable BigTable
+            history_storage: None, // Disable long-term history storage
             ..self.clone()
         }
     }
@@ -410,7 +426,7 @@ impl JsonRpcRequestProcessor {
         health: Arc<RpcHealth>,
         cluster_info: Arc<ClusterInfo>,
         genesis_hash: Hash,
-        bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+        history_storage: Option<Arc<dyn HistoryStorage>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
         largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
         max_slots: Arc<MaxSlots>,
@@ -432,7 +448,7 @@ impl JsonRpcRequestProcessor {
                 cluster_info,
                 genesis_hash,
                 transaction_sender,
-                bigtable_ledger_storage,
+                history_storage,
                 optimistically_confirmed_bank,
                 largest_accounts_cache,
                 max_slots,
@@ -519,7 +535,7 @@ impl JsonRpcRequestProcessor {
             cluster_info,
             genesis_hash,
             transaction_sender,
-            bigtable_ledger_storage: None,
+            history_storage: None,
             optimistically_confirmed_bank,
             largest_accounts_cache: Arc::new(RwLock::new(LargestAccountsCache::new(30))),
             max_slots: Arc::new(MaxSlots::default()),
@@ -718,7 +734,7 @@ impl JsonRpcRequestProcessor {
         // Rewards for this epoch are found in the first confirmed block of the next epoch
         let first_slot_in_epoch = epoch_schedule.get_first_slot_in_epoch(epoch.saturating_add(1));
         if first_slot_in_epoch < first_available_block {
-            if self.bigtable_ledger_storage.is_some() {
+            if self.history_storage.is_some() {
                 return Err(RpcCustomError::LongTermStorageSlotSkipped {
                     slot: first_slot_in_epoch,
                 }
@@ -1218,6 +1234,145 @@ impl JsonRpcRequestProcessor {
         })
     }
 
+    pub async fn get_vote_account_analytics(
+        &self,
+        vote_pubkey: Pubkey,
+        config: Option<RpcVoteAccountAnalyticsConfig>,
+    ) -> Result<RpcResponse<RpcVoteAccountAnalytics>> {
+        let config = config.unwrap_or_default();
+        let with_commission_history = config.with_commission_history.unwrap_or_default();
+        if with_commission_history {
+            self.check_if_transaction_history_enabled()?;
+        }
+        let num_epochs = config
+            .num_epochs
+            .unwrap_or(DEFAULT_VOTE_ANALYTICS_EPOCHS)
+            .clamp(1, MAX_EPOCH_CREDITS_HISTORY);
+        let bank = self.get_bank_with_config(RpcContextConfig {
+            commitment: config.commitment,
+            min_context_slot: config.min_context_slot,
+        })?;
+
+        let account = bank.get_account(&vote_pubkey).ok_or_else(|| {
+            Error::invalid_params("Invalid param: could not find account".to_string())
+        })?;
+        if account.owner() != &solana_vote_program::id() {
+            return Err(Error::invalid_params(
+                "Invalid param: not a vote account".to_string(),
+            ));
+        }
+        let vote_state = VoteState::deserialize(account.data()).map_err(|_| {
+            Error::invalid_params(
+                "Invalid param: vote account data could not be parsed".to_string(),
+            )
+        })?;
+
+        let current_epoch = bank.epoch();
+        let first_epoch = current_epoch.saturating_sub(num_epochs as Epoch - 1);
+
+        // The best any vote account earned in each epoch is the yardstick for
+        // efficiency, since the number of credits available depends on how many
+        // slots the cluster confirmed.
+        let vote_accounts = bank.vote_accounts();
+        let mut cluster_max_credits: HashMap<Epoch, u64> = HashMap::new();
+        for (_, (_, vote_account)) in vote_accounts.iter() {
+            for epoch_credits in vote_account.vote_state_view().epoch_credits_iter() {
+                if epoch_credits.epoch() < first_epoch {
+                    continue;
+                }
+                let earned = epoch_credits
+                    .credits()
+                    .saturating_sub(epoch_credits.prev_credits());
+                let epoch_max = cluster_max_credits
+                    .entry(epoch_credits.epoch())
+                    .or_default();
+                *epoch_max = max(*epoch_max, earned);
+            }
+        }
+        let activated_stake = vote_accounts
+            .get(&vote_pubkey)
+            .map(|(stake, _)| *stake)
+            .unwrap_or_default();
+
+        let mut epochs = Vec::with_capacity(num_epochs);
+        for epoch in first_epoch..=current_epoch {
+            let credits = vote_state
+                .epoch_credits
+                .iter()
+                .find(|(credits_epoch, _, _)| *credits_epoch == epoch)
+                .map(|(_, credits, prev_credits)| credits.saturating_sub(*prev_credits))
+                .unwrap_or_default();
+            let cluster_max_credits = cluster_max_credits
+                .get(&epoch)
+                .copied()
+                .unwrap_or_default()
+                .max(credits);
+            let efficiency = if cluster_max_credits == 0 {
+                0.0
+            } else {
+                credits as f64 * 100.0 / cluster_max_credits as f64
+            };
+            // Commission is only recorded in the rewards of completed epochs;
+            // the current epoch will be paid out at the current commission
+            let commission = if epoch == current_epoch {
+                Some(vote_state.commission)
+            } else if with_commission_history
+                && current_epoch - epoch <= MAX_COMMISSION_HISTORY_EPOCHS
+            {
+                self.get_inflation_reward(
+                    vec![vote_pubkey],
+                    Some(RpcEpochConfig {
+                        epoch: Some(epoch),
+                        commitment: config.commitment,
+                        min_context_slot: config.min_context_slot,
+                    }),
+                )
+                .await
+                .ok()
+                .and_then(|mut rewards| rewards.pop().flatten())
+                .and_then(|reward| reward.commission)
+            } else {
+                None
+            };
+            epochs.push(RpcVoteEpochAnalytics {
+                epoch,
+                credits,
+                cluster_max_credits,
+                missed_credits: cluster_max_credits.saturating_sub(credits),
+                efficiency,
+                commission,
+            });
+        }
+
+        // Votes that landed before vote latency was tracked have a latency of
+        // zero and are not counted against the account
+        let mut latency_counts: BTreeMap<u8, usize> = BTreeMap::new();
+        let mut late_vote_credits_missed = 0u64;
+        for (index, landed_vote) in vote_state.votes.iter().enumerate() {
+            *latency_counts.entry(landed_vote.latency).or_default() += 1;
+            if landed_vote.latency > 0 {
+                late_vote_credits_missed += u64::from(VOTE_CREDITS_MAXIMUM_PER_SLOT)
+                    .saturating_sub(vote_state.credits_for_vote_at_index(index));
+            }
+        }
+
+        Ok(new_response(
+            &bank,
+            RpcVoteAccountAnalytics {
+                vote_pubkey: vote_pubkey.to_string(),
+                node_pubkey: vote_state.node_pubkey.to_string(),
+                commission: vote_state.commission,
+                activated_stake,
+                epochs,
+                vote_latencies: latency_counts
+                    .into_iter()
+                    .map(|(latency, votes)| RpcVoteLatencyCount { latency, votes })
+                    .collect(),
+                late_vote_credits_missed,
+            },
+        ))
+    }
+
     fn check_blockstore_root<T>(
         &self,
         result: &std::result::Result<T, BlockstoreError>,
@@ -1263,11 +1418,11 @@ impl JsonRpcRequestProcessor {
         Ok(())
     }
 
-    fn check_bigtable_result<T>(
+    fn check_history_storage_result<T>(
         &self,
-        result: &std::result::Result<T, solana_storage_bigtable::Error>,
+        result: &std::result::Result<T, StorageError>,
     ) -> Result<()> {
-        if let Err(solana_storage_bigtable::Error::BlockNotFound(slot)) = result {
+        if let Err(StorageError::BlockNotFound(slot)) = result {
             return Err(RpcCustomError::LongTermStorageSlotSkipped { slot: *slot }.into());
         }
         Ok(())
@@ -1339,11 +1494,11 @@ impl JsonRpcRequestProcessor {
                 Ok::<UiConfirmedBlock, Error>(encoded_block)
             };
             if result.is_err() {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    let bigtable_result = bigtable_ledger_storage.get_confirmed_block(slot).await;
-                    self.check_bigtable_result(&bigtable_result)?;
+                if let Some(history_storage) = &self.history_storage {
+                    let history_result = history_storage.get_confirmed_block(slot).await;
+                    self.check_history_storage_result(&history_result)?;
                     let encoded_block_future: OptionFuture<_> =
-                        bigtable_result.ok().map(encode_block).into();
+                        history_result.ok().map(encode_block).into();
                     return encoded_block_future.await.transpose();
                 }
             }
@@ -1451,15 +1606,16 @@ impl JsonRpcRequestProcessor {
             .unwrap_or_default();
         if start_slot < lowest_blockstore_slot {
             // If the starting slot is lower than what's available in blockstore assume the entire
-            // [start_slot..end_slot] can be fetched from BigTable. This range should not ever run
-            // into unfinalized confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return bigtable_ledger_storage
+            // [start_slot..end_slot] can be fetched from long-term history storage. This range
+            // should not ever run into unfinalized confirmed blocks due to
+            // MAX_GET_CONFIRMED_BLOCKS_RANGE
+            if let Some(history_storage) = &self.history_storage {
+                return history_storage
                     .get_confirmed_blocks(start_slot, (end_slot - start_slot) as usize + 1) // increment limit by 1 to ensure returned range is inclusive of both start_slot and end_slot
                     .await
-                    .map(|mut bigtable_blocks| {
-                        bigtable_blocks.retain(|&slot| slot <= end_slot);
-                        bigtable_blocks
+                    .map(|mut history_blocks| {
+                        history_blocks.retain(|&slot| slot <= end_slot);
+                        history_blocks
                     })
                     .map_err(|_| {
                         Error::invalid_params(
@@ -1521,10 +1677,10 @@ impl JsonRpcRequestProcessor {
 
         if start_slot < lowest_blockstore_slot {
             // If the starting slot is lower than what's available in blockstore assume the entire
-            // range can be fetched from BigTable. This range should not ever run into unfinalized
-            // confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return Ok(bigtable_ledger_storage
+            // range can be fetched from long-term history storage. This range should not ever run
+            // into unfinalized confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
+            if let Some(history_storage) = &self.history_storage {
+                return Ok(history_storage
                     .get_confirmed_blocks(start_slot, limit)
                     .await
                     .unwrap_or_default());
@@ -1591,10 +1747,10 @@ impl JsonRpcRequestProcessor {
             let result = self.blockstore.get_rooted_block_time(slot);
             self.check_blockstore_root(&result, slot)?;
             if result.is_err() {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    let bigtable_result = bigtable_ledger_storage.get_confirmed_block(slot).await;
-                    self.check_bigtable_result(&bigtable_result)?;
-                    return Ok(bigtable_result
+                if let Some(history_storage) = &self.history_storage {
+                    let history_result = history_storage.get_confirmed_block(slot).await;
+                    self.check_history_storage_result(&history_result)?;
+                    return Ok(history_result
                         .ok()
                         .and_then(|confirmed_block| confirmed_block.block_time));
                 }
@@ -1611,6 +1767,64 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    pub fn get_block_cost_details(
+        &self,
+        slot: Slot,
+        config: Option<RpcContextConfig>,
+    ) -> Result<Option<RpcBlockCostDetails>> {
+        let commitment = config
+            .and_then(|config| config.commitment)
+            .unwrap_or_default();
+        check_is_at_least_confirmed(commitment)?;
+
+        // Costs are recorded for every replayed bank, so only serve them for blocks on the
+        // rooted or confirmed fork, as `getBlock` does
+        if slot
+            <= self
+                .block_commitment_cache
+                .read()
+                .unwrap()
+                .highest_super_majority_root()
+        {
+            if !self.blockstore.is_root(slot) {
+                if self.blockstore.is_skipped(slot) {
+                    return Err(RpcCustomError::SlotSkipped { slot }.into());
+                }
+                return Err(RpcCustomError::BlockNotAvailable { slot }.into());
+            }
+        } else if !(commitment.is_confirmed()
+            && self
+                .bank(Some(CommitmentConfig::confirmed()))
+                .status_cache_ancestors()
+                .contains(&slot))
+        {
+            return Err(RpcCustomError::BlockNotAvailable { slot }.into());
+        }
+
+        let result = self.blockstore.get_block_cost_details(slot);
+        self.check_slot_cleaned_up(&result, slot)?;
+        let block_cost_details = result.map_err(|err| {
+            warn!("get_block_cost_details failed for slot {slot}: {err:?}");
+            Error::internal_error()
+        })?;
+        Ok(block_cost_details.map(|details| RpcBlockCostDetails {
+            slot,
+            block_cost: details.block_cost,
+            block_cost_limit: details.block_cost_limit,
+            vote_cost: details.vote_cost,
+            transaction_count: details.transaction_count,
+            is_leader_block: details.is_leader_block,
+            top_writable_accounts: details
+                .top_writable_accounts
+                .into_iter()
+                .map(|(pubkey, block_cost)| RpcWritableAccountCost {
+                    pubkey: pubkey.to_string(),
+                    block_cost,
+                })
+                .collect(),
+        }))
+    }
+
     pub fn get_signature_confirmation_status(
         &self,
         signature: Signature,
@@ -1683,8 +1897,8 @@ impl JsonRpcRequestProcessor {
                     })
                 {
                     Some(status)
-                } else if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    bigtable_ledger_storage
+                } else if let Some(history_storage) = &self.history_storage {
+                    history_storage
                         .get_signature_status(&signature)
                         .await
                         .map(Some)
@@ -1802,8 +2016,8 @@ impl JsonRpcRequestProcessor {
                 }
             }
             None => {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    return bigtable_ledger_storage
+                if let Some(history_storage) = &self.history_storage {
+                    return history_storage
                         .get_confirmed_transaction(&signature)
                         .await
                         .unwrap_or(None)
@@ -1878,23 +2092,23 @@ impl JsonRpcRequestProcessor {
         };
 
         if results.len() < limit {
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                let mut bigtable_before = before;
+            if let Some(history_storage) = &self.history_storage {
+                let mut history_before = before;
                 if !results.is_empty() {
                     limit -= results.len();
-                    bigtable_before = results.last().map(|x| x.signature);
+                    history_before = results.last().map(|x| x.signature);
                 }
 
                 // If the oldest address-signature found in Blockstore has not yet been
                 // uploaded to long-term storage, modify the storage query to return all latest
                 // signatures to prevent erroring on RowNotFound. This can race with upload.
-                if found_before && bigtable_before.is_some() {
-                    match bigtable_ledger_storage
-                        .get_signature_status(&bigtable_before.unwrap())
+                if found_before && history_before.is_some() {
+                    match history_storage
+                        .get_signature_status(&history_before.unwrap())
                         .await
                     {
                         Err(StorageError::SignatureNotFound) => {
-                            bigtable_before = None;
+                            history_before = None;
                         }
                         Err(err) => {
                             warn!("Failed to query Bigtable: {err:?}");
@@ -1904,26 +2118,26 @@ impl JsonRpcRequestProcessor {
                     }
                 }
 
-                let bigtable_results = bigtable_ledger_storage
+                let history_results = history_storage
                     .get_confirmed_signatures_for_address(
                         &address,
-                        bigtable_before.as_ref(),
+                        history_before.as_ref(),
                         until.as_ref(),
                         limit,
                     )
                     .await;
-                match bigtable_results {
-                    Ok(bigtable_results) => {
+                match history_results {
+                    Ok(history_results) => {
                         let results_set: HashSet<_> =
                             results.iter().map(|result| result.signature).collect();
-                        for (bigtable_result, _) in bigtable_results {
+                        for (history_result, _) in history_results {
                             // In the upload race condition, latest address-signatures in
                             // long-term storage may include original `before` signature...
-                            if before != Some(bigtable_result.signature)
+                            if before != Some(history_result.signature)
                                     // ...or earlier Blockstore signatures
-                                    && !results_set.contains(&bigtable_result.signature)
+                                    && !results_set.contains(&history_result.signature)
                             {
-                                results.push(bigtable_result);
+                                results.push(history_result);
                             }
                         }
                     }
@@ -1945,15 +2159,15 @@ impl JsonRpcRequestProcessor {
             .get_first_available_block()
             .unwrap_or_default();
 
-        if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-            let bigtable_slot = bigtable_ledger_storage
+        if let Some(history_storage) = &self.history_storage {
+            let history_slot = history_storage
                 .get_first_available_block()
                 .await
                 .unwrap_or(None)
                 .unwrap_or(slot);
 
-            if bigtable_slot < slot {
-                return bigtable_slot;
+            if history_slot < slot {
+                return history_slot;
             }
         }
         slot
@@ -3468,6 +3682,14 @@ pub mod rpc_full {
             config: Option<RpcEpochConfig>,
         ) -> BoxFuture<Result<Vec<Option<RpcInflationReward>>>>;
 
+        #[rpc(meta, name = "getVoteAccountAnalytics")]
+        fn get_vote_account_analytics(
+            &self,
+            meta: Self::Metadata,
+            vote_pubkey_str: String,
+            config: Option<RpcVoteAccountAnalyticsConfig>,
+        ) -> BoxFuture<Result<RpcResponse<RpcVoteAccountAnalytics>>>;
+
         #[rpc(meta, name = "getClusterNodes")]
         fn get_cluster_nodes(&self, meta: Self::Metadata) -> Result<Vec<RpcContactInfo>>;
 
@@ -3535,6 +3757,14 @@ pub mod rpc_full {
             slot: Slot,
         ) -> BoxFuture<Result<Option<UnixTimestamp>>>;
 
+        #[rpc(meta, name = "getBlockCostDetails")]
+        fn get_block_cost_details(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+            config: Option<RpcContextConfig>,
+        ) -> Result<Option<RpcBlockCostDetails>>;
+
         #[rpc(meta, name = "getBlocks")]
         fn get_blocks(
             &self,
@@ -3595,6 +3825,14 @@ pub mod rpc_full {
             config: Option<RpcContextConfig>,
         ) -> Result<RpcResponse<Option<u64>>>;
 
+        #[rpc(meta, name = "getTransactionCost")]
+        fn get_transaction_cost(
+            &self,
+            meta: Self::Metadata,
+            data: String,
+            config: Option<RpcTransactionCostConfig>,
+        ) -> Result<RpcResponse<RpcTransactionCost>>;
+
         #[rpc(meta, name = "getStakeMinimumDelegation")]
         fn get_stake_minimum_delegation(
             &self,
@@ -4115,6 +4353,16 @@ pub mod rpc_full {
             Box::pin(async move { meta.get_block_time(slot).await })
         }
 
+        fn get_block_cost_details(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+            config: Option<RpcContextConfig>,
+        ) -> Result<Option<RpcBlockCostDetails>> {
+            debug!("get_block_cost_details rpc request received: {slot}");
+            meta.get_block_cost_details(slot, config)
+        }
+
         fn get_transaction(
             &self,
             meta: Self::Metadata,
@@ -4192,6 +4440,20 @@ pub mod rpc_full {
             Box::pin(async move { meta.get_inflation_reward(addresses, config).await })
         }
 
+        fn get_vote_account_analytics(
+            &self,
+            meta: Self::Metadata,
+            vote_pubkey_str: String,
+            config: Option<RpcVoteAccountAnalyticsConfig>,
+        ) -> BoxFuture<Result<RpcResponse<RpcVoteAccountAnalytics>>> {
+            debug!("get_vote_account_analytics rpc request received: {vote_pubkey_str:?}");
+            let vote_pubkey = match verify_pubkey(&vote_pubkey_str) {
+                Ok(pubkey) => pubkey,
+                Err(err) => return Box::pin(future::err(err)),
+            };
+            Box::pin(async move { meta.get_vote_account_analytics(vote_pubkey, config).await })
+        }
+
         fn get_latest_blockhash(
             &self,
             meta: Self::Metadata,
@@ -4238,6 +4500,68 @@ pub mod rpc_full {
             Ok(new_response(bank, fee))
         }
 
+        fn get_transaction_cost(
+            &self,
+            meta: Self::Metadata,
+            data: String,
+            config: Option<RpcTransactionCostConfig>,
+        ) -> Result<RpcResponse<RpcTransactionCost>> {
+            debug!("get_transaction_cost rpc request received");
+            let RpcTransactionCostConfig {
+                commitment,
+                encoding,
+                min_context_slot,
+            } = config.unwrap_or_default();
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+            let (_, unsanitized_tx) =
+                decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+            // The cost is checked against the block being built unless asked otherwise
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment: Some(commitment.unwrap_or_else(CommitmentConfig::processed)),
+                min_context_slot,
+            })?;
+            let transaction =
+                sanitize_transaction(unsanitized_tx, bank, bank.get_reserved_account_keys())?;
+            let tx_cost = CostModel::calculate_cost(&transaction, &bank.feature_set);
+            let cost_tracker = bank.read_cost_tracker().unwrap();
+            let estimate = cost_tracker.estimate(&tx_cost);
+
+            Ok(new_response(
+                bank,
+                RpcTransactionCost {
+                    signature_cost: tx_cost.signature_cost(),
+                    write_lock_cost: tx_cost.write_lock_cost(),
+                    data_bytes_cost: u64::from(tx_cost.data_bytes_cost()),
+                    programs_execution_cost: tx_cost.programs_execution_cost(),
+                    loaded_accounts_data_size_cost: tx_cost.loaded_accounts_data_size_cost(),
+                    total_cost: tx_cost.sum(),
+                    is_simple_vote: tx_cost.is_simple_vote(),
+                    block_cost: estimate.block_cost,
+                    block_cost_limit: cost_tracker.get_block_limit(),
+                    vote_cost: estimate.vote_cost,
+                    vote_cost_limit: cost_tracker.get_vote_limit(),
+                    account_cost_limit: cost_tracker.get_account_limit(),
+                    writable_accounts: estimate
+                        .writable_account_costs
+                        .into_iter()
+                        .map(|(pubkey, block_cost)| RpcWritableAccountCost {
+                            pubkey: pubkey.to_string(),
+                            block_cost,
+                        })
+                        .collect(),
+                    err: estimate
+                        .result
+                        .err()
+                        .map(|err| TransactionError::from(err).into()),
+                },
+            ))
+        }
+
         fn get_stake_minimum_delegation(
             &self,
             meta: Self::Metadata,
@@ -4496,7 +4820,7 @@ pub mod tests {
         solana_instruction::{error::InstructionError, AccountMeta, Instruction},
         solana_keypair::Keypair,
         solana_ledger::{
-            blockstore_meta::PerfSampleV2,
+            blockstore_meta::{BlockCostDetails, PerfSampleV2},
             blockstore_processor::fill_blockstore_slot_with_ticks,
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
             get_tmp_ledger_path,
@@ -7553,6 +7877,191 @@ pub mod tests {
         assert_eq!(response, expected);
     }
 
+    #[test]
+    fn test_get_block_cost_details() {
+        let rpc = RpcHandler::start();
+        rpc.add_roots_to_blockstore(vec![1, 2, 3]);
+        rpc.block_commitment_cache
+            .write()
+            .unwrap()
+            .set_highest_super_majority_root(3);
+        let contended_account = Pubkey::new_unique();
+        let block_cost_details = BlockCostDetails {
+            block_cost: 1_000,
+            block_cost_limit: 48_000_000,
+            vote_cost: 200,
+            transaction_count: 4,
+            is_leader_block: true,
+            top_writable_accounts: vec![(contended_account, 600)],
+        };
+        // Slot 4 is a child of the root that is neither rooted nor confirmed
+        for slot in [3, 4] {
+            rpc.blockstore
+                .write_block_cost_details(slot, block_cost_details.clone())
+                .unwrap();
+        }
+
+        let request = create_test_request("getBlockCostDetails", Some(json!([3u64])));
+        let result: Option<RpcBlockCostDetails> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result,
+            Some(RpcBlockCostDetails {
+                slot: 3,
+                block_cost: 1_000,
+                block_cost_limit: 48_000_000,
+                vote_cost: 200,
+                transaction_count: 4,
+                is_leader_block: true,
+                top_writable_accounts: vec![RpcWritableAccountCost {
+                    pubkey: contended_account.to_string(),
+                    block_cost: 600,
+                }],
+            })
+        );
+
+        let request = create_test_request("getBlockCostDetails", Some(json!([2u64])));
+        let result: Option<RpcBlockCostDetails> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result, None);
+
+        for commitment in ["finalized", "confirmed"] {
+            let request = create_test_request(
+                "getBlockCostDetails",
+                Some(json!([4u64, { "commitment": commitment }])),
+            );
+            let response = parse_failure_response(rpc.handle_request_sync(request));
+            let expected = (
+                JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                String::from("Block not available for slot 4"),
+            );
+            assert_eq!(response, expected);
+        }
+
+        let request = create_test_request(
+            "getBlockCostDetails",
+            Some(json!([3u64, { "commitment": "processed" }])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(response.0, ErrorCode::InvalidParams.code());
+    }
+
+    #[test]
+    fn test_get_vote_account_analytics() {
+        let mut rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let clock = bank.get_sysvar_cache_for_tests().get_clock().unwrap();
+        let epoch = bank.epoch();
+
+        let new_vote_state = |commission, credits, latencies: &[u8]| {
+            let mut vote_state = VoteState::new(
+                &VoteInit {
+                    node_pubkey: solana_pubkey::new_rand(),
+                    authorized_voter: solana_pubkey::new_rand(),
+                    authorized_withdrawer: solana_pubkey::new_rand(),
+                    commission,
+                },
+                &clock,
+            );
+            vote_state.epoch_credits = vec![(epoch, credits, 0)];
+            vote_state.votes = latencies
+                .iter()
+                .enumerate()
+                .map(|(slot, latency)| vote_state::LandedVote {
+                    latency: *latency,
+                    lockout: vote_state::Lockout::new(slot as Slot),
+                })
+                .collect();
+            vote_state
+        };
+        let alice_vote_pubkey = solana_pubkey::new_rand();
+        rpc.store_vote_account(&alice_vote_pubkey, new_vote_state(5, 100, &[1, 1]));
+        let bob_vote_pubkey = solana_pubkey::new_rand();
+        let bob_vote_state = new_vote_state(10, 80, &[1, 2, 5, 5, 0]);
+        let bob_node_pubkey = bob_vote_state.node_pubkey;
+        rpc.store_vote_account(&bob_vote_pubkey, bob_vote_state);
+
+        let request = create_test_request(
+            "getVoteAccountAnalytics",
+            Some(json!([bob_vote_pubkey.to_string()])),
+        );
+        let response: RpcResponse<RpcVoteAccountAnalytics> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let analytics = response.value;
+        assert_eq!(analytics.node_pubkey, bob_node_pubkey.to_string());
+        assert_eq!(analytics.commission, 10);
+        assert_eq!(analytics.activated_stake, 0);
+        assert_eq!(
+            analytics.epochs.last().unwrap(),
+            &RpcVoteEpochAnalytics {
+                epoch,
+                credits: 80,
+                cluster_max_credits: 100,
+                missed_credits: 20,
+                efficiency: 80.0,
+                commission: Some(10),
+            }
+        );
+        assert_eq!(
+            analytics.vote_latencies,
+            vec![
+                RpcVoteLatencyCount {
+                    latency: 0,
+                    votes: 1
+                },
+                RpcVoteLatencyCount {
+                    latency: 1,
+                    votes: 1
+                },
+                RpcVoteLatencyCount {
+                    latency: 2,
+                    votes: 1
+                },
+                RpcVoteLatencyCount {
+                    latency: 5,
+                    votes: 2
+                },
+            ]
+        );
+        // Votes landing 5 slots late are 3 slots past the grace period
+        assert_eq!(analytics.late_vote_credits_missed, 6);
+
+        // Accounts not owned by the vote program are rejected
+        let request = create_test_request(
+            "getVoteAccountAnalytics",
+            Some(json!([rpc.mint_keypair.pubkey().to_string()])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(
+            response,
+            (
+                ErrorCode::InvalidParams.code(),
+                "Invalid param: not a vote account".to_string()
+            )
+        );
+
+        // Commission history needs the blocks holding the epoch rewards
+        rpc.meta.config.enable_rpc_transaction_history = false;
+        let request = create_test_request(
+            "getVoteAccountAnalytics",
+            Some(json!([
+                bob_vote_pubkey.to_string(),
+                RpcVoteAccountAnalyticsConfig {
+                    with_commission_history: Some(true),
+                    ..RpcVoteAccountAnalyticsConfig::default()
+                }
+            ])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(
+            response,
+            (
+                JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+                String::from("Transaction history is not available from this node"),
+            )
+        );
+    }
+
     #[test]
     fn test_get_vote_accounts() {
         let rpc = RpcHandler::start();
@@ -9110,6 +9619,64 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_get_transaction_cost() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let bob_pubkey = solana_pubkey::new_rand();
+        let tx =
+            system_transaction::transfer(&rpc.mint_keypair, &bob_pubkey, 1, bank.last_blockhash());
+        let tx_serialized_encoded = BASE64_STANDARD.encode(serialize(&tx).unwrap());
+        let base64_config = json!({"encoding": "base64"});
+
+        let request = create_test_request(
+            "getTransactionCost",
+            Some(json!([tx_serialized_encoded, base64_config])),
+        );
+        let response: RpcResponse<RpcTransactionCost> =
+            parse_success_result(rpc.handle_request_sync(request));
+        // defaults to the working bank
+        assert_eq!(response.context.slot, bank.slot());
+        let cost = response.value;
+        assert!(!cost.is_simple_vote);
+        assert_eq!(
+            cost.total_cost,
+            cost.signature_cost
+                + cost.write_lock_cost
+                + cost.data_bytes_cost
+                + cost.programs_execution_cost
+                + cost.loaded_accounts_data_size_cost
+        );
+        assert_eq!(
+            cost.writable_accounts
+                .iter()
+                .map(|account| account.pubkey.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                rpc.mint_keypair.pubkey().to_string(),
+                bob_pubkey.to_string()
+            ]
+        );
+        assert_eq!(cost.err, None);
+
+        // leave no room in the block
+        let block_cost = bank.read_cost_tracker().unwrap().block_cost();
+        bank.write_cost_tracker()
+            .unwrap()
+            .set_limits(u64::MAX, block_cost, block_cost);
+        // base58 is the default encoding
+        let tx_serialized_encoded = bs58::encode(serialize(&tx).unwrap()).into_string();
+        let request =
+            create_test_request("getTransactionCost", Some(json!([tx_serialized_encoded])));
+        let response: RpcResponse<RpcTransactionCost> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(response.value.block_cost_limit, block_cost);
+        assert_eq!(
+            response.value.err,
+            Some(TransactionError::WouldExceedMaxBlockCostLimit.into())
+        );
+    }
+
     #[test]
     fn test_rpc_get_recent_prioritization_fees() {
         fn wait_for_cache_blocks(cache: &PrioritizationFeeCache, num_blocks: usize) {
@@ -3,7 +3,7 @@ use {
     solana_clock::Slot,
     solana_ledger::blockstore::Blockstore,
     std::sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc, RwLock,
     },
 };
@@ -18,7 +18,10 @@ pub enum RpcHealthStatus {
 pub struct RpcHealth {
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     blockstore: Arc<Blockstore>,
-    health_check_slot_distance: u64,
+    /// Distance the node was started with, `health_check_slot_distance` can
+    /// be changed at runtime
+    startup_health_check_slot_distance: u64,
+    health_check_slot_distance: AtomicU64,
     override_health_check: Arc<AtomicBool>,
     startup_verification_complete: Arc<AtomicBool>,
     #[cfg(test)]
@@ -36,7 +39,8 @@ impl RpcHealth {
         Self {
             optimistically_confirmed_bank,
             blockstore,
-            health_check_slot_distance,
+            startup_health_check_slot_distance: health_check_slot_distance,
+            health_check_slot_distance: AtomicU64::new(health_check_slot_distance),
             override_health_check,
             startup_verification_complete,
             #[cfg(test)]
@@ -44,6 +48,21 @@ impl RpcHealth {
         }
     }
 
+    pub fn startup_health_check_slot_distance(&self) -> u64 {
+        self.startup_health_check_slot_distance
+    }
+
+    pub fn health_check_slot_distance(&self) -> u64 {
+        self.health_check_slot_distance.load(Ordering::Relaxed)
+    }
+
+    /// Changes how far the node may fall behind the cluster before it is
+    /// reported as unhealthy
+    pub fn set_health_check_slot_distance(&self, health_check_slot_distance: u64) {
+        self.health_check_slot_distance
+            .store(health_check_slot_distance, Ordering::Relaxed);
+    }
+
     pub fn check(&self) -> RpcHealthStatus {
         #[cfg(test)]
         {
@@ -97,7 +116,7 @@ impl RpcHealth {
 
         if my_latest_optimistically_confirmed_slot
             >= cluster_latest_optimistically_confirmed_slot
-                .saturating_sub(self.health_check_slot_distance)
+                .saturating_sub(self.health_check_slot_distance())
         {
             RpcHealthStatus::Ok
         } else {
@@ -201,6 +220,16 @@ pub mod tests {
         optimistically_confirmed_bank.write().unwrap().bank = bank5.clone();
         assert_eq!(health.check(), RpcHealthStatus::Ok);
 
+        // Tightening the distance at runtime makes the node behind again
+        health.set_health_check_slot_distance(5);
+        assert_eq!(health.check(), RpcHealthStatus::Behind { num_slots: 10 });
+        assert_eq!(
+            health.startup_health_check_slot_distance(),
+            health_check_slot_distance
+        );
+        health.set_health_check_slot_distance(health_check_slot_distance);
+        assert_eq!(health.check(), RpcHealthStatus::Ok);
+
         // Node now up with tip of cluster
         let bank15 = Arc::new(Bank::new_from_parent(bank5, &Pubkey::default(), 15));
         optimistically_confirmed_bank.write().unwrap().bank = bank15.clone();
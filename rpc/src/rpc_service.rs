@@ -2,6 +2,7 @@
 
 use {
     crate::{
+        byte_range::seek_to_byte_range,
         cluster_tpu_info::ClusterTpuInfo,
         max_slots::MaxSlots,
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
@@ -46,6 +47,7 @@ use {
         transaction_client::{ConnectionCacheClient, TpuClientNextClient, TransactionClient},
     },
     solana_storage_bigtable::CredentialType,
+    solana_storage_history::HistoryStorage,
     solana_validator_exit::Exit,
     std::{
         net::{SocketAddr, UdpSocket},
@@ -59,7 +61,10 @@ use {
         thread::{self, Builder, JoinHandle},
         time::{Duration, Instant},
     },
-    tokio::runtime::{Builder as TokioBuilder, Handle as RuntimeHandle, Runtime as TokioRuntime},
+    tokio::{
+        io::AsyncReadExt,
+        runtime::{Builder as TokioBuilder, Handle as RuntimeHandle, Runtime as TokioRuntime},
+    },
     tokio_util::{
         bytes::Bytes,
         codec::{BytesCodec, FramedRead},
@@ -122,6 +127,10 @@ pub struct JsonRpcService {
     close_handle: Option<CloseHandle>,
 
     client_updater: Arc<dyn NotifyKeyUpdate + Send + Sync>,
+
+    request_count: Arc<AtomicU64>,
+
+    health: Arc<RpcHealth>,
 }
 
 struct RpcRequestMiddleware {
@@ -253,7 +262,7 @@ impl RpcRequestMiddleware {
         )
     }
 
-    fn process_file_get(&self, path: &str) -> RequestMiddlewareAction {
+    fn process_file_get(&self, path: &str, range: Option<&str>) -> RequestMiddlewareAction {
         let (filename, snapshot_type) = {
             let stem = Self::strip_leading_slash(path).expect("path already verified");
             match path {
@@ -268,11 +277,9 @@ impl RpcRequestMiddleware {
                 }
             }
         };
-        let file_length = std::fs::metadata(&filename)
-            .map(|m| m.len())
-            .unwrap_or(0)
-            .to_string();
-        info!("get {path} -> {filename:?} ({file_length} bytes)");
+        let file_length = std::fs::metadata(&filename).map(|m| m.len()).unwrap_or(0);
+        info!("get {path} -> {filename:?} ({file_length} bytes, range: {range:?})");
+        let range = range.map(str::to_string);
 
         if cfg!(not(test)) {
             assert!(
@@ -311,16 +318,26 @@ impl RpcRequestMiddleware {
                     } else {
                         Self::internal_server_error()
                     }),
-                    Ok(file) => {
-                        let stream =
-                            FramedRead::new(file, BytesCodec::new()).map_ok(|b| b.freeze());
+                    Ok(mut file) => {
+                        let (response, content_length) = match seek_to_byte_range(
+                            &mut file,
+                            file_length,
+                            range.as_deref(),
+                        )
+                        .await
+                        {
+                            Ok(response) => response,
+                            Err(response) => return Ok(response),
+                        };
+                        let stream = FramedRead::new(file.take(content_length), BytesCodec::new())
+                            .map_ok(|b| b.freeze());
                         let body = if let Some(timeout) = snapshot_timeout {
                             hyper::Body::wrap_stream(TimeoutStream::new(stream, timeout))
                         } else {
                             hyper::Body::wrap_stream(stream)
                         };
-                        Ok(hyper::Response::builder()
-                            .header(hyper::header::CONTENT_LENGTH, file_length)
+                        Ok(response
+                            .header(hyper::header::CONTENT_LENGTH, content_length)
                             .body(body)
                             .unwrap())
                     }
@@ -391,7 +408,13 @@ impl RequestMiddleware for RpcRequestMiddleware {
         if let Some(path) = match_supply_path(request.uri().path()) {
             process_rest(&self.bank_forks, path)
         } else if self.is_file_get_path(request.uri().path()) {
-            self.process_file_get(request.uri().path())
+            self.process_file_get(
+                request.uri().path(),
+                request
+                    .headers()
+                    .get(hyper::header::RANGE)
+                    .and_then(|range| range.to_str().ok()),
+            )
         } else if request.uri().path() == "/health" {
             hyper::Response::builder()
                 .status(hyper::StatusCode::OK)
@@ -732,58 +755,71 @@ impl JsonRpcService {
             LARGEST_ACCOUNTS_CACHE_DURATION,
         )));
 
-        let exit_bigtable_ledger_upload_service = Arc::new(AtomicBool::new(false));
+        let exit_ledger_upload_service = Arc::new(AtomicBool::new(false));
 
-        let (bigtable_ledger_storage, _bigtable_ledger_upload_service) =
-            if let Some(RpcBigtableConfig {
-                enable_bigtable_ledger_upload,
-                ref bigtable_instance_name,
-                ref bigtable_app_profile_id,
+        let (history_storage, enable_ledger_upload) = if let Some(RpcBigtableConfig {
+            enable_bigtable_ledger_upload,
+            ref bigtable_instance_name,
+            ref bigtable_app_profile_id,
+            timeout,
+            max_message_size,
+        }) = config.rpc_bigtable_config
+        {
+            let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
+                read_only: !enable_bigtable_ledger_upload,
                 timeout,
+                credential_type: CredentialType::Filepath(None),
+                instance_name: bigtable_instance_name.clone(),
+                app_profile_id: bigtable_app_profile_id.clone(),
                 max_message_size,
-            }) = config.rpc_bigtable_config
-            {
-                let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
-                    read_only: !enable_bigtable_ledger_upload,
-                    timeout,
-                    credential_type: CredentialType::Filepath(None),
-                    instance_name: bigtable_instance_name.clone(),
-                    app_profile_id: bigtable_app_profile_id.clone(),
-                    max_message_size,
-                };
-                runtime
-                    .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
-                        bigtable_config,
-                    ))
-                    .map(|bigtable_ledger_storage| {
-                        info!("BigTable ledger storage initialized");
-
-                        let bigtable_ledger_upload_service = if enable_bigtable_ledger_upload {
-                            Some(Arc::new(BigTableUploadService::new_with_config(
-                                runtime.clone(),
-                                bigtable_ledger_storage.clone(),
-                                blockstore.clone(),
-                                block_commitment_cache.clone(),
-                                max_complete_transaction_status_slot.clone(),
-                                ConfirmedBlockUploadConfig::default(),
-                                exit_bigtable_ledger_upload_service.clone(),
-                            )))
-                        } else {
-                            None
-                        };
-
-                        (
-                            Some(bigtable_ledger_storage),
-                            bigtable_ledger_upload_service,
-                        )
-                    })
-                    .unwrap_or_else(|err| {
-                        error!("Failed to initialize BigTable ledger storage: {err:?}");
-                        (None, None)
-                    })
-            } else {
-                (None, None)
             };
+            runtime
+                .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
+                    bigtable_config,
+                ))
+                .map(|bigtable_ledger_storage| {
+                    info!("BigTable ledger storage initialized");
+                    (
+                        Some(Arc::new(bigtable_ledger_storage) as Arc<dyn HistoryStorage>),
+                        enable_bigtable_ledger_upload,
+                    )
+                })
+                .unwrap_or_else(|err| {
+                    error!("Failed to initialize BigTable ledger storage: {err:?}");
+                    (None, false)
+                })
+        } else if let Some(RpcHistoryStorageConfig {
+            enable_history_storage_upload,
+            ref storage,
+        }) = config.rpc_history_storage_config
+        {
+            solana_storage_history::open(storage)
+                .map(|history_storage| {
+                    info!("History storage initialized: {storage:?}");
+                    (Some(history_storage), enable_history_storage_upload)
+                })
+                .unwrap_or_else(|err| {
+                    error!("Failed to initialize history storage: {err:?}");
+                    (None, false)
+                })
+        } else {
+            (None, false)
+        };
+
+        let _ledger_upload_service = history_storage
+            .as_ref()
+            .filter(|_| enable_ledger_upload)
+            .map(|history_storage| {
+                Arc::new(BigTableUploadService::new_with_config(
+                    runtime.clone(),
+                    history_storage.clone(),
+                    blockstore.clone(),
+                    block_commitment_cache.clone(),
+                    max_complete_transaction_status_slot.clone(),
+                    ConfirmedBlockUploadConfig::default(),
+                    exit_ledger_upload_service.clone(),
+                ))
+            });
 
         let full_api = config.full_api;
         let max_request_body_size = config
@@ -799,7 +835,7 @@ impl JsonRpcService {
             health.clone(),
             cluster_info.clone(),
             genesis_hash,
-            bigtable_ledger_storage,
+            history_storage,
             optimistically_confirmed_bank,
             largest_accounts_cache,
             max_slots,
@@ -821,6 +857,9 @@ impl JsonRpcService {
         let test_request_processor = request_processor.clone();
 
         let ledger_path = ledger_path.to_path_buf();
+        let request_count = Arc::new(AtomicU64::new(0));
+        let request_count_ = request_count.clone();
+        let health_ = health.clone();
 
         let (close_handle_sender, close_handle_receiver) = unbounded();
         let thread_hdl = Builder::new()
@@ -842,14 +881,15 @@ impl JsonRpcService {
                     ledger_path,
                     snapshot_config,
                     bank_forks.clone(),
-                    health.clone(),
+                    health_,
                 );
                 let server = ServerBuilder::with_meta_extractor(
                     io,
                     move |req: &hyper::Request<hyper::Body>| {
+                        request_count_.fetch_add(1, Ordering::Relaxed);
                         let xbigtable = req.headers().get("x-bigtable");
                         if xbigtable.is_some_and(|v| v == "disabled") {
-                            request_processor.clone_without_bigtable()
+                            request_processor.clone_without_history_storage()
                         } else {
                             request_processor.clone()
                         }
@@ -878,7 +918,7 @@ impl JsonRpcService {
                 let server = server.unwrap();
                 close_handle_sender.send(Ok(server.close_handle())).unwrap();
                 server.wait();
-                exit_bigtable_ledger_upload_service.store(true, Ordering::Relaxed);
+                exit_ledger_upload_service.store(true, Ordering::Relaxed);
             })
             .unwrap();
 
@@ -896,6 +936,8 @@ impl JsonRpcService {
             request_processor: test_request_processor,
             close_handle: Some(close_handle),
             client_updater: Arc::new(client) as Arc<dyn NotifyKeyUpdate + Send + Sync>,
+            request_count,
+            health,
         })
     }
 
@@ -913,6 +955,16 @@ impl JsonRpcService {
     pub fn get_client_key_updater(&self) -> Arc<dyn NotifyKeyUpdate + Send + Sync> {
         self.client_updater.clone()
     }
+
+    /// Number of HTTP requests received since startup. A batch of JSON RPC
+    /// requests is counted once.
+    pub fn request_count(&self) -> Arc<AtomicU64> {
+        self.request_count.clone()
+    }
+
+    pub fn health(&self) -> Arc<RpcHealth> {
+        self.health.clone()
+    }
 }
 
 pub fn service_runtime(
@@ -1237,7 +1289,7 @@ mod tests {
         );
 
         // File does not exist => request should fail.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, None);
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
@@ -1252,7 +1304,7 @@ mod tests {
         }
 
         // Normal file exist => request should succeed.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, None);
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
@@ -1261,6 +1313,34 @@ mod tests {
             panic!("Unexpected RequestMiddlewareAction variant");
         }
 
+        // Byte range of an existing file => partial content.
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, Some("bytes=3-4"));
+        if let RequestMiddlewareAction::Respond { response, .. } = action {
+            let response = runtime.block_on(response);
+            let response = response.unwrap();
+            assert_eq!(response.status(), 206);
+            assert_eq!(
+                response.headers()[hyper::header::CONTENT_RANGE],
+                "bytes 3-4/12"
+            );
+            let body = runtime
+                .block_on(hyper::body::to_bytes(response.into_body()))
+                .unwrap();
+            assert_eq!(&body[..], b"ul");
+        } else {
+            panic!("Unexpected RequestMiddlewareAction variant");
+        }
+
+        // Byte range past the end of the file => request should fail.
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, Some("bytes=12-"));
+        if let RequestMiddlewareAction::Respond { response, .. } = action {
+            let response = runtime.block_on(response);
+            let response = response.unwrap();
+            assert_eq!(response.status(), 416);
+        } else {
+            panic!("Unexpected RequestMiddlewareAction variant");
+        }
+
         std::fs::remove_file(&genesis_path).unwrap();
         {
             let mut file = std::fs::File::create(ledger_path.path().join("wrong")).unwrap();
@@ -1269,7 +1349,7 @@ mod tests {
         symlink::symlink_file("wrong", &genesis_path).unwrap();
 
         // File is a symbolic link => request should fail.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, None);
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
@@ -133,6 +133,7 @@ impl TransactionStatusService {
                 transaction_indexes,
             }) => {
                 let mut status_and_memos_batch = blockstore.get_write_batch()?;
+                let store_transactions = blockstore.stores_transactions();
 
                 for (
                     transaction,
@@ -224,6 +225,15 @@ impl TransactionStatusService {
                             )?;
                         }
 
+                        if store_transactions {
+                            blockstore.add_transaction_to_batch(
+                                transaction.signature(),
+                                slot,
+                                &transaction.to_versioned_transaction(),
+                                &mut status_and_memos_batch,
+                            )?;
+                        }
+
                         let message = transaction.message();
                         let keys_with_writable = message
                             .account_keys()
@@ -103,14 +103,15 @@ use {
     solana_hash::Hash,
     solana_inflation::Inflation,
     solana_keypair::Keypair,
-    solana_lattice_hash::lt_hash::LtHash,
+    solana_lattice_hash::lt_hash::{Checksum, LtHash},
     solana_measure::{meas_dur, measure::Measure, measure_time, measure_us},
     solana_message::{inner_instruction::InnerInstructions, AccountKeys, SanitizedMessage},
     solana_native_token::LAMPORTS_PER_SOL,
     solana_packet::PACKET_DATA_SIZE,
     solana_precompile_error::PrecompileError,
     solana_program_runtime::{
-        invoke_context::BuiltinFunctionWithContext, loaded_programs::ProgramCacheEntry,
+        invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::{ProgramCache, ProgramCacheEntry},
     },
     solana_pubkey::Pubkey,
     solana_rent_collector::RentCollector,
@@ -1577,6 +1578,55 @@ impl Bank {
             .prune_by_deployment_slot(deployment_slot);
     }
 
+    /// Returns the program cache shared by all banks of this bank's fork graph.
+    pub fn program_cache(&self) -> Arc<RwLock<ProgramCache<BankForks>>> {
+        self.transaction_processor.program_cache.clone()
+    }
+
+    /// Loads and compiles the given programs into the program cache ahead of their first use.
+    ///
+    /// Returns the number of programs which were successfully loaded. Programs which don't
+    /// exist, fail verification or are already loaded are skipped.
+    pub fn prewarm_program_cache(&self, program_ids: &[Pubkey]) -> usize {
+        let environments = self
+            .transaction_processor
+            .program_cache
+            .read()
+            .unwrap()
+            .get_environments_for_epoch(self.epoch);
+        let mut num_loaded: usize = 0;
+        for program_id in program_ids {
+            if self
+                .transaction_processor
+                .program_cache
+                .read()
+                .unwrap()
+                .has_loaded_entry(program_id)
+            {
+                continue;
+            }
+            let Some(program) = load_program_with_pubkey(
+                self,
+                &environments,
+                program_id,
+                self.slot,
+                &mut ExecuteTimings::default(),
+                false,
+            ) else {
+                continue;
+            };
+            if program.is_tombstone() {
+                continue;
+            }
+            program.update_access_slot(self.slot);
+            let mut program_cache = self.transaction_processor.program_cache.write().unwrap();
+            if program_cache.assign_program(*program_id, program) {
+                num_loaded = num_loaded.saturating_add(1);
+            }
+        }
+        num_loaded
+    }
+
     /// Epoch in which the new cooldown warmup rate for stake was activated
     pub fn new_warmup_cooldown_rate_epoch(&self) -> Option<Epoch> {
         self.feature_set
@@ -5568,6 +5618,11 @@ impl Bank {
         *self.accounts_lt_hash.lock().unwrap() = accounts_lt_hash;
     }
 
+    /// Returns the checksum of the accounts lt hash
+    pub fn accounts_lt_hash_checksum(&self) -> Checksum {
+        self.accounts_lt_hash.lock().unwrap().0.checksum()
+    }
+
     /// Return total transaction fee collected
     pub fn get_collector_fee_details(&self) -> CollectorFeeDetails {
         self.collector_fee_details.read().unwrap().clone()
@@ -5852,6 +5852,65 @@ fn test_bank_load_program() {
     }
 }
 
+#[test]
+fn test_bank_prewarm_program_cache() {
+    let (genesis_config, _mint_keypair) = create_genesis_config_no_tx_fee(1_000_000_000);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let (bank, bank_forks) = bank.wrap_with_bank_forks_for_tests();
+    goto_end_of_slot(bank.clone());
+    let bank = new_bank_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 42);
+    let bank = new_bank_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 50);
+
+    let program_key = solana_pubkey::new_rand();
+    let programdata_key = solana_pubkey::new_rand();
+    let missing_program_key = solana_pubkey::new_rand();
+
+    let mut file = File::open("../programs/bpf_loader/test_elfs/out/noop_aligned.so").unwrap();
+    let mut elf = Vec::new();
+    file.read_to_end(&mut elf).unwrap();
+    let mut program_account = AccountSharedData::new_data(
+        40,
+        &UpgradeableLoaderState::Program {
+            programdata_address: programdata_key,
+        },
+        &bpf_loader_upgradeable::id(),
+    )
+    .unwrap();
+    program_account.set_executable(true);
+    let programdata_data_offset = UpgradeableLoaderState::size_of_programdata_metadata();
+    let mut programdata_account = AccountSharedData::new(
+        40,
+        programdata_data_offset + elf.len(),
+        &bpf_loader_upgradeable::id(),
+    );
+    programdata_account
+        .set_state(&UpgradeableLoaderState::ProgramData {
+            slot: 42,
+            upgrade_authority_address: None,
+        })
+        .unwrap();
+    programdata_account.data_as_mut_slice()[programdata_data_offset..].copy_from_slice(&elf);
+    bank.store_account_and_update_capitalization(&program_key, &program_account);
+    bank.store_account_and_update_capitalization(&programdata_key, &programdata_account);
+
+    assert!(!bank
+        .program_cache()
+        .read()
+        .unwrap()
+        .has_loaded_entry(&program_key));
+    assert_eq!(
+        bank.prewarm_program_cache(&[program_key, missing_program_key]),
+        1
+    );
+    assert!(bank
+        .program_cache()
+        .read()
+        .unwrap()
+        .has_loaded_entry(&program_key));
+    // Programs which are already loaded are skipped
+    assert_eq!(bank.prewarm_program_cache(&[program_key]), 0);
+}
+
 #[allow(deprecated)]
 #[test_case(false; "informal_loaded_size")]
 #[test_case(true; "simd186_loaded_size")]
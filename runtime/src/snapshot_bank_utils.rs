@@ -1018,6 +1018,39 @@ mod tests {
         assert_eq!(original_bank, roundtrip_bank);
     }
 
+    #[test]
+    fn test_read_snapshot_archive_manifest() {
+        let genesis_config = GenesisConfig::default();
+        let bank = Bank::new_for_tests(&genesis_config);
+        bank.fill_bank_with_ticks_for_tests();
+
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_info = bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank,
+            None,
+            snapshot_archives_dir.path(),
+            snapshot_archives_dir.path(),
+            SnapshotConfig::default().archive_format,
+        )
+        .unwrap();
+
+        let manifest =
+            snapshot_utils::read_snapshot_archive_manifest(&snapshot_archive_info).unwrap();
+        assert_eq!(manifest.snapshot_version, SnapshotVersion::default());
+        assert_eq!(manifest.slot, bank.slot());
+        assert_eq!(manifest.hash, bank.hash());
+        assert_eq!(manifest.capitalization, bank.capitalization());
+        assert_eq!(
+            manifest.accounts_lt_hash_checksum,
+            bank.accounts_lt_hash_checksum()
+        );
+        assert_eq!(manifest.status_cache_slots, 1);
+        assert_eq!(manifest.storages_count, 1);
+        assert!(manifest.storages_len > 0);
+    }
+
     /// This tests handling of obsolete accounts during a full snapshot with obsolete accounts
     /// marked in the accounts database. This test injects them directly
     #[test]
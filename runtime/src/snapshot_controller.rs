@@ -14,20 +14,24 @@ use {
     std::{
         sync::{
             atomic::{AtomicU64, Ordering},
-            Arc,
+            Arc, RwLock,
         },
         time::Instant,
     },
 };
 
-struct SnapshotGenerationIntervals {
-    full_snapshot_interval: SnapshotInterval,
-    incremental_snapshot_interval: SnapshotInterval,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapshotGenerationIntervals {
+    pub full_snapshot_interval: SnapshotInterval,
+    pub incremental_snapshot_interval: SnapshotInterval,
 }
 
 pub struct SnapshotController {
     abs_request_sender: SnapshotRequestSender,
     snapshot_config: SnapshotConfig,
+    /// Intervals snapshot requests are sent at. They start out as the
+    /// intervals in `snapshot_config` and may be changed at runtime.
+    snapshot_intervals: RwLock<SnapshotGenerationIntervals>,
     latest_abs_request_slot: AtomicU64,
 }
 
@@ -37,9 +41,14 @@ impl SnapshotController {
         snapshot_config: SnapshotConfig,
         root_slot: Slot,
     ) -> Self {
+        let snapshot_intervals = RwLock::new(SnapshotGenerationIntervals {
+            full_snapshot_interval: snapshot_config.full_snapshot_archive_interval,
+            incremental_snapshot_interval: snapshot_config.incremental_snapshot_archive_interval,
+        });
         Self {
             abs_request_sender,
             snapshot_config,
+            snapshot_intervals,
             latest_abs_request_slot: AtomicU64::new(root_slot),
         }
     }
@@ -48,6 +57,17 @@ impl SnapshotController {
         &self.snapshot_config
     }
 
+    /// Returns the intervals snapshot requests are currently sent at
+    pub fn snapshot_intervals(&self) -> SnapshotGenerationIntervals {
+        *self.snapshot_intervals.read().unwrap()
+    }
+
+    /// Changes the intervals snapshot requests are sent at, taking effect on
+    /// the next root. The caller is responsible for validating the intervals.
+    pub fn set_snapshot_intervals(&self, snapshot_intervals: SnapshotGenerationIntervals) {
+        *self.snapshot_intervals.write().unwrap() = snapshot_intervals;
+    }
+
     pub fn request_sender(&self) -> &SnapshotRequestSender {
         &self.abs_request_sender
     }
@@ -141,11 +161,6 @@ impl SnapshotController {
     fn snapshot_generation_intervals(&self) -> Option<SnapshotGenerationIntervals> {
         self.snapshot_config
             .should_generate_snapshots()
-            .then_some(SnapshotGenerationIntervals {
-                full_snapshot_interval: self.snapshot_config.full_snapshot_archive_interval,
-                incremental_snapshot_interval: self
-                    .snapshot_config
-                    .incremental_snapshot_archive_interval,
-            })
+            .then(|| self.snapshot_intervals())
     }
 }
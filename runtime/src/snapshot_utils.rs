@@ -17,6 +17,7 @@ use {
             get_slot_and_append_vec_id, SnapshotStorageRebuilder,
         },
     },
+    bincode::Options,
     crossbeam_channel::{Receiver, Sender},
     log::*,
     regex::Regex,
@@ -30,6 +31,7 @@ use {
     },
     solana_clock::{Epoch, Slot},
     solana_hash::Hash,
+    solana_lattice_hash::lt_hash::Checksum,
     solana_measure::{measure::Measure, measure_time, measure_us},
     std::{
         cmp::Ordering,
@@ -2439,6 +2441,157 @@ pub fn verify_unpacked_snapshots_dir_and_version(
     Ok((snapshot_version, root_paths))
 }
 
+/// The manifest of a snapshot archive, as printed by `agave-ledger-tool snapshot inspect`
+#[derive(Debug)]
+pub struct SnapshotArchiveManifest {
+    pub snapshot_version: SnapshotVersion,
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    pub epoch: Epoch,
+    pub block_height: u64,
+    pub hash: Hash,
+    pub parent_hash: Hash,
+    pub capitalization: u64,
+    pub transaction_count: u64,
+    pub signature_count: u64,
+    pub accounts_data_len: u64,
+    pub accounts_lt_hash_checksum: Checksum,
+    pub hard_forks: Vec<Slot>,
+    pub vote_accounts_count: usize,
+    pub total_stake: u64,
+    /// Epochs the bank holds epoch stakes for
+    pub epoch_stakes_epochs: Vec<Epoch>,
+    pub status_cache_slots: usize,
+    pub status_cache_entries: usize,
+    pub storages_count: usize,
+    /// Total length of the account storages in bytes
+    pub storages_len: u64,
+}
+
+/// Reads the manifest of a snapshot archive without unpacking its account storages
+///
+/// The version file and the snapshots directory are archived before the account storages, so
+/// only the beginning of the archive has to be decompressed.
+pub fn read_snapshot_archive_manifest(
+    snapshot_archive_info: &impl SnapshotArchiveInfoGetter,
+) -> Result<SnapshotArchiveManifest> {
+    let mut archive = Archive::new(decompressed_tar_reader(
+        snapshot_archive_info.archive_format(),
+        snapshot_archive_info.path(),
+    )?);
+    let mut snapshot_version = None;
+    let mut fields = None;
+    let mut slot_deltas = None;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        if path.starts_with("accounts") {
+            break;
+        }
+        let Some(filename) = path.file_name().and_then(|filename| filename.to_str()) else {
+            continue;
+        };
+        if filename == SNAPSHOT_STATUS_CACHE_FILENAME {
+            let deltas: Vec<BankSlotDelta> = bincode::options()
+                .with_limit(MAX_SNAPSHOT_DATA_FILE_SIZE)
+                .with_fixint_encoding()
+                .allow_trailing_bytes()
+                .deserialize_from(entry)?;
+            slot_deltas = Some(deltas);
+            continue;
+        }
+        match get_snapshot_file_kind(filename) {
+            Some(SnapshotFileKind::Version) => {
+                let mut version = String::new();
+                entry
+                    .take(MAX_SNAPSHOT_VERSION_FILE_SIZE)
+                    .read_to_string(&mut version)?;
+                let version = version.trim();
+                snapshot_version = Some(version.parse::<SnapshotVersion>().map_err(|err| {
+                    IoError::other(format!("unsupported snapshot version '{version}': {err}"))
+                })?);
+            }
+            Some(SnapshotFileKind::BankFields) => {
+                let Some(snapshot_version) = snapshot_version else {
+                    return Err(IoError::other(
+                        "invalid snapshot archive: bank snapshot found before the version file",
+                    )
+                    .into());
+                };
+                let mut stream = BufReader::new(entry);
+                fields = Some(match snapshot_version {
+                    SnapshotVersion::V1_2_0 => serde_snapshot::fields_from_stream(&mut stream)?,
+                });
+            }
+            Some(SnapshotFileKind::Storage) | None => {}
+        }
+    }
+
+    let (Some(snapshot_version), Some((bank_fields, accounts_db_fields)), Some(slot_deltas)) =
+        (snapshot_version, fields, slot_deltas)
+    else {
+        return Err(IoError::other(format!(
+            "invalid snapshot archive '{}': missing the version file, the bank snapshot or the \
+             status cache",
+            snapshot_archive_info.path().display(),
+        ))
+        .into());
+    };
+    let storage_lengths = serde_snapshot::snapshot_storage_lengths_from_fields(&accounts_db_fields);
+    Ok(SnapshotArchiveManifest {
+        snapshot_version,
+        slot: bank_fields.slot,
+        parent_slot: bank_fields.parent_slot,
+        epoch: bank_fields.epoch,
+        block_height: bank_fields.block_height,
+        hash: bank_fields.hash,
+        parent_hash: bank_fields.parent_hash,
+        capitalization: bank_fields.capitalization,
+        transaction_count: bank_fields.transaction_count,
+        signature_count: bank_fields.signature_count,
+        accounts_data_len: bank_fields.accounts_data_len,
+        accounts_lt_hash_checksum: bank_fields.accounts_lt_hash.0.checksum(),
+        hard_forks: bank_fields
+            .hard_forks
+            .iter()
+            .map(|(slot, _)| *slot)
+            .collect(),
+        vote_accounts_count: bank_fields.stakes.vote_accounts().len(),
+        total_stake: bank_fields
+            .stakes
+            .vote_accounts()
+            .delegated_stakes()
+            .map(|(_, stake)| stake)
+            .sum(),
+        epoch_stakes_epochs: {
+            let mut epochs: Vec<_> = bank_fields.versioned_epoch_stakes.into_keys().collect();
+            epochs.sort_unstable();
+            epochs
+        },
+        status_cache_slots: slot_deltas.len(),
+        status_cache_entries: slot_deltas
+            .iter()
+            .map(|(_, _, status)| {
+                status
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .map(|(_, entries)| entries.len())
+                    .sum::<usize>()
+            })
+            .sum(),
+        storages_count: storage_lengths.values().map(HashMap::len).sum(),
+        storages_len: storage_lengths
+            .values()
+            .flat_map(HashMap::values)
+            .map(|len| *len as u64)
+            .sum(),
+    })
+}
+
 /// Returns the file name of the bank snapshot for `slot`
 pub fn get_snapshot_file_name(slot: Slot) -> String {
     slot.to_string()
@@ -0,0 +1,269 @@
+//! The catalog of snapshot archives being served, refreshed from the snapshot archive
+//! directories.
+
+use {
+    log::*,
+    serde_derive::Serialize,
+    sha2::{Digest, Sha256},
+    solana_clock::Slot,
+    solana_hash::Hash,
+    solana_runtime::{snapshot_archive_info::SnapshotArchiveInfoGetter, snapshot_utils},
+    std::{
+        collections::HashMap,
+        fs::File,
+        io::{self, Read},
+        path::{Path, PathBuf},
+        sync::RwLock,
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotArchive {
+    pub file_name: String,
+    #[serde(skip)]
+    pub path: PathBuf,
+    pub slot: Slot,
+    #[serde(with = "hash_as_string")]
+    pub hash: Hash,
+    /// Set for incremental snapshot archives: the full snapshot archive must be downloaded
+    /// for this slot as well
+    pub base_slot: Option<Slot>,
+    pub size: u64,
+    /// Hex encoded SHA-256 of the archive, once computed
+    pub sha256: Option<String>,
+}
+
+mod hash_as_string {
+    use {serde::Serializer, solana_hash::Hash};
+
+    pub fn serialize<S: Serializer>(hash: &Hash, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(hash)
+    }
+}
+
+impl SnapshotArchive {
+    fn new(archive: &impl SnapshotArchiveInfoGetter, base_slot: Option<Slot>) -> Option<Self> {
+        let path = archive.path().clone();
+        let file_name = path.file_name()?.to_str()?.to_string();
+        let size = path.metadata().ok()?.len();
+        Some(Self {
+            file_name,
+            path,
+            slot: archive.slot(),
+            hash: archive.hash().0,
+            base_slot,
+            size,
+            sha256: None,
+        })
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.base_slot.is_none()
+    }
+}
+
+pub struct SnapshotArchives {
+    full_snapshot_archives_dir: PathBuf,
+    incremental_snapshot_archives_dir: PathBuf,
+    // Keyed by file name
+    archives: RwLock<HashMap<String, SnapshotArchive>>,
+}
+
+impl SnapshotArchives {
+    pub fn new(
+        full_snapshot_archives_dir: PathBuf,
+        incremental_snapshot_archives_dir: PathBuf,
+    ) -> Self {
+        Self {
+            full_snapshot_archives_dir,
+            incremental_snapshot_archives_dir,
+            archives: RwLock::default(),
+        }
+    }
+
+    /// Rescans the snapshot archive directories. Returns true if the set of archives changed.
+    pub fn refresh(&self) -> bool {
+        let full_snapshot_archives =
+            snapshot_utils::get_full_snapshot_archives(&self.full_snapshot_archives_dir)
+                .into_iter()
+                .filter_map(|archive| SnapshotArchive::new(&archive, None));
+        let incremental_snapshot_archives = snapshot_utils::get_incremental_snapshot_archives(
+            &self.incremental_snapshot_archives_dir,
+        )
+        .into_iter()
+        .filter_map(|archive| SnapshotArchive::new(&archive, Some(archive.base_slot())));
+        let mut found: HashMap<_, _> = full_snapshot_archives
+            .chain(incremental_snapshot_archives)
+            .map(|archive| (archive.file_name.clone(), archive))
+            .collect();
+
+        let mut archives = self.archives.write().unwrap();
+        // Archives are immutable once in place, keep the checksums computed so far.
+        for (file_name, archive) in found.iter_mut() {
+            if let Some(known) = archives.get(file_name) {
+                if known.size == archive.size {
+                    archive.sha256.clone_from(&known.sha256);
+                }
+            }
+        }
+        let changed = found.len() != archives.len()
+            || found.iter().any(|(file_name, archive)| {
+                archives
+                    .get(file_name)
+                    .is_none_or(|known| known.size != archive.size)
+            });
+        if changed {
+            info!(
+                "Serving snapshot archives: {:?}",
+                found.keys().collect::<Vec<_>>()
+            );
+        }
+        *archives = found;
+        changed
+    }
+
+    pub fn get(&self, file_name: &str) -> Option<SnapshotArchive> {
+        self.archives.read().unwrap().get(file_name).cloned()
+    }
+
+    /// Returns all archives, ordered by slot
+    pub fn list(&self) -> Vec<SnapshotArchive> {
+        let mut archives: Vec<_> = self.archives.read().unwrap().values().cloned().collect();
+        archives.sort_by_key(|archive| (archive.slot, archive.base_slot));
+        archives
+    }
+
+    pub fn highest_full(&self) -> Option<SnapshotArchive> {
+        self.archives
+            .read()
+            .unwrap()
+            .values()
+            .filter(|archive| archive.is_full())
+            .max_by_key(|archive| archive.slot)
+            .cloned()
+    }
+
+    /// Returns the highest incremental snapshot archive based on the highest full snapshot
+    pub fn highest_incremental(&self) -> Option<SnapshotArchive> {
+        let full_snapshot_slot = self.highest_full()?.slot;
+        self.archives
+            .read()
+            .unwrap()
+            .values()
+            .filter(|archive| archive.base_slot == Some(full_snapshot_slot))
+            .max_by_key(|archive| archive.slot)
+            .cloned()
+    }
+
+    /// Returns the highest full snapshot hash and the highest incremental snapshot hash based on
+    /// it, as advertised in gossip
+    pub fn snapshot_hashes(&self) -> Option<((Slot, Hash), Option<(Slot, Hash)>)> {
+        let full = self.highest_full()?;
+        let incremental = self.highest_incremental();
+        Some((
+            (full.slot, full.hash),
+            incremental.map(|incremental| (incremental.slot, incremental.hash)),
+        ))
+    }
+
+    /// Computes the checksum of one archive which does not have one yet. Returns false if all
+    /// archives have a checksum.
+    pub fn compute_next_checksum(&self) -> bool {
+        let Some(archive) = self
+            .list()
+            .into_iter()
+            .rev()
+            .find(|archive| archive.sha256.is_none())
+        else {
+            return false;
+        };
+        match sha256_file(&archive.path) {
+            Ok(sha256) => {
+                debug!("{}: sha256 {sha256}", archive.file_name);
+                if let Some(known) = self.archives.write().unwrap().get_mut(&archive.file_name) {
+                    known.sha256 = Some(sha256);
+                }
+            }
+            Err(err) => {
+                // The archive was most likely purged. If not, the next refresh adds it back.
+                warn!(
+                    "Failed to compute the checksum of {}: {err}",
+                    archive.file_name
+                );
+                self.archives.write().unwrap().remove(&archive.file_name);
+            }
+        }
+        true
+    }
+}
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 1024 * 1024];
+    loop {
+        let size = file.read(&mut buffer)?;
+        if size == 0 {
+            break;
+        }
+        hasher.update(&buffer[..size]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::fs, tempfile::TempDir};
+
+    #[test]
+    fn test_snapshot_archives() {
+        let full_dir = TempDir::new().unwrap();
+        let incremental_dir = TempDir::new().unwrap();
+        let archives = SnapshotArchives::new(
+            full_dir.path().to_path_buf(),
+            incremental_dir.path().to_path_buf(),
+        );
+        assert!(!archives.refresh());
+        assert_eq!(archives.snapshot_hashes(), None);
+
+        let hash = Hash::new_unique();
+        let full_100 = format!("snapshot-100-{hash}.tar.zst");
+        let full_200 = format!("snapshot-200-{hash}.tar.zst");
+        let incremental_150 = format!("incremental-snapshot-100-150-{hash}.tar.zst");
+        let incremental_250 = format!("incremental-snapshot-200-250-{hash}.tar.zst");
+        for file_name in [&full_100, &full_200, "not-a-snapshot.tar.zst"] {
+            fs::write(full_dir.path().join(file_name), file_name).unwrap();
+        }
+        fs::write(incremental_dir.path().join(&incremental_150), "").unwrap();
+        assert!(archives.refresh());
+        assert!(!archives.refresh());
+        assert_eq!(archives.list().len(), 3);
+        assert_eq!(archives.get("not-a-snapshot.tar.zst"), None);
+        assert_eq!(archives.highest_full().unwrap().file_name, full_200);
+        // The incremental snapshot is not based on the highest full snapshot.
+        assert_eq!(archives.highest_incremental(), None);
+        assert_eq!(archives.snapshot_hashes(), Some(((200, hash), None)));
+
+        fs::write(incremental_dir.path().join(&incremental_250), "").unwrap();
+        assert!(archives.refresh());
+        let incremental = archives.highest_incremental().unwrap();
+        assert_eq!(incremental.file_name, incremental_250);
+        assert_eq!(incremental.base_slot, Some(200));
+        assert_eq!(
+            archives.snapshot_hashes(),
+            Some(((200, hash), Some((250, hash))))
+        );
+
+        while archives.compute_next_checksum() {}
+        assert_eq!(
+            archives.get(&full_200).unwrap().sha256,
+            Some(hex::encode(Sha256::digest(full_200.as_bytes())))
+        );
+        // Checksums survive a refresh.
+        fs::remove_file(full_dir.path().join(&full_100)).unwrap();
+        assert!(archives.refresh());
+        assert!(archives.get(&full_100).is_none());
+        assert!(archives.get(&full_200).unwrap().sha256.is_some());
+    }
+}
@@ -0,0 +1,386 @@
+//! A standalone server for snapshot archives.
+//!
+//! Serves the full and incremental snapshot archives found in a directory over HTTP, with byte
+//! ranges, checksums and per client limits, and optionally advertises them in gossip as the
+//! snapshot hashes of its own node.
+use {
+    crate::{
+        archives::SnapshotArchives,
+        rate_limit::{ClientRateLimiter, RateLimitConfig},
+        service::SnapshotService,
+    },
+    clap::{crate_description, crate_name, value_t, value_t_or_exit, App, Arg},
+    hyper::{
+        server::conn::AddrStream,
+        service::{make_service_fn, service_fn},
+        Server,
+    },
+    log::*,
+    solana_clap_utils::{
+        input_parsers::keypair_of,
+        input_validators::{is_keypair, is_parsable, is_port},
+    },
+    solana_gossip::{
+        cluster_info::ClusterInfo, contact_info::ContactInfo, gossip_service::GossipService,
+    },
+    solana_hash::Hash,
+    solana_keypair::Keypair,
+    solana_net_utils::{IpEchoServer, DEFAULT_IP_ECHO_SERVER_THREADS},
+    solana_signer::Signer,
+    solana_streamer::{atomic_udp_socket::AtomicUdpSocket, socket::SocketAddrSpace},
+    std::{
+        convert::Infallible,
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        path::PathBuf,
+        process::exit,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, sleep},
+        time::Duration,
+    },
+};
+
+mod archives;
+mod rate_limit;
+mod service;
+
+const DEFAULT_REFRESH_INTERVAL_SECS: &str = "5";
+const CHECKSUM_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Parameters for advertising the served snapshots in gossip
+struct GossipConfig {
+    identity: Keypair,
+    entrypoint: SocketAddr,
+    gossip_addr: SocketAddr,
+    shred_version: u16,
+    /// Address of the HTTP service, advertised as the node's RPC address
+    http_addr: SocketAddr,
+}
+
+fn start_gossip(
+    config: GossipConfig,
+    exit: Arc<AtomicBool>,
+) -> (Arc<ClusterInfo>, GossipService, Option<IpEchoServer>) {
+    let GossipConfig {
+        identity,
+        entrypoint,
+        gossip_addr,
+        shred_version,
+        http_addr,
+    } = config;
+    let (mut node, gossip_socket, ip_echo) =
+        ClusterInfo::gossip_node(identity.pubkey(), &gossip_addr, shred_version);
+    // Validators bootstrapping from this node find the snapshot archives at its RPC address.
+    node.set_rpc(http_addr).unwrap();
+    let cluster_info = ClusterInfo::new(node, Arc::new(identity), SocketAddrSpace::Unspecified);
+    cluster_info.set_entrypoint(ContactInfo::new_gossip_entry_point(&entrypoint));
+    let cluster_info = Arc::new(cluster_info);
+    let ip_echo_server = ip_echo.map(|tcp_listener| {
+        solana_net_utils::ip_echo_server(
+            tcp_listener,
+            DEFAULT_IP_ECHO_SERVER_THREADS,
+            Some(shred_version),
+        )
+    });
+    let gossip_service = GossipService::new(
+        &cluster_info,
+        None,
+        AtomicUdpSocket::new(gossip_socket),
+        None,
+        true, // should_check_duplicate_instance
+        None,
+        exit,
+    );
+    info!(
+        "Advertising snapshot hashes in gossip as {} at {gossip_addr}",
+        cluster_info.id()
+    );
+    (cluster_info, gossip_service, ip_echo_server)
+}
+
+/// Rescans the snapshot archives periodically and pushes the highest snapshot hashes to gossip
+/// when they change
+fn refresh_archives(
+    archives: &SnapshotArchives,
+    cluster_info: Option<&ClusterInfo>,
+    refresh_interval: Duration,
+    exit: &AtomicBool,
+) {
+    let mut pushed_snapshot_hashes = None;
+    while !exit.load(Ordering::Relaxed) {
+        archives.refresh();
+        if let Some(cluster_info) = cluster_info {
+            let snapshot_hashes = archives.snapshot_hashes();
+            if snapshot_hashes != pushed_snapshot_hashes {
+                if let Some((full, incremental)) = snapshot_hashes {
+                    info!("Pushing snapshot hashes, full: {full:?}, incremental: {incremental:?}");
+                    if let Err(err) =
+                        cluster_info.push_snapshot_hashes(full, incremental.into_iter().collect())
+                    {
+                        warn!("Failed to push snapshot hashes: {err:?}");
+                    }
+                }
+                pushed_snapshot_hashes = snapshot_hashes;
+            }
+        }
+        sleep(refresh_interval);
+    }
+}
+
+fn parse_entrypoint_shred_version(entrypoint: &SocketAddr) -> u16 {
+    match solana_net_utils::get_cluster_shred_version(entrypoint) {
+        Ok(shred_version) if shred_version != 0 => {
+            info!("obtained shred-version {shred_version} from entrypoint: {entrypoint}");
+            shred_version
+        }
+        Ok(_) => {
+            eprintln!("entrypoint {entrypoint} returned shred-version zero");
+            exit(1);
+        }
+        Err(err) => {
+            eprintln!("get_cluster_shred_version failed: {entrypoint}, {err}");
+            exit(1);
+        }
+    }
+}
+
+fn main() {
+    solana_logger::setup_with_default_filter();
+    let matches = App::new(crate_name!())
+        .about(crate_description!())
+        .version(solana_version::version!())
+        .arg(
+            Arg::with_name("snapshots")
+                .long("snapshots")
+                .value_name("DIR")
+                .takes_value(true)
+                .required(true)
+                .help("Directory of the full snapshot archives to serve"),
+        )
+        .arg(
+            Arg::with_name("incremental_snapshot_archive_path")
+                .long("incremental-snapshot-archive-path")
+                .value_name("DIR")
+                .takes_value(true)
+                .help(
+                    "Directory of the incremental snapshot archives to serve [default: \
+                     --snapshots]",
+                ),
+        )
+        .arg(
+            Arg::with_name("bind_address")
+                .long("bind-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .default_value("0.0.0.0:8899")
+                .validator(solana_net_utils::is_host_port)
+                .help("Address to serve snapshot archives on"),
+        )
+        .arg(
+            Arg::with_name("refresh_interval")
+                .long("refresh-interval")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .default_value(DEFAULT_REFRESH_INTERVAL_SECS)
+                .validator(is_parsable::<u64>)
+                .help("How often to rescan the snapshot archive directories"),
+        )
+        .arg(
+            Arg::with_name("max_downloads_per_client")
+                .long("max-downloads-per-client")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .help("Maximum number of concurrent downloads from one client IP address"),
+        )
+        .arg(
+            Arg::with_name("max_bytes_per_second_per_client")
+                .long("max-bytes-per-second-per-client")
+                .value_name("BYTES")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .help(
+                    "Maximum download rate of one client IP address, shared by all of its \
+                     downloads",
+                ),
+        )
+        .arg(
+            Arg::with_name("genesis_archive")
+                .long("genesis-archive")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Genesis archive to serve to bootstrapping validators"),
+        )
+        .arg(
+            Arg::with_name("expected_genesis_hash")
+                .long("expected-genesis-hash")
+                .value_name("HASH")
+                .takes_value(true)
+                .validator(is_parsable::<Hash>)
+                .help("Genesis hash to report to bootstrapping validators"),
+        )
+        .arg(
+            Arg::with_name("entrypoint")
+                .long("entrypoint")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(solana_net_utils::is_host_port)
+                .help(
+                    "Advertise the highest snapshot archives in gossip, joining the cluster at \
+                     this entrypoint",
+                ),
+        )
+        .arg(
+            Arg::with_name("identity")
+                .long("identity")
+                .value_name("KEYPAIR")
+                .takes_value(true)
+                .requires("entrypoint")
+                .validator(is_keypair)
+                .help("Gossip identity of the server [default: a new keypair]"),
+        )
+        .arg(
+            Arg::with_name("gossip_port")
+                .long("gossip-port")
+                .value_name("PORT")
+                .takes_value(true)
+                .requires("entrypoint")
+                .validator(is_port)
+                .help("Gossip port number for the node [default: any available port]"),
+        )
+        .arg(
+            Arg::with_name("public_address")
+                .long("public-address")
+                .value_name("IP")
+                .takes_value(true)
+                .requires("entrypoint")
+                .validator(solana_net_utils::is_host)
+                .help("IP address advertised in gossip [default: as seen by the entrypoint]"),
+        )
+        .arg(
+            Arg::with_name("expected_shred_version")
+                .long("expected-shred-version")
+                .value_name("VERSION")
+                .takes_value(true)
+                .requires("entrypoint")
+                .validator(is_parsable::<u16>)
+                .help("Shred version of the cluster [default: obtained from the entrypoint]"),
+        )
+        .get_matches();
+
+    let full_snapshot_archives_dir = PathBuf::from(matches.value_of("snapshots").unwrap());
+    let incremental_snapshot_archives_dir = matches
+        .value_of("incremental_snapshot_archive_path")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| full_snapshot_archives_dir.clone());
+    let bind_address = solana_net_utils::parse_host_port(matches.value_of("bind_address").unwrap())
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to parse --bind-address: {err}");
+            exit(1);
+        });
+    let refresh_interval = Duration::from_secs(value_t_or_exit!(matches, "refresh_interval", u64));
+    let rate_limit_config = RateLimitConfig {
+        max_downloads_per_client: value_t!(matches, "max_downloads_per_client", usize).ok(),
+        max_bytes_per_second_per_client: value_t!(matches, "max_bytes_per_second_per_client", u64)
+            .ok(),
+    };
+
+    let gossip_config = matches.value_of("entrypoint").map(|entrypoint| {
+        let entrypoint = solana_net_utils::parse_host_port(entrypoint).unwrap_or_else(|err| {
+            eprintln!("Failed to parse --entrypoint: {err}");
+            exit(1);
+        });
+        let public_ip = match matches.value_of("public_address") {
+            Some(public_address) => solana_net_utils::parse_host(public_address).unwrap(),
+            None => solana_net_utils::get_public_ip_addr_with_binding(
+                &entrypoint,
+                IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to contact cluster entrypoint {entrypoint}: {err}");
+                exit(1);
+            }),
+        };
+        let shred_version = value_t!(matches, "expected_shred_version", u16)
+            .unwrap_or_else(|_| parse_entrypoint_shred_version(&entrypoint));
+        GossipConfig {
+            identity: keypair_of(&matches, "identity").unwrap_or_else(Keypair::new),
+            entrypoint,
+            gossip_addr: SocketAddr::new(
+                public_ip,
+                value_t!(matches, "gossip_port", u16).unwrap_or(0),
+            ),
+            shred_version,
+            http_addr: SocketAddr::new(public_ip, bind_address.port()),
+        }
+    });
+
+    let exit = Arc::new(AtomicBool::new(false));
+    let archives = Arc::new(SnapshotArchives::new(
+        full_snapshot_archives_dir,
+        incremental_snapshot_archives_dir,
+    ));
+    archives.refresh();
+    let (cluster_info, _gossip_service, _ip_echo_server) = match gossip_config {
+        Some(gossip_config) => {
+            let (cluster_info, gossip_service, ip_echo_server) =
+                start_gossip(gossip_config, exit.clone());
+            (Some(cluster_info), Some(gossip_service), ip_echo_server)
+        }
+        None => (None, None, None),
+    };
+    thread::Builder::new()
+        .name("solSnapRefresh".to_string())
+        .spawn({
+            let archives = archives.clone();
+            let exit = exit.clone();
+            move || refresh_archives(&archives, cluster_info.as_deref(), refresh_interval, &exit)
+        })
+        .unwrap();
+    thread::Builder::new()
+        .name("solSnapChecksum".to_string())
+        .spawn({
+            let archives = archives.clone();
+            let exit = exit.clone();
+            move || {
+                while !exit.load(Ordering::Relaxed) {
+                    if !archives.compute_next_checksum() {
+                        sleep(CHECKSUM_POLL_INTERVAL);
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+    let service = Arc::new(SnapshotService {
+        archives,
+        rate_limiter: Arc::new(ClientRateLimiter::new(rate_limit_config)),
+        genesis_archive: matches.value_of("genesis_archive").map(PathBuf::from),
+        genesis_hash: value_t!(matches, "expected_genesis_hash", Hash).ok(),
+    });
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .thread_name("solSnapServer")
+        .enable_all()
+        .build()
+        .unwrap();
+    let result = runtime.block_on(async move {
+        let make_service = make_service_fn(move |connection: &AddrStream| {
+            let service = service.clone();
+            let client = connection.remote_addr();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    service.clone().handle(client, request)
+                }))
+            }
+        });
+        let server = Server::try_bind(&bind_address)?.serve(make_service);
+        info!("Serving snapshot archives on http://{bind_address}");
+        server.await
+    });
+    if let Err(err) = result {
+        error!("Server error: {err}");
+    }
+    exit.store(true, Ordering::Relaxed);
+}
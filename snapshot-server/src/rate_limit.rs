@@ -0,0 +1,147 @@
+//! Per client limits on the number of concurrent downloads and on the download rate.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RateLimitConfig {
+    /// Maximum number of concurrent downloads from one client IP address
+    pub max_downloads_per_client: Option<usize>,
+    /// Maximum download rate of one client IP address in bytes per second, shared by all of its
+    /// downloads
+    pub max_bytes_per_second_per_client: Option<u64>,
+}
+
+struct ClientState {
+    active_downloads: usize,
+    // Token bucket holding up to one second worth of bytes. Negative while the client is
+    // throttled.
+    tokens: f64,
+    last_update: Instant,
+}
+
+pub struct ClientRateLimiter {
+    config: RateLimitConfig,
+    clients: Mutex<HashMap<IpAddr, ClientState>>,
+}
+
+/// Counts as one of the client's active downloads until dropped
+pub struct DownloadPermit {
+    limiter: Arc<ClientRateLimiter>,
+    client: IpAddr,
+}
+
+impl Drop for DownloadPermit {
+    fn drop(&mut self) {
+        let mut clients = self.limiter.clients.lock().unwrap();
+        if let Some(state) = clients.get_mut(&self.client) {
+            state.active_downloads = state.active_downloads.saturating_sub(1);
+            if state.active_downloads == 0 {
+                clients.remove(&self.client);
+            }
+        }
+    }
+}
+
+impl DownloadPermit {
+    /// Accounts for `bytes` sent to the client and returns how long to wait before sending them
+    /// to stay within the client's rate limit
+    pub fn throttle(&self, bytes: usize) -> Duration {
+        let Some(max_bytes_per_second) = self.limiter.config.max_bytes_per_second_per_client else {
+            return Duration::ZERO;
+        };
+        let rate = max_bytes_per_second as f64;
+        let mut clients = self.limiter.clients.lock().unwrap();
+        let Some(state) = clients.get_mut(&self.client) else {
+            return Duration::ZERO;
+        };
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_update).as_secs_f64();
+        state.last_update = now;
+        state.tokens = (state.tokens + elapsed * rate).min(rate) - bytes as f64;
+        if state.tokens < 0.0 {
+            Duration::from_secs_f64(-state.tokens / rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+impl ClientRateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            clients: Mutex::default(),
+        }
+    }
+
+    /// Starts a download for `client`, or returns None if the client has reached its maximum
+    /// number of concurrent downloads
+    pub fn start_download(self: &Arc<Self>, client: IpAddr) -> Option<DownloadPermit> {
+        let mut clients = self.clients.lock().unwrap();
+        let state = clients.entry(client).or_insert_with(|| ClientState {
+            active_downloads: 0,
+            tokens: self
+                .config
+                .max_bytes_per_second_per_client
+                .unwrap_or_default() as f64,
+            last_update: Instant::now(),
+        });
+        if self
+            .config
+            .max_downloads_per_client
+            .is_some_and(|max_downloads| state.active_downloads >= max_downloads)
+        {
+            return None;
+        }
+        state.active_downloads += 1;
+        Some(DownloadPermit {
+            limiter: self.clone(),
+            client,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::net::Ipv4Addr};
+
+    #[test]
+    fn test_max_downloads_per_client() {
+        let limiter = Arc::new(ClientRateLimiter::new(RateLimitConfig {
+            max_downloads_per_client: Some(2),
+            max_bytes_per_second_per_client: None,
+        }));
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let other_client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let first = limiter.start_download(client).unwrap();
+        let _second = limiter.start_download(client).unwrap();
+        assert!(limiter.start_download(client).is_none());
+        assert!(limiter.start_download(other_client).is_some());
+        drop(first);
+        assert!(limiter.start_download(client).is_some());
+    }
+
+    #[test]
+    fn test_max_bytes_per_second_per_client() {
+        let limiter = Arc::new(ClientRateLimiter::new(RateLimitConfig {
+            max_downloads_per_client: None,
+            max_bytes_per_second_per_client: Some(1000),
+        }));
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let first = limiter.start_download(client).unwrap();
+        let second = limiter.start_download(client).unwrap();
+        // One second worth of bytes may be sent right away, shared by the client's downloads.
+        assert_eq!(first.throttle(600), Duration::ZERO);
+        let delay = second.throttle(900);
+        assert!(delay > Duration::from_millis(400) && delay <= Duration::from_millis(500));
+
+        let unlimited = Arc::new(ClientRateLimiter::new(RateLimitConfig::default()));
+        let permit = unlimited.start_download(client).unwrap();
+        assert_eq!(permit.throttle(usize::MAX), Duration::ZERO);
+    }
+}
@@ -0,0 +1,301 @@
+//! The HTTP service serving snapshot archives.
+
+use {
+    crate::{archives::SnapshotArchives, rate_limit::ClientRateLimiter},
+    futures::{StreamExt, TryStreamExt},
+    hyper::{header, Body, Method, Request, Response, StatusCode},
+    log::*,
+    serde_json::{json, Value},
+    solana_genesis_config::DEFAULT_GENESIS_DOWNLOAD_PATH,
+    solana_hash::Hash,
+    solana_rpc::byte_range::seek_to_byte_range,
+    solana_rpc_client_types::response::RpcVersionInfo,
+    std::{
+        convert::Infallible,
+        net::SocketAddr,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+    tokio::io::AsyncReadExt,
+    tokio_util::codec::{BytesCodec, FramedRead},
+};
+
+// Same paths as the RPC service, redirecting to the highest snapshot archives
+const FULL_SNAPSHOT_REQUEST_PATH: &str = "/snapshot.tar.bz2";
+const INCREMENTAL_SNAPSHOT_REQUEST_PATH: &str = "/incremental-snapshot.tar.bz2";
+const SNAPSHOTS_PATH: &str = "/snapshots";
+const CHECKSUM_EXTENSION: &str = ".sha256";
+
+pub struct SnapshotService {
+    pub archives: Arc<SnapshotArchives>,
+    pub rate_limiter: Arc<ClientRateLimiter>,
+    /// Genesis archive served to bootstrapping validators, if any
+    pub genesis_archive: Option<PathBuf>,
+    /// Genesis hash reported by `getGenesisHash`, if any
+    pub genesis_hash: Option<Hash>,
+}
+
+fn response(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(body.into())
+        .unwrap()
+}
+
+fn json_response(value: &Value) -> Response<Body> {
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(value.to_string()))
+        .unwrap()
+}
+
+fn redirect(location: &str) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header(header::LOCATION, location)
+        .body(Body::from(location.to_string()))
+        .unwrap()
+}
+
+impl SnapshotService {
+    pub async fn handle(
+        self: Arc<Self>,
+        client: SocketAddr,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, Infallible> {
+        trace!("{client}: {} {}", request.method(), request.uri());
+        if request.method() == Method::POST && request.uri().path() == "/" {
+            return Ok(self.handle_json_rpc(request).await);
+        }
+        let response = match (request.method(), request.uri().path()) {
+            (&Method::GET, "/health") => response(StatusCode::OK, "ok"),
+            (&Method::GET, SNAPSHOTS_PATH) => json_response(&json!(self.archives.list())),
+            (&Method::GET, FULL_SNAPSHOT_REQUEST_PATH) => match self.archives.highest_full() {
+                Some(archive) => redirect(&format!("/{}", archive.file_name)),
+                None => response(StatusCode::NOT_FOUND, ""),
+            },
+            (&Method::GET, INCREMENTAL_SNAPSHOT_REQUEST_PATH) => {
+                match self.archives.highest_incremental() {
+                    Some(archive) => redirect(&format!("/{}", archive.file_name)),
+                    None => response(StatusCode::NOT_FOUND, ""),
+                }
+            }
+            (&Method::GET, DEFAULT_GENESIS_DOWNLOAD_PATH) => match &self.genesis_archive {
+                Some(genesis_archive) => self.serve_file(client, &request, genesis_archive).await,
+                None => response(StatusCode::NOT_FOUND, ""),
+            },
+            (&Method::GET, path) => {
+                let file_name = path.strip_prefix('/').unwrap_or(path);
+                if let Some(file_name) = file_name.strip_suffix(CHECKSUM_EXTENSION) {
+                    match self.archives.get(file_name) {
+                        Some(archive) => match archive.sha256 {
+                            // Same format as `sha256sum`
+                            Some(sha256) => {
+                                response(StatusCode::OK, format!("{sha256}  {file_name}\n"))
+                            }
+                            None => response(StatusCode::SERVICE_UNAVAILABLE, "not computed yet"),
+                        },
+                        None => response(StatusCode::NOT_FOUND, ""),
+                    }
+                } else {
+                    // Only archives in the catalog are served, never arbitrary paths.
+                    match self.archives.get(file_name) {
+                        Some(archive) => self.serve_file(client, &request, &archive.path).await,
+                        None => response(StatusCode::NOT_FOUND, ""),
+                    }
+                }
+            }
+            _ => response(StatusCode::METHOD_NOT_ALLOWED, ""),
+        };
+        Ok(response)
+    }
+
+    async fn serve_file(
+        &self,
+        client: SocketAddr,
+        request: &Request<Body>,
+        path: &Path,
+    ) -> Response<Body> {
+        let Some(permit) = self.rate_limiter.start_download(client.ip()) else {
+            return response(
+                StatusCode::TOO_MANY_REQUESTS,
+                "too many concurrent downloads",
+            );
+        };
+        let mut file = match tokio::fs::File::open(path).await {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return response(StatusCode::NOT_FOUND, "")
+            }
+            Err(err) => {
+                warn!("Failed to open {}: {err}", path.display());
+                return response(StatusCode::INTERNAL_SERVER_ERROR, "");
+            }
+        };
+        let Ok(file_length) = file.metadata().await.map(|metadata| metadata.len()) else {
+            return response(StatusCode::INTERNAL_SERVER_ERROR, "");
+        };
+
+        let range = request
+            .headers()
+            .get(header::RANGE)
+            .and_then(|range| range.to_str().ok());
+        let (builder, content_length) =
+            match seek_to_byte_range(&mut file, file_length, range).await {
+                Ok(response) => response,
+                Err(response) => return response,
+            };
+        info!(
+            "{client}: serving {} ({content_length} of {file_length} bytes)",
+            path.display()
+        );
+
+        let stream = FramedRead::new(file.take(content_length), BytesCodec::new())
+            .map_ok(|bytes| bytes.freeze())
+            .then(move |chunk| {
+                let delay = match &chunk {
+                    Ok(bytes) => permit.throttle(bytes.len()),
+                    Err(_) => std::time::Duration::ZERO,
+                };
+                async move {
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    chunk
+                }
+            });
+        builder
+            .header(header::CONTENT_LENGTH, content_length)
+            .body(Body::wrap_stream(stream))
+            .unwrap()
+    }
+
+    /// Answers the few JSON RPC methods validators call on a node before downloading
+    /// snapshots from it
+    async fn handle_json_rpc(&self, request: Request<Body>) -> Response<Body> {
+        let Ok(body) = hyper::body::to_bytes(request.into_body()).await else {
+            return response(StatusCode::BAD_REQUEST, "");
+        };
+        let Ok(request) = serde_json::from_slice::<Value>(&body) else {
+            return json_response(&json!({
+                "jsonrpc": "2.0",
+                "error": {"code": -32700, "message": "Parse error"},
+                "id": null,
+            }));
+        };
+        let result = match request["method"].as_str() {
+            Some("getHealth") => Ok(json!("ok")),
+            Some("getVersion") => {
+                let version = solana_version::Version::default();
+                Ok(json!(RpcVersionInfo {
+                    solana_core: version.to_string(),
+                    feature_set: Some(version.feature_set),
+                }))
+            }
+            Some("getSlot") => self
+                .archives
+                .highest_incremental()
+                .or_else(|| self.archives.highest_full())
+                .map(|archive| json!(archive.slot))
+                .ok_or((-32002, "No snapshot archives")),
+            Some("getGenesisHash") => self
+                .genesis_hash
+                .map(|genesis_hash| json!(genesis_hash.to_string()))
+                .ok_or((-32002, "Genesis hash not configured")),
+            _ => Err((-32601, "Method not found")),
+        };
+        json_response(&match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "result": result, "id": request["id"]}),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "error": {"code": code, "message": message},
+                "id": request["id"],
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rate_limit::RateLimitConfig,
+        std::{
+            fs,
+            net::{IpAddr, Ipv4Addr},
+        },
+        tempfile::TempDir,
+        tokio::runtime::Runtime,
+    };
+
+    #[test]
+    fn test_snapshot_service() {
+        let runtime = Runtime::new().unwrap();
+        let dir = TempDir::new().unwrap();
+        let hash = Hash::new_unique();
+        let file_name = format!("snapshot-100-{hash}.tar.zst");
+        fs::write(dir.path().join(&file_name), "snapshot archive").unwrap();
+        let archives = Arc::new(SnapshotArchives::new(
+            dir.path().to_path_buf(),
+            dir.path().to_path_buf(),
+        ));
+        archives.refresh();
+        let service = Arc::new(SnapshotService {
+            archives,
+            rate_limiter: Arc::new(ClientRateLimiter::new(RateLimitConfig::default())),
+            genesis_archive: None,
+            genesis_hash: None,
+        });
+        let client = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1234);
+        let get = |uri: &str, range: Option<&str>| {
+            let mut request = Request::get(uri);
+            if let Some(range) = range {
+                request = request.header(header::RANGE, range);
+            }
+            let response = runtime
+                .block_on(
+                    service
+                        .clone()
+                        .handle(client, request.body(Body::empty()).unwrap()),
+                )
+                .unwrap();
+            let status = response.status();
+            let location = response.headers().get(header::LOCATION).cloned();
+            let body = runtime
+                .block_on(hyper::body::to_bytes(response.into_body()))
+                .unwrap();
+            (status, location, body)
+        };
+
+        let (status, location, _) = get(FULL_SNAPSHOT_REQUEST_PATH, None);
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        assert_eq!(location.unwrap(), format!("/{file_name}").as_str());
+        let (status, _, _) = get(INCREMENTAL_SNAPSHOT_REQUEST_PATH, None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, body) = get(&format!("/{file_name}"), None);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(&body[..], b"snapshot archive");
+        let (status, _, body) = get(&format!("/{file_name}"), Some("bytes=9-"));
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(&body[..], b"archive");
+        let (status, _, _) = get(&format!("/{file_name}"), Some("bytes=100-"));
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        let (status, _, _) = get("/../snapshot.tar.zst", None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, _) = get(&format!("/{file_name}.sha256"), None);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        service.archives.compute_next_checksum();
+        let (status, _, body) = get(&format!("/{file_name}.sha256"), None);
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.ends_with(format!("  {file_name}\n").as_bytes()));
+
+        let (status, _, body) = get(SNAPSHOTS_PATH, None);
+        assert_eq!(status, StatusCode::OK);
+        let list: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list[0]["fileName"], file_name);
+        assert_eq!(list[0]["slot"], 100);
+        assert_eq!(list[0]["hash"], hash.to_string());
+    }
+}
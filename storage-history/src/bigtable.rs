@@ -0,0 +1,76 @@
+use {
+    crate::{HistoryStorage, Result},
+    async_trait::async_trait,
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_storage_bigtable::LedgerStorage,
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, EntrySummary, TransactionStatus,
+        VersionedConfirmedBlockWithEntries,
+    },
+};
+
+#[async_trait]
+impl HistoryStorage for LedgerStorage {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        Ok(LedgerStorage::get_first_available_block(self).await?)
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        Ok(LedgerStorage::get_confirmed_blocks(self, start_slot, limit).await?)
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        Ok(LedgerStorage::get_confirmed_block(self, slot).await?)
+    }
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
+        Ok(LedgerStorage::confirmed_block_exists(self, slot).await?)
+    }
+
+    async fn get_entries(&self, slot: Slot) -> Result<Vec<EntrySummary>> {
+        Ok(LedgerStorage::get_entries(self, slot).await?.collect())
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        Ok(LedgerStorage::get_signature_status(self, signature).await?)
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        Ok(LedgerStorage::get_confirmed_transaction(self, signature).await?)
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        Ok(LedgerStorage::get_confirmed_signatures_for_address(
+            self,
+            address,
+            before_signature,
+            until_signature,
+            limit,
+        )
+        .await?)
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        Ok(LedgerStorage::upload_confirmed_block_with_entries(self, slot, confirmed_block).await?)
+    }
+
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()> {
+        Ok(LedgerStorage::delete_confirmed_block(self, slot, dry_run).await?)
+    }
+}
@@ -0,0 +1,621 @@
+//! [`HistoryStorage`] on top of a plain ordered key-value store.
+//!
+//! Rows use the same tables and keys as the Bigtable backend:
+//!
+//! - `blocks/<slot>`: protobuf encoded `ConfirmedBlock`
+//! - `entries/<slot>`: protobuf encoded `Entries`
+//! - `tx/<signature>`: bincode encoded [`StoredTransactionInfo`]
+//! - `tx-by-addr/<address>/<!slot>`: protobuf encoded `TransactionByAddr`
+//!
+//! Slots are encoded as 16 hex digits so lexical order matches numerical order. The slot in
+//! `tx-by-addr` keys is inverted so that listing an address returns the most recent slots first.
+
+use {
+    crate::{Error, HistoryStorage, Result},
+    agave_reserved_account_keys::ReservedAccountKeys,
+    async_trait::async_trait,
+    log::*,
+    prost::Message,
+    serde_derive::{Deserialize, Serialize},
+    solana_clock::Slot,
+    solana_metrics::datapoint_info,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_storage_proto::convert::{entries, generated, tx_by_addr},
+    solana_transaction_error::TransactionError,
+    solana_transaction_status::{
+        extract_and_fmt_memos, ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, EntrySummary, TransactionByAddrInfo,
+        TransactionConfirmationStatus, TransactionStatus, VersionedConfirmedBlockWithEntries,
+        VersionedTransactionWithStatusMeta,
+    },
+    std::collections::{HashMap, HashSet},
+};
+
+pub const BLOCKS_TABLE: &str = "blocks";
+pub const ENTRIES_TABLE: &str = "entries";
+pub const TX_TABLE: &str = "tx";
+pub const TX_BY_ADDR_TABLE: &str = "tx-by-addr";
+
+/// Number of keys requested at a time when scanning the `tx-by-addr` table
+const TX_BY_ADDR_LIST_BATCH_SIZE: usize = 100;
+
+/// An ordered key-value store, partitioned into tables.
+#[async_trait]
+pub trait KeyValueStore: Send + Sync {
+    /// Returns the value stored at `key`, if any
+    async fn get(&self, table: &str, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Returns true if a value is stored at `key`
+    async fn contains_key(&self, table: &str, key: &str) -> Result<bool> {
+        Ok(self.get(table, key).await?.is_some())
+    }
+
+    /// Stores all the given rows, returning the number of bytes written
+    async fn put(&self, table: &str, rows: Vec<(String, Vec<u8>)>) -> Result<usize>;
+
+    /// Deletes the given keys, ignoring keys which don't exist
+    async fn delete(&self, table: &str, keys: Vec<String>) -> Result<()>;
+
+    /// Returns up to `limit` keys of `table` in ascending lexical order which start with
+    /// `prefix` and sort after `start_after`
+    async fn list_keys(
+        &self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>>;
+}
+
+/// Where a transaction is located, stored in the `tx` table
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StoredTransactionInfo {
+    pub slot: Slot,
+    pub index: u32,
+    pub err: Option<TransactionError>,
+    pub memo: Option<String>,
+}
+
+impl From<StoredTransactionInfo> for TransactionStatus {
+    fn from(transaction_info: StoredTransactionInfo) -> Self {
+        let StoredTransactionInfo { slot, err, .. } = transaction_info;
+        let status = match &err {
+            None => Ok(()),
+            Some(err) => Err(err.clone()),
+        };
+        Self {
+            slot,
+            confirmations: None,
+            status,
+            err,
+            confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+        }
+    }
+}
+
+fn slot_to_key(slot: Slot) -> String {
+    format!("{slot:016x}")
+}
+
+fn key_to_slot(key: &str) -> Option<Slot> {
+    match Slot::from_str_radix(key, 16) {
+        Ok(slot) => Some(slot),
+        Err(err) => {
+            warn!("Failed to parse object key as a slot: {key}: {err}");
+            None
+        }
+    }
+}
+
+fn tx_by_addr_key(address: &Pubkey, slot: Slot) -> String {
+    format!("{address}/{}", slot_to_key(!slot))
+}
+
+fn deserialize_transaction_info(key: &str, data: &[u8]) -> Result<StoredTransactionInfo> {
+    bincode::deserialize(data)
+        .map_err(|err| Error::ObjectCorrupt(format!("{TX_TABLE}/{key}: {err}")))
+}
+
+/// A [`HistoryStorage`] which keeps its rows in a [`KeyValueStore`]
+pub struct KeyValueHistoryStorage<S> {
+    store: S,
+}
+
+impl<S: KeyValueStore> KeyValueHistoryStorage<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    async fn get_transaction_info(&self, signature: &Signature) -> Result<StoredTransactionInfo> {
+        let key = signature.to_string();
+        let data = self
+            .store
+            .get(TX_TABLE, &key)
+            .await?
+            .ok_or(Error::SignatureNotFound)?;
+        deserialize_transaction_info(&key, &data)
+    }
+
+    async fn get_tx_by_addr(&self, key: &str) -> Result<Option<Vec<TransactionByAddrInfo>>> {
+        let Some(data) = self.store.get(TX_BY_ADDR_TABLE, key).await? else {
+            return Ok(None);
+        };
+        let tx_by_addr = tx_by_addr::TransactionByAddr::decode(data.as_slice())
+            .map_err(|err| Error::ObjectCorrupt(format!("{TX_BY_ADDR_TABLE}/{key}: {err}")))?;
+        tx_by_addr
+            .try_into()
+            .map(Some)
+            .map_err(|err| Error::ObjectCorrupt(format!("{TX_BY_ADDR_TABLE}/{key}: {err}")))
+    }
+}
+
+#[async_trait]
+impl<S: KeyValueStore> HistoryStorage for KeyValueHistoryStorage<S> {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        let keys = self.store.list_keys(BLOCKS_TABLE, "", None, 1).await?;
+        Ok(keys.first().and_then(|key| key_to_slot(key)))
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        let start_after = start_slot.checked_sub(1).map(slot_to_key);
+        let keys = self
+            .store
+            .list_keys(BLOCKS_TABLE, "", start_after.as_deref(), limit)
+            .await?;
+        Ok(keys.iter().filter_map(|key| key_to_slot(key)).collect())
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        let key = slot_to_key(slot);
+        let data = self
+            .store
+            .get(BLOCKS_TABLE, &key)
+            .await?
+            .ok_or(Error::BlockNotFound(slot))?;
+        generated::ConfirmedBlock::decode(data.as_slice())
+            .map_err(|err| Error::ObjectCorrupt(format!("{BLOCKS_TABLE}/{key}: {err}")))?
+            .try_into()
+            .map_err(|err| Error::ObjectCorrupt(format!("{BLOCKS_TABLE}/{key}: {err}")))
+    }
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
+        self.store
+            .contains_key(BLOCKS_TABLE, &slot_to_key(slot))
+            .await
+    }
+
+    async fn get_entries(&self, slot: Slot) -> Result<Vec<EntrySummary>> {
+        let key = slot_to_key(slot);
+        let data = self
+            .store
+            .get(ENTRIES_TABLE, &key)
+            .await?
+            .ok_or(Error::BlockNotFound(slot))?;
+        let entries = entries::Entries::decode(data.as_slice())
+            .map_err(|err| Error::ObjectCorrupt(format!("{ENTRIES_TABLE}/{key}: {err}")))?;
+        Ok(entries.entries.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        Ok(self.get_transaction_info(signature).await?.into())
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        // Figure out which block the transaction is located in
+        let StoredTransactionInfo { slot, index, .. } =
+            self.get_transaction_info(signature).await?;
+
+        // Load the block and return the transaction
+        let block = self.get_confirmed_block(slot).await?;
+        match block.transactions.into_iter().nth(index as usize) {
+            None => {
+                warn!("Transaction info for {signature} is corrupt");
+                Ok(None)
+            }
+            Some(tx_with_meta) => {
+                if tx_with_meta.transaction_signature() != signature {
+                    warn!("Transaction info or confirmed block for {signature} is corrupt");
+                    Ok(None)
+                } else {
+                    Ok(Some(ConfirmedTransactionWithStatusMeta {
+                        slot,
+                        tx_with_meta,
+                        block_time: block.block_time,
+                    }))
+                }
+            }
+        }
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        // Figure out where to start listing from based on `before_signature`
+        let (first_slot, before_transaction_index) = match before_signature {
+            None => (Slot::MAX, 0),
+            Some(before_signature) => {
+                let StoredTransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(before_signature).await?;
+                (slot, index)
+            }
+        };
+
+        // Figure out where to end listing from based on `until_signature`
+        let (last_slot, until_transaction_index) = match until_signature {
+            None => (0, u32::MAX),
+            Some(until_signature) => {
+                let StoredTransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(until_signature).await?;
+                (slot, index)
+            }
+        };
+
+        let address_prefix = format!("{address}/");
+        let last_key = tx_by_addr_key(address, last_slot);
+        // Keys are listed exclusive of `start_after`, so start right before `first_slot`
+        let mut start_after = (!first_slot)
+            .checked_sub(1)
+            .map(|inverted_slot| tx_by_addr_key(address, !inverted_slot));
+        let mut infos = vec![];
+
+        'outer: loop {
+            let keys = self
+                .store
+                .list_keys(
+                    TX_BY_ADDR_TABLE,
+                    &address_prefix,
+                    start_after.as_deref(),
+                    TX_BY_ADDR_LIST_BATCH_SIZE,
+                )
+                .await?;
+            let num_keys = keys.len();
+
+            for key in keys {
+                if key > last_key {
+                    break 'outer;
+                }
+                let slot = key_to_slot(&key[address_prefix.len()..])
+                    .map(|inverted_slot| !inverted_slot)
+                    .ok_or_else(|| {
+                        Error::ObjectCorrupt(format!(
+                            "Failed to convert key to slot: {TX_BY_ADDR_TABLE}/{key}"
+                        ))
+                    })?;
+
+                let mut tx_by_addr_infos = self.get_tx_by_addr(&key).await?.unwrap_or_default();
+                tx_by_addr_infos.reverse();
+                for tx_by_addr_info in tx_by_addr_infos {
+                    // Filter out records before `before_transaction_index`
+                    if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
+                        continue;
+                    }
+                    // Filter out records after `until_transaction_index`
+                    if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
+                        continue;
+                    }
+                    infos.push((
+                        ConfirmedTransactionStatusWithSignature {
+                            signature: tx_by_addr_info.signature,
+                            slot,
+                            err: tx_by_addr_info.err,
+                            memo: tx_by_addr_info.memo,
+                            block_time: tx_by_addr_info.block_time,
+                        },
+                        tx_by_addr_info.index,
+                    ));
+                    // Respect limit
+                    if infos.len() >= limit {
+                        break 'outer;
+                    }
+                }
+                start_after = Some(key);
+            }
+
+            if num_keys < TX_BY_ADDR_LIST_BATCH_SIZE {
+                break;
+            }
+        }
+        Ok(infos)
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        trace!("KeyValueHistoryStorage::upload_confirmed_block_with_entries request received: {slot:?}");
+        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let VersionedConfirmedBlockWithEntries {
+            block: confirmed_block,
+            entries,
+        } = confirmed_block;
+
+        let reserved_account_keys = ReservedAccountKeys::new_all_activated();
+        let mut tx_rows = Vec::with_capacity(confirmed_block.transactions.len());
+        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
+            let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
+            let err = meta.status.clone().err();
+            let index = index as u32;
+            let signature = transaction.signatures[0];
+            let memo = extract_and_fmt_memos(transaction_with_meta);
+
+            for address in transaction_with_meta.account_keys().iter() {
+                if !reserved_account_keys.is_reserved(address) {
+                    by_addr
+                        .entry(address)
+                        .or_default()
+                        .push(TransactionByAddrInfo {
+                            signature,
+                            err: err.clone(),
+                            index,
+                            memo: memo.clone(),
+                            block_time: confirmed_block.block_time,
+                        });
+                }
+            }
+
+            let transaction_info = StoredTransactionInfo {
+                slot,
+                index,
+                err,
+                memo,
+            };
+            tx_rows.push((
+                signature.to_string(),
+                bincode::serialize(&transaction_info).expect("serialize transaction info"),
+            ));
+        }
+
+        let tx_by_addr_rows: Vec<_> = by_addr
+            .into_iter()
+            .map(|(address, transaction_info_by_addr)| {
+                (
+                    tx_by_addr_key(address, slot),
+                    tx_by_addr::TransactionByAddr {
+                        tx_by_addrs: transaction_info_by_addr
+                            .into_iter()
+                            .map(|by_addr| by_addr.into())
+                            .collect(),
+                    }
+                    .encode_to_vec(),
+                )
+            })
+            .collect();
+
+        let num_entries = entries.len();
+        let entries_row = (
+            slot_to_key(slot),
+            entries::Entries {
+                entries: entries.into_iter().enumerate().map(Into::into).collect(),
+            }
+            .encode_to_vec(),
+        );
+
+        let mut bytes_written: usize = 0;
+        if !tx_rows.is_empty() {
+            bytes_written = bytes_written.saturating_add(self.store.put(TX_TABLE, tx_rows).await?);
+        }
+        if !tx_by_addr_rows.is_empty() {
+            bytes_written = bytes_written
+                .saturating_add(self.store.put(TX_BY_ADDR_TABLE, tx_by_addr_rows).await?);
+        }
+        if num_entries > 0 {
+            bytes_written = bytes_written
+                .saturating_add(self.store.put(ENTRIES_TABLE, vec![entries_row]).await?);
+        }
+
+        let num_transactions = confirmed_block.transactions.len();
+
+        // Store the block itself last, after all other metadata about the block has been
+        // successfully stored.  This avoids partial uploaded blocks from becoming visible to
+        // `get_confirmed_block()` and `get_confirmed_blocks()`
+        let block_row = (
+            slot_to_key(slot),
+            generated::ConfirmedBlock::from(confirmed_block).encode_to_vec(),
+        );
+        bytes_written =
+            bytes_written.saturating_add(self.store.put(BLOCKS_TABLE, vec![block_row]).await?);
+        datapoint_info!(
+            "storage-history-upload-block",
+            ("slot", slot, i64),
+            ("transactions", num_transactions, i64),
+            ("entries", num_entries, i64),
+            ("bytes", bytes_written, i64),
+        );
+        Ok(())
+    }
+
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()> {
+        let confirmed_block = self.get_confirmed_block(slot).await?;
+        let mut addresses: HashSet<Pubkey> = HashSet::new();
+        let mut signatures = Vec::with_capacity(confirmed_block.transactions.len());
+        for transaction_with_meta in &confirmed_block.transactions {
+            // Rows are deleted for all addresses, including reserved ones, since it's hard to
+            // be sure which were uploaded
+            addresses.extend(transaction_with_meta.account_keys().iter().copied());
+            signatures.push(*transaction_with_meta.transaction_signature());
+        }
+
+        let tx_by_addr_keys: Vec<_> = addresses
+            .iter()
+            .map(|address| tx_by_addr_key(address, slot))
+            .collect();
+
+        // Only delete transaction rows which still point at this block
+        let mut tx_keys = Vec::with_capacity(signatures.len());
+        for signature in signatures {
+            match self.get_transaction_info(&signature).await {
+                Ok(transaction_info) if transaction_info.slot == slot => {
+                    tx_keys.push(signature.to_string());
+                }
+                Ok(transaction_info) => {
+                    warn!(
+                        "skipped tx row {signature} because it points at slot {}",
+                        transaction_info.slot
+                    );
+                }
+                Err(err) => warn!("skipped tx row {signature}: {err}"),
+            }
+        }
+
+        if dry_run {
+            info!(
+                "Dry run: would delete block {slot}, {} tx rows and {} tx-by-addr rows",
+                tx_keys.len(),
+                tx_by_addr_keys.len()
+            );
+            return Ok(());
+        }
+
+        // Delete the block first, so a partially deleted block is no longer visible
+        self.store
+            .delete(BLOCKS_TABLE, vec![slot_to_key(slot)])
+            .await?;
+        self.store
+            .delete(ENTRIES_TABLE, vec![slot_to_key(slot)])
+            .await?;
+        self.store.delete(TX_TABLE, tx_keys).await?;
+        self.store.delete(TX_BY_ADDR_TABLE, tx_by_addr_keys).await?;
+        info!("Deleted block {slot}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rocksdb_store::RocksDbStore,
+        solana_hash::Hash,
+        solana_keypair::Keypair,
+        solana_system_transaction as system_transaction,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::{TransactionStatusMeta, VersionedConfirmedBlock},
+    };
+
+    fn block_with_transfer(
+        slot: Slot,
+        from: &Keypair,
+        recipient: &Pubkey,
+    ) -> (VersionedConfirmedBlockWithEntries, Signature) {
+        let transaction = system_transaction::transfer(from, recipient, 42, Hash::new_unique());
+        let signature = transaction.signatures[0];
+        let block = VersionedConfirmedBlock {
+            previous_blockhash: Hash::default().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot: slot.saturating_sub(1),
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction::from(transaction),
+                meta: TransactionStatusMeta {
+                    fee: 5000,
+                    pre_balances: vec![10_000, 0, 1],
+                    post_balances: vec![4958, 42, 1],
+                    ..TransactionStatusMeta::default()
+                },
+            }],
+            rewards: vec![],
+            num_partitions: None,
+            block_time: Some(1_700_000_000),
+            block_height: Some(slot),
+        };
+        let entries = vec![EntrySummary {
+            num_hashes: 1,
+            hash: Hash::new_unique(),
+            num_transactions: 1,
+            starting_transaction_index: 0,
+        }];
+        (
+            VersionedConfirmedBlockWithEntries { block, entries },
+            signature,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_upload_and_query() {
+        let ledger_path = tempfile::TempDir::new().unwrap();
+        let storage = KeyValueHistoryStorage::new(RocksDbStore::open(ledger_path.path()).unwrap());
+        let from = Keypair::new();
+        let recipient = solana_pubkey::new_rand();
+
+        let mut signatures = vec![];
+        for slot in [3, 5, 8] {
+            let (block, signature) = block_with_transfer(slot, &from, &recipient);
+            storage
+                .upload_confirmed_block_with_entries(slot, block)
+                .await
+                .unwrap();
+            signatures.push(signature);
+        }
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(3));
+        assert_eq!(
+            storage.get_confirmed_blocks(4, 10).await.unwrap(),
+            vec![5, 8]
+        );
+        assert!(storage.confirmed_block_exists(5).await.unwrap());
+        assert!(!storage.confirmed_block_exists(4).await.unwrap());
+        assert!(matches!(
+            storage.get_confirmed_block(4).await,
+            Err(Error::BlockNotFound(4))
+        ));
+        assert_eq!(storage.get_entries(5).await.unwrap().len(), 1);
+
+        let confirmed_block = storage.get_confirmed_block(5).await.unwrap();
+        assert_eq!(confirmed_block.transactions.len(), 1);
+        assert_eq!(confirmed_block.block_height, Some(5));
+
+        let status = storage.get_signature_status(&signatures[1]).await.unwrap();
+        assert_eq!(status.slot, 5);
+        let transaction = storage
+            .get_confirmed_transaction(&signatures[1])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.slot, 5);
+        assert!(matches!(
+            storage.get_signature_status(&Signature::default()).await,
+            Err(Error::SignatureNotFound)
+        ));
+
+        // Most recent first, and `before` is exclusive
+        let results = storage
+            .get_confirmed_signatures_for_address(&recipient, None, None, 10)
+            .await
+            .unwrap();
+        let slots: Vec<_> = results.iter().map(|(status, _)| status.slot).collect();
+        assert_eq!(slots, vec![8, 5, 3]);
+        let results = storage
+            .get_confirmed_signatures_for_address(&recipient, Some(&signatures[2]), None, 1)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.signature, signatures[1]);
+
+        storage.delete_confirmed_block(5, true).await.unwrap();
+        assert!(storage.confirmed_block_exists(5).await.unwrap());
+        storage.delete_confirmed_block(5, false).await.unwrap();
+        assert!(!storage.confirmed_block_exists(5).await.unwrap());
+        assert!(matches!(
+            storage.get_confirmed_transaction(&signatures[1]).await,
+            Err(Error::SignatureNotFound)
+        ));
+        let results = storage
+            .get_confirmed_signatures_for_address(&recipient, None, None, 10)
+            .await
+            .unwrap();
+        let slots: Vec<_> = results.iter().map(|(status, _)| status.slot).collect();
+        assert_eq!(slots, vec![8, 3]);
+    }
+}
@@ -0,0 +1,164 @@
+//! Long-term storage of confirmed blocks and transaction history.
+//!
+//! RPC nodes fall back to a [`HistoryStorage`] for blocks and transactions that have been
+//! purged from their local ledger, and the ledger upload service copies newly rooted blocks
+//! into one. Three backends are provided:
+//!
+//! - Bigtable, through [`solana_storage_bigtable::LedgerStorage`]
+//! - a local RocksDB archive, see [`rocksdb_store::RocksDbStore`]
+//! - any S3 compatible object store, see [`s3::S3Store`]
+
+use {
+    async_trait::async_trait,
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, EntrySummary, TransactionStatus,
+        VersionedConfirmedBlock, VersionedConfirmedBlockWithEntries,
+    },
+    std::{path::PathBuf, sync::Arc},
+    thiserror::Error,
+};
+
+mod bigtable;
+pub mod key_value;
+pub mod rocksdb_store;
+pub mod s3;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Block not found: {0}")]
+    BlockNotFound(Slot),
+
+    #[error("Signature not found")]
+    SignatureNotFound,
+
+    #[error("BigTable: {0}")]
+    BigTable(solana_storage_bigtable::Error),
+
+    #[error("RocksDB: {0}")]
+    RocksDb(#[from] rocksdb::Error),
+
+    #[error("Object store: {0}")]
+    ObjectStore(String),
+
+    #[error("Object corrupt: {0}")]
+    ObjectCorrupt(String),
+
+    #[error("I/O Error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("tokio error")]
+    TokioJoinError(#[from] tokio::task::JoinError),
+}
+
+impl From<solana_storage_bigtable::Error> for Error {
+    fn from(err: solana_storage_bigtable::Error) -> Self {
+        match err {
+            solana_storage_bigtable::Error::BlockNotFound(slot) => Self::BlockNotFound(slot),
+            solana_storage_bigtable::Error::SignatureNotFound => Self::SignatureNotFound,
+            err => Self::BigTable(err),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Storage for the full history of confirmed blocks and the transactions in them.
+#[async_trait]
+pub trait HistoryStorage: Send + Sync {
+    /// Return the available slot that contains a block
+    async fn get_first_available_block(&self) -> Result<Option<Slot>>;
+
+    /// Fetch the next slots after the provided slot that contains a block
+    ///
+    /// start_slot: slot to start the search from (inclusive)
+    /// limit: stop after this many slots have been found
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>>;
+
+    /// Fetch the confirmed block from the desired slot
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock>;
+
+    /// Does the confirmed block exist in the storage
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool>;
+
+    /// Fetch the entries of the confirmed block from the desired slot
+    async fn get_entries(&self, slot: Slot) -> Result<Vec<EntrySummary>>;
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus>;
+
+    /// Fetch a confirmed transaction
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>>;
+
+    /// Get confirmed signatures for the provided address, in descending ledger order
+    ///
+    /// address: address to search for
+    /// before_signature: start with the first signature older than this one
+    /// until_signature: end with the last signature more recent than this one
+    /// limit: stop after this many signatures
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32, /*slot index*/
+        )>,
+    >;
+
+    /// Upload a new confirmed block and associated meta data.
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<()> {
+        self.upload_confirmed_block_with_entries(
+            slot,
+            VersionedConfirmedBlockWithEntries {
+                block: confirmed_block,
+                entries: vec![],
+            },
+        )
+        .await
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()>;
+
+    /// Delete a confirmed block and associated meta data.
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()>;
+}
+
+/// Configuration of the backends which are not Bigtable.
+///
+/// Bigtable is configured through [`solana_storage_bigtable::LedgerStorageConfig`].
+#[derive(Clone, Debug)]
+pub enum HistoryStorageConfig {
+    /// A RocksDB archive at the given directory
+    RocksDb { path: PathBuf },
+    /// An S3 compatible object store
+    S3(s3::S3Config),
+}
+
+/// Opens the backend described by `config`
+pub fn open(config: &HistoryStorageConfig) -> Result<Arc<dyn HistoryStorage>> {
+    Ok(match config {
+        HistoryStorageConfig::RocksDb { path } => Arc::new(key_value::KeyValueHistoryStorage::new(
+            rocksdb_store::RocksDbStore::open(path)?,
+        )),
+        HistoryStorageConfig::S3(config) => Arc::new(key_value::KeyValueHistoryStorage::new(
+            s3::S3Store::new(config.clone())?,
+        )),
+    })
+}
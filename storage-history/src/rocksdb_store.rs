@@ -0,0 +1,163 @@
+//! A local archive kept in a RocksDB database.
+
+use {
+    crate::{key_value::KeyValueStore, Result},
+    async_trait::async_trait,
+    rocksdb::{DBCompressionType, Direction, IteratorMode, Options, WriteBatch, DB},
+    std::{path::Path, sync::Arc},
+};
+
+/// A [`KeyValueStore`] which keeps every table in a single RocksDB database, with keys of the
+/// form `<table>/<key>`
+///
+/// RocksDB calls block, so they run on tokio's blocking thread pool rather than on the runtime
+/// serving the requests.
+pub struct RocksDbStore {
+    db: Arc<DB>,
+}
+
+impl RocksDbStore {
+    /// Opens the database at `path`, creating it if it doesn't exist yet
+    pub fn open(path: &Path) -> Result<Self> {
+        std::fs::create_dir_all(path)?;
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.set_compression_type(DBCompressionType::Lz4);
+        let db = DB::open(&options, path)?;
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    fn db_key(table: &str, key: &str) -> String {
+        format!("{table}/{key}")
+    }
+
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&DB) -> Result<T> + Send + 'static,
+    {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || f(&db)).await?
+    }
+}
+
+#[async_trait]
+impl KeyValueStore for RocksDbStore {
+    async fn get(&self, table: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        let db_key = Self::db_key(table, key);
+        self.run_blocking(move |db| Ok(db.get(db_key)?)).await
+    }
+
+    async fn put(&self, table: &str, rows: Vec<(String, Vec<u8>)>) -> Result<usize> {
+        let mut batch = WriteBatch::default();
+        let mut bytes_written: usize = 0;
+        for (key, value) in rows {
+            bytes_written = bytes_written.saturating_add(value.len());
+            batch.put(Self::db_key(table, &key), value);
+        }
+        self.run_blocking(move |db| Ok(db.write(batch)?)).await?;
+        Ok(bytes_written)
+    }
+
+    async fn delete(&self, table: &str, keys: Vec<String>) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        for key in keys {
+            batch.delete(Self::db_key(table, &key));
+        }
+        self.run_blocking(move |db| Ok(db.write(batch)?)).await
+    }
+
+    async fn list_keys(
+        &self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let table_prefix_len = table.len().saturating_add(1);
+        let db_prefix = Self::db_key(table, prefix);
+        // Keys sorting before the prefix can't match it, so never start before the prefix
+        let start_after = start_after.map(|key| Self::db_key(table, key));
+        let start = match &start_after {
+            Some(start_after) if *start_after > db_prefix => start_after.clone(),
+            _ => db_prefix.clone(),
+        };
+        self.run_blocking(move |db| {
+            let mut keys = Vec::new();
+            for item in db.iterator(IteratorMode::From(start.as_bytes(), Direction::Forward)) {
+                if keys.len() >= limit {
+                    break;
+                }
+                let (db_key, _value) = item?;
+                if !db_key.starts_with(db_prefix.as_bytes()) {
+                    break;
+                }
+                let Ok(db_key) = std::str::from_utf8(&db_key) else {
+                    continue;
+                };
+                if start_after.as_deref() == Some(db_key) {
+                    continue;
+                }
+                keys.push(db_key[table_prefix_len..].to_string());
+            }
+            Ok(keys)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[tokio::test]
+    async fn test_list_keys() {
+        let ledger_path = TempDir::new().unwrap();
+        let store = RocksDbStore::open(ledger_path.path()).unwrap();
+        store
+            .put(
+                "a",
+                ["x/1", "x/2", "x/3", "y/1"]
+                    .into_iter()
+                    .map(|key| (key.to_string(), vec![0]))
+                    .collect(),
+            )
+            .await
+            .unwrap();
+        store
+            .put("b", vec![("x/0".to_string(), vec![1, 2])])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.list_keys("a", "x/", None, 10).await.unwrap(),
+            vec!["x/1", "x/2", "x/3"]
+        );
+        assert_eq!(
+            store.list_keys("a", "x/", Some("x/1"), 1).await.unwrap(),
+            vec!["x/2"]
+        );
+        assert_eq!(
+            store.list_keys("a", "", Some("x/3"), 10).await.unwrap(),
+            vec!["y/1"]
+        );
+        // starting before the prefix still fills the page with keys matching it
+        assert_eq!(
+            store.list_keys("a", "y/", Some("x/1"), 10).await.unwrap(),
+            vec!["y/1"]
+        );
+        assert_eq!(
+            store.list_keys("a", "x/", Some("a"), 2).await.unwrap(),
+            vec!["x/1", "x/2"]
+        );
+        assert!(store
+            .list_keys("a", "z/", None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(store.get("b", "x/0").await.unwrap(), Some(vec![1, 2]));
+
+        store.delete("b", vec!["x/0".to_string()]).await.unwrap();
+        assert_eq!(store.get("b", "x/0").await.unwrap(), None);
+    }
+}
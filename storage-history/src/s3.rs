@@ -0,0 +1,476 @@
+//! An archive kept in an S3 compatible object store, such as AWS S3 or MinIO.
+//!
+//! Requests are signed with AWS Signature Version 4 and use path-style addressing
+//! (`<endpoint>/<bucket>/<object>`), which every S3 compatible store supports.
+
+use {
+    crate::{key_value::KeyValueStore, Error, Result},
+    async_trait::async_trait,
+    futures::stream::{self, StreamExt, TryStreamExt},
+    hmac::{Hmac, Mac},
+    quick_xml::{events::Event, Reader},
+    reqwest::{Method, StatusCode, Url},
+    sha2::{Digest, Sha256},
+    std::time::Duration,
+};
+
+pub const DEFAULT_REGION: &str = "us-east-1";
+/// Largest number of keys S3 returns from a single list request
+const MAX_KEYS_PER_LIST_REQUEST: usize = 1000;
+/// Number of object requests that are in flight at a time when writing or deleting rows
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+
+#[derive(Clone)]
+pub struct S3Config {
+    /// Base URL of the object store, e.g. `https://s3.us-east-1.amazonaws.com` or
+    /// `http://127.0.0.1:9000` for a local MinIO
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub timeout: Option<Duration>,
+}
+
+impl std::fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Config")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("access_key_id", &self.access_key_id)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`KeyValueStore`] which keeps each row in an object named `<table>/<key>`
+pub struct S3Store {
+    client: reqwest::Client,
+    config: S3Config,
+}
+
+impl S3Store {
+    pub fn new(config: S3Config) -> Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let client = builder
+            .build()
+            .map_err(|err| Error::ObjectStore(err.to_string()))?;
+        Ok(Self { client, config })
+    }
+
+    fn url(&self, object: Option<&str>, query: &[(&str, &str)]) -> Result<Url> {
+        let mut url = format!(
+            "{}/{}",
+            self.config.endpoint.trim_end_matches('/'),
+            uri_encode(&self.config.bucket, false)
+        );
+        if let Some(object) = object {
+            url.push('/');
+            url.push_str(&uri_encode(object, false));
+        }
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&canonical_query_string(query));
+        }
+        Url::parse(&url).map_err(|err| Error::ObjectStore(format!("{url}: {err}")))
+    }
+
+    async fn send(&self, method: Method, url: Url, body: Vec<u8>) -> Result<(StatusCode, Vec<u8>)> {
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(Error::ObjectStore(format!("{url}: missing host"))),
+        };
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let authorization = authorization_header(
+            &self.config,
+            method.as_str(),
+            url.path(),
+            url.query().unwrap_or_default(),
+            &host,
+            &payload_hash,
+            &amz_date,
+        );
+
+        let response = self
+            .client
+            .request(method.clone(), url.clone())
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| Error::ObjectStore(format!("{method} {url}: {err}")))?;
+        let status = response.status();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| Error::ObjectStore(format!("{method} {url}: {err}")))?;
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            return Err(Error::ObjectStore(format!(
+                "{method} {url}: {status}: {}",
+                String::from_utf8_lossy(&body)
+            )));
+        }
+        Ok((status, body.to_vec()))
+    }
+
+    fn object_name(table: &str, key: &str) -> String {
+        format!("{table}/{key}")
+    }
+}
+
+#[async_trait]
+impl KeyValueStore for S3Store {
+    async fn get(&self, table: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        let url = self.url(Some(&Self::object_name(table, key)), &[])?;
+        let (status, body) = self.send(Method::GET, url, vec![]).await?;
+        Ok((status != StatusCode::NOT_FOUND).then_some(body))
+    }
+
+    async fn contains_key(&self, table: &str, key: &str) -> Result<bool> {
+        let url = self.url(Some(&Self::object_name(table, key)), &[])?;
+        let (status, _body) = self.send(Method::HEAD, url, vec![]).await?;
+        Ok(status != StatusCode::NOT_FOUND)
+    }
+
+    async fn put(&self, table: &str, rows: Vec<(String, Vec<u8>)>) -> Result<usize> {
+        stream::iter(rows)
+            .map(|(key, value)| async move {
+                let url = self.url(Some(&Self::object_name(table, &key)), &[])?;
+                let bytes_written = value.len();
+                self.send(Method::PUT, url, value).await?;
+                Ok::<_, Error>(bytes_written)
+            })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .try_fold(0usize, |total, bytes_written| async move {
+                Ok(total.saturating_add(bytes_written))
+            })
+            .await
+    }
+
+    async fn delete(&self, table: &str, keys: Vec<String>) -> Result<()> {
+        stream::iter(keys)
+            .map(|key| async move {
+                let url = self.url(Some(&Self::object_name(table, &key)), &[])?;
+                self.send(Method::DELETE, url, vec![]).await?;
+                Ok::<_, Error>(())
+            })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+
+    async fn list_keys(
+        &self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let table_prefix = format!("{table}/");
+        let object_prefix = Self::object_name(table, prefix);
+        let start_after = start_after.map(|key| Self::object_name(table, key));
+        let mut continuation_token = None;
+        let mut keys = Vec::new();
+        while keys.len() < limit {
+            let max_keys = limit
+                .saturating_sub(keys.len())
+                .min(MAX_KEYS_PER_LIST_REQUEST)
+                .to_string();
+            let mut query = vec![
+                ("list-type", "2"),
+                ("max-keys", max_keys.as_str()),
+                ("prefix", object_prefix.as_str()),
+            ];
+            // Later pages pick up where the previous one ended, `start-after` only applies to
+            // the first one
+            if let Some(continuation_token) = &continuation_token {
+                query.push(("continuation-token", continuation_token.as_str()));
+            } else if let Some(start_after) = &start_after {
+                query.push(("start-after", start_after.as_str()));
+            }
+            let url = self.url(None, &query)?;
+            let (status, body) = self.send(Method::GET, url, vec![]).await?;
+            if status == StatusCode::NOT_FOUND {
+                return Err(Error::ObjectStore(format!(
+                    "bucket {} not found",
+                    self.config.bucket
+                )));
+            }
+            let page = parse_list_objects(&body)?;
+            keys.extend(
+                page.objects
+                    .into_iter()
+                    .filter_map(|object| object.strip_prefix(&table_prefix).map(str::to_string)),
+            );
+            continuation_token = page.next_continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// Percent-encodes everything but the unreserved characters, and `/` unless `encode_slash`
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn canonical_query_string(query: &[(&str, &str)]) -> String {
+    let mut query: Vec<_> = query
+        .iter()
+        .map(|(name, value)| (uri_encode(name, true), uri_encode(value, true)))
+        .collect();
+    query.sort();
+    query
+        .into_iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let date_key = hmac_sha256(format!("AWS4{secret_access_key}").as_bytes(), date);
+    let region_key = hmac_sha256(&date_key, region);
+    let service_key = hmac_sha256(&region_key, service);
+    hmac_sha256(&service_key, "aws4_request")
+}
+
+/// Builds the `Authorization` header of a request signed with AWS Signature Version 4
+fn authorization_header(
+    config: &S3Config,
+    method: &str,
+    canonical_uri: &str,
+    canonical_query: &str,
+    host: &str,
+    payload_hash: &str,
+    amz_date: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{method}\n{canonical_uri}\n{canonical_query}\nhost:{host}\nx-amz-content-sha256:\
+         {payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+    );
+    let scope = format!("{date}/{}/s3/aws4_request", config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signature = hex::encode(hmac_sha256(
+        &signing_key(&config.secret_access_key, date, &config.region, "s3"),
+        &string_to_sign,
+    ));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, \
+         Signature={signature}",
+        config.access_key_id
+    )
+}
+
+/// One page of a `ListObjectsV2` response
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ListObjectsPage {
+    objects: Vec<String>,
+    /// Set when the listing was truncated, and passed back to fetch the next page
+    next_continuation_token: Option<String>,
+}
+
+/// Parses a `ListObjectsV2` response. Elements are matched by their local name so that
+/// namespace prefixes don't matter.
+fn parse_list_objects(body: &[u8]) -> Result<ListObjectsPage> {
+    let invalid_response = |err: quick_xml::Error| {
+        Error::ObjectStore(format!("invalid ListObjectsV2 response: {err}"))
+    };
+    let mut reader = Reader::from_reader(body);
+    let mut buf = Vec::new();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut text = String::new();
+    let mut is_truncated = false;
+    let mut page = ListObjectsPage::default();
+    loop {
+        match reader.read_event_into(&mut buf).map_err(invalid_response)? {
+            Event::Start(element) => {
+                path.push(element.local_name().as_ref().to_vec());
+                text.clear();
+            }
+            Event::Text(element_text) => {
+                text.push_str(&element_text.unescape().map_err(invalid_response)?)
+            }
+            Event::CData(element_text) => text.push_str(&String::from_utf8_lossy(&element_text)),
+            Event::End(_) => {
+                match path.join(&b'/').as_slice() {
+                    b"ListBucketResult/Contents/Key" => {
+                        page.objects.push(std::mem::take(&mut text))
+                    }
+                    b"ListBucketResult/IsTruncated" => is_truncated = text.trim() == "true",
+                    b"ListBucketResult/NextContinuationToken" => {
+                        page.next_continuation_token = Some(std::mem::take(&mut text))
+                    }
+                    _ => (),
+                }
+                path.pop();
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    if !is_truncated {
+        page.next_continuation_token = None;
+    }
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{key_value::KeyValueHistoryStorage, HistoryStorage},
+    };
+
+    #[test]
+    fn test_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_canonical_query_string() {
+        assert_eq!(
+            canonical_query_string(&[
+                ("prefix", "tx-by-addr/abc/"),
+                ("list-type", "2"),
+                ("start-after", "a b"),
+            ]),
+            "list-type=2&prefix=tx-by-addr%2Fabc%2F&start-after=a%20b"
+        );
+    }
+
+    #[test]
+    fn test_parse_list_objects() {
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>history</Name>
+  <Prefix>blocks/</Prefix>
+  <KeyCount>3</KeyCount>
+  <MaxKeys>3</MaxKeys>
+  <IsTruncated>true</IsTruncated>
+  <NextContinuationToken>1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=</NextContinuationToken>
+  <Contents><Key>blocks/0000000000000001</Key><Size>10</Size></Contents>
+  <Contents><Key>blocks/a&amp;b&lt;/Key&gt;</Key><Size>10</Size></Contents>
+  <Contents><Key><![CDATA[blocks/<Key>]]></Key><Size>10</Size></Contents>
+</ListBucketResult>"#;
+        assert_eq!(
+            parse_list_objects(body).unwrap(),
+            ListObjectsPage {
+                objects: vec![
+                    "blocks/0000000000000001".to_string(),
+                    "blocks/a&b</Key>".to_string(),
+                    "blocks/<Key>".to_string(),
+                ],
+                next_continuation_token: Some(
+                    "1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=".to_string()
+                ),
+            }
+        );
+
+        // Namespace prefixes are ignored, and so is the token of a listing that isn't truncated
+        let body = br#"<s3:ListBucketResult xmlns:s3="http://s3.amazonaws.com/doc/2006-03-01/">
+  <s3:IsTruncated>false</s3:IsTruncated>
+  <s3:NextContinuationToken>token</s3:NextContinuationToken>
+  <s3:Contents><s3:Key>blocks/1</s3:Key></s3:Contents>
+</s3:ListBucketResult>"#;
+        assert_eq!(
+            parse_list_objects(body).unwrap(),
+            ListObjectsPage {
+                objects: vec!["blocks/1".to_string()],
+                next_continuation_token: None,
+            }
+        );
+
+        assert_eq!(
+            parse_list_objects(
+                b"<ListBucketResult><IsTruncated>false</IsTruncated></ListBucketResult>"
+            )
+            .unwrap(),
+            ListObjectsPage::default()
+        );
+        assert!(parse_list_objects(b"<ListBucketResult><Contents></Key>").is_err());
+    }
+
+    /// Runs against a local MinIO, e.g.
+    /// `docker run -p 9000:9000 minio/minio server /data` followed by creating the
+    /// `history` bucket with `mc mb`
+    #[tokio::test]
+    #[ignore]
+    async fn test_minio() {
+        let store = S3Store::new(S3Config {
+            endpoint: std::env::var("S3_ENDPOINT")
+                .unwrap_or_else(|_| "http://127.0.0.1:9000".to_string()),
+            region: DEFAULT_REGION.to_string(),
+            bucket: std::env::var("S3_BUCKET").unwrap_or_else(|_| "history".to_string()),
+            access_key_id: std::env::var("AWS_ACCESS_KEY_ID")
+                .unwrap_or_else(|_| "minioadmin".to_string()),
+            secret_access_key: std::env::var("AWS_SECRET_ACCESS_KEY")
+                .unwrap_or_else(|_| "minioadmin".to_string()),
+            timeout: Some(Duration::from_secs(10)),
+        })
+        .unwrap();
+
+        let table = "test-minio";
+        let rows: Vec<_> = (0..3).map(|i| (format!("key/{i}"), vec![i; 4])).collect();
+        assert_eq!(store.put(table, rows).await.unwrap(), 12);
+        assert_eq!(store.get(table, "key/1").await.unwrap(), Some(vec![1; 4]));
+        assert!(store.contains_key(table, "key/2").await.unwrap());
+        assert_eq!(
+            store
+                .list_keys(table, "key/", Some("key/0"), 10)
+                .await
+                .unwrap(),
+            vec!["key/1", "key/2"]
+        );
+        store
+            .delete(table, (0..3).map(|i| format!("key/{i}")).collect())
+            .await
+            .unwrap();
+        assert_eq!(store.get(table, "key/1").await.unwrap(), None);
+
+        let storage = KeyValueHistoryStorage::new(store);
+        assert!(!storage.confirmed_block_exists(u64::MAX).await.unwrap());
+    }
+}
@@ -0,0 +1,153 @@
+use {
+    solana_account::{Account, AccountSharedData, ReadableAccount},
+    solana_pubkey::Pubkey,
+    solana_svm::{
+        rollback_accounts::RollbackAccounts,
+        transaction_processing_result::{ProcessedTransaction, TransactionProcessingResult},
+    },
+    solana_svm_callback::{InvokeContextCallback, TransactionProcessingCallback},
+    solana_svm_transaction::svm_message::SVMMessage,
+    std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// An account source that transaction results can be committed back to.
+///
+/// Implement this on top of any `TransactionProcessingCallback` to plug a
+/// different account source, e.g. one backed by RPC or a database, into
+/// [`crate::MemorySvm`].
+pub trait AccountStore: TransactionProcessingCallback {
+    /// Store the post-transaction state of an account. Accounts left with
+    /// zero lamports are dead and should be removed.
+    fn store_account(&self, pubkey: &Pubkey, account: &AccountSharedData);
+}
+
+/// Accounts held in a `HashMap`.
+///
+/// Clones share the same underlying map.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryAccounts {
+    accounts: Arc<RwLock<HashMap<Pubkey, AccountSharedData>>>,
+}
+
+impl InMemoryAccounts {
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.read().unwrap().get(pubkey).cloned()
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        self.accounts.write().unwrap().insert(pubkey, account);
+    }
+
+    pub fn remove_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.write().unwrap().remove(pubkey)
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.read().unwrap().is_empty()
+    }
+
+    /// Returns a copy of every stored account.
+    pub fn snapshot(&self) -> HashMap<Pubkey, AccountSharedData> {
+        self.accounts.read().unwrap().clone()
+    }
+}
+
+impl InvokeContextCallback for InMemoryAccounts {}
+
+impl TransactionProcessingCallback for InMemoryAccounts {
+    fn account_matches_owners(&self, account: &Pubkey, owners: &[Pubkey]) -> Option<usize> {
+        self.accounts
+            .read()
+            .unwrap()
+            .get(account)
+            .filter(|account| account.lamports() != 0)
+            .and_then(|account| owners.iter().position(|owner| account.owner() == owner))
+    }
+
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.get_account(pubkey)
+    }
+
+    fn add_builtin_account(&self, name: &str, program_id: &Pubkey) {
+        let account = AccountSharedData::from(Account {
+            lamports: 1,
+            data: name.as_bytes().to_vec(),
+            owner: solana_sdk_ids::native_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        });
+        self.set_account(*program_id, account);
+    }
+}
+
+impl AccountStore for InMemoryAccounts {
+    fn store_account(&self, pubkey: &Pubkey, account: &AccountSharedData) {
+        if account.lamports() == 0 {
+            self.remove_account(pubkey);
+        } else {
+            self.set_account(*pubkey, account.clone());
+        }
+    }
+}
+
+/// Store the accounts modified by a processed batch.
+///
+/// Follows the same rules as the bank: successful transactions store their
+/// writable accounts, while failed and fees-only transactions only store the
+/// fee payer and nonce accounts they were charged against.
+pub fn commit_transactions(
+    store: &impl AccountStore,
+    transactions: &[impl SVMMessage],
+    processing_results: &[TransactionProcessingResult],
+) {
+    for (transaction, processing_result) in transactions.iter().zip(processing_results) {
+        let Ok(processed_transaction) = processing_result else {
+            // Transactions that weren't processed don't modify any accounts
+            continue;
+        };
+        match processed_transaction {
+            ProcessedTransaction::Executed(executed_transaction) => {
+                let loaded_transaction = &executed_transaction.loaded_transaction;
+                if executed_transaction.was_successful() {
+                    commit_successful_transaction(store, transaction, &loaded_transaction.accounts);
+                } else {
+                    commit_rollback_accounts(store, &loaded_transaction.rollback_accounts);
+                }
+            }
+            ProcessedTransaction::FeesOnly(fees_only_transaction) => {
+                commit_rollback_accounts(store, &fees_only_transaction.rollback_accounts);
+            }
+        }
+    }
+}
+
+fn commit_successful_transaction(
+    store: &impl AccountStore,
+    transaction: &impl SVMMessage,
+    transaction_accounts: &[(Pubkey, AccountSharedData)],
+) {
+    for (index, (pubkey, account)) in transaction_accounts.iter().enumerate() {
+        if !transaction.is_writable(index) {
+            continue;
+        }
+        // Invoked programs that aren't passed to an instruction can't be
+        // modified by the transaction
+        if transaction.is_invoked(index) && !transaction.is_instruction_account(index) {
+            continue;
+        }
+        store.store_account(pubkey, account);
+    }
+}
+
+fn commit_rollback_accounts(store: &impl AccountStore, rollback_accounts: &RollbackAccounts) {
+    for (pubkey, account) in rollback_accounts {
+        store.store_account(pubkey, account);
+    }
+}
@@ -0,0 +1,23 @@
+use {
+    solana_clock::Slot,
+    solana_program_runtime::loaded_programs::{BlockRelation, ForkGraph},
+    std::cmp::Ordering,
+};
+
+/// A fork graph for a chain without forks.
+///
+/// Every slot is an ancestor of all slots after it, which is what a rollup or
+/// test harness processing one block after another needs from the program
+/// cache.
+#[derive(Debug, Default)]
+pub struct LinearForkGraph;
+
+impl ForkGraph for LinearForkGraph {
+    fn relationship(&self, a: Slot, b: Slot) -> BlockRelation {
+        match a.cmp(&b) {
+            Ordering::Less => BlockRelation::Ancestor,
+            Ordering::Equal => BlockRelation::Equal,
+            Ordering::Greater => BlockRelation::Descendant,
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! An in-memory backend for the standalone SVM.
+//!
+//! [`MemorySvm`] wraps a `TransactionBatchProcessor` together with what the
+//! processor expects from its host: an account source, a fork graph, builtin
+//! programs and sysvars. The results of each processed batch are committed
+//! back to the account source, so the SVM can be driven one batch after
+//! another like a chain without forks.
+//!
+//! Both the account source and the fork graph are pluggable. By default
+//! accounts are held in [`InMemoryAccounts`] and slots are ordered by
+//! [`LinearForkGraph`].
+//!
+//! Unlike the bank, `MemorySvm` does not verify signatures, check blockhash
+//! age or deduplicate transactions. Callers that need these checks must
+//! perform them before submitting a batch. Durable nonce transactions are
+//! not supported.
+
+pub mod accounts;
+pub mod fork_graph;
+
+pub use crate::{
+    accounts::{commit_transactions, AccountStore, InMemoryAccounts},
+    fork_graph::LinearForkGraph,
+};
+use {
+    agave_feature_set::FeatureSet,
+    agave_reserved_account_keys::ReservedAccountKeys,
+    log::*,
+    solana_account::{
+        create_account_shared_data_with_fields, AccountSharedData, ReadableAccount, WritableAccount,
+    },
+    solana_bpf_loader_program::syscalls::{
+        create_program_runtime_environment_v1, create_program_runtime_environment_v2,
+    },
+    solana_builtins::BUILTINS,
+    solana_clock::{Clock, Epoch, Slot, UnixTimestamp, DEFAULT_MS_PER_SLOT},
+    solana_compute_budget_instruction::instructions_processor::process_compute_budget_instructions,
+    solana_epoch_schedule::EpochSchedule,
+    solana_fee_structure::FeeDetails,
+    solana_hash::Hash,
+    solana_program_runtime::{
+        execution_budget::SVMTransactionExecutionBudget,
+        invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::{ForkGraph, ProgramCacheEntry},
+    },
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_rent_collector::RentCollector,
+    solana_sha256_hasher::hashv,
+    solana_svm::{
+        account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        transaction_processing_result::{ProcessedTransaction, TransactionProcessingResult},
+        transaction_processor::{
+            ExecutionRecordingConfig, LoadAndExecuteSanitizedTransactionsOutput,
+            TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    solana_svm_transaction::{svm_message::SVMMessage, svm_transaction::SVMTransaction},
+    solana_sysvar::Sysvar,
+    solana_transaction::{sanitized::SanitizedTransaction, Transaction},
+    std::{
+        collections::HashSet,
+        sync::{Arc, RwLock},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Slot of the first block processed by a new [`MemorySvm`].
+///
+/// Builtins and programs are deployed in slot 0, and only become visible to
+/// the program cache from the next slot on.
+pub const INITIAL_SLOT: Slot = 1;
+
+pub const DEFAULT_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// A `TransactionBatchProcessor` with its account source, fork graph,
+/// builtins and sysvars.
+pub struct MemorySvm<A: AccountStore = InMemoryAccounts, FG: ForkGraph = LinearForkGraph> {
+    accounts: A,
+    // The program cache only holds a weak reference to the fork graph
+    _fork_graph: Arc<RwLock<FG>>,
+    processor: TransactionBatchProcessor<FG>,
+    slot: Slot,
+    feature_set: FeatureSet,
+    reserved_account_keys: HashSet<Pubkey>,
+    epoch_schedule: EpochSchedule,
+    rent_collector: RentCollector,
+    lamports_per_signature: u64,
+    blockhash: Hash,
+    genesis_unix_timestamp: UnixTimestamp,
+    recording_config: ExecutionRecordingConfig,
+}
+
+impl Default for MemorySvm {
+    fn default() -> Self {
+        Self::new(
+            InMemoryAccounts::default(),
+            Arc::new(RwLock::new(LinearForkGraph)),
+            FeatureSet::all_enabled(),
+        )
+    }
+}
+
+impl<A: AccountStore, FG: ForkGraph> MemorySvm<A, FG> {
+    /// Create an SVM at [`INITIAL_SLOT`] on top of `accounts`.
+    ///
+    /// The builtins enabled by `feature_set` are added, and the clock, epoch
+    /// schedule and rent sysvars are written to `accounts`.
+    pub fn new(accounts: A, fork_graph: Arc<RwLock<FG>>, feature_set: FeatureSet) -> Self {
+        let epoch_schedule = EpochSchedule::default();
+        let epoch = epoch_schedule.get_epoch(INITIAL_SLOT);
+        let rent_collector = RentCollector {
+            epoch,
+            epoch_schedule: epoch_schedule.clone(),
+            ..RentCollector::default()
+        };
+
+        let execution_budget = SVMTransactionExecutionBudget::default();
+        let program_runtime_environment_v1 = create_program_runtime_environment_v1(
+            &feature_set.runtime_features(),
+            &execution_budget,
+            false, /* deployment */
+            false, /* debugging_features */
+        )
+        .expect("failed to create program runtime environment");
+        let program_runtime_environment_v2 =
+            create_program_runtime_environment_v2(&execution_budget, false);
+        let processor = TransactionBatchProcessor::new(
+            INITIAL_SLOT,
+            epoch,
+            Arc::downgrade(&fork_graph),
+            Some(Arc::new(program_runtime_environment_v1)),
+            Some(Arc::new(program_runtime_environment_v2)),
+        );
+
+        let mut reserved_account_keys = ReservedAccountKeys::default();
+        reserved_account_keys.update_active_set(&feature_set);
+
+        let genesis_unix_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as UnixTimestamp;
+
+        let svm = Self {
+            accounts,
+            _fork_graph: fork_graph,
+            processor,
+            slot: INITIAL_SLOT,
+            feature_set,
+            reserved_account_keys: reserved_account_keys.active,
+            epoch_schedule,
+            rent_collector,
+            lamports_per_signature: DEFAULT_LAMPORTS_PER_SIGNATURE,
+            blockhash: Hash::default(),
+            genesis_unix_timestamp,
+            recording_config: ExecutionRecordingConfig {
+                enable_log_recording: true,
+                enable_return_data_recording: true,
+                ..ExecutionRecordingConfig::default()
+            },
+        };
+        svm.add_default_builtins();
+        svm.update_sysvars();
+        svm
+    }
+
+    pub fn accounts(&self) -> &A {
+        &self.accounts
+    }
+
+    pub fn feature_set(&self) -> &FeatureSet {
+        &self.feature_set
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.epoch_schedule.get_epoch(self.slot)
+    }
+
+    pub fn latest_blockhash(&self) -> Hash {
+        self.blockhash
+    }
+
+    pub fn lamports_per_signature(&self) -> u64 {
+        self.lamports_per_signature
+    }
+
+    pub fn rent(&self) -> &Rent {
+        &self.rent_collector.rent
+    }
+
+    /// The clock advances by `DEFAULT_MS_PER_SLOT` every slot, starting from
+    /// the wallclock time at which the SVM was created.
+    pub fn clock(&self) -> Clock {
+        let epoch = self.epoch();
+        let first_slot_in_epoch = self.epoch_schedule.get_first_slot_in_epoch(epoch);
+        Clock {
+            slot: self.slot,
+            epoch_start_timestamp: self.slot_unix_timestamp(first_slot_in_epoch),
+            epoch,
+            leader_schedule_epoch: self.epoch_schedule.get_leader_schedule_epoch(self.slot),
+            unix_timestamp: self.slot_unix_timestamp(self.slot),
+        }
+    }
+
+    pub fn set_lamports_per_signature(&mut self, lamports_per_signature: u64) {
+        self.lamports_per_signature = lamports_per_signature;
+    }
+
+    pub fn set_recording_config(&mut self, recording_config: ExecutionRecordingConfig) {
+        self.recording_config = recording_config;
+    }
+
+    /// Add a builtin program, replacing any builtin with the same id.
+    pub fn add_builtin(
+        &self,
+        program_id: Pubkey,
+        name: &str,
+        entrypoint: BuiltinFunctionWithContext,
+    ) {
+        self.processor.add_builtin(
+            &self.accounts,
+            program_id,
+            name,
+            ProgramCacheEntry::new_builtin(0, name.len(), entrypoint),
+        );
+    }
+
+    /// Store `elf` as an executable account owned by the BPF loader.
+    pub fn add_program(&self, program_id: Pubkey, elf: &[u8]) {
+        let mut account = AccountSharedData::new(
+            self.rent().minimum_balance(elf.len()),
+            0,
+            &solana_sdk_ids::bpf_loader::id(),
+        );
+        account.set_data_from_slice(elf);
+        account.set_executable(true);
+        self.accounts.store_account(&program_id, &account);
+    }
+
+    /// Process and commit a batch of transactions.
+    ///
+    /// Transactions within the batch are processed in order, and see the
+    /// account changes made by the transactions before them.
+    pub fn process_transactions(
+        &mut self,
+        transactions: &[impl SVMTransaction],
+    ) -> LoadAndExecuteSanitizedTransactionsOutput {
+        let check_results = self.check_transactions(transactions);
+        let environment = TransactionProcessingEnvironment {
+            blockhash: self.blockhash,
+            blockhash_lamports_per_signature: self.lamports_per_signature,
+            epoch_total_stake: 0,
+            feature_set: self.feature_set.runtime_features(),
+            rent_collector: Some(&self.rent_collector),
+        };
+        let config = TransactionProcessingConfig {
+            check_program_modification_slot: true,
+            recording_config: self.recording_config,
+            ..TransactionProcessingConfig::default()
+        };
+        let output = self.processor.load_and_execute_sanitized_transactions(
+            &self.accounts,
+            transactions,
+            check_results,
+            &environment,
+            &config,
+        );
+
+        commit_transactions(&self.accounts, transactions, &output.processing_results);
+        self.commit_modified_programs(&output.processing_results);
+        output
+    }
+
+    /// Sanitize, process and commit a single legacy transaction.
+    pub fn process_transaction(&mut self, transaction: Transaction) -> TransactionProcessingResult {
+        let transaction = SanitizedTransaction::try_from_legacy_transaction(
+            transaction,
+            &self.reserved_account_keys,
+        )?;
+        self.process_transactions(&[transaction])
+            .processing_results
+            .pop()
+            .expect("one result per transaction")
+    }
+
+    /// Move to the next slot. See [`Self::warp_to_slot`].
+    pub fn advance_slot(&mut self) -> Slot {
+        let slot = self.slot().saturating_add(1);
+        self.warp_to_slot(slot);
+        slot
+    }
+
+    /// Move to `slot`, rooting the program cache there and producing a new
+    /// blockhash and clock.
+    pub fn warp_to_slot(&mut self, slot: Slot) {
+        assert!(
+            slot > self.slot(),
+            "cannot warp from slot {} back to slot {slot}",
+            self.slot()
+        );
+        let epoch = self.epoch_schedule.get_epoch(slot);
+        self.slot = slot;
+        self.processor = self.processor.new_from(slot, epoch);
+        self.processor
+            .program_cache
+            .write()
+            .unwrap()
+            .prune(slot, epoch);
+        self.rent_collector.epoch = epoch;
+        self.blockhash = hashv(&[self.blockhash.as_ref(), &slot.to_le_bytes()]);
+        self.update_sysvars();
+    }
+
+    fn add_default_builtins(&self) {
+        for builtin in BUILTINS {
+            let enabled = builtin
+                .enable_feature_id
+                .is_none_or(|feature_id| self.feature_set.is_active(&feature_id));
+            let migrated = builtin
+                .core_bpf_migration_config
+                .as_ref()
+                .is_some_and(|config| self.feature_set.is_active(&config.feature_id));
+            if enabled && !migrated {
+                self.add_builtin(builtin.program_id, builtin.name, builtin.entrypoint);
+            } else {
+                debug!("Skipping builtin {}", builtin.name);
+            }
+        }
+    }
+
+    fn update_sysvars(&self) {
+        self.store_sysvar(&self.clock());
+        self.store_sysvar(&self.epoch_schedule);
+        self.store_sysvar(self.rent());
+
+        self.processor.reset_sysvar_cache();
+        self.processor
+            .fill_missing_sysvar_cache_entries(&self.accounts);
+    }
+
+    fn store_sysvar<S: Sysvar>(&self, sysvar: &S) {
+        let mut account = create_account_shared_data_with_fields(sysvar, (1, 0));
+        account.set_lamports(self.rent().minimum_balance(account.data().len()));
+        self.accounts.store_account(&S::id(), &account);
+    }
+
+    fn slot_unix_timestamp(&self, slot: Slot) -> UnixTimestamp {
+        let elapsed_ms = slot.saturating_mul(DEFAULT_MS_PER_SLOT);
+        self.genesis_unix_timestamp
+            .saturating_add((elapsed_ms / 1_000) as UnixTimestamp)
+    }
+
+    fn check_transactions(
+        &self,
+        transactions: &[impl SVMTransaction],
+    ) -> Vec<TransactionCheckResult> {
+        transactions
+            .iter()
+            .map(|transaction| {
+                let compute_budget_and_limits = process_compute_budget_instructions(
+                    SVMMessage::program_instructions_iter(transaction),
+                    &self.feature_set,
+                )
+                .map(|compute_budget_limits| {
+                    let signature_count = transaction
+                        .num_transaction_signatures()
+                        .saturating_add(transaction.num_ed25519_signatures())
+                        .saturating_add(transaction.num_secp256k1_signatures())
+                        .saturating_add(transaction.num_secp256r1_signatures());
+                    let fee_details = FeeDetails::new(
+                        signature_count.saturating_mul(self.lamports_per_signature),
+                        compute_budget_limits.get_prioritization_fee(),
+                    );
+                    compute_budget_limits.get_compute_budget_and_limits(
+                        compute_budget_limits.loaded_accounts_bytes,
+                        fee_details,
+                    )
+                });
+                Ok(CheckedTransactionDetails::new(
+                    None,
+                    compute_budget_and_limits,
+                ))
+            })
+            .collect()
+    }
+
+    fn commit_modified_programs(&self, processing_results: &[TransactionProcessingResult]) {
+        let mut program_cache = self.processor.program_cache.write().unwrap();
+        for processing_result in processing_results {
+            if let Ok(ProcessedTransaction::Executed(executed_transaction)) = processing_result {
+                if executed_transaction.was_successful() {
+                    program_cache.merge(&executed_transaction.programs_modified_by_tx);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_svm::transaction_processing_result::TransactionProcessingResultExtensions,
+    solana_svm_memory::{MemorySvm, DEFAULT_LAMPORTS_PER_SIGNATURE, INITIAL_SLOT},
+    solana_system_transaction as system_transaction,
+    solana_transaction::sanitized::SanitizedTransaction,
+    solana_transaction_error::TransactionError,
+    std::collections::HashSet,
+};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+fn funded_svm(payer: &Keypair) -> MemorySvm {
+    let svm = MemorySvm::default();
+    svm.accounts().set_account(
+        payer.pubkey(),
+        AccountSharedData::new(
+            10 * LAMPORTS_PER_SOL,
+            0,
+            &solana_system_interface::program::id(),
+        ),
+    );
+    svm
+}
+
+#[test]
+fn test_transfer_is_committed() {
+    let payer = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let mut svm = funded_svm(&payer);
+
+    let transaction =
+        system_transaction::transfer(&payer, &recipient, LAMPORTS_PER_SOL, svm.latest_blockhash());
+    let result = svm.process_transaction(transaction);
+    assert!(result.was_processed_with_successful_result());
+
+    let payer_account = svm.accounts().get_account(&payer.pubkey()).unwrap();
+    assert_eq!(
+        payer_account.lamports(),
+        9 * LAMPORTS_PER_SOL - DEFAULT_LAMPORTS_PER_SIGNATURE
+    );
+    let recipient_account = svm.accounts().get_account(&recipient).unwrap();
+    assert_eq!(recipient_account.lamports(), LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn test_batch_sees_earlier_transactions() {
+    let payer = Keypair::new();
+    let intermediary = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let mut svm = funded_svm(&payer);
+    let blockhash = svm.latest_blockhash();
+
+    let transactions = [
+        system_transaction::transfer(&payer, &intermediary.pubkey(), LAMPORTS_PER_SOL, blockhash),
+        system_transaction::transfer(&intermediary, &recipient, LAMPORTS_PER_SOL / 2, blockhash),
+    ]
+    .map(|transaction| {
+        SanitizedTransaction::try_from_legacy_transaction(transaction, &HashSet::new()).unwrap()
+    });
+    let output = svm.process_transactions(&transactions);
+    assert!(output
+        .processing_results
+        .iter()
+        .all(|result| result.was_processed_with_successful_result()));
+
+    let recipient_account = svm.accounts().get_account(&recipient).unwrap();
+    assert_eq!(recipient_account.lamports(), LAMPORTS_PER_SOL / 2);
+}
+
+#[test]
+fn test_failed_transaction_only_charges_fee() {
+    let payer = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let mut svm = funded_svm(&payer);
+
+    let transaction = system_transaction::transfer(
+        &payer,
+        &recipient,
+        100 * LAMPORTS_PER_SOL,
+        svm.latest_blockhash(),
+    );
+    let result = svm.process_transaction(transaction);
+    assert!(result.was_processed());
+    assert!(matches!(
+        result.flattened_result(),
+        Err(TransactionError::InstructionError(0, _))
+    ));
+
+    let payer_account = svm.accounts().get_account(&payer.pubkey()).unwrap();
+    assert_eq!(
+        payer_account.lamports(),
+        10 * LAMPORTS_PER_SOL - DEFAULT_LAMPORTS_PER_SIGNATURE
+    );
+    assert!(svm.accounts().get_account(&recipient).is_none());
+}
+
+#[test]
+fn test_advance_slot() {
+    let mut svm = MemorySvm::default();
+    assert_eq!(svm.slot(), INITIAL_SLOT);
+    let blockhash = svm.latest_blockhash();
+    let clock = svm.clock();
+
+    assert_eq!(svm.advance_slot(), INITIAL_SLOT + 1);
+    assert_ne!(svm.latest_blockhash(), blockhash);
+
+    svm.warp_to_slot(INITIAL_SLOT + 1_000);
+    let warped_clock = svm.clock();
+    assert_eq!(warped_clock.slot, INITIAL_SLOT + 1_000);
+    assert!(warped_clock.unix_timestamp > clock.unix_timestamp);
+
+    let clock_account = svm
+        .accounts()
+        .get_account(&solana_sdk_ids::sysvar::clock::id())
+        .unwrap();
+    let stored_clock: solana_clock::Clock = bincode::deserialize(clock_account.data()).unwrap();
+    assert_eq!(stored_clock, warped_clock);
+}
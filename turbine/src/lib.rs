@@ -5,6 +5,7 @@ pub mod broadcast_stage;
 pub mod cluster_nodes;
 pub mod quic_endpoint;
 pub mod retransmit_stage;
+pub mod shred_arrivals;
 pub mod sigverify_shreds;
 pub mod xdp;
 
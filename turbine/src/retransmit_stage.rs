@@ -4,6 +4,7 @@ use {
     crate::{
         addr_cache::AddrCache,
         cluster_nodes::{self, ClusterNodes, ClusterNodesCache, Error, MAX_NUM_TURBINE_HOPS},
+        shred_arrivals::{ShredArrivals, TurbineTreePosition},
         xdp::{XdpSender, XdpShredPayload},
     },
     bytes::Bytes,
@@ -90,6 +91,9 @@ pub(crate) struct RetransmitSlotStats {
     // Root distance and socket-addresses the shreds were sent to if there was
     // a cache miss.
     pub(crate) addrs: Vec<(ShredId, /*root_distance:*/ u8, Box<[SocketAddr]>)>,
+    // This node's turbine tree position for each shred, only if shred arrival
+    // telemetry is enabled.
+    tree_positions: Vec<TurbineTreePosition>,
 }
 
 struct RetransmitStats {
@@ -241,6 +245,7 @@ fn retransmit(
     max_slots: &MaxSlots,
     rpc_subscriptions: Option<&RpcSubscriptions>,
     slot_status_notifier: Option<&SlotStatusNotifier>,
+    shred_arrivals: Option<&ShredArrivals>,
     shred_buf: &mut Vec<Vec<shred::Payload>>,
 ) -> Result<(), RecvError> {
     // Try to receive shreds from the channel without blocking. If the channel
@@ -325,7 +330,7 @@ fn retransmit(
     let record = |mut stats: HashMap<Slot, RetransmitSlotStats>, out: RetransmitShredOutput| {
         let now = timestamp();
         let entry = stats.entry(out.shred.slot()).or_default();
-        entry.record(now, out);
+        entry.record(now, out, shred_arrivals.is_some());
         stats
     };
     let retransmit_shred = |shred, socket, stats| {
@@ -349,7 +354,7 @@ fn retransmit(
         socket
     };
 
-    let slot_stats = if num_shreds < PAR_ITER_MIN_NUM_SHREDS {
+    let mut slot_stats = if num_shreds < PAR_ITER_MIN_NUM_SHREDS {
         stats.num_small_batches += 1;
         shred_buf
             .drain(..)
@@ -373,6 +378,12 @@ fn retransmit(
                 .reduce(HashMap::new, RetransmitSlotStats::merge)
         })
     };
+    if let Some(shred_arrivals) = shred_arrivals {
+        for (&slot, slot_stats) in &mut slot_stats {
+            shred_arrivals
+                .record_tree_positions(slot, std::mem::take(&mut slot_stats.tree_positions));
+        }
+    }
 
     stats.upsert_slot_stats(
         slot_stats,
@@ -581,6 +592,7 @@ impl RetransmitStage {
     /// * `leader_schedule_cache` - The leader schedule to verify shreds
     /// * `cluster_info` - This structure needs to be updated and populated by the bank and via gossip.
     /// * `retransmit_receiver` - Receive channel for batches of shreds to be retransmitted.
+    /// * `shred_arrivals` - Records this node's turbine tree position for each retransmitted shred.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         bank_forks: Arc<RwLock<BankForks>>,
@@ -593,6 +605,7 @@ impl RetransmitStage {
         rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
         slot_status_notifier: Option<SlotStatusNotifier>,
         xdp_sender: Option<XdpSender>,
+        shred_arrivals: Option<Arc<ShredArrivals>>,
     ) -> Self {
         let cluster_nodes_cache = ClusterNodesCache::<RetransmitStage>::new(
             CLUSTER_NODES_CACHE_NUM_EPOCH_CAP,
@@ -633,6 +646,7 @@ impl RetransmitStage {
                         &max_slots,
                         rpc_subscriptions.as_deref(),
                         slot_status_notifier.as_ref(),
+                        shred_arrivals.as_deref(),
                         &mut shred_buf,
                     )
                     .is_ok()
@@ -662,6 +676,7 @@ impl AddAssign for RetransmitSlotStats {
             num_shreds_received,
             num_shreds_sent,
             mut addrs,
+            mut tree_positions,
         } = other;
         self.asof = self.asof.max(asof);
         self.max_index_code = self.max_index_code.max(max_index_code);
@@ -676,6 +691,7 @@ impl AddAssign for RetransmitSlotStats {
             std::mem::swap(&mut self.addrs, &mut addrs);
         }
         self.addrs.append(&mut addrs);
+        self.tree_positions.append(&mut tree_positions);
         for k in 0..MAX_NUM_TURBINE_HOPS {
             self.num_shreds_received[k] += num_shreds_received[k];
             self.num_shreds_sent[k] += num_shreds_sent[k];
@@ -750,7 +766,7 @@ impl RetransmitStats {
 }
 
 impl RetransmitSlotStats {
-    fn record(&mut self, now: u64, out: RetransmitShredOutput) {
+    fn record(&mut self, now: u64, out: RetransmitShredOutput, record_tree_position: bool) {
         self.outset = if self.outset == 0 {
             now
         } else {
@@ -765,6 +781,14 @@ impl RetransmitSlotStats {
         self.last_shred_in_slot |= out.last_shred_in_slot;
         self.num_shreds_received[usize::from(out.root_distance)] += 1;
         self.num_shreds_sent[usize::from(out.root_distance)] += out.num_nodes;
+        if record_tree_position {
+            self.tree_positions.push(TurbineTreePosition {
+                shred_type: out.shred.shred_type(),
+                index: out.shred.index(),
+                root_distance: out.root_distance,
+                num_children: out.num_nodes,
+            });
+        }
         if let Some(addrs) = out.addrs {
             self.addrs.push((out.shred, out.root_distance, addrs));
         }
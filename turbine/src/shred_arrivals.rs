@@ -0,0 +1,562 @@
+//! Per-slot records of when and from where shreds were received, used to
+//! diagnose slow block propagation.
+//!
+//! The shred sigverify stage records every shred which passes verification,
+//! whether it was received from turbine or in a repair response, and the
+//! address it was received from. The retransmit stage records this node's
+//! position in the turbine tree for each shred it retransmits. Records of the
+//! most recent slots are kept in a ring buffer indexed by slot.
+
+use {
+    crate::cluster_nodes::MAX_NUM_TURBINE_HOPS,
+    serde_derive::{Deserialize, Serialize},
+    solana_clock::Slot,
+    solana_ledger::shred::ShredType,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt,
+        net::SocketAddr,
+        sync::Mutex,
+    },
+};
+
+/// Number of slots records are kept for.
+pub const SHRED_ARRIVALS_NUM_SLOTS: usize = 128;
+// Bounds the arrival and tree position records of a slot. This covers the data
+// and coding shreds of a typical block, while keeping shred spam for slots in
+// the ring buffer from growing it past roughly 128 * 4096 * (80 + 24) bytes.
+const MAX_RECORDS_PER_SLOT: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShredSource {
+    Turbine,
+    Repair,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShredArrival {
+    #[serde(with = "shred_type")]
+    pub shred_type: ShredType,
+    pub index: u32,
+    pub fec_set_index: u32,
+    /// Milliseconds since the unix epoch at which the packet batch carrying
+    /// the shred was pulled from the shred fetch stage. Packets carry no
+    /// receive time of their own, so the shreds of a batch share a timestamp.
+    pub timestamp: u64,
+    pub source: ShredSource,
+    /// Address the shred was received from.
+    pub from: SocketAddr,
+    /// Identity of the node the shred was received from, resolved from gossip
+    /// when the records are queried.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_node: Option<String>,
+}
+
+/// This node's position in the turbine tree of a retransmitted shred.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurbineTreePosition {
+    #[serde(with = "shred_type")]
+    pub shred_type: ShredType,
+    pub index: u32,
+    /// Distance from the root of the turbine tree.
+    pub root_distance: u8,
+    /// Number of nodes the shred was retransmitted to.
+    pub num_children: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotShredArrivals {
+    pub slot: Slot,
+    pub arrivals: Vec<ShredArrival>,
+    pub tree_positions: Vec<TurbineTreePosition>,
+}
+
+/// Arrivals of the shreds of one erasure batch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErasureBatchArrivals {
+    pub fec_set_index: u32,
+    pub num_turbine: usize,
+    pub num_repair: usize,
+    /// Milliseconds since the first shred of the slot was received.
+    pub first_arrival: u64,
+    pub last_arrival: u64,
+    /// Number of the batch's shreds retransmitted at each distance from the
+    /// root of the turbine tree.
+    pub root_distances: [usize; MAX_NUM_TURBINE_HOPS],
+    /// Number of nodes the batch's shreds were retransmitted to.
+    pub num_children: usize,
+}
+
+/// Shreds received from one address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SenderArrivals {
+    pub from: SocketAddr,
+    pub from_node: Option<String>,
+    pub source: ShredSource,
+    pub num_shreds: usize,
+    /// Mean milliseconds since the first shred of the slot was received.
+    pub mean_arrival: u64,
+}
+
+/// Ring buffer of the shred arrival records of the most recent slots.
+pub struct ShredArrivals {
+    slots: Vec<Mutex<SlotShredArrivals>>,
+}
+
+impl ShredArrivals {
+    pub fn new(num_slots: usize) -> Self {
+        Self {
+            slots: std::iter::repeat_with(Mutex::default)
+                .take(num_slots.max(1))
+                .collect(),
+        }
+    }
+
+    fn update(&self, slot: Slot, update: impl FnOnce(&mut SlotShredArrivals)) {
+        let index = (slot % self.slots.len() as u64) as usize;
+        let mut entry = self.slots[index].lock().unwrap();
+        if entry.slot != slot {
+            // Records of late shreds are dropped rather than evicting a more
+            // recent slot.
+            if entry.slot > slot {
+                return;
+            }
+            *entry = SlotShredArrivals {
+                slot,
+                ..SlotShredArrivals::default()
+            };
+        }
+        update(&mut entry);
+    }
+
+    pub(crate) fn record_arrivals(&self, arrivals: impl IntoIterator<Item = (Slot, ShredArrival)>) {
+        let mut slots = HashMap::<Slot, Vec<ShredArrival>>::new();
+        for (slot, arrival) in arrivals {
+            slots.entry(slot).or_default().push(arrival);
+        }
+        for (slot, arrivals) in slots {
+            self.update(slot, |entry| {
+                let num_records = MAX_RECORDS_PER_SLOT.saturating_sub(entry.arrivals.len());
+                entry
+                    .arrivals
+                    .extend(arrivals.into_iter().take(num_records));
+            });
+        }
+    }
+
+    pub(crate) fn record_tree_positions(&self, slot: Slot, positions: Vec<TurbineTreePosition>) {
+        if positions.is_empty() {
+            return;
+        }
+        self.update(slot, |entry| {
+            let num_records = MAX_RECORDS_PER_SLOT.saturating_sub(entry.tree_positions.len());
+            entry
+                .tree_positions
+                .extend(positions.into_iter().take(num_records));
+        });
+    }
+
+    /// Returns the records of `slot` if it is still in the ring buffer.
+    pub fn get(&self, slot: Slot) -> Option<SlotShredArrivals> {
+        let index = (slot % self.slots.len() as u64) as usize;
+        let entry = self.slots[index].lock().unwrap();
+        (entry.slot == slot && !entry.is_empty()).then(|| entry.clone())
+    }
+
+    /// Returns the slots with records, in ascending order.
+    pub fn slots(&self) -> Vec<Slot> {
+        let mut slots: Vec<_> = self
+            .slots
+            .iter()
+            .filter_map(|entry| {
+                let entry = entry.lock().unwrap();
+                (!entry.is_empty()).then_some(entry.slot)
+            })
+            .collect();
+        slots.sort_unstable();
+        slots
+    }
+}
+
+impl ShredArrival {
+    /// Returns the slot and arrival record of the shred in the payload.
+    pub(crate) fn new(
+        shred: &[u8],
+        timestamp: u64,
+        source: ShredSource,
+        from: SocketAddr,
+    ) -> Option<(Slot, Self)> {
+        let shred_id = solana_ledger::shred::layout::get_shred_id(shred)?;
+        let arrival = Self {
+            shred_type: shred_id.shred_type(),
+            index: shred_id.index(),
+            fec_set_index: solana_ledger::shred::layout::get_fec_set_index(shred)?,
+            timestamp,
+            source,
+            from,
+            from_node: None,
+        };
+        Some((shred_id.slot(), arrival))
+    }
+}
+
+impl SlotShredArrivals {
+    fn is_empty(&self) -> bool {
+        self.arrivals.is_empty() && self.tree_positions.is_empty()
+    }
+
+    /// Timestamp at which the first shred of the slot was received.
+    pub fn first_arrival(&self) -> Option<u64> {
+        self.arrivals.iter().map(|arrival| arrival.timestamp).min()
+    }
+
+    /// Summarizes the arrivals by erasure batch, in order of fec_set_index.
+    pub fn erasure_batches(&self) -> Vec<ErasureBatchArrivals> {
+        let first_arrival = self.first_arrival().unwrap_or_default();
+        let mut batches = BTreeMap::<u32, ErasureBatchArrivals>::new();
+        let mut fec_set_indices = HashMap::new();
+        for arrival in &self.arrivals {
+            fec_set_indices.insert((arrival.shred_type, arrival.index), arrival.fec_set_index);
+            let offset = arrival.timestamp.saturating_sub(first_arrival);
+            let batch =
+                batches
+                    .entry(arrival.fec_set_index)
+                    .or_insert_with(|| ErasureBatchArrivals {
+                        fec_set_index: arrival.fec_set_index,
+                        first_arrival: offset,
+                        last_arrival: offset,
+                        ..ErasureBatchArrivals::default()
+                    });
+            match arrival.source {
+                ShredSource::Turbine => batch.num_turbine += 1,
+                ShredSource::Repair => batch.num_repair += 1,
+            }
+            batch.first_arrival = batch.first_arrival.min(offset);
+            batch.last_arrival = batch.last_arrival.max(offset);
+        }
+        for position in &self.tree_positions {
+            let Some(fec_set_index) = fec_set_indices.get(&(position.shred_type, position.index))
+            else {
+                continue;
+            };
+            let Some(batch) = batches.get_mut(fec_set_index) else {
+                continue;
+            };
+            if let Some(count) = batch
+                .root_distances
+                .get_mut(usize::from(position.root_distance))
+            {
+                *count += 1;
+            }
+            batch.num_children += position.num_children;
+        }
+        batches.into_values().collect()
+    }
+
+    /// Summarizes the arrivals by the address they were received from, in
+    /// descending order of the number of shreds.
+    pub fn senders(&self) -> Vec<SenderArrivals> {
+        let first_arrival = self.first_arrival().unwrap_or_default();
+        let mut senders = HashMap::<(SocketAddr, ShredSource), SenderArrivals>::new();
+        let mut offsets = HashMap::<(SocketAddr, ShredSource), u64>::new();
+        for arrival in &self.arrivals {
+            let key = (arrival.from, arrival.source);
+            let sender = senders.entry(key).or_insert_with(|| SenderArrivals {
+                from: arrival.from,
+                from_node: arrival.from_node.clone(),
+                source: arrival.source,
+                num_shreds: 0,
+                mean_arrival: 0,
+            });
+            sender.num_shreds += 1;
+            *offsets.entry(key).or_default() += arrival.timestamp.saturating_sub(first_arrival);
+        }
+        let mut senders: Vec<_> = senders
+            .into_iter()
+            .map(|(key, mut sender)| {
+                sender.mean_arrival = offsets[&key] / sender.num_shreds as u64;
+                sender
+            })
+            .collect();
+        senders.sort_unstable_by(|a, b| {
+            b.num_shreds
+                .cmp(&a.num_shreds)
+                .then_with(|| a.from.cmp(&b.from))
+        });
+        senders
+    }
+}
+
+impl fmt::Display for SlotShredArrivals {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let num_repair = self
+            .arrivals
+            .iter()
+            .filter(|arrival| arrival.source == ShredSource::Repair)
+            .count();
+        let elapsed = self
+            .arrivals
+            .iter()
+            .map(|arrival| arrival.timestamp)
+            .max()
+            .unwrap_or_default()
+            .saturating_sub(self.first_arrival().unwrap_or_default());
+        writeln!(
+            f,
+            "Slot {}: {} shreds received over {elapsed}ms, {} from turbine, {num_repair} from \
+             repair",
+            self.slot,
+            self.arrivals.len(),
+            self.arrivals.len() - num_repair,
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>8}  {:>7}  {:>6}  {:>10}  {:>9}  {:>13}  {:>8}",
+            "FEC Set", "Turbine", "Repair", "First (ms)", "Last (ms)", "Root Distance", "Children",
+        )?;
+        for batch in self.erasure_batches() {
+            let root_distances = batch
+                .root_distances
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("/");
+            writeln!(
+                f,
+                "{:>8}  {:>7}  {:>6}  {:>10}  {:>9}  {:>13}  {:>8}",
+                batch.fec_set_index,
+                batch.num_turbine,
+                batch.num_repair,
+                batch.first_arrival,
+                batch.last_arrival,
+                root_distances,
+                batch.num_children,
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<21}  {:<44}  {:<7}  {:>6}  {:>9}",
+            "From", "Node", "Source", "Shreds", "Mean (ms)",
+        )?;
+        for sender in self.senders() {
+            writeln!(
+                f,
+                "{:<21}  {:<44}  {:<7}  {:>6}  {:>9}",
+                sender.from.to_string(),
+                sender.from_node.as_deref().unwrap_or("-"),
+                match sender.source {
+                    ShredSource::Turbine => "turbine",
+                    ShredSource::Repair => "repair",
+                },
+                sender.num_shreds,
+                sender.mean_arrival,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Serializes ShredType as "data" or "code" rather than its wire discriminant.
+mod shred_type {
+    use {
+        serde::{de::Error, Deserialize, Deserializer, Serializer},
+        solana_ledger::shred::ShredType,
+    };
+
+    pub(super) fn serialize<S: Serializer>(
+        shred_type: &ShredType,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match shred_type {
+            ShredType::Data => "data",
+            ShredType::Code => "code",
+        })
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ShredType, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "data" => Ok(ShredType::Data),
+            "code" => Ok(ShredType::Code),
+            other => Err(D::Error::unknown_variant(other, &["data", "code"])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_arrival(
+        shred_type: ShredType,
+        index: u32,
+        fec_set_index: u32,
+        timestamp: u64,
+        source: ShredSource,
+        from: &str,
+    ) -> (Slot, ShredArrival) {
+        let arrival = ShredArrival {
+            shred_type,
+            index,
+            fec_set_index,
+            timestamp,
+            source,
+            from: from.parse().unwrap(),
+            from_node: None,
+        };
+        (7, arrival)
+    }
+
+    #[test]
+    fn test_shred_arrivals() {
+        let shred_arrivals = ShredArrivals::new(4);
+        shred_arrivals.record_arrivals([
+            make_arrival(
+                ShredType::Data,
+                0,
+                0,
+                100,
+                ShredSource::Turbine,
+                "1.1.1.1:8000",
+            ),
+            make_arrival(
+                ShredType::Code,
+                0,
+                0,
+                104,
+                ShredSource::Turbine,
+                "1.1.1.1:8000",
+            ),
+            make_arrival(
+                ShredType::Data,
+                1,
+                0,
+                110,
+                ShredSource::Turbine,
+                "2.2.2.2:8000",
+            ),
+            make_arrival(
+                ShredType::Data,
+                32,
+                32,
+                190,
+                ShredSource::Repair,
+                "3.3.3.3:8001",
+            ),
+        ]);
+        shred_arrivals.record_tree_positions(
+            7,
+            vec![
+                TurbineTreePosition {
+                    shred_type: ShredType::Data,
+                    index: 0,
+                    root_distance: 1,
+                    num_children: 200,
+                },
+                TurbineTreePosition {
+                    shred_type: ShredType::Code,
+                    index: 0,
+                    root_distance: 2,
+                    num_children: 0,
+                },
+            ],
+        );
+        assert_eq!(shred_arrivals.slots(), vec![7]);
+        assert_eq!(shred_arrivals.get(3), None);
+        let entry = shred_arrivals.get(7).unwrap();
+        assert_eq!(entry.arrivals.len(), 4);
+        assert_eq!(entry.tree_positions.len(), 2);
+
+        assert_eq!(
+            entry.erasure_batches(),
+            vec![
+                ErasureBatchArrivals {
+                    fec_set_index: 0,
+                    num_turbine: 3,
+                    num_repair: 0,
+                    first_arrival: 0,
+                    last_arrival: 10,
+                    root_distances: [0, 1, 1, 0],
+                    num_children: 200,
+                },
+                ErasureBatchArrivals {
+                    fec_set_index: 32,
+                    num_turbine: 0,
+                    num_repair: 1,
+                    first_arrival: 90,
+                    last_arrival: 90,
+                    root_distances: [0; MAX_NUM_TURBINE_HOPS],
+                    num_children: 0,
+                },
+            ]
+        );
+        let senders = entry.senders();
+        assert_eq!(senders.len(), 3);
+        assert_eq!(senders[0].from, "1.1.1.1:8000".parse().unwrap());
+        assert_eq!(senders[0].num_shreds, 2);
+        assert_eq!(senders[0].mean_arrival, 2);
+
+        // A more recent slot mapped to the same entry replaces the records,
+        // and late shreds of the older slot are dropped.
+        shred_arrivals.record_arrivals([(
+            11,
+            make_arrival(
+                ShredType::Data,
+                0,
+                0,
+                500,
+                ShredSource::Turbine,
+                "1.1.1.1:8000",
+            )
+            .1,
+        )]);
+        shred_arrivals.record_arrivals([make_arrival(
+            ShredType::Data,
+            2,
+            0,
+            510,
+            ShredSource::Turbine,
+            "1.1.1.1:8000",
+        )]);
+        assert_eq!(shred_arrivals.slots(), vec![11]);
+        assert_eq!(shred_arrivals.get(7), None);
+        assert_eq!(shred_arrivals.get(11).unwrap().arrivals.len(), 1);
+
+        // Records round trip through json.
+        let entry = shred_arrivals.get(11).unwrap();
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains("\"shredType\":\"data\""));
+        assert_eq!(
+            serde_json::from_str::<SlotShredArrivals>(&json).unwrap(),
+            entry
+        );
+    }
+
+    #[test]
+    fn test_shred_arrivals_max_records_per_slot() {
+        let shred_arrivals = ShredArrivals::new(4);
+        for _ in 0..2 {
+            shred_arrivals.record_arrivals((0..MAX_RECORDS_PER_SLOT as u32 - 1).map(|index| {
+                make_arrival(
+                    ShredType::Data,
+                    index,
+                    0,
+                    100,
+                    ShredSource::Repair,
+                    "1.1.1.1:8000",
+                )
+            }));
+        }
+        assert_eq!(
+            shred_arrivals.get(7).unwrap().arrivals.len(),
+            MAX_RECORDS_PER_SLOT
+        );
+    }
+}
@@ -2,6 +2,7 @@ use {
     crate::{
         cluster_nodes::{self, check_feature_activation, ClusterNodesCache},
         retransmit_stage::RetransmitStage,
+        shred_arrivals::{ShredArrival, ShredArrivals, ShredSource},
     },
     agave_feature_set as feature_set,
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
@@ -20,6 +21,7 @@ use {
     solana_runtime::{bank::Bank, bank_forks::BankForks},
     solana_signer::Signer,
     solana_streamer::{evicting_sender::EvictingSender, streamer::ChannelSend},
+    solana_time_utils::timestamp,
     std::{
         collections::HashMap,
         num::NonZeroUsize,
@@ -65,6 +67,7 @@ pub fn spawn_shred_sigverify(
     retransmit_sender: EvictingSender<Vec<shred::Payload>>,
     verified_sender: Sender<Vec<(shred::Payload, /*is_repaired:*/ bool)>>,
     num_sigverify_threads: NonZeroUsize,
+    shred_arrivals: Option<Arc<ShredArrivals>>,
 ) -> JoinHandle<()> {
     let recycler_cache = RecyclerCache::warmed();
     let mut stats = ShredSigVerifyStats::new(Instant::now());
@@ -102,6 +105,7 @@ pub fn spawn_shred_sigverify(
                 &verified_sender,
                 &cluster_nodes_cache,
                 &cache,
+                shred_arrivals.as_deref(),
                 &mut stats,
                 &mut shred_buffer,
             ) {
@@ -133,17 +137,23 @@ fn run_shred_sigverify<const K: usize>(
     verified_sender: &Sender<Vec<(shred::Payload, /*is_repaired:*/ bool)>>,
     cluster_nodes_cache: &ClusterNodesCache<RetransmitStage>,
     cache: &RwLock<LruCache>,
+    shred_arrivals: Option<&ShredArrivals>,
     stats: &mut ShredSigVerifyStats,
     shred_buffer: &mut Vec<PacketBatch>,
 ) -> Result<(), Error> {
     const RECV_TIMEOUT: Duration = Duration::from_secs(1);
     let packets = shred_fetch_receiver.recv_timeout(RECV_TIMEOUT)?;
+    // Time at which each batch was pulled from the channel, recorded as the
+    // arrival time of its shreds.
+    let mut recv_timestamps = Vec::with_capacity(SIGVERIFY_SHRED_BATCH_SIZE);
+    recv_timestamps.push(timestamp());
     stats.num_packets += packets.len();
     shred_buffer.push(packets);
     for packets in shred_fetch_receiver
         .try_iter()
         .take(SIGVERIFY_SHRED_BATCH_SIZE - 1)
     {
+        recv_timestamps.push(timestamp());
         stats.num_packets += packets.len();
         shred_buffer.push(packets);
     }
@@ -249,6 +259,30 @@ fn run_shred_sigverify<const K: usize>(
             })
     });
     stats.resign_micros += resign_start.elapsed().as_micros() as u64;
+    if let Some(shred_arrivals) = shred_arrivals {
+        shred_arrivals.record_arrivals(
+            shred_buffer
+                .iter()
+                .zip(&recv_timestamps)
+                .flat_map(|(batch, &recv_timestamp)| {
+                    batch.iter().map(move |packet| (packet, recv_timestamp))
+                })
+                .filter(|(packet, _)| !packet.meta().discard())
+                .filter_map(|(packet, recv_timestamp)| {
+                    let source = if packet.meta().repair() {
+                        ShredSource::Repair
+                    } else {
+                        ShredSource::Turbine
+                    };
+                    ShredArrival::new(
+                        shred::layout::get_shred(packet)?,
+                        recv_timestamp,
+                        source,
+                        packet.meta().socket_addr(),
+                    )
+                }),
+        );
+    }
     // Extract shred payload from packets, and separate out repaired shreds.
     let (shreds, repairs): (Vec<_>, Vec<_>) = shred_buffer
         .iter()
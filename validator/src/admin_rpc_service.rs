@@ -9,26 +9,40 @@ use {
     log::*,
     serde::{de::Deserializer, Deserialize, Serialize},
     solana_accounts_db::accounts_index::AccountIndex,
+    solana_clock::Slot,
     solana_core::{
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
-        consensus::{tower_storage::TowerStorage, Tower},
+        banking_stage::{SchedulerCountTotals, SCHEDULER_COUNT_TOTALS},
+        consensus::{
+            tower_storage::TowerStorage, vote_decision_history::VoteDecisionRecord, Tower,
+        },
         repair::repair_service,
-        validator::ValidatorStartProgress,
+        validator::{is_snapshot_config_valid, ValidatorStartProgress},
     },
     solana_geyser_plugin_manager::GeyserPluginManagerRequest,
     solana_gossip::contact_info::{ContactInfo, Protocol, SOCKET_ADDR_UNSPECIFIED},
     solana_keypair::{read_keypair_file, Keypair},
+    solana_ledger::blockstore::shred_archive::ShredArchiveStats,
     solana_net_utils::sockets::bind_to,
+    solana_program_runtime::loaded_programs::{ProgramCacheEntry, ProgramCacheEntryType},
     solana_pubkey::Pubkey,
     solana_rpc::rpc::verify_pubkey,
     solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
+    solana_runtime::{
+        snapshot_config::SnapshotConfig, snapshot_controller::SnapshotGenerationIntervals,
+        snapshot_utils::SnapshotInterval,
+    },
     solana_signer::Signer,
+    solana_turbine::shred_arrivals::{ShredArrivals, SlotShredArrivals},
     solana_validator_exit::Exit,
     std::{
         collections::{HashMap, HashSet},
         env, error,
         fmt::{self, Display},
+        fs::File,
+        io::BufReader,
         net::{IpAddr, SocketAddr},
+        num::NonZeroU64,
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, Ordering},
@@ -52,6 +66,7 @@ pub struct AdminRpcRequestMetadata {
     pub staked_nodes_overrides: Arc<RwLock<HashMap<Pubkey, u64>>>,
     pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
     pub rpc_to_plugin_manager_sender: Option<Sender<GeyserPluginManagerRequest>>,
+    pub shred_archive_import: Arc<RwLock<ShredArchiveImportStatus>>,
 }
 
 impl Metadata for AdminRpcRequestMetadata {}
@@ -93,6 +108,146 @@ pub struct AdminRpcRepairWhitelist {
     pub whitelist: Vec<Pubkey>,
 }
 
+/// State of the most recent shred archive import started over admin RPC.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "state")]
+pub enum ShredArchiveImportStatus {
+    #[default]
+    Idle,
+    Running {
+        path: String,
+    },
+    Completed {
+        path: String,
+        stats: ShredArchiveStats,
+    },
+    Failed {
+        path: String,
+        error: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum AdminRpcProgramCacheEntryType {
+    Loaded,
+    Unloaded,
+    FailedVerification,
+    DelayVisibility,
+    Closed,
+    Builtin,
+}
+
+impl AdminRpcProgramCacheEntryType {
+    fn new(entry: &ProgramCacheEntry, slot: u64) -> Self {
+        if entry.is_delay_visibility(slot) {
+            return Self::DelayVisibility;
+        }
+        match entry.program {
+            ProgramCacheEntryType::Loaded(_) => Self::Loaded,
+            ProgramCacheEntryType::Unloaded(_) => Self::Unloaded,
+            ProgramCacheEntryType::FailedVerification(_) => Self::FailedVerification,
+            ProgramCacheEntryType::DelayVisibility => Self::DelayVisibility,
+            ProgramCacheEntryType::Closed => Self::Closed,
+            ProgramCacheEntryType::Builtin(_) => Self::Builtin,
+        }
+    }
+}
+
+impl Display for AdminRpcProgramCacheEntryType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entry_type = match self {
+            Self::Loaded => "loaded",
+            Self::Unloaded => "unloaded",
+            Self::FailedVerification => "failed-verification",
+            Self::DelayVisibility => "delay-visibility",
+            Self::Closed => "closed",
+            Self::Builtin => "builtin",
+        };
+        write!(f, "{entry_type}")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcProgramCacheEntry {
+    pub program_id: String,
+    #[serde(rename = "type")]
+    pub entry_type: AdminRpcProgramCacheEntryType,
+    pub owner: String,
+    pub account_size: usize,
+    pub deployment_slot: u64,
+    pub effective_slot: u64,
+    pub tx_usage_count: u64,
+    pub ix_usage_count: u64,
+    pub latest_access_slot: u64,
+    pub jit_size: Option<usize>,
+    pub pinned: bool,
+    pub evictions: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcProgramCache {
+    pub slot: u64,
+    pub root_slot: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub water_level: u64,
+    pub pinned_programs: Vec<String>,
+    pub entries: Vec<AdminRpcProgramCacheEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcLeaderSlotStats {
+    pub slot: Slot,
+    pub frozen: bool,
+    pub transaction_count: u64,
+    pub failed_transaction_count: u64,
+    pub block_cost: u64,
+    pub vote_cost: u64,
+    pub block_cost_limit: u64,
+}
+
+/// Counts of the banking stage scheduler since the validator started
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcBankingStageStats {
+    pub received_packets: u64,
+    pub buffered_transactions: u64,
+    pub scheduled_transactions: u64,
+    pub finished_transactions: u64,
+    pub retryable_transactions: u64,
+    pub dropped_transactions: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcMonitorStats {
+    pub vote_account: Pubkey,
+    pub root_slot: Slot,
+    pub working_slot: Slot,
+    /// Requests received by the JSON RPC service, if it is enabled
+    pub rpc_request_count: Option<u64>,
+    /// Banks this node produced that have not been rooted or pruned yet
+    pub leader_slots: Vec<AdminRpcLeaderSlotStats>,
+    #[serde(default)]
+    pub banking_stage: AdminRpcBankingStageStats,
+}
+
+impl From<&SchedulerCountTotals> for AdminRpcBankingStageStats {
+    fn from(totals: &SchedulerCountTotals) -> Self {
+        Self {
+            received_packets: totals.num_received.load(Ordering::Relaxed),
+            buffered_transactions: totals.num_buffered.load(Ordering::Relaxed),
+            scheduled_transactions: totals.num_scheduled.load(Ordering::Relaxed),
+            finished_transactions: totals.num_finished.load(Ordering::Relaxed),
+            retryable_transactions: totals.num_retryable.load(Ordering::Relaxed),
+            dropped_transactions: totals.num_dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
 impl From<ContactInfo> for AdminRpcContactInfo {
     fn from(node: ContactInfo) -> Self {
         macro_rules! unwrap_socket {
@@ -148,6 +303,221 @@ impl Display for AdminRpcRepairWhitelist {
 impl solana_cli_output::VerboseDisplay for AdminRpcRepairWhitelist {}
 impl solana_cli_output::QuietDisplay for AdminRpcRepairWhitelist {}
 
+impl Display for AdminRpcProgramCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Slot: {}", self.slot)?;
+        writeln!(f, "Root Slot: {}", self.root_slot)?;
+        writeln!(f, "Hits: {}", self.hits)?;
+        writeln!(f, "Misses: {}", self.misses)?;
+        writeln!(f, "Loaded Entries: {}", self.water_level)?;
+        writeln!(f, "Pinned Programs: {:?}", self.pinned_programs)?;
+        writeln!(
+            f,
+            "{:<44}  {:<19}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>9}  {:>6}",
+            "Program Id",
+            "Type",
+            "Deployed",
+            "Effective",
+            "Last Used",
+            "Tx Usage",
+            "Ix Usage",
+            "JIT Size",
+            "Pinned",
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:<44}  {:<19}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>9}  {:>6}",
+                entry.program_id,
+                entry.entry_type.to_string(),
+                entry.deployment_slot,
+                entry.effective_slot,
+                entry.latest_access_slot,
+                entry.tx_usage_count,
+                entry.ix_usage_count,
+                entry
+                    .jit_size
+                    .map(|jit_size| jit_size.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                if entry.pinned { "yes" } else { "no" },
+            )?;
+        }
+        Ok(())
+    }
+}
+impl solana_cli_output::VerboseDisplay for AdminRpcProgramCache {}
+impl solana_cli_output::QuietDisplay for AdminRpcProgramCache {}
+
+/// Validator settings that can be changed at runtime with `setConfig`.
+///
+/// When returned by `getConfig`, `None` means the setting does not apply to
+/// this validator. When passed to `setConfig`, `None` leaves it unchanged.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcRuntimeConfig {
+    pub full_snapshot_interval_slots: Option<u64>,
+    /// Zero means incremental snapshots are disabled
+    pub incremental_snapshot_interval_slots: Option<u64>,
+    pub health_check_slot_distance: Option<u64>,
+}
+
+impl AdminRpcRuntimeConfig {
+    fn new(
+        snapshot_intervals: Option<SnapshotGenerationIntervals>,
+        health_check_slot_distance: Option<u64>,
+    ) -> Self {
+        let interval_slots = |interval| match interval {
+            SnapshotInterval::Disabled => 0,
+            SnapshotInterval::Slots(slots) => NonZeroU64::get(slots),
+        };
+        Self {
+            full_snapshot_interval_slots: snapshot_intervals
+                .map(|intervals| interval_slots(intervals.full_snapshot_interval)),
+            incremental_snapshot_interval_slots: snapshot_intervals
+                .map(|intervals| interval_slots(intervals.incremental_snapshot_interval)),
+            health_check_slot_distance,
+        }
+    }
+
+    fn startup(post_init: &AdminRpcRequestMetadataPostInit) -> Self {
+        let snapshot_config = post_init.snapshot_controller.snapshot_config();
+        Self::new(
+            snapshot_config
+                .should_generate_snapshots()
+                .then_some(SnapshotGenerationIntervals {
+                    full_snapshot_interval: snapshot_config.full_snapshot_archive_interval,
+                    incremental_snapshot_interval: snapshot_config
+                        .incremental_snapshot_archive_interval,
+                }),
+            post_init
+                .rpc_health
+                .as_ref()
+                .map(|rpc_health| rpc_health.startup_health_check_slot_distance()),
+        )
+    }
+
+    fn current(post_init: &AdminRpcRequestMetadataPostInit) -> Self {
+        let snapshot_controller = &post_init.snapshot_controller;
+        Self::new(
+            snapshot_controller
+                .snapshot_config()
+                .should_generate_snapshots()
+                .then(|| snapshot_controller.snapshot_intervals()),
+            post_init
+                .rpc_health
+                .as_ref()
+                .map(|rpc_health| rpc_health.health_check_slot_distance()),
+        )
+    }
+
+    fn settings(&self) -> [(&'static str, Option<u64>); 3] {
+        [
+            (
+                "full-snapshot-interval-slots",
+                self.full_snapshot_interval_slots,
+            ),
+            (
+                "incremental-snapshot-interval-slots",
+                self.incremental_snapshot_interval_slots,
+            ),
+            (
+                "health-check-slot-distance",
+                self.health_check_slot_distance,
+            ),
+        ]
+    }
+}
+
+fn format_setting(value: Option<u64>) -> String {
+    value.map_or_else(|| "n/a".to_string(), |value| value.to_string())
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcConfig {
+    /// Settings the validator was started with
+    pub startup: AdminRpcRuntimeConfig,
+    /// Settings currently in effect
+    pub current: AdminRpcRuntimeConfig,
+}
+
+impl AdminRpcConfig {
+    /// Returns the settings that have been changed since startup
+    pub fn changes(&self) -> AdminRpcConfigChanges {
+        let changes = self
+            .startup
+            .settings()
+            .into_iter()
+            .zip(self.current.settings())
+            .filter(|((_, startup), (_, current))| startup != current)
+            .map(|((setting, startup), (_, current))| AdminRpcConfigChange {
+                setting: setting.to_string(),
+                startup,
+                current,
+            })
+            .collect();
+        AdminRpcConfigChanges { changes }
+    }
+}
+
+impl Display for AdminRpcConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<37} {:>12} {:>12}", "Setting", "Startup", "Current")?;
+        for ((setting, startup), (_, current)) in self
+            .startup
+            .settings()
+            .into_iter()
+            .zip(self.current.settings())
+        {
+            writeln!(
+                f,
+                "{:<37} {:>12} {:>12}{}",
+                setting,
+                format_setting(startup),
+                format_setting(current),
+                if startup != current { " (changed)" } else { "" },
+            )?;
+        }
+        Ok(())
+    }
+}
+impl solana_cli_output::VerboseDisplay for AdminRpcConfig {}
+impl solana_cli_output::QuietDisplay for AdminRpcConfig {}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcConfigChange {
+    pub setting: String,
+    pub startup: Option<u64>,
+    pub current: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcConfigChanges {
+    pub changes: Vec<AdminRpcConfigChange>,
+}
+
+impl Display for AdminRpcConfigChanges {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "No settings have been changed since startup");
+        }
+        for change in &self.changes {
+            writeln!(
+                f,
+                "{}: {} -> {}",
+                change.setting,
+                format_setting(change.startup),
+                format_setting(change.current),
+            )?;
+        }
+        Ok(())
+    }
+}
+impl solana_cli_output::VerboseDisplay for AdminRpcConfigChanges {}
+impl solana_cli_output::QuietDisplay for AdminRpcConfigChanges {}
+
 #[rpc]
 pub trait AdminRpc {
     type Metadata;
@@ -240,6 +610,49 @@ pub trait AdminRpc {
     #[rpc(meta, name = "setRepairWhitelist")]
     fn set_repair_whitelist(&self, meta: Self::Metadata, whitelist: Vec<Pubkey>) -> Result<()>;
 
+    #[rpc(meta, name = "importShredArchive")]
+    fn import_shred_archive(&self, meta: Self::Metadata, path: String) -> Result<()>;
+
+    #[rpc(meta, name = "shredArchiveImportStatus")]
+    fn shred_archive_import_status(&self, meta: Self::Metadata)
+        -> Result<ShredArchiveImportStatus>;
+
+    #[rpc(meta, name = "shredArrivalSlots")]
+    fn shred_arrival_slots(&self, meta: Self::Metadata) -> Result<Vec<Slot>>;
+
+    #[rpc(meta, name = "shredArrivals")]
+    fn shred_arrivals(&self, meta: Self::Metadata, slot: Slot)
+        -> Result<Option<SlotShredArrivals>>;
+
+    #[rpc(meta, name = "programCache")]
+    fn program_cache(&self, meta: Self::Metadata) -> Result<AdminRpcProgramCache>;
+
+    #[rpc(meta, name = "monitorStats")]
+    fn monitor_stats(&self, meta: Self::Metadata) -> Result<AdminRpcMonitorStats>;
+
+    #[rpc(meta, name = "pinPrograms")]
+    fn pin_programs(&self, meta: Self::Metadata, program_ids: Vec<Pubkey>) -> Result<()>;
+
+    #[rpc(meta, name = "unpinPrograms")]
+    fn unpin_programs(&self, meta: Self::Metadata, program_ids: Vec<Pubkey>) -> Result<()>;
+
+    /// Return the startup and current values of the settings `setConfig` can change
+    #[rpc(meta, name = "getConfig")]
+    fn get_config(&self, meta: Self::Metadata) -> Result<AdminRpcConfig>;
+
+    /// Change the settings that are set in `config`. Either all of them are
+    /// applied or, if any is invalid, none are.
+    #[rpc(meta, name = "setConfig")]
+    fn set_config(&self, meta: Self::Metadata, config: AdminRpcRuntimeConfig) -> Result<()>;
+
+    /// Return up to `limit` of the most recent voting decisions, oldest first
+    #[rpc(meta, name = "voteDecisions")]
+    fn vote_decisions(
+        &self,
+        meta: Self::Metadata,
+        limit: Option<usize>,
+    ) -> Result<Vec<VoteDecisionRecord>>;
+
     #[rpc(meta, name = "getSecondaryIndexKeySize")]
     fn get_secondary_index_key_size(
         &self,
@@ -629,6 +1042,350 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
+    fn import_shred_archive(&self, meta: Self::Metadata, path: String) -> Result<()> {
+        debug!("import_shred_archive request received: {path}");
+
+        let (blockstore, bank_forks, leader_schedule_cache) = meta.with_post_init(|post_init| {
+            Ok((
+                post_init.blockstore.clone(),
+                post_init.bank_forks.clone(),
+                post_init.leader_schedule_cache.clone(),
+            ))
+        })?;
+        let mut status = meta.shred_archive_import.write().unwrap();
+        if let ShredArchiveImportStatus::Running { path } = &*status {
+            return Err(jsonrpc_core::error::Error::invalid_params(format!(
+                "Shred archive {path} is still being imported"
+            )));
+        }
+        let file = File::open(&path).map_err(|err| {
+            jsonrpc_core::error::Error::invalid_params(format!(
+                "Failed to open shred archive {path}: {err}"
+            ))
+        })?;
+
+        // Importing an archive can take a long time, so run it off the admin rpc thread and
+        // let the caller poll `shredArchiveImportStatus` for the result.
+        let shred_archive_import = meta.shred_archive_import.clone();
+        Builder::new()
+            .name("solShredArcImp".into())
+            .spawn({
+                let path = path.clone();
+                move || {
+                    let root_bank = bank_forks.read().unwrap().root_bank();
+                    let result = blockstore.import_shred_archive(BufReader::new(file), |slot| {
+                        leader_schedule_cache.slot_leader_at(slot, Some(&root_bank))
+                    });
+                    *shred_archive_import.write().unwrap() = match result {
+                        Ok(stats) => {
+                            info!("Imported shred archive {path}: {stats:?}");
+                            ShredArchiveImportStatus::Completed { path, stats }
+                        }
+                        Err(err) => {
+                            warn!("Failed to import shred archive {path}: {err}");
+                            ShredArchiveImportStatus::Failed {
+                                path,
+                                error: err.to_string(),
+                            }
+                        }
+                    };
+                }
+            })
+            .map_err(|err| {
+                jsonrpc_core::error::Error::invalid_params(format!(
+                    "Failed to start shred archive import: {err}"
+                ))
+            })?;
+        *status = ShredArchiveImportStatus::Running { path };
+        Ok(())
+    }
+
+    fn shred_archive_import_status(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<ShredArchiveImportStatus> {
+        debug!("shred_archive_import_status request received");
+
+        Ok(meta.shred_archive_import.read().unwrap().clone())
+    }
+
+    fn shred_arrival_slots(&self, meta: Self::Metadata) -> Result<Vec<Slot>> {
+        debug!("shred_arrival_slots request received");
+
+        meta.with_post_init(|post_init| Ok(shred_arrival_telemetry(post_init)?.slots()))
+    }
+
+    fn shred_arrivals(
+        &self,
+        meta: Self::Metadata,
+        slot: Slot,
+    ) -> Result<Option<SlotShredArrivals>> {
+        debug!("shred_arrivals request received: {slot}");
+
+        meta.with_post_init(|post_init| {
+            let Some(mut slot_arrivals) = shred_arrival_telemetry(post_init)?.get(slot) else {
+                return Ok(None);
+            };
+            // Shreds are retransmitted and repair responses sent from other
+            // sockets than the ones advertised in gossip, so senders are
+            // matched to nodes by IP address, unless several nodes share it.
+            let mut nodes = HashMap::<IpAddr, Option<Pubkey>>::new();
+            for (ip, pubkey) in post_init
+                .cluster_info
+                .tvu_peers(|node| Some((node.tvu(Protocol::UDP)?.ip(), *node.pubkey())))
+                .into_iter()
+                .flatten()
+            {
+                nodes
+                    .entry(ip)
+                    .and_modify(|node| *node = None)
+                    .or_insert(Some(pubkey));
+            }
+            for arrival in &mut slot_arrivals.arrivals {
+                arrival.from_node = nodes
+                    .get(&arrival.from.ip())
+                    .copied()
+                    .flatten()
+                    .map(|pubkey| pubkey.to_string());
+            }
+            Ok(Some(slot_arrivals))
+        })
+    }
+
+    fn program_cache(&self, meta: Self::Metadata) -> Result<AdminRpcProgramCache> {
+        debug!("program_cache request received");
+
+        meta.with_post_init(|post_init| {
+            let slot = post_init.bank_forks.read().unwrap().working_bank().slot();
+            let root_bank = post_init.bank_forks.read().unwrap().root_bank();
+            let program_cache = root_bank.program_cache();
+            let program_cache = program_cache.read().unwrap();
+            let mut entries: Vec<_> = program_cache
+                .get_all_entries()
+                .into_iter()
+                .map(|(program_id, entry)| AdminRpcProgramCacheEntry {
+                    program_id: program_id.to_string(),
+                    entry_type: AdminRpcProgramCacheEntryType::new(&entry, slot),
+                    owner: entry.account_owner().to_string(),
+                    account_size: entry.account_size,
+                    deployment_slot: entry.deployment_slot,
+                    effective_slot: entry.effective_slot,
+                    tx_usage_count: entry.tx_usage_counter.load(Ordering::Relaxed),
+                    ix_usage_count: entry.ix_usage_counter.load(Ordering::Relaxed),
+                    latest_access_slot: entry.latest_access_slot.load(Ordering::Relaxed),
+                    jit_size: entry.jit_size(),
+                    pinned: program_cache.is_pinned(&program_id),
+                    evictions: program_cache
+                        .stats
+                        .evictions
+                        .get(&program_id)
+                        .copied()
+                        .unwrap_or_default(),
+                })
+                .collect();
+            entries.sort_by(|a, b| {
+                a.program_id
+                    .cmp(&b.program_id)
+                    .then(a.effective_slot.cmp(&b.effective_slot))
+            });
+            let mut pinned_programs: Vec<_> = program_cache
+                .pinned_programs()
+                .iter()
+                .map(|program_id| program_id.to_string())
+                .collect();
+            pinned_programs.sort();
+            Ok(AdminRpcProgramCache {
+                slot,
+                root_slot: program_cache.latest_root_slot,
+                hits: program_cache.stats.hits.load(Ordering::Relaxed),
+                misses: program_cache.stats.misses.load(Ordering::Relaxed),
+                water_level: program_cache.stats.water_level.load(Ordering::Relaxed),
+                pinned_programs,
+                entries,
+            })
+        })
+    }
+
+    fn monitor_stats(&self, meta: Self::Metadata) -> Result<AdminRpcMonitorStats> {
+        debug!("monitor_stats request received");
+
+        meta.with_post_init(|post_init| {
+            let identity = post_init.cluster_info.id();
+            let bank_forks = post_init.bank_forks.read().unwrap();
+            let mut leader_slots: Vec<_> = bank_forks
+                .banks()
+                .values()
+                .filter(|bank| *bank.collector_id() == identity)
+                .map(|bank| {
+                    let cost_tracker = bank.read_cost_tracker().unwrap();
+                    AdminRpcLeaderSlotStats {
+                        slot: bank.slot(),
+                        frozen: bank.is_frozen(),
+                        transaction_count: bank.executed_transaction_count(),
+                        failed_transaction_count: bank.transaction_error_count(),
+                        block_cost: cost_tracker.block_cost(),
+                        vote_cost: cost_tracker.vote_cost(),
+                        block_cost_limit: cost_tracker.get_block_limit(),
+                    }
+                })
+                .collect();
+            leader_slots.sort_by_key(|leader_slot| leader_slot.slot);
+            Ok(AdminRpcMonitorStats {
+                vote_account: post_init.vote_account,
+                root_slot: bank_forks.root(),
+                working_slot: bank_forks.working_bank().slot(),
+                rpc_request_count: post_init
+                    .rpc_request_count
+                    .as_ref()
+                    .map(|request_count| request_count.load(Ordering::Relaxed)),
+                leader_slots,
+                banking_stage: AdminRpcBankingStageStats::from(&SCHEDULER_COUNT_TOTALS),
+            })
+        })
+    }
+
+    fn pin_programs(&self, meta: Self::Metadata, program_ids: Vec<Pubkey>) -> Result<()> {
+        debug!("pin_programs request received");
+
+        meta.with_post_init(|post_init| {
+            let program_cache = post_init
+                .bank_forks
+                .read()
+                .unwrap()
+                .root_bank()
+                .program_cache();
+            let mut program_cache = program_cache.write().unwrap();
+            for program_id in program_ids {
+                if program_cache.pin_program(program_id) {
+                    info!("Pinned program {program_id} in the program cache");
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn unpin_programs(&self, meta: Self::Metadata, program_ids: Vec<Pubkey>) -> Result<()> {
+        debug!("unpin_programs request received");
+
+        meta.with_post_init(|post_init| {
+            let program_cache = post_init
+                .bank_forks
+                .read()
+                .unwrap()
+                .root_bank()
+                .program_cache();
+            let mut program_cache = program_cache.write().unwrap();
+            for program_id in program_ids {
+                if program_cache.unpin_program(&program_id) {
+                    info!("Unpinned program {program_id} in the program cache");
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn get_config(&self, meta: Self::Metadata) -> Result<AdminRpcConfig> {
+        debug!("get_config request received");
+
+        meta.with_post_init(|post_init| {
+            Ok(AdminRpcConfig {
+                startup: AdminRpcRuntimeConfig::startup(post_init),
+                current: AdminRpcRuntimeConfig::current(post_init),
+            })
+        })
+    }
+
+    fn set_config(&self, meta: Self::Metadata, config: AdminRpcRuntimeConfig) -> Result<()> {
+        debug!("set_config request received: {config:?}");
+
+        meta.with_post_init(|post_init| {
+            let snapshot_controller = &post_init.snapshot_controller;
+            let mut snapshot_intervals = snapshot_controller.snapshot_intervals();
+            if config.full_snapshot_interval_slots.is_some()
+                || config.incremental_snapshot_interval_slots.is_some()
+            {
+                if !snapshot_controller
+                    .snapshot_config()
+                    .should_generate_snapshots()
+                {
+                    return Err(jsonrpc_core::error::Error::invalid_params(
+                        "Snapshot generation is disabled on this validator",
+                    ));
+                }
+                if let Some(slots) = config.full_snapshot_interval_slots {
+                    snapshot_intervals.full_snapshot_interval = NonZeroU64::new(slots)
+                        .map(SnapshotInterval::Slots)
+                        .ok_or_else(|| {
+                            jsonrpc_core::error::Error::invalid_params(
+                                "The full snapshot interval must be greater than zero",
+                            )
+                        })?;
+                }
+                if let Some(slots) = config.incremental_snapshot_interval_slots {
+                    snapshot_intervals.incremental_snapshot_interval = NonZeroU64::new(slots)
+                        .map_or(SnapshotInterval::Disabled, SnapshotInterval::Slots);
+                }
+                let snapshot_config = SnapshotConfig {
+                    full_snapshot_archive_interval: snapshot_intervals.full_snapshot_interval,
+                    incremental_snapshot_archive_interval: snapshot_intervals
+                        .incremental_snapshot_interval,
+                    ..snapshot_controller.snapshot_config().clone()
+                };
+                if !is_snapshot_config_valid(&snapshot_config) {
+                    return Err(jsonrpc_core::error::Error::invalid_params(
+                        "The incremental snapshot interval must be less than the full snapshot \
+                         interval",
+                    ));
+                }
+            }
+            if config.health_check_slot_distance.is_some() && post_init.rpc_health.is_none() {
+                return Err(jsonrpc_core::error::Error::invalid_params(
+                    "The JSON RPC service is not enabled on this validator",
+                ));
+            }
+
+            let previous_config = AdminRpcRuntimeConfig::current(post_init);
+            if config.full_snapshot_interval_slots.is_some()
+                || config.incremental_snapshot_interval_slots.is_some()
+            {
+                snapshot_controller.set_snapshot_intervals(snapshot_intervals);
+            }
+            if let (Some(rpc_health), Some(distance)) =
+                (&post_init.rpc_health, config.health_check_slot_distance)
+            {
+                rpc_health.set_health_check_slot_distance(distance);
+            }
+            for ((setting, previous), (_, current)) in previous_config
+                .settings()
+                .into_iter()
+                .zip(AdminRpcRuntimeConfig::current(post_init).settings())
+            {
+                if previous != current {
+                    info!(
+                        "setConfig: {setting} changed from {} to {}",
+                        format_setting(previous),
+                        format_setting(current),
+                    );
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn vote_decisions(
+        &self,
+        meta: Self::Metadata,
+        limit: Option<usize>,
+    ) -> Result<Vec<VoteDecisionRecord>> {
+        debug!("vote_decisions request received");
+
+        meta.with_post_init(|post_init| {
+            Ok(post_init
+                .vote_decision_history
+                .recent(limit.unwrap_or(usize::MAX)))
+        })
+    }
+
     fn get_secondary_index_key_size(
         &self,
         meta: Self::Metadata,
@@ -791,6 +1548,18 @@ impl AdminRpcImpl {
                             err
                         ))
                     })?;
+            } else {
+                // Restoring the tower above acquires the lease as well. Without
+                // it ReplayStage would only build a tower it can't vote with
+                meta.tower_storage
+                    .acquire_lease(&identity_keypair.pubkey())
+                    .map_err(|err| {
+                        jsonrpc_core::error::Error::invalid_params(format!(
+                            "Unable to acquire the tower lease for identity {}: {}",
+                            identity_keypair.pubkey(),
+                            err
+                        ))
+                    })?;
             }
 
             for (key, notifier) in &*post_init.notifies.read().unwrap() {
@@ -799,16 +1568,36 @@ impl AdminRpcImpl {
                 }
             }
 
+            let previous_identity = post_init.cluster_info.id();
             solana_metrics::set_host_id(identity_keypair.pubkey().to_string());
             post_init
                 .cluster_info
                 .set_keypair(Arc::new(identity_keypair));
             warn!("Identity set to {}", post_init.cluster_info.id());
+
+            // Let another instance take over the previous identity right away
+            if previous_identity != post_init.cluster_info.id() {
+                if let Err(err) = meta.tower_storage.release(&previous_identity) {
+                    warn!("Failed to release the tower lease for {previous_identity}: {err}");
+                }
+            }
             Ok(())
         })
     }
 }
 
+fn shred_arrival_telemetry(post_init: &AdminRpcRequestMetadataPostInit) -> Result<&ShredArrivals> {
+    post_init
+        .shred_arrivals
+        .as_deref()
+        .ok_or_else(|| jsonrpc_core::Error {
+            code: ErrorCode::InvalidRequest,
+            message: "Shred arrival telemetry is not enabled, see --shred-arrival-telemetry"
+                .to_string(),
+            data: None,
+        })
+}
+
 fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> RpcAccountIndex {
     match account_index {
         AccountIndex::ProgramId => RpcAccountIndex::ProgramId,
@@ -951,24 +1740,41 @@ mod tests {
         },
         solana_core::{
             admin_rpc_post_init::{KeyUpdaterType, KeyUpdaters},
-            consensus::tower_storage::NullTowerStorage,
+            consensus::{
+                tower_storage::NullTowerStorage,
+                vote_decision_history::{VoteDecision, VoteFailure, VoteSwitch},
+                SwitchForkDecision,
+            },
+            replay_stage::HeaviestForkFailures,
             validator::{Validator, ValidatorConfig, ValidatorTpuConfig},
         },
+        solana_entry::entry::create_ticks,
         solana_gossip::cluster_info::{ClusterInfo, Node},
+        solana_hash::Hash,
         solana_ledger::{
+            blockstore::Blockstore,
             create_new_tmp_ledger,
             genesis_utils::{
                 create_genesis_config, create_genesis_config_with_leader, GenesisConfigInfo,
             },
+            get_tmp_ledger_path_auto_delete,
+            leader_schedule_cache::LeaderScheduleCache,
+            shred::{ProcessShredsStats, ReedSolomonCache, Shredder},
         },
         solana_net_utils::bind_to_unspecified,
         solana_program_option::COption,
         solana_program_pack::Pack,
         solana_pubkey::Pubkey,
-        solana_rpc::rpc::create_validator_exit,
+        solana_rpc::{
+            optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
+            rpc::create_validator_exit, rpc_health::RpcHealth,
+        },
+        solana_rpc_client_api::request::DELINQUENT_VALIDATOR_SLOT_DISTANCE,
         solana_runtime::{
             bank::{Bank, BankTestConfig},
             bank_forks::BankForks,
+            snapshot_controller::SnapshotController,
+            snapshot_utils,
         },
         solana_streamer::socket::SocketAddrSpace,
         solana_system_interface::program as system_program,
@@ -976,6 +1782,7 @@ mod tests {
         spl_generic_token::token,
         spl_token_2022::state::{Account as TokenAccount, AccountState as TokenAccountState, Mint},
         std::{collections::HashSet, fs::remove_dir_all, sync::atomic::AtomicBool},
+        tempfile::TempDir,
     };
 
     #[derive(Default)]
@@ -987,10 +1794,11 @@ mod tests {
         io: MetaIoHandler<AdminRpcRequestMetadata>,
         meta: AdminRpcRequestMetadata,
         bank_forks: Arc<RwLock<BankForks>>,
+        _ledger_path: TempDir,
     }
 
     impl RpcHandler {
-        fn _start() -> Self {
+        fn start() -> Self {
             Self::start_with_config(TestConfig::default())
         }
 
@@ -1014,8 +1822,20 @@ mod tests {
                 },
             });
             let vote_account = vote_keypair.pubkey();
+            let ledger_path = get_tmp_ledger_path_auto_delete!();
+            let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+            let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(
+                &bank_forks.read().unwrap().root_bank(),
+            ));
             let start_progress = Arc::new(RwLock::new(ValidatorStartProgress::default()));
             let repair_whitelist = Arc::new(RwLock::new(HashSet::new()));
+            let rpc_health = Some(Arc::new(RpcHealth::new(
+                OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks),
+                blockstore.clone(),
+                DELINQUENT_VALIDATOR_SLOT_DISTANCE,
+                Arc::new(AtomicBool::new(false)),
+                Arc::new(AtomicBool::new(true)),
+            )));
             let meta = AdminRpcRequestMetadata {
                 rpc_addr: None,
                 start_time: SystemTime::now(),
@@ -1038,9 +1858,21 @@ mod tests {
                         solana_core::cluster_slots_service::cluster_slots::ClusterSlots::default(),
                     ),
                     gossip_socket: None,
+                    blockstore,
+                    leader_schedule_cache,
+                    shred_arrivals: None,
+                    rpc_request_count: None,
+                    rpc_health,
+                    snapshot_controller: Arc::new(SnapshotController::new(
+                        crossbeam_channel::unbounded().0,
+                        SnapshotConfig::default(),
+                        0,
+                    )),
+                    vote_decision_history: Arc::default(),
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
+                shred_archive_import: Arc::default(),
             };
             let mut io = MetaIoHandler::default();
             io.extend_with(AdminRpcImpl.to_delegate());
@@ -1049,6 +1881,7 @@ mod tests {
                 io,
                 meta,
                 bank_forks,
+                _ledger_path: ledger_path,
             }
         }
 
@@ -1376,6 +2209,294 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_monitor_stats() {
+        let rpc = RpcHandler::start();
+        let RpcHandler {
+            io,
+            meta,
+            bank_forks,
+            ..
+        } = rpc;
+
+        let get_monitor_stats = || {
+            let req = r#"{"jsonrpc":"2.0","id":1,"method":"monitorStats"}"#;
+            let res = io.handle_request_sync(req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            serde_json::from_value::<AdminRpcMonitorStats>(result["result"].clone()).unwrap()
+        };
+
+        let monitor_stats = get_monitor_stats();
+        assert_eq!(monitor_stats.root_slot, 0);
+        assert_eq!(monitor_stats.rpc_request_count, None);
+        assert!(monitor_stats.leader_slots.is_empty());
+
+        // Only banks collected by this node are reported
+        let identity = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .cluster_info
+            .id();
+        let root_bank = bank_forks.read().unwrap().root_bank();
+        bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::new_from_parent(root_bank.clone(), &identity, 1));
+        bank_forks.write().unwrap().insert(Bank::new_from_parent(
+            root_bank,
+            &Pubkey::new_unique(),
+            2,
+        ));
+
+        let monitor_stats = get_monitor_stats();
+        assert_eq!(monitor_stats.working_slot, 2);
+        assert_eq!(monitor_stats.leader_slots.len(), 1);
+        let leader_slot = &monitor_stats.leader_slots[0];
+        assert_eq!(leader_slot.slot, 1);
+        assert!(!leader_slot.frozen);
+        assert_eq!(leader_slot.transaction_count, 0);
+        assert_eq!(leader_slot.block_cost, 0);
+        assert_ne!(leader_slot.block_cost_limit, 0);
+
+        // Banking stage counts are totals since the validator started
+        SCHEDULER_COUNT_TOTALS
+            .num_received
+            .fetch_add(10, Ordering::Relaxed);
+        let received_packets = get_monitor_stats().banking_stage.received_packets;
+        assert!(received_packets >= monitor_stats.banking_stage.received_packets + 10);
+    }
+
+    #[test]
+    fn test_get_set_config() {
+        let rpc = RpcHandler::start();
+        let RpcHandler { io, meta, .. } = rpc;
+
+        let get_config = || {
+            let req = r#"{"jsonrpc":"2.0","id":1,"method":"getConfig"}"#;
+            let res = io.handle_request_sync(req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            serde_json::from_value::<AdminRpcConfig>(result["result"].clone()).unwrap()
+        };
+        let set_config = |config: &str| {
+            let req =
+                format!(r#"{{"jsonrpc":"2.0","id":1,"method":"setConfig","params":[{config}]}}"#);
+            let res = io.handle_request_sync(&req, meta.clone());
+            serde_json::from_str::<Value>(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+
+        let startup = AdminRpcRuntimeConfig {
+            full_snapshot_interval_slots: Some(
+                snapshot_utils::DEFAULT_FULL_SNAPSHOT_ARCHIVE_INTERVAL_SLOTS.get(),
+            ),
+            incremental_snapshot_interval_slots: Some(
+                snapshot_utils::DEFAULT_INCREMENTAL_SNAPSHOT_ARCHIVE_INTERVAL_SLOTS.get(),
+            ),
+            health_check_slot_distance: Some(DELINQUENT_VALIDATOR_SLOT_DISTANCE),
+        };
+        let config = get_config();
+        assert_eq!(config.startup, startup);
+        assert_eq!(config.current, startup);
+        assert!(config.changes().changes.is_empty());
+
+        // The incremental interval must stay below the full interval, and an
+        // invalid request changes nothing
+        let result = set_config(
+            r#"{"healthCheckSlotDistance":10,"incrementalSnapshotIntervalSlots":1000000000}"#,
+        );
+        assert_eq!(result["error"]["code"], ErrorCode::InvalidParams.code());
+        assert_eq!(get_config().current, startup);
+
+        let result = set_config(r#"{"fullSnapshotIntervalSlots":0}"#);
+        assert_eq!(result["error"]["code"], ErrorCode::InvalidParams.code());
+
+        let result =
+            set_config(r#"{"healthCheckSlotDistance":10,"incrementalSnapshotIntervalSlots":0}"#);
+        assert_eq!(result["result"], Value::Null);
+        let config = get_config();
+        assert_eq!(config.startup, startup);
+        assert_eq!(
+            config.current,
+            AdminRpcRuntimeConfig {
+                incremental_snapshot_interval_slots: Some(0),
+                health_check_slot_distance: Some(10),
+                ..startup.clone()
+            }
+        );
+        assert_eq!(
+            config.changes(),
+            AdminRpcConfigChanges {
+                changes: vec![
+                    AdminRpcConfigChange {
+                        setting: "incremental-snapshot-interval-slots".to_string(),
+                        startup: startup.incremental_snapshot_interval_slots,
+                        current: Some(0),
+                    },
+                    AdminRpcConfigChange {
+                        setting: "health-check-slot-distance".to_string(),
+                        startup: startup.health_check_slot_distance,
+                        current: Some(10),
+                    },
+                ],
+            }
+        );
+
+        let post_init = meta.post_init.read().unwrap();
+        let post_init = post_init.as_ref().unwrap();
+        assert_eq!(
+            post_init.snapshot_controller.snapshot_intervals(),
+            SnapshotGenerationIntervals {
+                full_snapshot_interval: SnapshotInterval::Slots(
+                    snapshot_utils::DEFAULT_FULL_SNAPSHOT_ARCHIVE_INTERVAL_SLOTS
+                ),
+                incremental_snapshot_interval: SnapshotInterval::Disabled,
+            }
+        );
+        assert_eq!(
+            post_init
+                .rpc_health
+                .as_ref()
+                .unwrap()
+                .health_check_slot_distance(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_vote_decisions() {
+        let rpc = RpcHandler::start();
+        let RpcHandler { io, meta, .. } = rpc;
+
+        {
+            let post_init = meta.post_init.read().unwrap();
+            let vote_decision_history = &post_init.as_ref().unwrap().vote_decision_history;
+            vote_decision_history.record_vote(1, 1, &SwitchForkDecision::SameFork);
+            vote_decision_history.record_no_vote(
+                3,
+                Some(1),
+                Some(&SwitchForkDecision::FailedSwitchThreshold(10, 100)),
+                &[HeaviestForkFailures::LockedOut(3)],
+            );
+        }
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"voteDecisions","params":[1]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let decisions =
+            serde_json::from_value::<Vec<VoteDecisionRecord>>(result["result"].clone()).unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(
+            decisions[0].decision,
+            VoteDecision::NoVote {
+                heaviest_slot: 3,
+                last_voted_slot: Some(1),
+                switch: Some(VoteSwitch::FailedSwitchThreshold {
+                    observed_stake: 10,
+                    total_stake: 100,
+                }),
+                failures: vec![VoteFailure::LockedOut { slot: 3 }],
+            }
+        );
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"voteDecisions","params":[null]}"#;
+        let res = io.handle_request_sync(req, meta);
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_shred_archive() {
+        let rpc = RpcHandler::start();
+        let RpcHandler { io, meta, .. } = rpc;
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive_path = archive_dir.path().join("shreds.bin");
+
+        // Make `leader` the leader of every slot so that its shreds pass verification
+        let leader = Keypair::new();
+        let genesis_config =
+            create_genesis_config_with_leader(1_000_000_000, &leader.pubkey(), 1000).genesis_config;
+        let bank_forks = BankForks::new_rw_arc(Bank::new_for_tests(&genesis_config));
+        let blockstore = {
+            let mut post_init = meta.post_init.write().unwrap();
+            let post_init = post_init.as_mut().unwrap();
+            post_init.leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(
+                &bank_forks.read().unwrap().root_bank(),
+            ));
+            post_init.bank_forks = bank_forks;
+            post_init.blockstore.clone()
+        };
+
+        let import_shred_archive = |path: &Path| {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"importShredArchive","params":[{}]}}"#,
+                serde_json::to_string(&path.display().to_string()).unwrap(),
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            serde_json::from_str::<Value>(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+        let wait_for_import = || loop {
+            let req = r#"{"jsonrpc":"2.0","id":1,"method":"shredArchiveImportStatus"}"#;
+            let res = io.handle_request_sync(req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let status: ShredArchiveImportStatus =
+                serde_json::from_value(result["result"].clone()).unwrap();
+            if !matches!(status, ShredArchiveImportStatus::Running { .. }) {
+                break status;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        // Missing archives are reported as errors
+        let result = import_shred_archive(&archive_path);
+        assert!(result["error"].is_object());
+        assert_eq!(wait_for_import(), ShredArchiveImportStatus::Idle);
+
+        // Export a full slot from another ledger
+        let source_ledger_path = get_tmp_ledger_path_auto_delete!();
+        let source = Blockstore::open(source_ledger_path.path()).unwrap();
+        let entries = create_ticks(64, 1, Hash::new_unique());
+        let shreds: Vec<_> = Shredder::new(1, 0, 0, 0)
+            .unwrap()
+            .make_merkle_shreds_from_entries(
+                &leader,
+                &entries,
+                true, // is_last_in_slot
+                Some(Hash::new_unique()),
+                0, // next_shred_index
+                0, // next_code_index
+                &ReedSolomonCache::default(),
+                &mut ProcessShredsStats::default(),
+            )
+            .collect();
+        let num_data_shreds = shreds.iter().filter(|shred| shred.is_data()).count();
+        source.insert_shreds(shreds, None, false).unwrap();
+        source
+            .export_shred_archive(std::fs::File::create(&archive_path).unwrap(), 0, 10)
+            .unwrap();
+        assert!(!blockstore.is_full(1));
+
+        let result = import_shred_archive(&archive_path);
+        assert_eq!(result["result"], Value::Null);
+        let ShredArchiveImportStatus::Completed { path, stats } = wait_for_import() else {
+            panic!("shred archive import did not complete");
+        };
+        assert_eq!(path, archive_path.display().to_string());
+        assert_eq!(stats.num_slots, 1);
+        assert_eq!(stats.num_rejected_slots, 0);
+        assert_eq!(stats.num_data_shreds, num_data_shreds);
+        assert!(blockstore.is_full(1));
+        assert_eq!(blockstore.get_slot_entries(1, 0).unwrap(), entries);
+    }
+
     // This test checks that the rpc call to `set_identity` works a expected with
     // Bank but without validator.
     #[test]
@@ -1419,6 +2540,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_program_cache() {
+        let rpc = RpcHandler::start();
+        let RpcHandler { io, meta, .. } = rpc;
+        let program_id = Pubkey::new_unique();
+
+        let get_program_cache = || {
+            let req = r#"{"jsonrpc":"2.0","id":1,"method":"programCache"}"#;
+            let res = io.handle_request_sync(req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            serde_json::from_value::<AdminRpcProgramCache>(result["result"].clone()).unwrap()
+        };
+
+        // Builtins are always in the cache
+        let program_cache = get_program_cache();
+        assert!(program_cache.pinned_programs.is_empty());
+        assert!(program_cache.entries.iter().any(|entry| entry.entry_type
+            == AdminRpcProgramCacheEntryType::Builtin
+            && entry.program_id == system_program::id().to_string()));
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"pinPrograms","params":[{}]}}"#,
+            serde_json::to_string(&vec![program_id]).unwrap(),
+        );
+        io.handle_request_sync(&req, meta.clone())
+            .expect("actual response");
+        assert_eq!(
+            get_program_cache().pinned_programs,
+            vec![program_id.to_string()]
+        );
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"unpinPrograms","params":[{}]}}"#,
+            serde_json::to_string(&vec![program_id]).unwrap(),
+        );
+        io.handle_request_sync(&req, meta.clone())
+            .expect("actual response");
+        assert!(get_program_cache().pinned_programs.is_empty());
+    }
+
     struct TestValidatorWithAdminRpc {
         meta: AdminRpcRequestMetadata,
         io: MetaIoHandler<AdminRpcRequestMetadata>,
@@ -1461,6 +2623,7 @@ mod tests {
                 post_init: post_init.clone(),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
+                shred_archive_import: Arc::default(),
             };
 
             let _validator = Validator::new(
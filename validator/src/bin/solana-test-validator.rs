@@ -428,6 +428,7 @@ fn main() {
             post_init: admin_service_post_init,
             tower_storage: tower_storage.clone(),
             rpc_to_plugin_manager_sender,
+            shred_archive_import: Arc::default(),
         },
     );
     let dashboard = if output == Output::Dashboard {
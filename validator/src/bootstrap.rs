@@ -6,7 +6,10 @@ use {
     solana_clock::Slot,
     solana_commitment_config::CommitmentConfig,
     solana_core::validator::{ValidatorConfig, ValidatorStartProgress},
-    solana_download_utils::{download_snapshot_archive, DownloadProgressRecord},
+    solana_download_utils::{
+        download_snapshot_archive, download_snapshot_archive_from_peers, DownloadProgressRecord,
+        ParallelDownloadConfig, ParallelDownloadError,
+    },
     solana_genesis_utils::download_then_check_genesis_hash,
     solana_gossip::{
         cluster_info::{ClusterInfo, Node},
@@ -64,6 +67,9 @@ pub struct RpcBootstrapConfig {
     pub max_genesis_archive_unpacked_size: u64,
     pub check_vote_account: Option<String>,
     pub incremental_snapshot_fetch: bool,
+    /// Maximum number of RPC peers advertising the same snapshot to try to download it from in
+    /// byte ranges
+    pub snapshot_download_peers: usize,
 }
 
 fn verify_reachable_ports(
@@ -374,6 +380,7 @@ pub fn attempt_download_genesis_and_snapshot(
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
+    snapshot_peers: &[SocketAddr],
     identity_keypair: &Arc<Keypair>,
     vote_account: &Pubkey,
     authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
@@ -410,6 +417,7 @@ pub fn attempt_download_genesis_and_snapshot(
         download_abort_count,
         snapshot_hash,
         rpc_contact_info,
+        snapshot_peers,
     )?;
 
     if let Some(url) = bootstrap_config.check_vote_account.as_ref() {
@@ -626,6 +634,18 @@ pub fn rpc_bootstrap(
             &bootstrap_config,
         );
         let (rpc_contact_info, snapshot_hash, rpc_client) = vetted_rpc_nodes.pop().unwrap();
+        // Other vetted nodes advertising the same snapshot are tried if this one fails.
+        let snapshot_peers: Vec<_> = std::iter::once(&rpc_contact_info)
+            .chain(
+                vetted_rpc_nodes
+                    .iter()
+                    .rev()
+                    .filter(|(_, peer_snapshot_hash, _)| *peer_snapshot_hash == snapshot_hash)
+                    .map(|(contact_info, _, _)| contact_info),
+            )
+            .filter_map(ContactInfo::rpc)
+            .take(bootstrap_config.snapshot_download_peers)
+            .collect();
         get_rpc_nodes_time += get_rpc_nodes_start.elapsed();
 
         let snapshot_download_start = Instant::now();
@@ -643,6 +663,7 @@ pub fn rpc_bootstrap(
             maximum_snapshot_download_abort,
             &mut download_abort_count,
             snapshot_hash,
+            &snapshot_peers,
             identity_keypair,
             vote_account,
             authorized_voter_keypairs.clone(),
@@ -1108,6 +1129,7 @@ fn download_snapshots(
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
     rpc_contact_info: &ContactInfo,
+    snapshot_peers: &[SocketAddr],
 ) -> Result<(), String> {
     if snapshot_hash.is_none() {
         return Ok(());
@@ -1155,6 +1177,7 @@ fn download_snapshots(
             maximum_snapshot_download_abort,
             download_abort_count,
             rpc_contact_info,
+            snapshot_peers,
             full_snapshot_hash,
             SnapshotKind::FullSnapshot,
         )?;
@@ -1186,6 +1209,7 @@ fn download_snapshots(
                     maximum_snapshot_download_abort,
                     download_abort_count,
                     rpc_contact_info,
+                    snapshot_peers,
                     incremental_snapshot_hash,
                     SnapshotKind::IncrementalSnapshot(full_snapshot_hash.0),
                 )?;
@@ -1207,6 +1231,7 @@ fn download_snapshot(
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     rpc_contact_info: &ContactInfo,
+    snapshot_peers: &[SocketAddr],
     desired_snapshot_hash: (Slot, Hash),
     snapshot_kind: SnapshotKind,
 ) -> Result<(), String> {
@@ -1231,6 +1256,32 @@ fn download_snapshot(
         desired_snapshot_hash.0,
         solana_runtime::snapshot_hash::SnapshotHash(desired_snapshot_hash.1),
     );
+
+    if !snapshot_peers.is_empty() {
+        let parallel_download_config = ParallelDownloadConfig {
+            // The minimal speed applies to the download as a whole, spread over all connections.
+            minimal_download_speed: minimal_snapshot_download_speed
+                / ParallelDownloadConfig::default().connections_per_peer as f32,
+            ..ParallelDownloadConfig::default()
+        };
+        match download_snapshot_archive_from_peers(
+            snapshot_peers,
+            full_snapshot_archives_dir,
+            incremental_snapshot_archives_dir,
+            desired_snapshot_hash,
+            snapshot_kind,
+            maximum_full_snapshot_archives_to_retain,
+            maximum_incremental_snapshot_archives_to_retain,
+            &parallel_download_config,
+        ) {
+            Ok(()) => return Ok(()),
+            Err(err @ ParallelDownloadError::RangesNotServed(_)) => {
+                info!("{err}, downloading over a single connection instead")
+            }
+            Err(err) => return Err(err.to_string()),
+        }
+    }
+
     download_snapshot_archive(
         &rpc_contact_info
             .rpc()
@@ -47,6 +47,8 @@ use thread_args::{thread_args, DefaultThreadArgs};
 const DEFAULT_MIN_SNAPSHOT_DOWNLOAD_SPEED: u64 = 10485760;
 // The maximum times of snapshot download abort and retry
 const MAX_SNAPSHOT_DOWNLOAD_ABORT: u32 = 5;
+// The maximum number of RPC peers to try to download a snapshot from in byte ranges
+const DEFAULT_SNAPSHOT_DOWNLOAD_PEERS: usize = 4;
 // We've observed missed leader slots leading to deadlocks on test validator
 // with less than 2 ticks per slot.
 const MINIMUM_TICKS_PER_SLOT: u64 = 2;
@@ -61,7 +63,9 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
         .global_setting(AppSettings::VersionlessSubcommands)
         .subcommand(commands::exit::command())
         .subcommand(commands::authorized_voter::command())
+        .subcommand(commands::config::command())
         .subcommand(commands::contact_info::command())
+        .subcommand(commands::import_shred_archive::command())
         .subcommand(commands::repair_shred_from_peer::command())
         .subcommand(commands::repair_whitelist::command())
         .subcommand(
@@ -70,9 +74,13 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
         .subcommand(commands::monitor::command())
         .subcommand(SubCommand::with_name("run").about("Run the validator"))
         .subcommand(commands::plugin::command())
+        .subcommand(commands::program_cache::command())
         .subcommand(commands::set_identity::command())
         .subcommand(commands::set_log_filter::command())
+        .subcommand(commands::shred_arrivals::command())
         .subcommand(commands::staked_nodes_overrides::command())
+        .subcommand(commands::tower_lease_coordinator::command())
+        .subcommand(commands::vote_decisions::command())
         .subcommand(commands::wait_for_restart_window::command())
         .subcommand(commands::set_public_address::command());
 
@@ -207,6 +215,7 @@ pub struct DefaultArgs {
     pub rpc_blocking_threads: String,
     pub rpc_niceness_adjustment: String,
     pub rpc_bigtable_timeout: String,
+    pub rpc_history_s3_timeout: String,
     pub rpc_bigtable_instance_name: String,
     pub rpc_bigtable_app_profile_id: String,
     pub rpc_bigtable_max_message_size: String,
@@ -222,6 +231,7 @@ pub struct DefaultArgs {
     pub incremental_snapshot_archive_interval_slots: String,
     pub min_snapshot_download_speed: String,
     pub max_snapshot_download_abort: String,
+    pub snapshot_download_peers: String,
 
     pub contact_debug_interval: String,
 
@@ -301,6 +311,7 @@ impl DefaultArgs {
             rpc_blocking_threads: 1.max(num_cpus::get() / 4).to_string(),
             rpc_niceness_adjustment: "0".to_string(),
             rpc_bigtable_timeout: "30".to_string(),
+            rpc_history_s3_timeout: "30".to_string(),
             rpc_bigtable_instance_name: solana_storage_bigtable::DEFAULT_INSTANCE_NAME.to_string(),
             rpc_bigtable_app_profile_id: solana_storage_bigtable::DEFAULT_APP_PROFILE_ID
                 .to_string(),
@@ -322,6 +333,7 @@ impl DefaultArgs {
                     .to_string(),
             min_snapshot_download_speed: DEFAULT_MIN_SNAPSHOT_DOWNLOAD_SPEED.to_string(),
             max_snapshot_download_abort: MAX_SNAPSHOT_DOWNLOAD_ABORT.to_string(),
+            snapshot_download_peers: DEFAULT_SNAPSHOT_DOWNLOAD_PEERS.to_string(),
             snapshot_archive_format: DEFAULT_ARCHIVE_COMPRESSION.to_string(),
             snapshot_zstd_compression_level: "1".to_string(), // level 1 is optimized for speed
             contact_debug_interval: "120000".to_string(),
@@ -0,0 +1,213 @@
+use {
+    crate::{
+        admin_rpc_service::{self, AdminRpcRuntimeConfig},
+        commands::{FromClapArgMatches, Result},
+    },
+    clap::{value_t, App, AppSettings, Arg, ArgMatches, SubCommand},
+    solana_clap_utils::input_validators::is_parsable,
+    solana_cli_output::OutputFormat,
+    std::path::Path,
+};
+
+pub const COMMAND: &str = "config";
+
+#[derive(Debug, PartialEq)]
+pub struct ConfigGetArgs {
+    pub diff: bool,
+    pub output: OutputFormat,
+}
+
+impl FromClapArgMatches for ConfigGetArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(ConfigGetArgs {
+            diff: matches.is_present("diff"),
+            output: OutputFormat::from_matches(matches, "output", false),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ConfigSetArgs {
+    pub config: AdminRpcRuntimeConfig,
+}
+
+impl FromClapArgMatches for ConfigSetArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(ConfigSetArgs {
+            config: AdminRpcRuntimeConfig {
+                full_snapshot_interval_slots: value_t!(
+                    matches,
+                    "full_snapshot_interval_slots",
+                    u64
+                )
+                .ok(),
+                incremental_snapshot_interval_slots: value_t!(
+                    matches,
+                    "incremental_snapshot_interval_slots",
+                    u64
+                )
+                .ok(),
+                health_check_slot_distance: value_t!(matches, "health_check_slot_distance", u64)
+                    .ok(),
+            },
+        })
+    }
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Inspect and change validator settings at runtime")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::InferSubcommands)
+        .subcommand(
+            SubCommand::with_name("get")
+                .about("Display the startup and current values of the runtime settings")
+                .arg(
+                    Arg::with_name("diff")
+                        .long("diff")
+                        .takes_value(false)
+                        .help("Only display the settings that have been changed since startup"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set")
+                .about("Change runtime settings")
+                .setting(AppSettings::ArgRequiredElseHelp)
+                .arg(
+                    Arg::with_name("full_snapshot_interval_slots")
+                        .long("full-snapshot-interval-slots")
+                        .takes_value(true)
+                        .value_name("NUMBER")
+                        .validator(is_parsable::<u64>)
+                        .help("Number of slots between generating full snapshots"),
+                )
+                .arg(
+                    Arg::with_name("incremental_snapshot_interval_slots")
+                        .long("incremental-snapshot-interval-slots")
+                        .takes_value(true)
+                        .value_name("NUMBER")
+                        .validator(is_parsable::<u64>)
+                        .help(
+                            "Number of slots between generating incremental snapshots. Set to 0 \
+                             to disable incremental snapshots",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("health_check_slot_distance")
+                        .long("health-check-slot-distance")
+                        .takes_value(true)
+                        .value_name("SLOT_DISTANCE")
+                        .validator(is_parsable::<u64>)
+                        .help(
+                            "Number of slots behind the latest optimistically confirmed slot \
+                             the node may be before the RPC health check reports it as behind",
+                        ),
+                )
+                .after_help(
+                    "Note: settings are validated together and either all or none of them are \
+                     applied. Changes only apply to the currently running validator instance",
+                ),
+        )
+}
+
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    match matches.subcommand() {
+        ("get", Some(subcommand_matches)) => {
+            let ConfigGetArgs { diff, output } =
+                ConfigGetArgs::from_clap_arg_match(subcommand_matches)?;
+
+            let admin_client = admin_rpc_service::connect(ledger_path);
+            let config = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.get_config().await })?;
+
+            if diff {
+                println!("{}", output.formatted_string(&config.changes()));
+            } else {
+                println!("{}", output.formatted_string(&config));
+            }
+        }
+        ("set", Some(subcommand_matches)) => {
+            let ConfigSetArgs { config } = ConfigSetArgs::from_clap_arg_match(subcommand_matches)?;
+
+            let admin_client = admin_rpc_service::connect(ledger_path);
+            let config = admin_rpc_service::runtime().block_on(async move {
+                let admin_client = admin_client.await?;
+                admin_client.set_config(config).await?;
+                admin_client.get_config().await
+            })?;
+
+            print!("{}", config.changes());
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_args_struct_by_command_config_get_default() {
+        let app = command();
+        let matches = app.get_matches_from(vec![COMMAND, "get"]);
+        let subcommand_matches = matches.subcommand_matches("get").unwrap();
+        let args = ConfigGetArgs::from_clap_arg_match(subcommand_matches).unwrap();
+        assert_eq!(
+            args,
+            ConfigGetArgs {
+                diff: false,
+                output: OutputFormat::Display,
+            }
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_config_get_diff_with_output() {
+        let app = command();
+        let matches = app.get_matches_from(vec![COMMAND, "get", "--diff", "--output", "json"]);
+        let subcommand_matches = matches.subcommand_matches("get").unwrap();
+        let args = ConfigGetArgs::from_clap_arg_match(subcommand_matches).unwrap();
+        assert_eq!(
+            args,
+            ConfigGetArgs {
+                diff: true,
+                output: OutputFormat::Json,
+            }
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_config_set() {
+        let app = command();
+        let matches = app.get_matches_from(vec![
+            COMMAND,
+            "set",
+            "--incremental-snapshot-interval-slots",
+            "0",
+            "--health-check-slot-distance",
+            "150",
+        ]);
+        let subcommand_matches = matches.subcommand_matches("set").unwrap();
+        let args = ConfigSetArgs::from_clap_arg_match(subcommand_matches).unwrap();
+        assert_eq!(
+            args,
+            ConfigSetArgs {
+                config: AdminRpcRuntimeConfig {
+                    full_snapshot_interval_slots: None,
+                    incremental_snapshot_interval_slots: Some(0),
+                    health_check_slot_distance: Some(150),
+                },
+            }
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use {
+    crate::{
+        admin_rpc_service::{self, ShredArchiveImportStatus},
+        commands::{Error, FromClapArgMatches, Result},
+    },
+    clap::{App, Arg, ArgMatches, SubCommand},
+    std::{path::Path, thread, time::Duration},
+};
+
+const COMMAND: &str = "import-shred-archive";
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq)]
+pub struct ImportShredArchiveArgs {
+    pub path: String,
+}
+
+impl FromClapArgMatches for ImportShredArchiveArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(ImportShredArchiveArgs {
+            path: matches
+                .value_of("path")
+                .expect("path is required")
+                .to_string(),
+        })
+    }
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Insert the shreds from a shred archive into the blockstore")
+        .arg(
+            Arg::with_name("path")
+                .value_name("PATH")
+                .takes_value(true)
+                .required(true)
+                .help(
+                    "Path to a shred archive created by `agave-ledger-tool blockstore \
+                     export-shred-archive`. The path is resolved by the validator process",
+                ),
+        )
+        .after_help(
+            "Note: slots whose shreds fail leader signature or merkle root verification are \
+             skipped",
+        )
+}
+
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    let import_shred_archive_args = ImportShredArchiveArgs::from_clap_arg_match(matches)?;
+
+    let runtime = admin_rpc_service::runtime();
+    let admin_client = admin_rpc_service::connect(ledger_path);
+    runtime.block_on(async move {
+        admin_client
+            .await?
+            .import_shred_archive(import_shred_archive_args.path)
+            .await
+    })?;
+    println!("Importing shred archive");
+
+    let status = loop {
+        let admin_client = admin_rpc_service::connect(ledger_path);
+        let status = runtime
+            .block_on(async move { admin_client.await?.shred_archive_import_status().await })?;
+        if !matches!(status, ShredArchiveImportStatus::Running { .. }) {
+            break status;
+        }
+        thread::sleep(STATUS_POLL_INTERVAL);
+    };
+    match status {
+        ShredArchiveImportStatus::Completed { stats, .. } => println!(
+            "Imported {} slots ({} data shreds, {} coding shreds), rejected {} slots",
+            stats.num_slots,
+            stats.num_data_shreds,
+            stats.num_coding_shreds,
+            stats.num_rejected_slots,
+        ),
+        ShredArchiveImportStatus::Failed { path, error } => {
+            return Err(Error::Dynamic(
+                format!("Failed to import shred archive {path}: {error}").into(),
+            ));
+        }
+        ShredArchiveImportStatus::Idle | ShredArchiveImportStatus::Running { .. } => {
+            return Err(Error::Dynamic(
+                "Shred archive import status was lost, was the validator restarted?".into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::commands::tests::{
+            verify_args_struct_by_command, verify_args_struct_by_command_is_error,
+        },
+    };
+
+    #[test]
+    fn verify_args_struct_by_command_import_shred_archive_default() {
+        verify_args_struct_by_command_is_error::<ImportShredArchiveArgs>(command(), vec![COMMAND]);
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_import_shred_archive_path() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND, "shreds.bin"],
+            ImportShredArchiveArgs {
+                path: "shreds.bin".to_string(),
+            },
+        );
+    }
+}
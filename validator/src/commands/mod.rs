@@ -1,15 +1,21 @@
 pub mod authorized_voter;
+pub mod config;
 pub mod contact_info;
 pub mod exit;
+pub mod import_shred_archive;
 pub mod monitor;
 pub mod plugin;
+pub mod program_cache;
 pub mod repair_shred_from_peer;
 pub mod repair_whitelist;
 pub mod run;
 pub mod set_identity;
 pub mod set_log_filter;
 pub mod set_public_address;
+pub mod shred_arrivals;
 pub mod staked_nodes_overrides;
+pub mod tower_lease_coordinator;
+pub mod vote_decisions;
 pub mod wait_for_restart_window;
 
 use thiserror::Error;
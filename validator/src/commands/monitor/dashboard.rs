@@ -0,0 +1,600 @@
+//! Full screen dashboard for `agave-validator monitor --dashboard`
+//!
+//! Samples JSON RPC and admin RPC every refresh interval and redraws the
+//! terminal with the latest values, keeping a short history of the most
+//! important ones as sparklines.
+
+use {
+    crate::{
+        admin_rpc_service::{self, AdminRpcLeaderSlotStats, AdminRpcMonitorStats},
+        dashboard::{get_health, wait_for_validator_startup},
+        new_spinner_progress_bar,
+    },
+    console::{style, Key, Term},
+    solana_clock::Slot,
+    solana_commitment_config::CommitmentConfig,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        config::RpcBlockProductionConfig,
+        response::{RpcContactInfo, RpcSnapshotSlotInfo},
+    },
+    solana_vote_program::vote_state::VoteState,
+    std::{
+        collections::{BTreeMap, VecDeque},
+        fmt::Write,
+        path::Path,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// Number of samples kept for each sparkline
+const HISTORY_LEN: usize = 60;
+const SPARKLINE_TICKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Number of recent leader slots shown
+const LEADER_SLOTS_SHOWN: usize = 8;
+/// Number of tower lockouts shown
+const LOCKOUTS_SHOWN: usize = 8;
+/// Slow changing values are only sampled every this many refreshes
+const SLOW_REFRESH_INTERVAL: u64 = 10;
+
+/// Fixed length history of samples that renders as a sparkline
+#[derive(Debug, Default)]
+struct History {
+    samples: VecDeque<u64>,
+}
+
+impl History {
+    fn push(&mut self, sample: u64) {
+        if self.samples.len() == HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    fn last(&self) -> Option<u64> {
+        self.samples.back().copied()
+    }
+
+    fn sparkline(&self) -> String {
+        let max = self.samples.iter().copied().max().unwrap_or_default();
+        self.samples
+            .iter()
+            .map(|sample| {
+                let top = (SPARKLINE_TICKS.len() - 1) as u128;
+                let tick = (*sample as u128 * top)
+                    .checked_div(max as u128)
+                    .unwrap_or_default();
+                SPARKLINE_TICKS[tick as usize]
+            })
+            .collect()
+    }
+}
+
+/// Turns an increasing counter into a per second rate
+#[derive(Debug, Default)]
+struct Rate {
+    last: Option<(u64, Instant)>,
+}
+
+impl Rate {
+    fn update(&mut self, count: u64, now: Instant) -> Option<u64> {
+        let rate = self.last.and_then(|(last_count, last_time)| {
+            let elapsed = now.duration_since(last_time).as_secs_f64();
+            (elapsed > 0.0).then(|| (count.saturating_sub(last_count) as f64 / elapsed) as u64)
+        });
+        self.last = Some((count, now));
+        rate
+    }
+}
+
+#[derive(Default)]
+struct DashboardState {
+    identity: Pubkey,
+    version: Option<String>,
+    health: String,
+    processed_slot: Slot,
+    confirmed_slot: Slot,
+    finalized_slot: Slot,
+    max_shred_insert_slot: Slot,
+    replay_lag: History,
+    transactions_per_second: History,
+    transactions: Rate,
+    rpc_requests_per_second: History,
+    rpc_requests: Rate,
+    banking_received_per_second: History,
+    banking_received: Rate,
+    banking_scheduled_per_second: History,
+    banking_scheduled: Rate,
+    banking_dropped_per_second: History,
+    banking_dropped: Rate,
+    monitor_stats: Option<AdminRpcMonitorStats>,
+    vote_state: Option<VoteState>,
+    vote_latency: History,
+    snapshot_slot_info: Option<RpcSnapshotSlotInfo>,
+    epoch_leader_slots: Option<(usize, usize)>,
+    leader_slots: BTreeMap<Slot, AdminRpcLeaderSlotStats>,
+    block_fullness: History,
+    leader_slot_transactions: History,
+}
+
+impl DashboardState {
+    fn update(
+        &mut self,
+        rpc_client: &RpcClient,
+        monitor_stats: Option<AdminRpcMonitorStats>,
+        refresh_slow_values: bool,
+    ) -> solana_rpc_client_api::client_error::Result<()> {
+        let now = Instant::now();
+        self.identity = rpc_client.get_identity()?;
+        self.processed_slot = rpc_client.get_slot_with_commitment(CommitmentConfig::processed())?;
+        self.confirmed_slot = rpc_client.get_slot_with_commitment(CommitmentConfig::confirmed())?;
+        self.finalized_slot = rpc_client.get_slot_with_commitment(CommitmentConfig::finalized())?;
+        self.max_shred_insert_slot = rpc_client.get_max_shred_insert_slot()?;
+        self.replay_lag.push(
+            self.max_shred_insert_slot
+                .saturating_sub(self.processed_slot),
+        );
+        self.health = get_health(rpc_client);
+
+        let transaction_count =
+            rpc_client.get_transaction_count_with_commitment(CommitmentConfig::processed())?;
+        if let Some(rate) = self.transactions.update(transaction_count, now) {
+            self.transactions_per_second.push(rate);
+        }
+
+        if let Some(monitor_stats) = monitor_stats {
+            if let Some(request_count) = monitor_stats.rpc_request_count {
+                if let Some(rate) = self.rpc_requests.update(request_count, now) {
+                    self.rpc_requests_per_second.push(rate);
+                }
+            }
+            let banking_stage = &monitor_stats.banking_stage;
+            if let Some(rate) = self
+                .banking_received
+                .update(banking_stage.received_packets, now)
+            {
+                self.banking_received_per_second.push(rate);
+            }
+            if let Some(rate) = self
+                .banking_scheduled
+                .update(banking_stage.scheduled_transactions, now)
+            {
+                self.banking_scheduled_per_second.push(rate);
+            }
+            if let Some(rate) = self
+                .banking_dropped
+                .update(banking_stage.dropped_transactions, now)
+            {
+                self.banking_dropped_per_second.push(rate);
+            }
+            for leader_slot in &monitor_stats.leader_slots {
+                // Record each leader slot in the histories once it is complete
+                let newly_frozen = leader_slot.frozen
+                    && !self
+                        .leader_slots
+                        .get(&leader_slot.slot)
+                        .is_some_and(|previous| previous.frozen);
+                if newly_frozen {
+                    self.block_fullness.push(
+                        (leader_slot.block_cost * 100)
+                            .checked_div(leader_slot.block_cost_limit)
+                            .unwrap_or_default(),
+                    );
+                    self.leader_slot_transactions
+                        .push(leader_slot.transaction_count);
+                }
+            }
+            self.leader_slots.extend(
+                monitor_stats
+                    .leader_slots
+                    .iter()
+                    .map(|leader_slot| (leader_slot.slot, leader_slot.clone())),
+            );
+            while self.leader_slots.len() > LEADER_SLOTS_SHOWN {
+                self.leader_slots.pop_first();
+            }
+
+            let vote_account = rpc_client
+                .get_account_with_commitment(
+                    &monitor_stats.vote_account,
+                    CommitmentConfig::processed(),
+                )?
+                .value;
+            self.vote_state = vote_account
+                .and_then(|vote_account| VoteState::deserialize(&vote_account.data).ok());
+            if let Some(landed_vote) = self
+                .vote_state
+                .as_ref()
+                .and_then(|vote_state| vote_state.votes.back())
+            {
+                self.vote_latency.push(u64::from(landed_vote.latency));
+            }
+            self.monitor_stats = Some(monitor_stats);
+        }
+
+        if refresh_slow_values {
+            self.snapshot_slot_info = rpc_client.get_highest_snapshot_slot().ok();
+            self.epoch_leader_slots = rpc_client
+                .get_block_production_with_config(RpcBlockProductionConfig {
+                    identity: Some(self.identity.to_string()),
+                    ..RpcBlockProductionConfig::default()
+                })
+                .ok()
+                .and_then(|block_production| {
+                    block_production
+                        .value
+                        .by_identity
+                        .get(&self.identity.to_string())
+                        .copied()
+                });
+            self.version = get_contact_info(rpc_client, &self.identity)
+                .and_then(|contact_info| contact_info.version);
+        }
+        Ok(())
+    }
+
+    fn render(&self, uptime: Duration) -> String {
+        let mut out = String::new();
+        let uptime = uptime.as_secs();
+        let _ = writeln!(
+            out,
+            "{} {}  {} {}  {} {:02}:{:02}:{:02}  {} {}",
+            style("Identity:").bold(),
+            self.identity,
+            style("Version:").bold(),
+            self.version.as_deref().unwrap_or("?"),
+            style("Uptime:").bold(),
+            uptime / 3600,
+            uptime / 60 % 60,
+            uptime % 60,
+            style("Health:").bold(),
+            if self.health == "ok" {
+                style(self.health.clone()).green()
+            } else {
+                style(self.health.clone()).red()
+            },
+        );
+
+        section(&mut out, "Replay");
+        let _ = writeln!(
+            out,
+            "  Processed {}  Confirmed {}  Finalized {}  Max Shred Insert {}",
+            self.processed_slot,
+            self.confirmed_slot,
+            self.finalized_slot,
+            self.max_shred_insert_slot,
+        );
+        sparkline_row(&mut out, "Replay Lag", &self.replay_lag, "slots");
+        sparkline_row(
+            &mut out,
+            "Transactions",
+            &self.transactions_per_second,
+            "tx/s",
+        );
+
+        section(&mut out, "Voting");
+        match (&self.monitor_stats, &self.vote_state) {
+            (Some(monitor_stats), Some(vote_state)) => {
+                let _ = writeln!(
+                    out,
+                    "  Vote Account {}  Last Vote {}  Root {}  Credits {}",
+                    monitor_stats.vote_account,
+                    vote_state
+                        .last_voted_slot()
+                        .map(|slot| slot.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    vote_state
+                        .root_slot
+                        .map(|slot| slot.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    vote_state.credits(),
+                );
+                sparkline_row(&mut out, "Vote Latency", &self.vote_latency, "slots");
+                let lockouts = vote_state
+                    .votes
+                    .iter()
+                    .rev()
+                    .take(LOCKOUTS_SHOWN)
+                    .map(|landed_vote| {
+                        format!(
+                            "{}({})",
+                            landed_vote.slot(),
+                            landed_vote.lockout.confirmation_count()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let _ = writeln!(out, "  Tower ({} votes) {lockouts}", vote_state.votes.len());
+            }
+            (Some(monitor_stats), None) => {
+                let _ = writeln!(
+                    out,
+                    "  Vote account {} not found",
+                    monitor_stats.vote_account
+                );
+            }
+            _ => {
+                let _ = writeln!(out, "  Waiting for admin RPC...");
+            }
+        }
+
+        section(&mut out, "Leader Slots");
+        if let Some((leader_slots, blocks_produced)) = self.epoch_leader_slots {
+            let skipped = leader_slots.saturating_sub(blocks_produced);
+            let _ = writeln!(
+                out,
+                "  This Epoch: {leader_slots} leader slots, {blocks_produced} produced, {skipped} \
+                 skipped ({:.1}%)",
+                (skipped * 100) as f64 / leader_slots.max(1) as f64,
+            );
+        }
+        sparkline_row(&mut out, "Block Fullness", &self.block_fullness, "%");
+        sparkline_row(
+            &mut out,
+            "Block Transactions",
+            &self.leader_slot_transactions,
+            "tx",
+        );
+        let _ = writeln!(
+            out,
+            "  {}",
+            style(format!(
+                "{:<12}  {:<11}  {:>8}  {:>8}  {:>9}  {:>9}",
+                "Slot", "Status", "Txs", "Failed", "Fullness", "Vote Cost"
+            ))
+            .bold()
+        );
+        let root_slot = self
+            .monitor_stats
+            .as_ref()
+            .map(|monitor_stats| monitor_stats.root_slot)
+            .unwrap_or_default();
+        for leader_slot in self.leader_slots.values().rev() {
+            let status = if leader_slot.slot <= root_slot {
+                "rooted"
+            } else if leader_slot.frozen {
+                "produced"
+            } else {
+                "in progress"
+            };
+            let _ = writeln!(
+                out,
+                "  {:<12}  {:<11}  {:>8}  {:>8}  {:>8}%  {:>9}",
+                leader_slot.slot,
+                status,
+                leader_slot.transaction_count,
+                leader_slot.failed_transaction_count,
+                (leader_slot.block_cost * 100)
+                    .checked_div(leader_slot.block_cost_limit)
+                    .unwrap_or_default(),
+                leader_slot.vote_cost,
+            );
+        }
+
+        section(&mut out, "Banking Stage");
+        match &self.monitor_stats {
+            Some(monitor_stats) => {
+                let banking_stage = &monitor_stats.banking_stage;
+                sparkline_row(
+                    &mut out,
+                    "Received",
+                    &self.banking_received_per_second,
+                    "pkt/s",
+                );
+                sparkline_row(
+                    &mut out,
+                    "Scheduled",
+                    &self.banking_scheduled_per_second,
+                    "tx/s",
+                );
+                sparkline_row(
+                    &mut out,
+                    "Dropped",
+                    &self.banking_dropped_per_second,
+                    "tx/s",
+                );
+                let _ = writeln!(
+                    out,
+                    "  Since Start: {} received, {} buffered, {} scheduled, {} finished, {} \
+                     retryable, {} dropped",
+                    banking_stage.received_packets,
+                    banking_stage.buffered_transactions,
+                    banking_stage.scheduled_transactions,
+                    banking_stage.finished_transactions,
+                    banking_stage.retryable_transactions,
+                    banking_stage.dropped_transactions,
+                );
+            }
+            None => {
+                let _ = writeln!(out, "  Waiting for admin RPC...");
+            }
+        }
+
+        section(&mut out, "Snapshots");
+        match &self.snapshot_slot_info {
+            Some(snapshot_slot_info) => {
+                let _ = writeln!(
+                    out,
+                    "  Full {} ({} slots behind root)  Incremental {}",
+                    snapshot_slot_info.full,
+                    root_slot.saturating_sub(snapshot_slot_info.full),
+                    snapshot_slot_info
+                        .incremental
+                        .map(|incremental| format!(
+                            "{incremental} ({} slots behind root)",
+                            root_slot.saturating_sub(incremental)
+                        ))
+                        .unwrap_or_else(|| "-".to_string()),
+                );
+            }
+            None => {
+                let _ = writeln!(out, "  No snapshots");
+            }
+        }
+
+        section(&mut out, "RPC");
+        if self
+            .monitor_stats
+            .as_ref()
+            .is_some_and(|monitor_stats| monitor_stats.rpc_request_count.is_some())
+        {
+            sparkline_row(&mut out, "Requests", &self.rpc_requests_per_second, "req/s");
+        } else {
+            let _ = writeln!(out, "  JSON RPC request counts unavailable");
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}", style("Press q to quit").dim());
+        out
+    }
+}
+
+fn section(out: &mut String, title: &str) {
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{}", style(title).bold().underlined());
+}
+
+fn sparkline_row(out: &mut String, name: &str, history: &History, unit: &str) {
+    let _ = writeln!(
+        out,
+        "  {:<20} {:>8} {:<6} {}",
+        name,
+        history
+            .last()
+            .map(|sample| sample.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        unit,
+        style(history.sparkline()).cyan(),
+    );
+}
+
+fn get_contact_info(rpc_client: &RpcClient, identity: &Pubkey) -> Option<RpcContactInfo> {
+    rpc_client
+        .get_cluster_nodes()
+        .ok()?
+        .into_iter()
+        .find(|node| node.pubkey == identity.to_string())
+}
+
+pub fn run(ledger_path: &Path, refresh_interval: Duration) {
+    let exit = Arc::new(AtomicBool::new(false));
+    let term = Term::stdout();
+    if term.is_term() {
+        // Reading keys puts the terminal in raw mode, so quitting is handled
+        // here rather than by the default SIGINT handler
+        let exit = exit.clone();
+        let term = term.clone();
+        thread::Builder::new()
+            .name("solMonitorKeys".to_string())
+            .spawn(move || loop {
+                match term.read_key() {
+                    Ok(Key::Char('q') | Key::Escape | Key::CtrlC) | Err(_) => {
+                        exit.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                    Ok(_) => {}
+                }
+            })
+            .unwrap();
+    }
+
+    let runtime = admin_rpc_service::runtime();
+    while !exit.load(Ordering::Relaxed) {
+        let progress_bar = new_spinner_progress_bar();
+        progress_bar.set_message("Connecting...");
+        let Some((rpc_addr, start_time)) = runtime.block_on(wait_for_validator_startup(
+            ledger_path,
+            &exit,
+            progress_bar,
+            refresh_interval,
+        )) else {
+            continue;
+        };
+
+        let rpc_client = RpcClient::new_socket(rpc_addr);
+        let mut admin_client = None;
+        let mut state = DashboardState::default();
+        let _ = term.clear_screen();
+        for i in 0.. {
+            if exit.load(Ordering::Relaxed) {
+                break;
+            }
+            if admin_client.is_none() {
+                admin_client = runtime
+                    .block_on(admin_rpc_service::connect(ledger_path))
+                    .ok();
+            }
+            let monitor_stats = admin_client
+                .as_ref()
+                .and_then(|admin_client| runtime.block_on(admin_client.monitor_stats()).ok());
+            if monitor_stats.is_none() {
+                // Reconnect on the next refresh, the validator may have
+                // restarted
+                admin_client = None;
+            }
+
+            if let Err(err) =
+                state.update(&rpc_client, monitor_stats, i % SLOW_REFRESH_INTERVAL == 0)
+            {
+                let _ = term.clear_screen();
+                println!("RPC connection failure: {err}");
+                break;
+            }
+            let frame = state.render(start_time.elapsed().unwrap_or_default());
+            let _ = term.move_cursor_to(0, 0);
+            let _ = term.clear_to_end_of_screen();
+            let _ = term.write_str(&frame.replace('\n', "\r\n"));
+            thread::sleep(refresh_interval);
+        }
+    }
+    let _ = term.write_line("");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_sparkline() {
+        let mut history = History::default();
+        assert_eq!(history.sparkline(), "");
+        assert_eq!(history.last(), None);
+
+        for sample in [0, 1, 2, 4, 8] {
+            history.push(sample);
+        }
+        assert_eq!(history.sparkline(), "▁▁▂▄█");
+        assert_eq!(history.last(), Some(8));
+
+        // All zero samples render as the lowest tick
+        let mut history = History::default();
+        history.push(0);
+        history.push(0);
+        assert_eq!(history.sparkline(), "▁▁");
+
+        // Only the most recent samples are kept
+        let mut history = History::default();
+        for sample in 0..(HISTORY_LEN as u64 * 2) {
+            history.push(sample);
+        }
+        assert_eq!(history.samples.len(), HISTORY_LEN);
+        assert_eq!(history.samples.front(), Some(&(HISTORY_LEN as u64)));
+        assert!(history.sparkline().ends_with('█'));
+    }
+
+    #[test]
+    fn test_rate() {
+        let mut rate = Rate::default();
+        let now = Instant::now();
+        assert_eq!(rate.update(100, now), None);
+        assert_eq!(rate.update(300, now + Duration::from_secs(2)), Some(100));
+        // Counters reset when the validator restarts
+        assert_eq!(rate.update(0, now + Duration::from_secs(4)), Some(0));
+    }
+}
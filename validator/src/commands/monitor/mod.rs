@@ -1,14 +1,50 @@
 use {
-    crate::{commands::Result, dashboard::Dashboard},
-    clap::{App, ArgMatches, SubCommand},
+    crate::{
+        commands::{FromClapArgMatches, Result},
+        dashboard::Dashboard,
+    },
+    clap::{App, Arg, ArgMatches, SubCommand},
     std::{path::Path, time::Duration},
 };
 
+mod dashboard;
+
+const COMMAND: &str = "monitor";
+
+#[derive(Debug, PartialEq)]
+pub struct MonitorArgs {
+    pub dashboard: bool,
+}
+
+impl FromClapArgMatches for MonitorArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(MonitorArgs {
+            dashboard: matches.is_present("dashboard"),
+        })
+    }
+}
+
 pub fn command<'a>() -> App<'a, 'a> {
-    SubCommand::with_name("monitor").about("Monitor the validator")
+    SubCommand::with_name(COMMAND)
+        .about("Monitor the validator")
+        .arg(
+            Arg::with_name("dashboard")
+                .long("dashboard")
+                .takes_value(false)
+                .help(
+                    "Show a full screen dashboard with replay lag, voting, leader slot, banking \
+                     stage, snapshot and RPC statistics and their recent history",
+                ),
+        )
 }
 
-pub fn execute(_matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    let monitor_args = MonitorArgs::from_clap_arg_match(matches)?;
+
+    if monitor_args.dashboard {
+        dashboard::run(ledger_path, Duration::from_secs(2));
+        return Ok(());
+    }
     monitor_validator(ledger_path)
 }
 
@@ -18,3 +54,22 @@ pub fn monitor_validator(ledger_path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::commands::tests::verify_args_struct_by_command};
+
+    #[test]
+    fn verify_args_struct_by_command_monitor_default() {
+        verify_args_struct_by_command(command(), vec![COMMAND], MonitorArgs { dashboard: false });
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_monitor_dashboard() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND, "--dashboard"],
+            MonitorArgs { dashboard: true },
+        );
+    }
+}
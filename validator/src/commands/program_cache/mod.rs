@@ -0,0 +1,185 @@
+use {
+    crate::{
+        admin_rpc_service,
+        commands::{FromClapArgMatches, Result},
+    },
+    clap::{values_t, App, AppSettings, Arg, ArgMatches, SubCommand},
+    itertools::Itertools,
+    solana_clap_utils::input_validators::is_pubkey,
+    solana_cli_output::OutputFormat,
+    solana_pubkey::Pubkey,
+    std::path::Path,
+};
+
+pub const COMMAND: &str = "program-cache";
+
+#[derive(Debug, PartialEq)]
+pub struct ProgramCacheListArgs {
+    pub output: OutputFormat,
+}
+
+impl FromClapArgMatches for ProgramCacheListArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(ProgramCacheListArgs {
+            output: OutputFormat::from_matches(matches, "output", false),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ProgramCachePinArgs {
+    pub program_ids: Vec<Pubkey>,
+}
+
+impl FromClapArgMatches for ProgramCachePinArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        let program_ids = values_t!(matches, "program_id", Pubkey)?
+            .into_iter()
+            .unique()
+            .collect::<Vec<_>>();
+        Ok(ProgramCachePinArgs { program_ids })
+    }
+}
+
+fn program_id_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("program_id")
+        .index(1)
+        .validator(is_pubkey)
+        .value_name("PROGRAM ID")
+        .multiple(true)
+        .takes_value(true)
+        .required(true)
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Inspect and manage the validator's program cache")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::InferSubcommands)
+        .subcommand(
+            SubCommand::with_name("list")
+                .about("Display the entries of the validator's program cache")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pin")
+                .about("Never evict the given programs from the program cache")
+                .setting(AppSettings::ArgRequiredElseHelp)
+                .arg(program_id_arg().help("Programs to pin"))
+                .after_help(
+                    "Note: pinned programs only apply to the currently running validator \
+                     instance, use --program-cache-pin to pin programs across restarts",
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("unpin")
+                .about("Allow the given programs to be evicted from the program cache again")
+                .setting(AppSettings::ArgRequiredElseHelp)
+                .arg(program_id_arg().help("Programs to unpin")),
+        )
+}
+
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    match matches.subcommand() {
+        ("list", Some(subcommand_matches)) => {
+            let program_cache_list_args =
+                ProgramCacheListArgs::from_clap_arg_match(subcommand_matches)?;
+
+            let admin_client = admin_rpc_service::connect(ledger_path);
+            let program_cache = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.program_cache().await })?;
+
+            println!(
+                "{}",
+                program_cache_list_args
+                    .output
+                    .formatted_string(&program_cache)
+            );
+        }
+        ("pin", Some(subcommand_matches)) => {
+            let ProgramCachePinArgs { program_ids } =
+                ProgramCachePinArgs::from_clap_arg_match(subcommand_matches)?;
+
+            let admin_client = admin_rpc_service::connect(ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.pin_programs(program_ids).await })?;
+        }
+        ("unpin", Some(subcommand_matches)) => {
+            let ProgramCachePinArgs { program_ids } =
+                ProgramCachePinArgs::from_clap_arg_match(subcommand_matches)?;
+
+            let admin_client = admin_rpc_service::connect(ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.unpin_programs(program_ids).await })?;
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::str::FromStr};
+
+    #[test]
+    fn verify_args_struct_by_command_program_cache_list_default() {
+        let app = command();
+        let matches = app.get_matches_from(vec![COMMAND, "list"]);
+        let subcommand_matches = matches.subcommand_matches("list").unwrap();
+        let args = ProgramCacheListArgs::from_clap_arg_match(subcommand_matches).unwrap();
+        assert_eq!(
+            args,
+            ProgramCacheListArgs {
+                output: OutputFormat::Display
+            }
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_program_cache_list_with_output() {
+        let app = command();
+        let matches = app.get_matches_from(vec![COMMAND, "list", "--output", "json"]);
+        let subcommand_matches = matches.subcommand_matches("list").unwrap();
+        let args = ProgramCacheListArgs::from_clap_arg_match(subcommand_matches).unwrap();
+        assert_eq!(
+            args,
+            ProgramCacheListArgs {
+                output: OutputFormat::Json
+            }
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_program_cache_pin_with_multiple_programs() {
+        for subcommand in ["pin", "unpin"] {
+            let app = command();
+            let matches = app.get_matches_from(vec![
+                COMMAND,
+                subcommand,
+                "ch1do11111111111111111111111111111111111111",
+                "ch1do11111111111111111111111111111111111112",
+                "ch1do11111111111111111111111111111111111111",
+            ]);
+            let subcommand_matches = matches.subcommand_matches(subcommand).unwrap();
+            let mut args = ProgramCachePinArgs::from_clap_arg_match(subcommand_matches).unwrap();
+            args.program_ids.sort();
+            assert_eq!(
+                args,
+                ProgramCachePinArgs {
+                    program_ids: vec![
+                        Pubkey::from_str("ch1do11111111111111111111111111111111111111").unwrap(),
+                        Pubkey::from_str("ch1do11111111111111111111111111111111111112").unwrap(),
+                    ]
+                }
+            );
+        }
+    }
+}
@@ -271,6 +271,73 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .takes_value(false)
             .help("Upload new confirmed blocks into a BigTable instance"),
     )
+    .arg(
+        Arg::with_name("rpc_history_rocksdb_path")
+            .long("rpc-history-rocksdb-path")
+            .value_name("DIR")
+            .takes_value(true)
+            .requires("enable_rpc_transaction_history")
+            .conflicts_with_all(&[
+                "enable_rpc_bigtable_ledger_storage",
+                "enable_bigtable_ledger_upload",
+                "rpc_history_s3_bucket",
+            ])
+            .help(
+                "Fetch historical transaction info from a local RocksDB archive as a fallback \
+                 to local ledger data",
+            ),
+    )
+    .arg(
+        Arg::with_name("rpc_history_s3_bucket")
+            .long("rpc-history-s3-bucket")
+            .value_name("BUCKET")
+            .takes_value(true)
+            .requires_all(&["enable_rpc_transaction_history", "rpc_history_s3_endpoint"])
+            .conflicts_with_all(&[
+                "enable_rpc_bigtable_ledger_storage",
+                "enable_bigtable_ledger_upload",
+            ])
+            .help(
+                "Fetch historical transaction info from an S3 compatible bucket as a fallback \
+                 to local ledger data. Credentials are read from the AWS_ACCESS_KEY_ID and \
+                 AWS_SECRET_ACCESS_KEY environment variables",
+            ),
+    )
+    .arg(
+        Arg::with_name("rpc_history_s3_endpoint")
+            .long("rpc-history-s3-endpoint")
+            .value_name("URL")
+            .takes_value(true)
+            .requires("rpc_history_s3_bucket")
+            .help("URL of the S3 compatible object store, such as http://127.0.0.1:9000"),
+    )
+    .arg(
+        Arg::with_name("rpc_history_s3_region")
+            .long("rpc-history-s3-region")
+            .value_name("REGION")
+            .takes_value(true)
+            .default_value(solana_storage_history::s3::DEFAULT_REGION)
+            .help("Region of the S3 compatible object store"),
+    )
+    .arg(
+        Arg::with_name("rpc_history_s3_timeout")
+            .long("rpc-history-s3-timeout")
+            .value_name("SECONDS")
+            .validator(is_parsable::<u64>)
+            .takes_value(true)
+            .default_value(&default_args.rpc_history_s3_timeout)
+            .help("Number of seconds before timing out requests to the S3 compatible object store"),
+    )
+    .arg(
+        Arg::with_name("enable_history_storage_upload")
+            .long("enable-history-storage-upload")
+            .takes_value(false)
+            .requires("enable_rpc_transaction_history")
+            .help(
+                "Upload new confirmed blocks into the history storage selected by \
+                 --rpc-history-rocksdb-path or --rpc-history-s3-bucket",
+            ),
+    )
     .arg(
         Arg::with_name("enable_extended_tx_metadata_storage")
             .long("enable-extended-tx-metadata-storage")
@@ -393,6 +460,54 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .takes_value(true)
             .help("Use DIR as file tower storage location [default: --ledger value]"),
     )
+    .arg(
+        Arg::with_name("tower_lease_path")
+            .long("tower-lease-path")
+            .value_name("DIR")
+            .takes_value(true)
+            .conflicts_with("tower_lease_coordinator")
+            .help(
+                "Only save towers and vote while holding the identity's tower lease, kept as a \
+                 file in DIR. DIR must be shared by all the validators that can take over the \
+                 identity on a file system supporting flock() across hosts, such as NFS, and \
+                 their clocks must be synchronized",
+            ),
+    )
+    .arg(
+        Arg::with_name("tower_lease_duration")
+            .long("tower-lease-duration")
+            .value_name("SECONDS")
+            .takes_value(true)
+            .requires("tower_lease_path")
+            .validator(|s| is_within_range(s, 1..))
+            .help(
+                "How long a tower lease in --tower-lease-path lasts without being renewed. \
+                 Another validator can take over the identity this long after the lease holder \
+                 stops voting [default: 10]",
+            ),
+    )
+    .arg(
+        Arg::with_name("tower_lease_coordinator")
+            .long("tower-lease-coordinator")
+            .value_name("HOST:PORT")
+            .takes_value(true)
+            .validator(solana_net_utils::is_host_port)
+            .help(
+                "Only save towers and vote while holding the identity's tower lease, granted by \
+                 the `agave-validator tower-lease-coordinator` at this address",
+            ),
+    )
+    .arg(
+        Arg::with_name("vote_decision_log")
+            .long("vote-decision-log")
+            .value_name("FILE")
+            .takes_value(true)
+            .help(
+                "Append every voting decision to FILE as a line of JSON: the votes cast, why the \
+                 heaviest fork could not be voted on and how duplicate slots were handled. The \
+                 most recent decisions are also available with `agave-validator vote-decisions`",
+            ),
+    )
     .arg(
         Arg::with_name("gossip_port")
             .long("gossip-port")
@@ -578,6 +693,21 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
                  snapshot download.",
             ),
     )
+    .arg(
+        Arg::with_name("snapshot_download_peers")
+            .long("snapshot-download-peers")
+            .value_name("NUMBER")
+            .takes_value(true)
+            .validator(is_parsable::<usize>)
+            .default_value(&default_args.snapshot_download_peers)
+            .help(
+                "The maximum number of RPC nodes advertising the same snapshot to try to download \
+                 it from in byte ranges over several connections. An interrupted download resumes \
+                 where it stopped from the same node, and moving on to another node starts over. \
+                 Nodes which do not serve byte ranges are skipped. Set to 0 to download over a \
+                 single connection.",
+            ),
+    )
     .arg(
         Arg::with_name("contact_debug_interval")
             .long("contact-debug-interval")
@@ -641,6 +771,22 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             /* .default_value() intentionally not used here! */
             .help("Keep this amount of shreds in root slots."),
     )
+    .arg(
+        Arg::with_name("ledger_retention")
+            .long("ledger-retention")
+            .value_name("GROUP=POLICY")
+            .takes_value(true)
+            .multiple(true)
+            .help(
+                "Retain a group of ledger columns for a different length of time than the \
+                 shreds. GROUP is one of shreds, transaction-history, rewards or block-time. \
+                 POLICY is slots:<N> to keep N slots before the latest root, age:<N>{s,m,h,d} \
+                 to keep slots younger than the given age, or size:<N>{K,M,G,T} to keep the \
+                 group under the given size on disk. Groups without a policy follow the shreds, \
+                 which follow --limit-ledger-size unless given a policy here. May be specified \
+                 multiple times",
+            ),
+    )
     .arg(
         Arg::with_name("rocksdb_shred_compaction")
             .long("rocksdb-shred-compaction")
@@ -787,6 +933,36 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .takes_value(true)
             .help("Log when transactions are processed which reference a given key."),
     )
+    .arg(
+        Arg::with_name("program_cache_pin")
+            .long("program-cache-pin")
+            .validator(is_pubkey)
+            .value_name("PROGRAM ID")
+            .multiple(true)
+            .takes_value(true)
+            .help("Never evict the given program from the program cache"),
+    )
+    .arg(
+        Arg::with_name("program_cache_prewarm")
+            .long("program-cache-prewarm")
+            .validator(is_pubkey)
+            .value_name("PROGRAM ID")
+            .multiple(true)
+            .takes_value(true)
+            .help("Load and compile the given program into the program cache at startup"),
+    )
+    .arg(
+        Arg::with_name("import_shred_archive")
+            .long("import-shred-archive")
+            .value_name("FILE")
+            .multiple(true)
+            .takes_value(true)
+            .help(
+                "Insert the shreds from the given shred archive into the blockstore at startup. \
+                 Slots whose shreds fail leader signature or merkle root verification are \
+                 skipped. May be specified multiple times",
+            ),
+    )
     .arg(
         Arg::with_name("only_known_rpc")
             .alias("no-untrusted-rpc")
@@ -807,6 +983,30 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
                  request from validators outside this set [default: all validators]",
             ),
     )
+    .arg(
+        Arg::with_name("repair_preferred_validators")
+            .long("repair-preferred-validator")
+            .validator(is_pubkey)
+            .value_name("VALIDATOR IDENTITY")
+            .multiple(true)
+            .takes_value(true)
+            .help(
+                "A list of validators, typically in the same data center, to send most repair \
+                 requests to. Those answering fastest receive the most requests. Other \
+                 validators still receive a share of the requests in case the preferred ones \
+                 lack the shreds",
+            ),
+    )
+    .arg(
+        Arg::with_name("local_repair_ledger")
+            .long("local-repair-ledger")
+            .value_name("DIR")
+            .takes_value(true)
+            .help(
+                "Ledger directory of another validator on the same host. Missing shreds found \
+                 in it are repaired from disk before requesting them from the network",
+            ),
+    )
     .arg(
         Arg::with_name("repair_whitelist")
             .hidden(hidden_unless_forced())
@@ -1690,6 +1890,16 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .requires("retransmit_xdp_cpu_cores")
             .help("EXPERIMENTAL: Enable XDP zero copy. Requires hardware support"),
     )
+    .arg(
+        Arg::with_name("shred_arrival_telemetry")
+            .long("shred-arrival-telemetry")
+            .takes_value(false)
+            .help(
+                "Record when and from where the shreds of recent slots were received, and this \
+                 node's position in the turbine tree for each retransmitted shred. The records \
+                 are available with `agave-validator shred-arrivals`",
+            ),
+    )
     .arg(
         Arg::with_name("use_connection_cache")
             .long("use-connection-cache")
@@ -16,6 +16,7 @@ impl Default for RpcBootstrapConfig {
             only_known_rpc: false,
             max_genesis_archive_unpacked_size: 10485760,
             incremental_snapshot_fetch: true,
+            snapshot_download_peers: 4,
         }
     }
 }
@@ -41,6 +42,13 @@ impl FromClapArgMatches for RpcBootstrapConfig {
 
         let no_incremental_snapshots = matches.is_present("no_incremental_snapshots");
 
+        let snapshot_download_peers =
+            value_t!(matches, "snapshot_download_peers", usize).map_err(|err| {
+                Box::<dyn std::error::Error>::from(format!(
+                    "failed to parse snapshot_download_peers: {err}"
+                ))
+            })?;
+
         Ok(Self {
             no_genesis_fetch,
             no_snapshot_fetch,
@@ -48,6 +56,7 @@ impl FromClapArgMatches for RpcBootstrapConfig {
             only_known_rpc,
             max_genesis_archive_unpacked_size,
             incremental_snapshot_fetch: !no_incremental_snapshots,
+            snapshot_download_peers,
         })
     }
 }
@@ -208,4 +217,24 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn verify_args_struct_by_command_run_with_snapshot_download_peers() {
+        // long arg
+        {
+            let default_run_args = RunArgs::default();
+            let expected_args = RunArgs {
+                rpc_bootstrap_config: RpcBootstrapConfig {
+                    snapshot_download_peers: 8,
+                    ..RpcBootstrapConfig::default()
+                },
+                ..default_run_args.clone()
+            };
+            verify_args_struct_by_command_run_with_identity_setup(
+                default_run_args,
+                vec!["--snapshot-download-peers", "8"],
+                expected_args,
+            );
+        }
+    }
 }
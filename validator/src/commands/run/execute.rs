@@ -29,8 +29,10 @@ use {
     solana_clock::{Slot, DEFAULT_SLOTS_PER_EPOCH},
     solana_core::{
         banking_trace::DISABLED_BAKING_TRACE_DIR,
-        consensus::tower_storage,
-        repair::repair_handler::RepairHandlerType,
+        consensus::{tower_lease, tower_storage},
+        repair::{
+            repair_handler::RepairHandlerType, repair_peer_selection::RepairPeerSelectionType,
+        },
         snapshot_packager_service::SnapshotPackagerService,
         system_monitor_service::SystemMonitorService,
         validator::{
@@ -46,7 +48,10 @@ use {
     solana_hash::Hash,
     solana_keypair::Keypair,
     solana_ledger::{
-        blockstore_cleanup_service::{DEFAULT_MAX_LEDGER_SHREDS, DEFAULT_MIN_MAX_LEDGER_SHREDS},
+        blockstore::ColumnGroup,
+        blockstore_cleanup_service::{
+            RetentionPolicy, DEFAULT_MAX_LEDGER_SHREDS, DEFAULT_MIN_MAX_LEDGER_SHREDS,
+        },
         use_snapshot_archives_at_startup::{self, UseSnapshotArchivesAtStartup},
     },
     solana_logger::redirect_stderr_to_file,
@@ -54,7 +59,7 @@ use {
     solana_poh::poh_service,
     solana_pubkey::Pubkey,
     solana_rpc::{
-        rpc::{JsonRpcConfig, RpcBigtableConfig},
+        rpc::{JsonRpcConfig, RpcBigtableConfig, RpcHistoryStorageConfig},
         rpc_pubsub_service::PubSubConfig,
     },
     solana_runtime::{
@@ -64,6 +69,7 @@ use {
     },
     solana_send_transaction_service::send_transaction_service,
     solana_signer::Signer,
+    solana_storage_history::{s3::S3Config, HistoryStorageConfig},
     solana_streamer::{
         quic::{QuicServerParams, DEFAULT_TPU_COALESCE},
         socket::SocketAddrSpace,
@@ -75,7 +81,8 @@ use {
     },
     solana_validator_exit::Exit,
     std::{
-        collections::HashSet,
+        collections::{HashMap, HashSet},
+        env,
         fs::{self, File},
         net::{IpAddr, Ipv4Addr, SocketAddr},
         num::{NonZeroU64, NonZeroUsize},
@@ -199,6 +206,22 @@ pub fn execute(
         None
     };
 
+    let blockstore_retention = matches
+        .values_of("ledger_retention")
+        .into_iter()
+        .flatten()
+        .map(|value| {
+            let (group, policy) = value
+                .split_once('=')
+                .ok_or_else(|| format!("invalid --ledger-retention value {value}"))?;
+            let group = ColumnGroup::from_str(group)
+                .map_err(|err| format!("invalid --ledger-retention group {group}: {err}"))?;
+            let policy = RetentionPolicy::from_str(policy)
+                .map_err(|err| format!("invalid --ledger-retention policy {policy}: {err}"))?;
+            Ok((group, policy))
+        })
+        .collect::<Result<HashMap<_, _>, String>>()?;
+
     let debug_keys: Option<Arc<HashSet<_>>> = if matches.is_present("debug_key") {
         Some(Arc::new(
             values_t_or_exit!(matches, "debug_key", Pubkey)
@@ -215,6 +238,14 @@ pub fn execute(
         "repair_validators",
         "--repair-validator",
     )?;
+    let repair_peer_selection = validators_set(
+        &identity_keypair.pubkey(),
+        matches,
+        "repair_preferred_validators",
+        "--repair-preferred-validator",
+    )?
+    .map(RepairPeerSelectionType::Preferred)
+    .unwrap_or_default();
     let repair_whitelist = validators_set(
         &identity_keypair.pubkey(),
         matches,
@@ -301,6 +332,29 @@ pub fn execute(
         .unwrap_or_else(|| ledger_path.clone());
     let tower_storage: Arc<dyn tower_storage::TowerStorage> =
         Arc::new(tower_storage::FileTowerStorage::new(tower_path));
+    let tower_lease: Option<Box<dyn tower_lease::TowerLease>> =
+        if let Ok(tower_lease_path) = value_t!(matches, "tower_lease_path", PathBuf) {
+            let duration = value_t!(matches, "tower_lease_duration", u64)
+                .map(Duration::from_secs)
+                .unwrap_or(tower_lease::DEFAULT_TOWER_LEASE_DURATION);
+            Some(Box::new(tower_lease::FileTowerLease::new(
+                tower_lease_path,
+                duration,
+            )))
+        } else if let Some(coordinator_addr) = matches.value_of("tower_lease_coordinator") {
+            let coordinator_addr = solana_net_utils::parse_host_port(coordinator_addr)
+                .map_err(|err| format!("failed to parse tower lease coordinator address: {err}"))?;
+            Some(Box::new(tower_lease::TcpTowerLease::new(coordinator_addr)))
+        } else {
+            None
+        };
+    let tower_storage: Arc<dyn tower_storage::TowerStorage> = match tower_lease {
+        Some(tower_lease) => Arc::new(tower_lease::LeasedTowerStorage::new(
+            tower_storage,
+            tower_lease,
+        )),
+        None => tower_storage,
+    };
 
     let mut accounts_index_config = AccountsIndexConfig {
         num_flush_threads: Some(accounts_index_flush_threads),
@@ -465,6 +519,39 @@ pub fn execute(
         None
     };
 
+    let history_storage = if let Some(path) = matches.value_of("rpc_history_rocksdb_path") {
+        Some(HistoryStorageConfig::RocksDb {
+            path: PathBuf::from(path),
+        })
+    } else if let Some(bucket) = matches.value_of("rpc_history_s3_bucket") {
+        let credential = |name| {
+            env::var(name).map_err(|err| format!("--rpc-history-s3-bucket requires {name}: {err}"))
+        };
+        Some(HistoryStorageConfig::S3(S3Config {
+            endpoint: value_t_or_exit!(matches, "rpc_history_s3_endpoint", String),
+            region: value_t_or_exit!(matches, "rpc_history_s3_region", String),
+            bucket: bucket.to_string(),
+            access_key_id: credential("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: credential("AWS_SECRET_ACCESS_KEY")?,
+            timeout: value_t!(matches, "rpc_history_s3_timeout", u64)
+                .ok()
+                .map(Duration::from_secs),
+        }))
+    } else {
+        None
+    };
+    let rpc_history_storage_config = match history_storage {
+        Some(storage) => Some(RpcHistoryStorageConfig {
+            enable_history_storage_upload: matches.is_present("enable_history_storage_upload"),
+            storage,
+        }),
+        None if matches.is_present("enable_history_storage_upload") => Err(
+            "--enable-history-storage-upload requires --rpc-history-rocksdb-path or \
+             --rpc-history-s3-bucket",
+        )?,
+        None => None,
+    };
+
     let rpc_send_retry_rate_ms = value_t_or_exit!(matches, "rpc_send_transaction_retry_ms", u64);
     let rpc_send_batch_size = value_t_or_exit!(matches, "rpc_send_transaction_batch_size", usize);
     let rpc_send_batch_send_rate_ms =
@@ -574,6 +661,7 @@ pub fn execute(
             enable_extended_tx_metadata_storage: matches
                 .is_present("enable_extended_tx_metadata_storage"),
             rpc_bigtable_config,
+            rpc_history_storage_config,
             faucet_addr: matches.value_of("rpc_faucet_addr").map(|address| {
                 solana_net_utils::parse_host_port(address).expect("failed to parse faucet address")
             }),
@@ -641,8 +729,19 @@ pub fn execute(
         repair_validators,
         repair_whitelist,
         repair_handler_type: RepairHandlerType::default(),
+        program_cache_pinned_programs: values_t!(matches, "program_cache_pin", Pubkey)
+            .unwrap_or_default(),
+        program_cache_prewarm_programs: values_t!(matches, "program_cache_prewarm", Pubkey)
+            .unwrap_or_default(),
+        shred_archives_to_import: values_t!(matches, "import_shred_archive", PathBuf)
+            .unwrap_or_default(),
+        repair_peer_selection,
+        shred_arrival_telemetry: matches.is_present("shred_arrival_telemetry"),
+        local_repair_ledger_path: value_t!(matches, "local_repair_ledger", PathBuf).ok(),
+        vote_decision_log_path: value_t!(matches, "vote_decision_log", PathBuf).ok(),
         gossip_validators,
         max_ledger_shreds,
+        blockstore_retention,
         blockstore_options: run_args.blockstore_options,
         run_verification: !matches.is_present("skip_startup_ledger_verification"),
         debug_keys,
@@ -833,6 +932,7 @@ pub fn execute(
             tower_storage: validator_config.tower_storage.clone(),
             staked_nodes_overrides,
             rpc_to_plugin_manager_sender,
+            shred_archive_import: Arc::default(),
         },
     );
 
@@ -0,0 +1,129 @@
+use {
+    crate::{
+        admin_rpc_service,
+        commands::{Error, FromClapArgMatches, Result},
+    },
+    clap::{value_t, App, Arg, ArgMatches, SubCommand},
+    serde::Serialize,
+    solana_clap_utils::input_validators::is_parsable,
+    solana_cli_output::OutputFormat,
+    solana_clock::Slot,
+    solana_turbine::shred_arrivals::SlotShredArrivals,
+    std::{
+        fmt::{self, Display},
+        path::Path,
+    },
+};
+
+const COMMAND: &str = "shred-arrivals";
+
+#[derive(Debug, PartialEq)]
+pub struct ShredArrivalsArgs {
+    pub slot: Option<Slot>,
+    pub output: OutputFormat,
+}
+
+impl FromClapArgMatches for ShredArrivalsArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(ShredArrivalsArgs {
+            slot: value_t!(matches, "slot", Slot).ok(),
+            output: OutputFormat::from_matches(matches, "output", false),
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct CliShredArrivals(SlotShredArrivals);
+
+impl Display for CliShredArrivals {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+impl solana_cli_output::VerboseDisplay for CliShredArrivals {}
+impl solana_cli_output::QuietDisplay for CliShredArrivals {}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Display when and from where the shreds of a recent slot were received")
+        .arg(
+            Arg::with_name("slot")
+                .value_name("SLOT")
+                .takes_value(true)
+                .validator(is_parsable::<Slot>)
+                .help("Slot to display. If not specified, lists the slots with records"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .takes_value(true)
+                .value_name("MODE")
+                .possible_values(&["json", "json-compact"])
+                .help("Output display mode"),
+        )
+        .after_help(
+            "Note: requires the validator to run with --shred-arrival-telemetry. The json output \
+             can be analyzed against the ledger with `agave-ledger-tool blockstore \
+             shred-arrivals`",
+        )
+}
+
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    let shred_arrivals_args = ShredArrivalsArgs::from_clap_arg_match(matches)?;
+
+    let admin_client = admin_rpc_service::connect(ledger_path);
+    let Some(slot) = shred_arrivals_args.slot else {
+        let slots = admin_rpc_service::runtime()
+            .block_on(async move { admin_client.await?.shred_arrival_slots().await })?;
+        match shred_arrivals_args.output {
+            OutputFormat::Json | OutputFormat::JsonCompact => {
+                println!("{}", serde_json::to_string(&slots).unwrap())
+            }
+            _ => println!("Slots with shred arrival records: {slots:?}"),
+        }
+        return Ok(());
+    };
+    let slot_arrivals = admin_rpc_service::runtime()
+        .block_on(async move { admin_client.await?.shred_arrivals(slot).await })?
+        .ok_or_else(|| {
+            Error::Dynamic(format!("No shred arrival records for slot {slot}").into())
+        })?;
+    println!(
+        "{}",
+        shred_arrivals_args
+            .output
+            .formatted_string(&CliShredArrivals(slot_arrivals))
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::commands::tests::verify_args_struct_by_command};
+
+    #[test]
+    fn verify_args_struct_by_command_shred_arrivals_default() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND],
+            ShredArrivalsArgs {
+                slot: None,
+                output: OutputFormat::Display,
+            },
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_shred_arrivals_slot_json() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND, "42", "--output", "json"],
+            ShredArrivalsArgs {
+                slot: Some(42),
+                output: OutputFormat::Json,
+            },
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use {
+    crate::commands::{FromClapArgMatches, Result},
+    clap::{value_t, App, Arg, ArgMatches, SubCommand},
+    solana_clap_utils::input_validators::is_within_range,
+    solana_core::consensus::tower_lease::{TowerLeaseCoordinator, DEFAULT_TOWER_LEASE_DURATION},
+    std::{net::SocketAddr, time::Duration},
+};
+
+const COMMAND: &str = "tower-lease-coordinator";
+
+#[derive(Debug, PartialEq)]
+pub struct TowerLeaseCoordinatorArgs {
+    pub bind_addr: SocketAddr,
+    pub lease_duration: Duration,
+}
+
+impl FromClapArgMatches for TowerLeaseCoordinatorArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        let bind_addr = matches.value_of("bind_addr").unwrap();
+        let bind_addr = solana_net_utils::parse_host_port(bind_addr).map_err(|err| {
+            Box::<dyn std::error::Error>::from(format!(
+                "failed to parse --bind-address. It must be in the HOST:PORT format. {err}"
+            ))
+        })?;
+        Ok(TowerLeaseCoordinatorArgs {
+            bind_addr,
+            lease_duration: value_t!(matches, "lease_duration", u64)
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_TOWER_LEASE_DURATION),
+        })
+    }
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Grant tower leases to validators started with --tower-lease-coordinator")
+        .long_about(
+            "Grant tower leases to validators started with --tower-lease-coordinator. Only the \
+             validator holding an identity's lease saves towers and votes with that identity, \
+             so a primary and a hot spare validator can't both vote. Run the coordinator on a \
+             host other than the validators it arbitrates between. Leases are kept in memory, \
+             and while the coordinator is unreachable no validator using it votes.",
+        )
+        .arg(
+            Arg::with_name("bind_addr")
+                .long("bind-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .required(true)
+                .validator(solana_net_utils::is_host_port)
+                .help(
+                    "Address to listen for validators on. Requests are not authenticated, so \
+                     this must be a loopback or private network address that only the \
+                     validators can reach",
+                ),
+        )
+        .arg(
+            Arg::with_name("lease_duration")
+                .long("lease-duration")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .validator(|s| is_within_range(s, 1..))
+                .help(
+                    "How long a lease lasts without being renewed. Another validator can take \
+                     over the identity this long after the lease holder stops voting \
+                     [default: 10]",
+                ),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let TowerLeaseCoordinatorArgs {
+        bind_addr,
+        lease_duration,
+    } = TowerLeaseCoordinatorArgs::from_clap_arg_match(matches)?;
+
+    let coordinator = TowerLeaseCoordinator::new(bind_addr, lease_duration)?;
+    println!(
+        "Tower lease coordinator listening on {}",
+        coordinator.local_addr()
+    );
+    coordinator.join().map_err(|_| {
+        crate::commands::Error::Dynamic("tower lease coordinator thread panicked".into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::commands::tests::{
+            verify_args_struct_by_command, verify_args_struct_by_command_is_error,
+        },
+    };
+
+    #[test]
+    fn verify_args_struct_by_command_tower_lease_coordinator_default() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND, "--bind-address", "127.0.0.1:8123"],
+            TowerLeaseCoordinatorArgs {
+                bind_addr: SocketAddr::from(([127, 0, 0, 1], 8123)),
+                lease_duration: DEFAULT_TOWER_LEASE_DURATION,
+            },
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_tower_lease_coordinator_with_lease_duration() {
+        verify_args_struct_by_command(
+            command(),
+            vec![
+                COMMAND,
+                "--bind-address",
+                "127.0.0.1:8123",
+                "--lease-duration",
+                "30",
+            ],
+            TowerLeaseCoordinatorArgs {
+                bind_addr: SocketAddr::from(([127, 0, 0, 1], 8123)),
+                lease_duration: Duration::from_secs(30),
+            },
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_tower_lease_coordinator_zero_lease_duration() {
+        verify_args_struct_by_command_is_error::<TowerLeaseCoordinatorArgs>(
+            command(),
+            vec![
+                COMMAND,
+                "--bind-address",
+                "127.0.0.1:8123",
+                "--lease-duration",
+                "0",
+            ],
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use {
+    crate::{
+        admin_rpc_service,
+        commands::{FromClapArgMatches, Result},
+    },
+    clap::{value_t, App, Arg, ArgMatches, SubCommand},
+    serde::Serialize,
+    solana_clap_utils::input_validators::is_parsable,
+    solana_cli_output::OutputFormat,
+    solana_core::consensus::vote_decision_history::VoteDecisionRecord,
+    std::{
+        fmt::{self, Display},
+        path::Path,
+    },
+};
+
+const COMMAND: &str = "vote-decisions";
+
+const DEFAULT_LIMIT: &str = "100";
+
+#[derive(Debug, PartialEq)]
+pub struct VoteDecisionsArgs {
+    pub limit: usize,
+    pub output: OutputFormat,
+}
+
+impl FromClapArgMatches for VoteDecisionsArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> Result<Self> {
+        Ok(VoteDecisionsArgs {
+            limit: value_t!(matches, "limit", usize)?,
+            output: OutputFormat::from_matches(matches, "output", false),
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct CliVoteDecisions(Vec<VoteDecisionRecord>);
+
+impl Display for CliVoteDecisions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "No vote decisions recorded");
+        }
+        for (i, record) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{record}")?;
+        }
+        Ok(())
+    }
+}
+impl solana_cli_output::VerboseDisplay for CliVoteDecisions {}
+impl solana_cli_output::QuietDisplay for CliVoteDecisions {}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name(COMMAND)
+        .about("Display the most recent voting decisions of the validator")
+        .arg(
+            Arg::with_name("limit")
+                .long("limit")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .default_value(DEFAULT_LIMIT)
+                .validator(is_parsable::<usize>)
+                .help("Maximum number of decisions to display"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .takes_value(true)
+                .value_name("MODE")
+                .possible_values(&["json", "json-compact"])
+                .help("Output display mode"),
+        )
+        .after_help(
+            "Note: the decisions are the votes cast, the reasons the heaviest fork could not be \
+             voted on, including failed vote and switch thresholds and lockouts, and the \
+             duplicate slots found and dumped. Repeated failures to vote are only displayed \
+             once. Use --vote-decision-log to keep the full history in a file",
+        )
+}
+
+pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<()> {
+    let VoteDecisionsArgs { limit, output } = VoteDecisionsArgs::from_clap_arg_match(matches)?;
+
+    let admin_client = admin_rpc_service::connect(ledger_path);
+    let decisions = admin_rpc_service::runtime()
+        .block_on(async move { admin_client.await?.vote_decisions(Some(limit)).await })?;
+    println!("{}", output.formatted_string(&CliVoteDecisions(decisions)));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::commands::tests::{
+            verify_args_struct_by_command, verify_args_struct_by_command_is_error,
+        },
+    };
+
+    #[test]
+    fn verify_args_struct_by_command_vote_decisions_default() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND],
+            VoteDecisionsArgs {
+                limit: 100,
+                output: OutputFormat::Display,
+            },
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_vote_decisions_with_limit_and_output() {
+        verify_args_struct_by_command(
+            command(),
+            vec![COMMAND, "--limit", "10", "--output", "json"],
+            VoteDecisionsArgs {
+                limit: 10,
+                output: OutputFormat::Json,
+            },
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_vote_decisions_invalid_limit() {
+        verify_args_struct_by_command_is_error::<VoteDecisionsArgs>(
+            command(),
+            vec![COMMAND, "--limit", "many"],
+        );
+    }
+}
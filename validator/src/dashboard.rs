@@ -191,7 +191,7 @@ impl Dashboard {
     }
 }
 
-async fn wait_for_validator_startup(
+pub(crate) async fn wait_for_validator_startup(
     ledger_path: &Path,
     exit: &AtomicBool,
     progress_bar: ProgressBar,
@@ -268,7 +268,22 @@ fn get_validator_stats(
         .get_balance_with_commitment(identity, CommitmentConfig::confirmed())?
         .value;
 
-    let health = match rpc_client.get_health() {
+    let health = get_health(rpc_client);
+
+    Ok((
+        processed_slot,
+        confirmed_slot,
+        finalized_slot,
+        transaction_count,
+        Sol(identity_balance),
+        health,
+    ))
+}
+
+/// Returns "ok" if the node is healthy, otherwise a short description of
+/// the problem
+pub(crate) fn get_health(rpc_client: &RpcClient) -> String {
+    match rpc_client.get_health() {
         Ok(()) => "ok".to_string(),
         Err(err) => {
             if let client_error::ErrorKind::RpcError(request::RpcError::RpcResponseError {
@@ -285,14 +300,5 @@ fn get_validator_stats(
                 "health unknown".to_string()
             }
         }
-    };
-
-    Ok((
-        processed_slot,
-        confirmed_slot,
-        finalized_slot,
-        transaction_count,
-        Sol(identity_balance),
-        health,
-    ))
+    }
 }
@@ -47,25 +47,45 @@ pub fn main() {
         ("authorized-voter", Some(authorized_voter_subcommand_matches)) => {
             commands::authorized_voter::execute(authorized_voter_subcommand_matches, &ledger_path)
         }
+        ("config", Some(subcommand_matches)) => {
+            commands::config::execute(subcommand_matches, &ledger_path)
+        }
         ("plugin", Some(plugin_subcommand_matches)) => {
             commands::plugin::execute(plugin_subcommand_matches, &ledger_path)
         }
+        ("program-cache", Some(subcommand_matches)) => {
+            commands::program_cache::execute(subcommand_matches, &ledger_path)
+        }
         ("contact-info", Some(subcommand_matches)) => {
             commands::contact_info::execute(subcommand_matches, &ledger_path)
         }
         ("exit", Some(subcommand_matches)) => {
             commands::exit::execute(subcommand_matches, &ledger_path)
         }
-        ("monitor", _) => commands::monitor::execute(&matches, &ledger_path),
+        ("import-shred-archive", Some(subcommand_matches)) => {
+            commands::import_shred_archive::execute(subcommand_matches, &ledger_path)
+        }
+        ("monitor", Some(subcommand_matches)) => {
+            commands::monitor::execute(subcommand_matches, &ledger_path)
+        }
         ("staked-nodes-overrides", Some(subcommand_matches)) => {
             commands::staked_nodes_overrides::execute(subcommand_matches, &ledger_path)
         }
         ("set-identity", Some(subcommand_matches)) => {
             commands::set_identity::execute(subcommand_matches, &ledger_path)
         }
+        ("shred-arrivals", Some(subcommand_matches)) => {
+            commands::shred_arrivals::execute(subcommand_matches, &ledger_path)
+        }
         ("set-log-filter", Some(subcommand_matches)) => {
             commands::set_log_filter::execute(subcommand_matches, &ledger_path)
         }
+        ("tower-lease-coordinator", Some(subcommand_matches)) => {
+            commands::tower_lease_coordinator::execute(subcommand_matches)
+        }
+        ("vote-decisions", Some(subcommand_matches)) => {
+            commands::vote_decisions::execute(subcommand_matches, &ledger_path)
+        }
         ("wait-for-restart-window", Some(subcommand_matches)) => {
             commands::wait_for_restart_window::execute(subcommand_matches, &ledger_path)
         }
@@ -0,0 +1,539 @@
+//! Offline rehearsal of the wen_restart protocol.
+//!
+//! Every validator taking part in a wen_restart records the
+//! `RestartLastVotedForkSlots` and `RestartHeaviestFork` messages it received
+//! from gossip in its `wen_restart_progress` file. [`run_dry_run`] feeds those
+//! recorded messages through the same aggregation and heaviest fork selection
+//! used by [`wait_for_wen_restart`](crate::wen_restart::wait_for_wen_restart),
+//! against a local ledger, and reports the outcome. Nothing is pushed to gossip
+//! and no progress file or snapshot is written, so operators can rehearse a
+//! restart or check a restart in progress without affecting the cluster.
+
+use {
+    crate::{
+        heaviest_fork_aggregate::{HeaviestForkAggregate, HeaviestForkAggregateResult},
+        last_voted_fork_slots_aggregate::{
+            LastVotedForkSlotsAggregate, LastVotedForkSlotsAggregateResult,
+        },
+        solana::wen_restart_proto::{HeaviestForkRecord, LastVotedForkSlotsRecord},
+        wen_restart::{find_heaviest_fork, read_wen_restart_records, REPAIR_THRESHOLD},
+    },
+    anyhow::Result,
+    solana_clock::{Epoch, Slot},
+    solana_hash::Hash,
+    solana_ledger::blockstore::Blockstore,
+    solana_pubkey::Pubkey,
+    solana_runtime::bank_forks::BankForks,
+    std::{
+        collections::HashMap,
+        fmt,
+        path::PathBuf,
+        sync::{atomic::AtomicBool, Arc, RwLock},
+    },
+};
+
+/// Restart messages collected from one or more `wen_restart_progress` files.
+///
+/// A progress file holds the messages its validator received from everyone
+/// else, plus its own messages once it has chosen a heaviest fork, so combine
+/// the files of several validators to cover the whole cluster.
+#[derive(Debug, Default)]
+pub struct RecordedRestartMessages {
+    last_voted_fork_slots: HashMap<String, LastVotedForkSlotsRecord>,
+    heaviest_forks: Vec<HeaviestForkRecord>,
+}
+
+impl RecordedRestartMessages {
+    pub fn load(paths: &[PathBuf]) -> Result<Self> {
+        let mut messages = Self::default();
+        for path in paths {
+            let progress = read_wen_restart_records(path)?;
+            // The validator's own LastVotedForkSlots carries no sender, it can only
+            // be attributed once the validator has published its heaviest fork.
+            if let (Some(my_last_voted_fork_slots), Some(my_heaviest_fork)) = (
+                &progress.my_last_voted_fork_slots,
+                &progress.my_heaviest_fork,
+            ) {
+                messages
+                    .last_voted_fork_slots
+                    .entry(my_heaviest_fork.from.clone())
+                    .or_insert_with(|| my_last_voted_fork_slots.clone());
+            }
+            if let Some(aggregate_record) = progress.last_voted_fork_slots_aggregate {
+                for (from, record) in aggregate_record.received {
+                    messages.last_voted_fork_slots.entry(from).or_insert(record);
+                }
+            }
+            messages.heaviest_forks.extend(
+                progress
+                    .my_heaviest_fork
+                    .into_iter()
+                    .chain(progress.coordinator_heaviest_fork)
+                    .filter(|record| !record.from.is_empty()),
+            );
+            if let Some(aggregate_record) = progress.heaviest_fork_aggregate {
+                messages.heaviest_forks.extend(aggregate_record.received);
+            }
+        }
+        Ok(messages)
+    }
+
+    pub fn num_last_voted_fork_slots(&self) -> usize {
+        self.last_voted_fork_slots.len()
+    }
+
+    pub fn num_heaviest_forks(&self) -> usize {
+        self.heaviest_forks.len()
+    }
+}
+
+/// How many of the recorded messages were accepted by an aggregate.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AggregateCounts {
+    pub inserted: usize,
+    /// Senders which sent different versions of the message.
+    pub conflicting: usize,
+    /// Messages which could not be parsed or were rejected as invalid.
+    pub rejected: usize,
+}
+
+/// Stake of the validators whose LastVotedForkSlots were aggregated.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EpochStakeCoverage {
+    pub epoch: Epoch,
+    pub total_stake: u64,
+    pub actively_voting_stake: u64,
+    pub actively_voting_for_this_epoch_stake: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HeaviestForkVote {
+    pub slot: Slot,
+    pub hash: Hash,
+    pub stake: u64,
+}
+
+#[derive(Debug)]
+pub struct DryRunReport {
+    pub root_slot: Slot,
+    pub last_voted_fork_slots_counts: AggregateCounts,
+    pub epoch_coverage: Vec<EpochStakeCoverage>,
+    /// The lowest active stake percentage over the epochs considered for exit,
+    /// the live protocol waits for this to reach the supermajority threshold.
+    pub active_percent: f64,
+    /// Slots above the root which enough stake voted for that they must be
+    /// repaired, in ascending order.
+    pub slots_to_repair: Vec<Slot>,
+    /// The subset of `slots_to_repair` which is not full in the blockstore.
+    pub missing_slots: Vec<Slot>,
+    /// The heaviest fork this validator would select, or why it could not.
+    pub heaviest_fork: std::result::Result<(Slot, Hash), String>,
+    pub heaviest_fork_counts: AggregateCounts,
+    /// Stake behind each heaviest fork received, heaviest first.
+    pub heaviest_fork_votes: Vec<HeaviestForkVote>,
+    pub heaviest_fork_active_stake: u64,
+    pub heaviest_fork_total_stake: u64,
+}
+
+/// Runs the wen_restart aggregation over `messages` with the root of
+/// `bank_forks` as the local root.
+///
+/// Selecting the heaviest fork may replay blocks above the root into
+/// `bank_forks` to compute the bank hash, exactly as the live protocol does.
+/// Open `blockstore` with secondary access so that replay cannot mark dead
+/// slots in the ledger.
+pub fn run_dry_run(
+    bank_forks: Arc<RwLock<BankForks>>,
+    blockstore: Arc<Blockstore>,
+    shred_version: u16,
+    messages: &RecordedRestartMessages,
+) -> Result<DryRunReport> {
+    let root_bank = bank_forks.read().unwrap().root_bank();
+    let root_slot = root_bank.slot();
+    // Aggregate as an unstaked observer so only the recorded messages count.
+    let observer = Pubkey::default();
+
+    let mut last_voted_fork_slots_counts = AggregateCounts::default();
+    let mut last_voted_fork_slots_aggregate = LastVotedForkSlotsAggregate::new(
+        root_bank.clone(),
+        REPAIR_THRESHOLD,
+        &vec![root_slot],
+        &observer,
+    );
+    for (from, record) in &messages.last_voted_fork_slots {
+        match last_voted_fork_slots_aggregate.aggregate_from_record(from, record) {
+            Ok(LastVotedForkSlotsAggregateResult::Inserted(_)) => {
+                last_voted_fork_slots_counts.inserted += 1
+            }
+            Ok(LastVotedForkSlotsAggregateResult::DifferentVersionExists(..)) => {
+                last_voted_fork_slots_counts.conflicting += 1
+            }
+            Ok(LastVotedForkSlotsAggregateResult::AlreadyExists) => (),
+            Err(_) => last_voted_fork_slots_counts.rejected += 1,
+        }
+    }
+    let active_percent = last_voted_fork_slots_aggregate.min_active_percent();
+    let slots_to_repair: Vec<Slot> = last_voted_fork_slots_aggregate
+        .slots_to_repair_iter()
+        .filter(|slot| **slot > root_slot)
+        .copied()
+        .collect();
+    let missing_slots = slots_to_repair
+        .iter()
+        .filter(|slot| !blockstore.is_full(**slot))
+        .copied()
+        .collect();
+    let final_result = last_voted_fork_slots_aggregate.get_final_result();
+    let epoch_coverage = final_result
+        .epoch_info_vec
+        .iter()
+        .map(|info| EpochStakeCoverage {
+            epoch: info.epoch,
+            total_stake: info.total_stake,
+            actively_voting_stake: info.actively_voting_stake,
+            actively_voting_for_this_epoch_stake: info.actively_voting_for_this_epoch_stake,
+        })
+        .collect();
+
+    let heaviest_fork = find_heaviest_fork(
+        final_result,
+        bank_forks,
+        blockstore,
+        Arc::new(AtomicBool::new(false)),
+    )
+    .map_err(|err| err.to_string());
+
+    let (heaviest_fork_slot, heaviest_fork_hash) = heaviest_fork
+        .clone()
+        .unwrap_or((root_slot, root_bank.hash()));
+    let epoch_stakes = root_bank
+        .epoch_stakes(root_bank.epoch_schedule().get_epoch(heaviest_fork_slot))
+        .ok_or_else(|| anyhow::anyhow!("no epoch stakes for slot {heaviest_fork_slot}"))?;
+    let mut heaviest_fork_counts = AggregateCounts::default();
+    let mut heaviest_fork_aggregate = HeaviestForkAggregate::new(
+        shred_version,
+        epoch_stakes,
+        heaviest_fork_slot,
+        heaviest_fork_hash,
+        &observer,
+    );
+    for record in &messages.heaviest_forks {
+        match heaviest_fork_aggregate.aggregate_from_record(record) {
+            Ok(HeaviestForkAggregateResult::Inserted(_)) => heaviest_fork_counts.inserted += 1,
+            Ok(HeaviestForkAggregateResult::DifferentVersionExists(..)) => {
+                heaviest_fork_counts.conflicting += 1
+            }
+            Ok(HeaviestForkAggregateResult::AlreadyExists) => (),
+            Ok(HeaviestForkAggregateResult::Malformed)
+            | Ok(HeaviestForkAggregateResult::ZeroStakeIgnored)
+            | Err(_) => heaviest_fork_counts.rejected += 1,
+        }
+    }
+    let mut heaviest_fork_votes: Vec<HeaviestForkVote> = heaviest_fork_aggregate
+        .block_stake_map()
+        .iter()
+        .filter(|(_, stake)| **stake > 0)
+        .map(|((slot, hash), stake)| HeaviestForkVote {
+            slot: *slot,
+            hash: *hash,
+            stake: *stake,
+        })
+        .collect();
+    heaviest_fork_votes.sort_by(|a, b| b.stake.cmp(&a.stake).then(a.slot.cmp(&b.slot)));
+
+    Ok(DryRunReport {
+        root_slot,
+        last_voted_fork_slots_counts,
+        epoch_coverage,
+        active_percent,
+        slots_to_repair,
+        missing_slots,
+        heaviest_fork,
+        heaviest_fork_counts,
+        heaviest_fork_votes,
+        heaviest_fork_active_stake: heaviest_fork_aggregate.total_active_stake(),
+        heaviest_fork_total_stake: epoch_stakes.total_stake(),
+    })
+}
+
+fn percent(stake: u64, total_stake: u64) -> f64 {
+    if total_stake == 0 {
+        0.0
+    } else {
+        stake as f64 / total_stake as f64 * 100.0
+    }
+}
+
+impl fmt::Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Local root: {}", self.root_slot)?;
+        let counts = &self.last_voted_fork_slots_counts;
+        writeln!(
+            f,
+            "LastVotedForkSlots: {} aggregated, {} conflicting, {} rejected",
+            counts.inserted, counts.conflicting, counts.rejected
+        )?;
+        for coverage in &self.epoch_coverage {
+            writeln!(
+                f,
+                "  Epoch {}: {} of {} stake active ({:.2}%), {:.2}% voted in this epoch",
+                coverage.epoch,
+                coverage.actively_voting_stake,
+                coverage.total_stake,
+                percent(coverage.actively_voting_stake, coverage.total_stake),
+                percent(
+                    coverage.actively_voting_for_this_epoch_stake,
+                    coverage.total_stake
+                ),
+            )?;
+        }
+        writeln!(f, "  Active stake: {:.2}%", self.active_percent)?;
+        writeln!(f, "  Slots to repair: {:?}", self.slots_to_repair)?;
+        writeln!(f, "  Missing blocks: {:?}", self.missing_slots)?;
+        match &self.heaviest_fork {
+            Ok((slot, hash)) => writeln!(f, "Selected heaviest fork: slot {slot} bankhash {hash}")?,
+            Err(err) => writeln!(f, "Heaviest fork selection failed: {err}")?,
+        }
+        let counts = &self.heaviest_fork_counts;
+        writeln!(
+            f,
+            "HeaviestFork: {} aggregated, {} conflicting, {} rejected",
+            counts.inserted, counts.conflicting, counts.rejected
+        )?;
+        writeln!(
+            f,
+            "  Active stake: {} of {} ({:.2}%)",
+            self.heaviest_fork_active_stake,
+            self.heaviest_fork_total_stake,
+            percent(
+                self.heaviest_fork_active_stake,
+                self.heaviest_fork_total_stake
+            ),
+        )?;
+        for vote in &self.heaviest_fork_votes {
+            writeln!(
+                f,
+                "  Slot {} bankhash {}: {} stake ({:.2}%)",
+                vote.slot,
+                vote.hash,
+                vote.stake,
+                percent(vote.stake, self.heaviest_fork_total_stake),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            solana::wen_restart_proto::{
+                HeaviestForkAggregateRecord, LastVotedForkSlotsAggregateRecord, WenRestartProgress,
+            },
+            wen_restart::{write_wen_restart_records, WenRestartError},
+        },
+        solana_accounts_db::hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        solana_ledger::{
+            blockstore::create_new_ledger,
+            blockstore_options::LedgerColumnOptions,
+            blockstore_processor::{
+                fill_blockstore_slot_with_ticks, test_process_blockstore, ProcessOptions,
+            },
+            get_tmp_ledger_path_auto_delete,
+        },
+        solana_runtime::genesis_utils::{
+            create_genesis_config_with_vote_accounts, GenesisConfigInfo, ValidatorVoteKeypairs,
+        },
+        solana_signer::Signer,
+        solana_time_utils::timestamp,
+    };
+
+    const SHRED_VERSION: u16 = 2;
+    const TOTAL_VALIDATOR_COUNT: usize = 10;
+
+    fn last_voted_fork_slots_progress(
+        validators: &[ValidatorVoteKeypairs],
+        last_voted_fork_slots: &[Slot],
+    ) -> WenRestartProgress {
+        let received = validators
+            .iter()
+            .map(|keypairs| {
+                (
+                    keypairs.node_keypair.pubkey().to_string(),
+                    LastVotedForkSlotsRecord {
+                        last_voted_fork_slots: last_voted_fork_slots.to_vec(),
+                        last_vote_bankhash: Hash::new_unique().to_string(),
+                        shred_version: SHRED_VERSION as u32,
+                        wallclock: timestamp(),
+                    },
+                )
+            })
+            .collect();
+        WenRestartProgress {
+            last_voted_fork_slots_aggregate: Some(LastVotedForkSlotsAggregateRecord {
+                received,
+                final_result: None,
+            }),
+            ..WenRestartProgress::default()
+        }
+    }
+
+    #[test]
+    fn test_run_dry_run() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let validator_voting_keypairs: Vec<_> = (0..TOTAL_VALIDATOR_COUNT)
+            .map(|_| ValidatorVoteKeypairs::new_rand())
+            .collect();
+        let GenesisConfigInfo {
+            mut genesis_config, ..
+        } = create_genesis_config_with_vote_accounts(
+            10_000,
+            &validator_voting_keypairs,
+            vec![100; TOTAL_VALIDATOR_COUNT],
+        );
+        genesis_config.ticks_per_slot = 2;
+        let mut last_hash = create_new_ledger(
+            ledger_path.path(),
+            &genesis_config,
+            MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let (bank_forks, ..) = test_process_blockstore(
+            &genesis_config,
+            &blockstore,
+            &ProcessOptions::default(),
+            Arc::default(),
+        );
+        // Slots 1 to 5 are in the blockstore, slot 6 is not.
+        for slot in 1..=5 {
+            last_hash = fill_blockstore_slot_with_ticks(
+                &blockstore,
+                genesis_config.ticks_per_slot,
+                slot,
+                slot - 1,
+                last_hash,
+            );
+        }
+
+        // Validators 0 to 7 voted up to slot 5, two of them also saw slot 6 which
+        // does not reach the repair threshold.
+        let progress_0 =
+            last_voted_fork_slots_progress(&validator_voting_keypairs[0..6], &[5, 4, 3, 2, 1, 0]);
+        let progress_1 = last_voted_fork_slots_progress(
+            &validator_voting_keypairs[6..8],
+            &[6, 5, 4, 3, 2, 1, 0],
+        );
+        let path_0 = ledger_path.path().join("progress_0");
+        let path_1 = ledger_path.path().join("progress_1");
+        write_wen_restart_records(&path_0, &progress_0).unwrap();
+        write_wen_restart_records(&path_1, &progress_1).unwrap();
+
+        let messages = RecordedRestartMessages::load(&[path_0.clone(), path_1.clone()]).unwrap();
+        assert_eq!(messages.num_last_voted_fork_slots(), 8);
+        assert_eq!(messages.num_heaviest_forks(), 0);
+        let report = run_dry_run(
+            bank_forks.clone(),
+            blockstore.clone(),
+            SHRED_VERSION,
+            &messages,
+        )
+        .unwrap();
+        assert_eq!(report.root_slot, 0);
+        assert_eq!(
+            report.last_voted_fork_slots_counts,
+            AggregateCounts {
+                inserted: 8,
+                conflicting: 0,
+                rejected: 0,
+            }
+        );
+        assert_eq!(report.active_percent, 80.0);
+        assert_eq!(report.slots_to_repair, vec![1, 2, 3, 4, 5]);
+        assert!(report.missing_slots.is_empty());
+        let (slot, hash) = report.heaviest_fork.clone().unwrap();
+        assert_eq!(slot, 5);
+        assert_eq!(hash, bank_forks.read().unwrap().get(5).unwrap().hash());
+        assert!(report.heaviest_fork_votes.is_empty());
+
+        // Once the validators agree on the heaviest fork their progress files also
+        // carry the HeaviestFork messages.
+        let mut progress_2 =
+            last_voted_fork_slots_progress(&validator_voting_keypairs[0..1], &[5, 4, 3, 2, 1, 0]);
+        progress_2.heaviest_fork_aggregate = Some(HeaviestForkAggregateRecord {
+            received: validator_voting_keypairs[0..7]
+                .iter()
+                .map(|keypairs| HeaviestForkRecord {
+                    slot,
+                    bankhash: hash.to_string(),
+                    total_active_stake: 800,
+                    shred_version: SHRED_VERSION as u32,
+                    wallclock: timestamp(),
+                    from: keypairs.node_keypair.pubkey().to_string(),
+                })
+                .collect(),
+            total_active_stake: 700,
+        });
+        let path_2 = ledger_path.path().join("progress_2");
+        write_wen_restart_records(&path_2, &progress_2).unwrap();
+        let messages = RecordedRestartMessages::load(&[path_0, path_1, path_2]).unwrap();
+        assert_eq!(messages.num_last_voted_fork_slots(), 8);
+        assert_eq!(messages.num_heaviest_forks(), 7);
+        let report = run_dry_run(bank_forks, blockstore, SHRED_VERSION, &messages).unwrap();
+        assert_eq!(report.heaviest_fork, Ok((slot, hash)));
+        assert_eq!(report.heaviest_fork_counts.inserted, 7);
+        assert_eq!(
+            report.heaviest_fork_votes,
+            vec![HeaviestForkVote {
+                slot,
+                hash,
+                stake: 700,
+            }]
+        );
+        assert_eq!(report.heaviest_fork_active_stake, 700);
+        assert_eq!(report.heaviest_fork_total_stake, 1000);
+    }
+
+    #[test]
+    fn test_run_dry_run_missing_blocks() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let validator_voting_keypairs: Vec<_> = (0..TOTAL_VALIDATOR_COUNT)
+            .map(|_| ValidatorVoteKeypairs::new_rand())
+            .collect();
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config_with_vote_accounts(
+            10_000,
+            &validator_voting_keypairs,
+            vec![100; TOTAL_VALIDATOR_COUNT],
+        );
+        create_new_ledger(
+            ledger_path.path(),
+            &genesis_config,
+            MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let (bank_forks, ..) = test_process_blockstore(
+            &genesis_config,
+            &blockstore,
+            &ProcessOptions::default(),
+            Arc::default(),
+        );
+
+        let progress = last_voted_fork_slots_progress(&validator_voting_keypairs[0..8], &[2, 1, 0]);
+        let path = ledger_path.path().join("progress");
+        write_wen_restart_records(&path, &progress).unwrap();
+        let messages = RecordedRestartMessages::load(&[path]).unwrap();
+        let report = run_dry_run(bank_forks, blockstore, SHRED_VERSION, &messages).unwrap();
+        assert_eq!(report.slots_to_repair, vec![1, 2]);
+        assert_eq!(report.missing_slots, vec![1, 2]);
+        assert_eq!(
+            report.heaviest_fork,
+            Err(WenRestartError::BlockNotFound(1).to_string())
+        );
+        assert!(report.to_string().contains("Missing blocks: [1, 2]"));
+    }
+}
@@ -4,6 +4,7 @@ pub(crate) mod solana {
     }
 }
 
+pub mod dry_run;
 pub(crate) mod heaviest_fork_aggregate;
 pub(crate) mod last_voted_fork_slots_aggregate;
 pub mod wen_restart;
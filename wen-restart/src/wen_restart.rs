@@ -65,7 +65,7 @@ use {
 };
 
 // If >42% of the validators have this block, repair this block locally.
-const REPAIR_THRESHOLD: f64 = 0.42;
+pub(crate) const REPAIR_THRESHOLD: f64 = 0.42;
 // When counting Heaviest Fork, only count those with no less than
 // 67% - 5% - (100% - active_stake) = active_stake - 38% stake.
 // 67% is the supermajority threshold (2/3), 5% is the assumption we
@@ -1397,7 +1397,7 @@ pub(crate) fn initialize(
     }
 }
 
-fn read_wen_restart_records(records_path: &PathBuf) -> Result<WenRestartProgress> {
+pub(crate) fn read_wen_restart_records(records_path: &PathBuf) -> Result<WenRestartProgress> {
     let buffer = read(records_path)?;
     let progress = WenRestartProgress::decode(&mut Cursor::new(buffer))?;
     info!("read record {progress:?}");